@@ -0,0 +1,124 @@
+;; Sample Rustloader extractor plugin.
+;;
+;; Handles URLs containing "/sample/" by fetching `<url>?format=json` through
+;; the host and returning that JSON document as the video info. It shows the
+;; whole plugin ABI (see `src/extractor/plugins/wasm.rs`):
+;;
+;;   exports  memory, alloc(len) -> ptr, id() -> packed,
+;;            supports(ptr, len) -> i32, extract_info(ptr, len) -> packed
+;;            (get_direct_url is optional; the host falls back to
+;;            extract_info and looks the format up)
+;;   imports  rustloader.http_fetch(ptr, len) -> packed | -1,
+;;            rustloader.http_status() -> i32, rustloader.log(ptr, len)
+;;
+;; A "packed" value is `(ptr << 32) | len` as an i64. Results are JSON
+;; envelopes: {"ok": ...} or {"err": "message"}.
+;;
+;; Plugins can be written in any language that targets wasm32; drop the
+;; `.wasm` (or, like this one, `.wat`) file into the plugin directory.
+(module
+  (import "rustloader" "http_fetch" (func $http_fetch (param i32 i32) (result i64)))
+  (import "rustloader" "http_status" (func $http_status (result i32)))
+  (import "rustloader" "log" (func $log (param i32 i32)))
+
+  (memory (export "memory") 1)
+  (global $heap (mut i32) (i32.const 1024))
+
+  (data (i32.const 16) "wasm-sample")
+  (data (i32.const 64) "/sample/")
+  (data (i32.const 80) "?format=json")
+  (data (i32.const 96) "{\"ok\":")
+  (data (i32.const 112) "}")
+  (data (i32.const 128) "{\"err\":\"sample endpoint returned an error\"}")
+  (data (i32.const 192) "{\"err\":\"fetch failed\"}")
+
+  (func $pack (param $ptr i32) (param $len i32) (result i64)
+    (i64.or
+      (i64.shl (i64.extend_i32_u (local.get $ptr)) (i64.const 32))
+      (i64.extend_i32_u (local.get $len))))
+
+  ;; Bump allocator; never frees (every call gets a fresh instance).
+  (func $alloc (export "alloc") (param $len i32) (result i32)
+    (local $ptr i32)
+    (local $end i32)
+    (local.set $ptr
+      (i32.and (i32.add (global.get $heap) (i32.const 7)) (i32.const -8)))
+    (local.set $end (i32.add (local.get $ptr) (local.get $len)))
+    (block $ok
+      (loop $grow
+        (br_if $ok
+          (i32.le_u (local.get $end) (i32.mul (memory.size) (i32.const 65536))))
+        (if (i32.eq (memory.grow (i32.const 1)) (i32.const -1))
+          (then unreachable))
+        (br $grow)))
+    (global.set $heap (local.get $end))
+    (local.get $ptr))
+
+  (func (export "id") (result i64)
+    (call $pack (i32.const 16) (i32.const 11)))
+
+  ;; 1 when the needle occurs in the haystack.
+  (func $contains (param $h i32) (param $hl i32) (param $n i32) (param $nl i32) (result i32)
+    (local $i i32)
+    (local $j i32)
+    (block $not_found
+      (loop $outer
+        (br_if $not_found
+          (i32.gt_s (i32.add (local.get $i) (local.get $nl)) (local.get $hl)))
+        (local.set $j (i32.const 0))
+        (block $mismatch
+          (loop $inner
+            (if (i32.eq (local.get $j) (local.get $nl))
+              (then (return (i32.const 1))))
+            (br_if $mismatch
+              (i32.ne
+                (i32.load8_u (i32.add (local.get $h) (i32.add (local.get $i) (local.get $j))))
+                (i32.load8_u (i32.add (local.get $n) (local.get $j)))))
+            (local.set $j (i32.add (local.get $j) (i32.const 1)))
+            (br $inner)))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $outer)))
+    (i32.const 0))
+
+  (func (export "supports") (param $ptr i32) (param $len i32) (result i32)
+    (call $contains (local.get $ptr) (local.get $len) (i32.const 64) (i32.const 8)))
+
+  ;; Copy `len` bytes from `src` to `dst`; returns the end of the copy.
+  (func $put (param $dst i32) (param $src i32) (param $len i32) (result i32)
+    (memory.copy (local.get $dst) (local.get $src) (local.get $len))
+    (i32.add (local.get $dst) (local.get $len)))
+
+  (func (export "extract_info") (param $url i32) (param $url_len i32) (result i64)
+    (local $req i32)
+    (local $req_len i32)
+    (local $resp i64)
+    (local $body_len i32)
+    (local $out i32)
+    (local $end i32)
+    ;; request = url + "?format=json"
+    (local.set $req_len (i32.add (local.get $url_len) (i32.const 12)))
+    (local.set $req (call $alloc (local.get $req_len)))
+    (drop
+      (call $put
+        (call $put (local.get $req) (local.get $url) (local.get $url_len))
+        (i32.const 80) (i32.const 12)))
+    (call $log (local.get $req) (local.get $req_len))
+
+    (local.set $resp (call $http_fetch (local.get $req) (local.get $req_len)))
+    (if (i64.eq (local.get $resp) (i64.const -1))
+      (then (return (call $pack (i32.const 192) (i32.const 22)))))
+    (if (i32.ne (call $http_status) (i32.const 200))
+      (then (return (call $pack (i32.const 128) (i32.const 43)))))
+
+    ;; {"ok":<body>}
+    (local.set $body_len (i32.wrap_i64 (local.get $resp)))
+    (local.set $out (call $alloc (i32.add (local.get $body_len) (i32.const 7))))
+    (local.set $end (call $put (local.get $out) (i32.const 96) (i32.const 6)))
+    (local.set $end
+      (call $put
+        (local.get $end)
+        (i32.wrap_i64 (i64.shr_u (local.get $resp) (i64.const 32)))
+        (local.get $body_len)))
+    (local.set $end (call $put (local.get $end) (i32.const 112) (i32.const 1)))
+    (call $pack (local.get $out) (i32.sub (local.get $end) (local.get $out))))
+)
@@ -0,0 +1,160 @@
+//! Fan-out of [`BackendEvent`]s to every subscriber of a
+//! [`Rustloader`](super::Rustloader).
+//!
+//! Progress events are frequent and only the latest one matters, so they go
+//! over a bounded broadcast channel: a subscriber that falls behind skips
+//! ahead and never slows a download down. Everything else — task status,
+//! completions, failures, extraction results — changes state the subscriber
+//! cannot recover from a later event, so each subscriber gets those over its
+//! own unbounded channel and never loses one.
+
+use super::messages::BackendEvent;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{broadcast, mpsc};
+use tracing::debug;
+
+/// Capacity of the progress broadcast channel. A subscriber that falls
+/// further behind than this skips the progress it missed.
+const PROGRESS_CHANNEL_CAPACITY: usize = 256;
+
+/// Publishes backend events; cheap to clone.
+#[derive(Clone)]
+pub struct EventBus {
+    progress: broadcast::Sender<BackendEvent>,
+    state: Arc<Mutex<Vec<mpsc::UnboundedSender<BackendEvent>>>>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (progress, _) = broadcast::channel(PROGRESS_CHANNEL_CAPACITY);
+        Self {
+            progress,
+            state: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Publish `event` to every current subscriber. Subscribers that were
+    /// dropped are forgotten.
+    pub fn send(&self, event: BackendEvent) {
+        if event.is_progress() {
+            let _ = self.progress.send(event);
+        } else {
+            self.state
+                .lock()
+                .unwrap()
+                .retain(|subscriber| subscriber.send(event.clone()).is_ok());
+        }
+    }
+
+    /// A new stream of every event published from now on.
+    pub fn subscribe(&self) -> EventReceiver {
+        let (tx, state) = mpsc::unbounded_channel();
+        self.state.lock().unwrap().push(tx);
+        EventReceiver {
+            state,
+            progress: self.progress.subscribe(),
+            progress_open: true,
+        }
+    }
+}
+
+/// One subscriber's stream of backend events. State events are delivered
+/// ahead of pending progress, so a finished task's last progress can arrive
+/// after its completion; consumers ignore progress for finished tasks.
+pub struct EventReceiver {
+    state: mpsc::UnboundedReceiver<BackendEvent>,
+    progress: broadcast::Receiver<BackendEvent>,
+    progress_open: bool,
+}
+
+impl EventReceiver {
+    /// The next event, or `None` once the backend is gone.
+    pub async fn recv(&mut self) -> Option<BackendEvent> {
+        loop {
+            tokio::select! {
+                biased;
+                event = self.state.recv() => return event,
+                event = self.progress.recv(), if self.progress_open => match event {
+                    Ok(event) => return Some(event),
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        debug!("Event subscriber skipped {} progress updates", n);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => self.progress_open = false,
+                },
+            }
+        }
+    }
+
+    /// The next event if one is ready.
+    pub fn try_recv(&mut self) -> Option<BackendEvent> {
+        if let Ok(event) = self.state.try_recv() {
+            return Some(event);
+        }
+        loop {
+            match self.progress.try_recv() {
+                Ok(event) => return Some(event),
+                Err(broadcast::error::TryRecvError::Lagged(_)) => continue,
+                Err(_) => return None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gui::DownloadProgressData;
+
+    #[tokio::test]
+    async fn state_events_survive_a_progress_flood() {
+        let bus = EventBus::new();
+        let mut events = bus.subscribe();
+
+        bus.send(BackendEvent::TaskStatusUpdated {
+            task_id: "t".into(),
+            status: "Downloading".into(),
+        });
+        for _ in 0..PROGRESS_CHANNEL_CAPACITY * 4 {
+            bus.send(BackendEvent::DownloadProgress {
+                task_id: "t".into(),
+                data: DownloadProgressData {
+                    progress: 0.0,
+                    speed: 0.0,
+                    downloaded: 0,
+                    total: 0,
+                    eta: None,
+                    live: None,
+                },
+            });
+        }
+        bus.send(BackendEvent::DownloadCompleted {
+            task_id: "t".into(),
+            file_path: None,
+        });
+        drop(bus);
+
+        let mut statuses = 0;
+        let mut completed = false;
+        let mut progress = 0;
+        while let Some(event) = events.recv().await {
+            match event {
+                BackendEvent::TaskStatusUpdated { .. } => statuses += 1,
+                BackendEvent::DownloadCompleted { .. } => completed = true,
+                BackendEvent::DownloadProgress { .. } => progress += 1,
+                _ => {}
+            }
+        }
+        assert_eq!(statuses, 1);
+        assert!(completed);
+        assert!(
+            progress <= PROGRESS_CHANNEL_CAPACITY,
+            "old progress is skipped"
+        );
+    }
+}
@@ -0,0 +1,2242 @@
+//! Typed embedding facade over the backend components.
+//!
+//! [`Rustloader`] owns everything `BackendActor` used to wire up by hand —
+//! cookies, the hybrid extractor registry, the download engine, the file
+//! organizer, the metadata manager, the event log, the queue and the history
+//! database — behind one builder:
+//!
+//! ```no_run
+//! # async fn demo() -> anyhow::Result<()> {
+//! use rustloader::Rustloader;
+//!
+//! let rl = Rustloader::builder()
+//!     .data_dir("/tmp/rl-data")
+//!     .library_dir("/tmp/rl-library")
+//!     .build()
+//!     .await?;
+//! rl.start().await;
+//!
+//! let mut events = rl.subscribe();
+//! let info = rl.extract("https://example.com/clip.mp4").await?;
+//! let task_id = rl.enqueue(info, "/tmp/clip.mp4".into(), None).await?;
+//! while let Some(event) = events.recv().await {
+//!     println!("{event:?}");
+//! }
+//! # let _ = task_id;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The GUI's `BackendActor` is a thin message pump over this type and the
+//! CLI builds its extractor through [`RustloaderBuilder::build_extractor`],
+//! so every front-end shares one entry point.
+
+use super::events::{EventBus, EventReceiver};
+use super::messages::BackendEvent;
+use super::subscriptions::{
+    entry_key, plan_sync, MediaProfile, Subscription, SyncReport, MAX_ENTRY_ATTEMPTS,
+};
+use crate::database::{
+    initialize_database, DatabaseManager, DownloadRecord, SubscriptionItemRecord,
+};
+use crate::downloader::{
+    ChapterMode, DownloadConfig, DownloadEngine, DownloadStatus, LiveOptions, SharedProgressSink,
+    ThumbnailCache, YtDlpOptions,
+};
+use crate::extractor::{
+    formats,
+    native::direct::DirectMediaExtractor,
+    native::generic::GenericHtmlExtractor,
+    native::youtube::NativeYoutubeExtractor,
+    plugins::{default_plugin_dir, load_plugins},
+    CachePolicy, ExtractionCache, ExtractionStage, Extractor, ExtractorStats, Format,
+    FormatPreferences, FormatSelector, HybridExtractor, InOrder, Playlist, SearchPage, SearchQuery,
+    VideoInfo, YtDlpExtractor,
+};
+use crate::gui::{DownloadProgressData, LiveProgress};
+use crate::queue::{AddOutcome, ArchivePolicy, DownloadArchive};
+use crate::queue::{DownloadTask, EventLog, QueueManager, TaskBatch, TaskProgress, TaskStatus};
+use crate::utils::config::AppSettings;
+use crate::utils::{
+    get_app_support_dir, CookieConfig, CookieJar, FileOrganizer, MetadataManager,
+    OrganizationSettings, RustloaderError,
+};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{broadcast, mpsc, Semaphore};
+use tokio::task::AbortHandle;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+/// How often the cookie jar checks whether its cookie file or browser store
+/// changed.
+const COOKIE_RELOAD_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Extraction jobs (see [`Rustloader::start_extraction`]) run at once; later
+/// ones wait in the `Queued` stage.
+pub const MAX_CONCURRENT_EXTRACTIONS: usize = 3;
+
+/// Map a queue `TaskStatus` to the `downloads` table's `(status, completed_at,
+/// error_message)` columns. The single source of truth for that mapping, used
+/// both for the DB history writes and the GUI's `TaskStatusUpdated` event, so
+/// the two never drift apart.
+pub(crate) fn task_status_db_fields(
+    status: &TaskStatus,
+) -> (String, Option<DateTime<Utc>>, Option<String>) {
+    match status {
+        TaskStatus::Queued => ("Queued".to_string(), None, None),
+        TaskStatus::Waiting => ("Waiting".to_string(), None, None),
+        TaskStatus::Downloading => ("Downloading".to_string(), None, None),
+        TaskStatus::Paused => ("Paused".to_string(), None, None),
+        TaskStatus::Completed => ("Completed".to_string(), Some(Utc::now()), None),
+        TaskStatus::Failed(e) => ("Failed".to_string(), Some(Utc::now()), Some(e.clone())),
+        TaskStatus::Cancelled => ("Cancelled".to_string(), Some(Utc::now()), None),
+    }
+}
+
+/// Builder for [`Rustloader`]. Every setting has a default matching the GUI's
+/// historical wiring, so `Rustloader::builder().build()` is a working backend.
+#[derive(Default)]
+pub struct RustloaderBuilder {
+    settings: AppSettings,
+    data_dir: Option<PathBuf>,
+    library_dir: Option<PathBuf>,
+    database: Option<Arc<DatabaseManager>>,
+    database_path: Option<PathBuf>,
+    cookies: Option<CookieConfig>,
+    cookie_jar: Option<Arc<CookieJar>>,
+    ytdlp_options: Option<YtDlpOptions>,
+    organization: Option<OrganizationSettings>,
+    extractors: Vec<Arc<dyn Extractor>>,
+    fallback: Option<Arc<dyn Extractor>>,
+    plugin_dir: Option<PathBuf>,
+    cache_policy: Option<CachePolicy>,
+    disable_extraction_cache: bool,
+    thumbnail_dir: Option<PathBuf>,
+    chapter_mode: Option<ChapterMode>,
+    live_options: Option<LiveOptions>,
+}
+
+impl RustloaderBuilder {
+    /// Application settings (segments, concurrency, cookies, ...).
+    pub fn settings(mut self, settings: AppSettings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    /// Directory holding the queue event log (and the history database when
+    /// no database is supplied). Defaults to the app support directory.
+    pub fn data_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.data_dir = Some(dir.into());
+        self
+    }
+
+    /// Root of the organized library completed downloads are moved into.
+    /// Defaults to `~/Downloads/Rustloader`.
+    pub fn library_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.library_dir = Some(dir.into());
+        self
+    }
+
+    /// Share an already-open history database (the GUI passes the one it
+    /// reads settings from, so both look at the same sqlite file).
+    pub fn database(mut self, db: Arc<DatabaseManager>) -> Self {
+        self.database = Some(db);
+        self
+    }
+
+    /// Open (or create) the history database at this path. Ignored when
+    /// [`database`](Self::database) is set.
+    pub fn database_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.database_path = Some(path.into());
+        self
+    }
+
+    /// Cookie source for extraction and download. Defaults to the cookies in
+    /// [`settings`](Self::settings).
+    pub fn cookies(mut self, cookies: CookieConfig) -> Self {
+        self.cookies = Some(cookies);
+        self
+    }
+
+    /// Cookie jar for the native engine's requests (direct-link probes and
+    /// native downloads). Defaults to one loaded from the
+    /// [`cookies`](Self::cookies) in [`build`](Self::build); without it,
+    /// [`build_extractor`](Self::build_extractor) probes without cookies.
+    pub fn cookie_jar(mut self, jar: Arc<CookieJar>) -> Self {
+        self.cookie_jar = Some(jar);
+        self
+    }
+
+    /// yt-dlp download options. The cookie source is always overridden with
+    /// the builder's so extraction and download never disagree; empty format
+    /// preferences and disabled subtitles fall back to the settings'.
+    pub fn ytdlp_options(mut self, options: YtDlpOptions) -> Self {
+        self.ytdlp_options = Some(options);
+        self
+    }
+
+    /// File organization settings for completed downloads.
+    pub fn organization(mut self, settings: OrganizationSettings) -> Self {
+        self.organization = Some(settings);
+        self
+    }
+
+    /// Register an extra extractor. Extractors are consulted in registration
+    /// order, ahead of the built-in native extractors and the yt-dlp fallback.
+    pub fn extractor(mut self, extractor: Arc<dyn Extractor>) -> Self {
+        self.extractors.push(extractor);
+        self
+    }
+
+    /// Replace the yt-dlp fallback extractor. Lets an embedder run without a
+    /// yt-dlp binary on `PATH`.
+    pub fn fallback_extractor(mut self, extractor: Arc<dyn Extractor>) -> Self {
+        self.fallback = Some(extractor);
+        self
+    }
+
+    /// Directory of extractor plugins (site-rule `.toml`/`.json` files and
+    /// `.wasm` modules).
+    /// Defaults to `<config dir>/extractors`.
+    pub fn plugin_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.plugin_dir = Some(dir.into());
+        self
+    }
+
+    /// Cache extraction results in the history database (on by default).
+    pub fn extraction_cache(mut self, enabled: bool) -> Self {
+        self.disable_extraction_cache = !enabled;
+        self
+    }
+
+    /// TTLs for cached extraction results. Defaults to
+    /// [`CachePolicy::default`].
+    pub fn cache_policy(mut self, policy: CachePolicy) -> Self {
+        self.cache_policy = Some(policy);
+        self
+    }
+
+    /// Where download thumbnails are cached. Defaults to
+    /// `<data_dir>/thumbnails`.
+    pub fn thumbnail_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.thumbnail_dir = Some(dir.into());
+        self
+    }
+
+    /// What to do with the chapters of finished videos. Defaults to the
+    /// settings' [`chapter_mode`](AppSettings::chapter_mode).
+    pub fn chapter_mode(mut self, mode: ChapterMode) -> Self {
+        self.chapter_mode = Some(mode);
+        self
+    }
+
+    /// How live streams are recorded. Defaults to the settings'
+    /// [`live_options`](AppSettings::live_options).
+    pub fn live_options(mut self, live: LiveOptions) -> Self {
+        self.live_options = Some(live);
+        self
+    }
+
+    fn resolved_cookies(&self) -> CookieConfig {
+        self.cookies.clone().unwrap_or_else(|| {
+            CookieConfig::new(
+                self.settings.cookies_from_browser.clone(),
+                self.settings.cookies_file.clone(),
+            )
+        })
+    }
+
+    /// Build just the extractor registry: registered extractors, plugins
+    /// from the [`plugin_dir`](Self::plugin_dir), the native extractors, then
+    /// yt-dlp as the fallback and the generic page scanner as the last
+    /// resort. Without yt-dlp installed the native extractors keep working.
+    pub fn build_extractor(&self) -> Result<HybridExtractor> {
+        let fallback: Arc<dyn Extractor> = match &self.fallback {
+            Some(fallback) => Arc::clone(fallback),
+            None => match YtDlpExtractor::new() {
+                Ok(ytdlp) => Arc::new(ytdlp.with_cookies(self.resolved_cookies())),
+                // Native extractors still work; only URLs that reach the
+                // fallback report the missing binary.
+                Err(e) => {
+                    warn!("{}; only native extractors are available", e);
+                    Arc::new(MissingYtDlp)
+                }
+            },
+        };
+        let mut extractors = self.extractors.clone();
+        let plugin_dir = self.plugin_dir.clone().unwrap_or_else(default_plugin_dir);
+        extractors.extend(load_plugins(&plugin_dir));
+        let mut direct = DirectMediaExtractor::new();
+        if let Some(jar) = &self.cookie_jar {
+            direct = direct.with_cookie_jar(Arc::clone(jar));
+        }
+        extractors.push(Arc::new(direct));
+        extractors.push(Arc::new(NativeYoutubeExtractor::new()));
+        Ok(HybridExtractor::new(extractors, fallback)
+            .with_last_resort(Arc::new(GenericHtmlExtractor::new())))
+    }
+
+    /// Wire up every component. Nothing runs until [`Rustloader::start`].
+    pub async fn build(mut self) -> Result<Rustloader> {
+        let cookies = self.resolved_cookies();
+        let cookie_jar = match self.cookie_jar.clone() {
+            Some(jar) => jar,
+            None => Arc::new(CookieJar::load(&cookies).await),
+        };
+        self.cookie_jar = Some(Arc::clone(&cookie_jar));
+        let mut extractor = self.build_extractor()?;
+
+        let download_config = DownloadConfig {
+            segments: self.settings.segments,
+            connections_per_segment: 1,
+            chunk_size: self.settings.chunk_size,
+            retry_attempts: self.settings.retry_attempts,
+            retry_delay: std::time::Duration::from_secs(2),
+            enable_resume: self.settings.enable_resume,
+            request_delay: std::time::Duration::from_millis(100),
+        };
+        let mut ytdlp_options = YtDlpOptions {
+            cookies,
+            ..self.ytdlp_options.unwrap_or_default()
+        };
+        if ytdlp_options.preferences.is_empty() {
+            ytdlp_options.preferences = self.settings.format_preferences.clone();
+        }
+        if !ytdlp_options.subtitles && self.settings.download_subtitles {
+            ytdlp_options.subtitles = true;
+            ytdlp_options.subtitle_options = self.settings.subtitle_options.clone();
+        }
+        let format_preferences = ytdlp_options.effective_preferences();
+        let live_options = self
+            .live_options
+            .clone()
+            .unwrap_or_else(|| self.settings.live_options.clone());
+        let engine = DownloadEngine::new(download_config)
+            .with_cookie_jar(Arc::clone(&cookie_jar))
+            .with_ytdlp_options(ytdlp_options)
+            .with_live_options(live_options);
+
+        let org_settings = self.organization.unwrap_or_default();
+        let file_organizer = match self.library_dir {
+            Some(dir) => FileOrganizer::with_base_dir(dir, org_settings).await,
+            None => FileOrganizer::new(org_settings).await,
+        }
+        .map_err(|e| anyhow::anyhow!("Failed to initialize file organizer: {}", e))?;
+        let metadata_manager = MetadataManager::new(&file_organizer.base_dir);
+
+        let data_dir = self.data_dir.unwrap_or_else(get_app_support_dir);
+        let thumbnails = Arc::new(ThumbnailCache::new(
+            self.thumbnail_dir
+                .unwrap_or_else(|| data_dir.join("thumbnails")),
+        ));
+        let event_log = Arc::new(
+            EventLog::new(&data_dir)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to initialize event log: {}", e))?,
+        );
+
+        let db_manager = match self.database {
+            Some(db) => db,
+            None => {
+                let path = self
+                    .database_path
+                    .unwrap_or_else(|| data_dir.join("rustloader.db"));
+                let url = format!("sqlite://{}?mode=rwc", path.to_string_lossy());
+                let pool = initialize_database(&url)
+                    .await
+                    .with_context(|| format!("Failed to open database {}", path.display()))?;
+                Arc::new(DatabaseManager::new(pool))
+            }
+        };
+
+        let archive = Arc::new(DownloadArchive::new(Arc::clone(&db_manager)));
+        match archive.import_metadata(&metadata_manager).await {
+            Ok(0) => {}
+            Ok(added) => info!("Backfilled {} library download(s) into the archive", added),
+            Err(e) => warn!("Could not backfill the download archive: {}", e),
+        }
+
+        if !self.disable_extraction_cache {
+            let policy = self.cache_policy.unwrap_or_default();
+            extractor = extractor.with_cache(ExtractionCache::new(Arc::clone(&db_manager), policy));
+        }
+        let extractor = Arc::new(extractor);
+
+        let queue_manager = Arc::new(
+            QueueManager::new(
+                self.settings.max_concurrent,
+                engine,
+                file_organizer,
+                metadata_manager,
+                event_log,
+            )
+            .with_archive(Arc::clone(&archive))
+            .with_thumbnails(Arc::clone(&thumbnails))
+            .with_chapter_mode(self.chapter_mode.unwrap_or(self.settings.chapter_mode)),
+        );
+
+        let events = EventBus::new();
+
+        Ok(Rustloader {
+            extractor,
+            queue_manager,
+            db_manager,
+            archive,
+            thumbnails,
+            events,
+            download_dir: self.settings.download_location.clone(),
+            format_preferences,
+            cookie_jar,
+            extraction_slots: Arc::new(Semaphore::new(MAX_CONCURRENT_EXTRACTIONS)),
+            extractions: Arc::new(Mutex::new(HashMap::new())),
+            started: AtomicBool::new(false),
+        })
+    }
+}
+
+/// The Rustloader backend: extraction, queue, history and a typed event
+/// stream behind async methods. Cheap to share behind an `Arc`.
+pub struct Rustloader {
+    extractor: Arc<HybridExtractor>,
+    queue_manager: Arc<QueueManager>,
+    db_manager: Arc<DatabaseManager>,
+    archive: Arc<DownloadArchive>,
+    thumbnails: Arc<ThumbnailCache>,
+    events: EventBus,
+    /// Where subscription downloads without their own folder are written.
+    download_dir: PathBuf,
+    /// Steers the native format choice the way `-S`/`-f` steer yt-dlp.
+    format_preferences: FormatPreferences,
+    /// Cookies for the native engine; reloaded when their source changes.
+    cookie_jar: Arc<CookieJar>,
+    extraction_slots: Arc<Semaphore>,
+    /// Running extraction jobs by ID, so they can be cancelled.
+    extractions: Arc<Mutex<HashMap<String, AbortHandle>>>,
+    started: AtomicBool,
+}
+
+impl Rustloader {
+    /// Start configuring a backend.
+    pub fn builder() -> RustloaderBuilder {
+        RustloaderBuilder::default()
+    }
+
+    /// Rehydrate the queue from the event log and spawn the queue processor
+    /// and the status monitor on the current Tokio runtime. Idempotent.
+    pub async fn start(&self) {
+        if self.started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        if let Err(e) = self.queue_manager.rehydrate().await {
+            tracing::error!("Failed to rehydrate queue state: {}", e);
+        }
+
+        // Load persisted download history (Shape-3 PR-1). This is separate
+        // from the queue rehydrate above: rehydrate reconstructs the LIVE
+        // queue's runtime state from the EventLog; this reads the `downloads`
+        // table, the durable history of every download ever started,
+        // including ones long since cleared from the live queue.
+        match self.db_manager.get_all_downloads().await {
+            Ok(history) => info!(
+                "Loaded {} historical download record(s) from the downloads table",
+                history.len()
+            ),
+            Err(e) => warn!("Failed to load download history: {}", e),
+        }
+
+        if let Some(cache) = self.extractor.cache() {
+            match cache.prune().await {
+                Ok(0) => {}
+                Ok(n) => info!("Pruned {} stale extraction cache entries", n),
+                Err(e) => warn!("Failed to prune extraction cache: {}", e),
+            }
+        }
+
+        self.cookie_jar.watch(COOKIE_RELOAD_INTERVAL);
+
+        let qm = Arc::clone(&self.queue_manager);
+        let progress = qm.subscribe_progress();
+        let events = self.events.clone();
+        tokio::spawn(async move {
+            progress_loop(qm, progress, events).await;
+        });
+
+        let qm = Arc::clone(&self.queue_manager);
+        tokio::spawn(async move {
+            qm.start().await;
+        });
+
+        // Monitor (polls the queue and publishes status events). In a future
+        // refactor, QueueManager should push status changes too.
+        let qm = Arc::clone(&self.queue_manager);
+        let events = self.events.clone();
+        let db = Arc::clone(&self.db_manager);
+        tokio::spawn(async move {
+            monitor_loop(qm, events, db).await;
+        });
+    }
+
+    /// Subscribe to backend events. Each subscriber gets its own stream; a
+    /// subscriber that stops reading only skips progress, never a state
+    /// change (see [`EventBus`]).
+    pub fn subscribe(&self) -> EventReceiver {
+        self.events.subscribe()
+    }
+
+    /// Extract video information with the hybrid extractor registry,
+    /// reusing a cached result while it is fresh.
+    pub async fn extract(&self, url: &str) -> Result<VideoInfo> {
+        self.extractor.extract_info(url).await
+    }
+
+    /// Extract video information again, ignoring (and replacing) any cached
+    /// result.
+    pub async fn refresh(&self, url: &str) -> Result<VideoInfo> {
+        self.extractor.refresh_info(url).await
+    }
+
+    /// Extract `url` in the background as job `id` (chosen by the caller),
+    /// ignoring any cached result if `refresh` is set. At most
+    /// [`MAX_CONCURRENT_EXTRACTIONS`] jobs run at once. The job's stages are
+    /// published as `ExtractionProgress` and its result as
+    /// `ExtractionCompleted`, both tagged with `id`.
+    pub fn start_extraction(&self, id: String, url: String, refresh: bool) {
+        let extractor = Arc::clone(&self.extractor);
+        let events = self.events.clone();
+        let slots = Arc::clone(&self.extraction_slots);
+        let jobs = Arc::clone(&self.extractions);
+        let job_id = id.clone();
+        // Held until the handle is stored, so a job that finishes at once
+        // cannot try to remove itself first.
+        let mut running = self.extractions.lock().unwrap();
+        let handle = tokio::spawn(async move {
+            let report = |stage| {
+                events.send(BackendEvent::ExtractionProgress {
+                    id: job_id.clone(),
+                    stage,
+                });
+            };
+            let _permit = match slots.try_acquire() {
+                Ok(permit) => permit,
+                Err(_) => {
+                    report(ExtractionStage::Queued);
+                    slots
+                        .acquire()
+                        .await
+                        .expect("extraction slots are never closed")
+                }
+            };
+            let result = if refresh {
+                extractor.refresh_info_reporting(&url, &report).await
+            } else {
+                extractor.extract_info_reporting(&url, &report).await
+            };
+            jobs.lock().unwrap().remove(&job_id);
+            events.send(BackendEvent::ExtractionCompleted {
+                id: job_id,
+                result: result.map_err(|e| e.to_string()),
+            });
+        });
+        running.insert(id, handle.abort_handle());
+    }
+
+    /// Cancel extraction job `id`. Dropping the job kills its yt-dlp process,
+    /// if it has one. Publishes `ExtractionCancelled`; returns `false` if the
+    /// job had already finished.
+    pub fn cancel_extraction(&self, id: &str) -> bool {
+        let Some(handle) = self.extractions.lock().unwrap().remove(id) else {
+            return false;
+        };
+        handle.abort();
+        debug!("Cancelled extraction {}", id);
+        self.events
+            .send(BackendEvent::ExtractionCancelled { id: id.to_string() });
+        true
+    }
+
+    /// Search a site (through yt-dlp's search prefixes) for one page of hits.
+    pub async fn search(&self, query: &SearchQuery) -> Result<SearchPage> {
+        self.extractor.search(query).await
+    }
+
+    /// Per-extractor success/failure counts, latency and circuit-breaker
+    /// state, for diagnostics.
+    pub fn extractor_stats(&self) -> Vec<ExtractorStats> {
+        self.extractor.stats()
+    }
+
+    /// The extractor registry, for callers that need playlists, formats or
+    /// direct URLs.
+    pub fn extractor(&self) -> &Arc<HybridExtractor> {
+        &self.extractor
+    }
+
+    /// Queue a download of `video_info` to `output_path` and record it in the
+    /// history. `format_id` picks an exact format; `None` selects the best
+    /// one. Returns the new task's ID; a video that was downloaded before is
+    /// an `AlreadyDownloaded` error.
+    pub async fn enqueue(
+        &self,
+        video_info: VideoInfo,
+        output_path: PathBuf,
+        format_id: Option<String>,
+    ) -> Result<String> {
+        self.enqueue_with(video_info, output_path, format_id, ArchivePolicy::Skip)
+            .await?
+            .into_task_id()
+    }
+
+    /// [`enqueue`](Self::enqueue), with `policy` deciding what happens if the
+    /// video is already in the download archive.
+    pub async fn enqueue_with(
+        &self,
+        video_info: VideoInfo,
+        output_path: PathBuf,
+        format_id: Option<String>,
+        policy: ArchivePolicy,
+    ) -> Result<AddOutcome> {
+        self.enqueue_task(video_info, output_path, format_id, None, None, policy)
+            .await
+    }
+
+    /// The download archive (what was downloaded before).
+    pub fn archive(&self) -> &Arc<DownloadArchive> {
+        &self.archive
+    }
+
+    async fn enqueue_task(
+        &self,
+        video_info: VideoInfo,
+        output_path: PathBuf,
+        format_id: Option<String>,
+        batch: Option<TaskBatch>,
+        target_dir: Option<PathBuf>,
+        policy: ArchivePolicy,
+    ) -> Result<AddOutcome> {
+        // Checked before resolving the download URL, which can be slow.
+        if policy != ArchivePolicy::Force {
+            if let Some(record) = self.queue_manager.archived(&video_info).await {
+                if let Some(outcome) = policy.on_archived(record) {
+                    return Ok(outcome);
+                }
+            }
+        }
+
+        let preferences = &self.format_preferences;
+        let explicit = format_id.is_some() || preferences.selector().is_some();
+        let format =
+            select_format(&video_info, format_id, preferences).map_err(anyhow::Error::msg)?;
+        // yt-dlp gets the ids picked here, not the expression, so both
+        // download paths fetch the same streams.
+        let requested_format = explicit.then(|| format.format_id.clone());
+        let download_url = self.download_url(&video_info, &format).await?;
+
+        let task_id = Uuid::new_v4().to_string();
+        let mut updated_format = format.clone();
+        updated_format.url = download_url;
+
+        let task = DownloadTask {
+            id: task_id.clone(),
+            video_info: video_info.clone(),
+            output_path: output_path.clone(),
+            format: updated_format,
+            status: TaskStatus::Queued,
+            progress: None,
+            added_at: Utc::now(),
+            batch: batch.clone(),
+            target_dir,
+            requested_format,
+        };
+        self.queue_manager
+            .add_task_with(task, ArchivePolicy::Force)
+            .await?;
+
+        // Persist the initial history row now that the task is actually
+        // queued. Best-effort: a write failure here must not fail the
+        // download — the queue (added above) is already the runtime source
+        // of truth for this task; this is a durable log of it, not a second
+        // one.
+        let record = DownloadRecord {
+            id: task_id.clone(),
+            url: video_info.url.clone(),
+            title: video_info.title.clone(),
+            output_path,
+            file_size: format.filesize,
+            status: "Queued".to_string(),
+            created_at: Utc::now(),
+            completed_at: None,
+            error_message: None,
+        };
+        if let Err(e) = self.db_manager.save_download(&record).await {
+            warn!("Failed to persist download history for {}: {}", task_id, e);
+        }
+
+        self.events.send(BackendEvent::DownloadStarted {
+            task_id: task_id.clone(),
+            video_info: video_info.clone(),
+            batch,
+        });
+
+        // Fetch the thumbnail while the task waits, so the queue can show it.
+        let thumbnails = Arc::clone(&self.thumbnails);
+        let events = self.events.clone();
+        let id = task_id.clone();
+        tokio::spawn(async move {
+            match thumbnails.fetch(&id, &video_info).await {
+                Ok(path) => {
+                    events.send(BackendEvent::ThumbnailReady { task_id: id, path });
+                }
+                Err(e) => debug!("No thumbnail for task {}: {}", id, e),
+            }
+        });
+        Ok(AddOutcome::Queued(task_id))
+    }
+
+    /// List a playlist's entries (flat: titles and URLs, usually no formats),
+    /// marking the ones already in the download archive.
+    pub async fn expand_playlist(&self, url: &str) -> Result<Playlist> {
+        let entries = self.extractor.extract_playlist(url).await?;
+        if entries.is_empty() {
+            return Err(RustloaderError::ExtractionError(format!(
+                "No playlist entries found at {url}"
+            ))
+            .into());
+        }
+        let mut playlist = Playlist::new(url, entries);
+        for (i, entry) in playlist.entries.iter().enumerate() {
+            if self.queue_manager.archived(entry).await.is_some() {
+                playlist.archived.push(i + 1);
+            }
+        }
+        Ok(playlist)
+    }
+
+    /// Queue the entries at the 1-based `indices` of `playlist` into
+    /// `output_dir`, one task each, grouped as one batch. Flat entries are
+    /// extracted together first (see [`Self::extract_entries`]) and queued in
+    /// playlist order as they come in; an entry that fails is reported as an
+    /// `Error` event and skipped. Entries already downloaded are left out
+    /// unless `policy` is `Force`; a playlist cannot stop to ask about each
+    /// one, so `Ask` leaves them out too. Returns the queued task IDs.
+    pub async fn enqueue_playlist(
+        &self,
+        playlist: &Playlist,
+        indices: &[usize],
+        output_dir: &Path,
+        policy: ArchivePolicy,
+    ) -> Result<Vec<String>> {
+        let policy = match policy {
+            ArchivePolicy::Ask => ArchivePolicy::Skip,
+            policy => policy,
+        };
+        let mut wanted = Vec::new();
+        let mut archived = 0;
+        for &index in indices {
+            let Some(entry) = playlist.entry(index) else {
+                warn!("Playlist has no entry #{}; skipping", index);
+                continue;
+            };
+            // Flat entries usually carry an ID, so most archived entries are
+            // caught here without extracting them.
+            if policy == ArchivePolicy::Skip && self.queue_manager.archived(entry).await.is_some() {
+                archived += 1;
+                continue;
+            }
+            wanted.push((index, entry));
+        }
+
+        let batch_id = Uuid::new_v4().to_string();
+        let mut task_ids = Vec::new();
+        let mut extracted =
+            self.extract_entries(wanted.iter().map(|(_, entry)| (*entry).clone()).collect());
+        while let Some((i, result)) = extracted.recv().await {
+            let (index, entry) = wanted[i];
+            let result = match result {
+                Ok(info) => {
+                    let output_path = output_dir.join(format!(
+                        "{}.mp4",
+                        crate::utils::sanitize_filename(&info.title)
+                    ));
+                    let batch = TaskBatch {
+                        id: batch_id.clone(),
+                        name: playlist.title.clone(),
+                        index,
+                    };
+                    self.enqueue_task(info, output_path, None, Some(batch), None, policy)
+                        .await
+                }
+                Err(e) => Err(anyhow::anyhow!(e)),
+            };
+            match result {
+                Ok(AddOutcome::Queued(task_id)) => task_ids.push(task_id),
+                Ok(_) => archived += 1,
+                Err(e) => {
+                    warn!("Skipping playlist entry #{} ({}): {}", index, entry.url, e);
+                    self.events.send(BackendEvent::Error(format!(
+                        "Skipped \"{}\": {}",
+                        entry.title, e
+                    )));
+                }
+            }
+        }
+        if archived > 0 {
+            info!(
+                "Left out {} already downloaded entr{} of {}",
+                archived,
+                if archived == 1 { "y" } else { "ies" },
+                playlist.title
+            );
+        }
+        if task_ids.is_empty() && archived < indices.len() {
+            return Err(anyhow::anyhow!(
+                "None of the selected entries of \"{}\" could be queued",
+                playlist.title
+            ));
+        }
+        Ok(task_ids)
+    }
+
+    /// Queue a list of URLs (pasted or imported) into `output_dir` as one
+    /// batch, extracting them together. Works like [`Self::enqueue_playlist`]:
+    /// failures are reported as `Error` events and downloaded videos are left
+    /// out.
+    pub async fn enqueue_urls(&self, urls: &[String], output_dir: &Path) -> Result<Vec<String>> {
+        let entries = urls
+            .iter()
+            .map(|url| VideoInfo {
+                url: url.clone(),
+                title: url.clone(),
+                ..Default::default()
+            })
+            .collect();
+        let list = Playlist {
+            title: format!("{} imported URLs", urls.len()),
+            entries,
+            ..Default::default()
+        };
+        let indices: Vec<usize> = (1..=urls.len()).collect();
+        self.enqueue_playlist(&list, &indices, output_dir, ArchivePolicy::Skip)
+            .await
+    }
+
+    /// Fully extract flat `entries` as one batch, in the background, so a
+    /// long playlist costs a few yt-dlp runs rather than one per entry.
+    /// Results come back in entry order as `(position, result)`, each as soon
+    /// as it and every earlier one are in. Entries that already carry
+    /// formats are passed through as they are.
+    fn extract_entries(
+        &self,
+        entries: Vec<VideoInfo>,
+    ) -> mpsc::Receiver<(usize, Result<VideoInfo, String>)> {
+        let extractor = Arc::clone(&self.extractor);
+        let mut order = InOrder::new(entries.iter().map(|e| e.url.clone()).collect());
+        let mut urls: Vec<String> = Vec::new();
+        let mut known = Vec::new();
+        for entry in entries {
+            if !entry.formats.is_empty() {
+                known.push(entry);
+            } else if !urls.contains(&entry.url) {
+                urls.push(entry.url);
+            }
+        }
+
+        let (tx, rx) = mpsc::channel(16);
+        tokio::spawn(async move {
+            let (batch_tx, mut batch_rx) = mpsc::channel(16);
+            let extraction = extractor.extract_batch(&urls, batch_tx);
+            let forward = async move {
+                let known = known.into_iter().map(|info| (info.url.clone(), Ok(info)));
+                let mut due = Vec::new();
+                for (url, result) in known {
+                    due.extend(order.push(url, result));
+                }
+                loop {
+                    for item in due.drain(..) {
+                        if tx.send(item).await.is_err() {
+                            // Dropping `batch_rx` stops the extraction too.
+                            return;
+                        }
+                    }
+                    match batch_rx.recv().await {
+                        Some((url, result)) => due = order.push(url, result),
+                        None => break,
+                    }
+                }
+                for item in order.finish() {
+                    if tx.send(item).await.is_err() {
+                        return;
+                    }
+                }
+            };
+            tokio::join!(extraction, forward);
+        });
+        rx
+    }
+
+    /// Extract `url` and queue it into `output_dir`, named after its title.
+    pub async fn enqueue_url(&self, url: &str, output_dir: &Path) -> Result<String> {
+        let info = self.extract(url).await?;
+        let output_path = output_dir.join(format!(
+            "{}.mp4",
+            crate::utils::sanitize_filename(&info.title)
+        ));
+        self.enqueue(info, output_path, None).await
+    }
+
+    /// Follow a channel or playlist. The source is listed once up front,
+    /// which also names a subscription still called by its URL. Unless
+    /// `download_existing`, everything listed now is only marked seen, so
+    /// later checks queue just what is uploaded afterwards.
+    pub async fn add_subscription(
+        &self,
+        mut subscription: Subscription,
+        download_existing: bool,
+    ) -> Result<SyncReport> {
+        let playlist = self.expand_playlist(&subscription.url).await?;
+        if subscription.name == subscription.url {
+            subscription.name = playlist.title.clone();
+        }
+        self.db_manager
+            .save_subscription(&subscription.to_record())
+            .await?;
+        info!("Subscribed to {} ({})", subscription.name, subscription.url);
+        self.sync_subscription(&subscription, &playlist.entries, !download_existing)
+            .await
+    }
+
+    /// All subscriptions, oldest first.
+    pub async fn subscriptions(&self) -> Result<Vec<Subscription>> {
+        Ok(self
+            .db_manager
+            .get_all_subscriptions()
+            .await?
+            .into_iter()
+            .map(Subscription::from_record)
+            .collect())
+    }
+
+    /// Save changed settings (name, quality, folder, interval, enabled, ...).
+    pub async fn update_subscription(&self, subscription: &Subscription) -> Result<()> {
+        self.db_manager
+            .save_subscription(&subscription.to_record())
+            .await
+    }
+
+    /// Stop following a subscription and forget what it has seen. Files
+    /// already downloaded are kept.
+    pub async fn remove_subscription(&self, id: &str) -> Result<()> {
+        self.db_manager.delete_subscription(id).await
+    }
+
+    /// Every entry a subscription has seen, including removed ones.
+    pub async fn subscription_items(&self, id: &str) -> Result<Vec<SubscriptionItemRecord>> {
+        self.db_manager.get_subscription_items(id).await
+    }
+
+    /// List a subscription's source now and queue its new entries, whether
+    /// or not it is due.
+    pub async fn check_subscription(&self, id: &str) -> Result<SyncReport> {
+        let subscription = self
+            .db_manager
+            .get_subscription(id)
+            .await?
+            .map(Subscription::from_record)
+            .with_context(|| format!("No subscription with id {id}"))?;
+        let listed = self.extractor.extract_playlist(&subscription.url).await;
+        // A failed listing still counts as a check, so a broken source is
+        // retried on its interval rather than on every tick.
+        self.db_manager
+            .mark_subscription_checked(&subscription.id, Utc::now())
+            .await?;
+        let entries =
+            listed.with_context(|| format!("Failed to list subscription {}", subscription.name))?;
+        self.sync_subscription(&subscription, &entries, false).await
+    }
+
+    /// Check every enabled subscription whose interval has passed. Failures
+    /// are logged and published as `Error` events; the rest carry on.
+    pub async fn check_due_subscriptions(&self) -> Vec<SyncReport> {
+        let subscriptions = match self.subscriptions().await {
+            Ok(subscriptions) => subscriptions,
+            Err(e) => {
+                warn!("Failed to load subscriptions: {}", e);
+                return Vec::new();
+            }
+        };
+        let now = Utc::now();
+        let mut reports = Vec::new();
+        for subscription in subscriptions.iter().filter(|s| s.is_due(now)) {
+            match self.check_subscription(&subscription.id).await {
+                Ok(report) => reports.push(report),
+                Err(e) => {
+                    warn!("Subscription check failed: {:#}", e);
+                    self.events.send(BackendEvent::Error(format!("{e:#}")));
+                }
+            }
+        }
+        reports
+    }
+
+    /// Check due subscriptions every `tick`, forever. Spawn it next to
+    /// [`start`](Self::start) to poll in the background.
+    pub async fn watch_subscriptions(self: Arc<Self>, tick: std::time::Duration) {
+        let mut interval = tokio::time::interval(tick);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            self.check_due_subscriptions().await;
+        }
+    }
+
+    /// Apply one listing of a subscription: queue (or, for a baseline, just
+    /// record) new entries and, in mirror mode, track removals. An entry that
+    /// fails to queue is not recorded, so the next check retries it; one that
+    /// keeps failing to extract is recorded after [`MAX_ENTRY_ATTEMPTS`].
+    async fn sync_subscription(
+        &self,
+        subscription: &Subscription,
+        entries: &[VideoInfo],
+        baseline_only: bool,
+    ) -> Result<SyncReport> {
+        let seen = self
+            .db_manager
+            .get_subscription_items(&subscription.id)
+            .await?;
+        let plan = plan_sync(&seen, entries, subscription.mirror);
+        let now = Utc::now();
+        let mut report = SyncReport {
+            subscription_id: subscription.id.clone(),
+            name: subscription.name.clone(),
+            listed: entries.len(),
+            ..Default::default()
+        };
+
+        // Entries already downloaded some other way are only recorded; the
+        // rest are extracted together and queued in listing order.
+        let mut wanted = Vec::new();
+        let mut record = Vec::new();
+        for (index, entry) in plan.new {
+            if baseline_only || self.queue_manager.archived(&entry).await.is_some() {
+                report.skipped += 1;
+                record.push(entry);
+            } else {
+                wanted.push((index, entry));
+            }
+        }
+        let batch_id = Uuid::new_v4().to_string();
+        let mut extracted =
+            self.extract_entries(wanted.iter().map(|(_, entry)| entry.clone()).collect());
+        while let Some((i, result)) = extracted.recv().await {
+            let (index, entry) = &wanted[i];
+            let batch = TaskBatch {
+                id: batch_id.clone(),
+                name: subscription.name.clone(),
+                index: *index,
+            };
+            let result = match result {
+                Ok(info) => {
+                    self.enqueue_subscription_entry(subscription, info, batch)
+                        .await
+                }
+                Err(e) => {
+                    report.failed += 1;
+                    let attempts = self
+                        .db_manager
+                        .record_subscription_failure(&subscription.id, &entry_key(entry), &e, now)
+                        .await?;
+                    if attempts < MAX_ENTRY_ATTEMPTS {
+                        warn!(
+                            "Could not extract {} from {} (attempt {}): {}",
+                            entry.url, subscription.name, attempts, e
+                        );
+                        continue;
+                    }
+                    warn!(
+                        "Giving up on {} from {} after {} failed attempts: {}",
+                        entry.url, subscription.name, attempts, e
+                    );
+                    record.push(entry.clone());
+                    continue;
+                }
+            };
+            match result {
+                Ok(AddOutcome::Queued(_)) => report.queued += 1,
+                // Already downloaded some other way.
+                Ok(_) => report.skipped += 1,
+                Err(e) => {
+                    warn!(
+                        "Could not queue {} from {}: {}",
+                        entry.url, subscription.name, e
+                    );
+                    report.failed += 1;
+                    continue;
+                }
+            }
+            self.db_manager
+                .clear_subscription_failures(&subscription.id, &entry_key(entry))
+                .await?;
+            record.push(entry.clone());
+        }
+        for entry in record {
+            let item = SubscriptionItemRecord {
+                subscription_id: subscription.id.clone(),
+                video_id: entry_key(&entry),
+                url: entry.url.clone(),
+                title: entry.title.clone(),
+                first_seen: now,
+                removed_at: None,
+            };
+            self.db_manager.save_subscription_item(&item).await?;
+        }
+
+        if !plan.removed.is_empty() {
+            self.db_manager
+                .mark_subscription_items_removed(&subscription.id, &plan.removed, now)
+                .await?;
+            report.removed = plan.removed.len();
+        }
+        for mut item in plan.restored {
+            item.removed_at = None;
+            self.db_manager.save_subscription_item(&item).await?;
+            report.restored += 1;
+        }
+        self.db_manager
+            .mark_subscription_checked(&subscription.id, now)
+            .await?;
+
+        info!("Checked subscription {}", report);
+        self.events
+            .send(BackendEvent::SubscriptionChecked(report.clone()));
+        Ok(report)
+    }
+
+    /// Queue one new, extracted subscription entry with the subscription's
+    /// quality, profile and folder, unless it is already in the download
+    /// archive.
+    async fn enqueue_subscription_entry(
+        &self,
+        subscription: &Subscription,
+        info: VideoInfo,
+        batch: TaskBatch,
+    ) -> Result<AddOutcome> {
+        let sort = self.format_preferences.format_sort();
+        let picked = subscription.pick_format(&info, &sort);
+        // Provisional; the engine settles the extension from what it fetches.
+        let ext = match (&picked, subscription.profile) {
+            (Some(format), _) if !format.ext.is_empty() => format.ext.clone(),
+            (_, MediaProfile::Video) => "mp4".to_string(),
+            (_, MediaProfile::Audio) => "m4a".to_string(),
+        };
+        let format_id = picked.map(|_| subscription.format_selector().to_string());
+        let output_dir = subscription
+            .target_dir
+            .clone()
+            .unwrap_or_else(|| self.download_dir.clone());
+        let output_path = output_dir.join(format!(
+            "{}.{}",
+            crate::utils::sanitize_filename(&info.title),
+            ext
+        ));
+        self.enqueue_task(
+            info,
+            output_path,
+            format_id,
+            Some(batch),
+            subscription.target_dir.clone(),
+            ArchivePolicy::Skip,
+        )
+        .await
+    }
+
+    /// Attach a progress sink (a [`JsonLinesSink`] log, a [`WatchSink`] for an
+    /// API, ...) to one task. Sinks never slow the transfer down.
+    ///
+    /// [`JsonLinesSink`]: crate::downloader::JsonLinesSink
+    /// [`WatchSink`]: crate::downloader::WatchSink
+    pub fn observe(&self, task_id: &str, sink: SharedProgressSink) {
+        self.queue_manager.observe_task(task_id, sink);
+    }
+
+    /// Pause a queued or downloading task.
+    pub async fn pause(&self, task_id: &str) -> Result<()> {
+        self.queue_manager.pause_task(task_id).await
+    }
+
+    /// Resume a paused or failed task.
+    pub async fn resume(&self, task_id: &str) -> Result<()> {
+        self.queue_manager.resume_task(task_id).await
+    }
+
+    /// Cancel a task and discard its partial files.
+    pub async fn cancel(&self, task_id: &str) -> Result<()> {
+        self.queue_manager.cancel_task(task_id).await
+    }
+
+    /// Stop a live recording and keep what was recorded.
+    pub async fn stop_recording(&self, task_id: &str) -> Result<()> {
+        self.queue_manager.stop_recording(task_id).await
+    }
+
+    /// Remove a task from the queue.
+    pub async fn remove(&self, task_id: &str) -> Result<()> {
+        self.queue_manager.remove_task(task_id).await
+    }
+
+    /// Drop completed tasks from the live queue (history is kept).
+    pub async fn clear_completed(&self) -> Result<()> {
+        self.queue_manager.clear_completed().await
+    }
+
+    /// Resume every paused and failed task.
+    pub async fn resume_all(&self) -> Result<()> {
+        self.queue_manager.resume_all().await
+    }
+
+    /// Snapshot of the live queue.
+    pub async fn tasks(&self) -> Vec<DownloadTask> {
+        self.queue_manager.get_all_tasks().await
+    }
+
+    /// All persisted download history (all-time, including completed/failed/
+    /// cancelled downloads that may no longer be in the live queue),
+    /// most-recent-first.
+    pub async fn history(&self) -> Result<Vec<DownloadRecord>> {
+        self.db_manager.get_all_downloads().await
+    }
+
+    /// The thumbnail cache, keyed by task id.
+    pub fn thumbnails(&self) -> &Arc<ThumbnailCache> {
+        &self.thumbnails
+    }
+
+    /// The history database, for settings and other shared tables.
+    pub fn database(&self) -> &Arc<DatabaseManager> {
+        &self.db_manager
+    }
+
+    async fn download_url(&self, video_info: &VideoInfo, format: &Format) -> Result<String> {
+        // A video+audio pair has no single direct URL: yt-dlp fetches and
+        // merges both from the page.
+        if formats::is_merge(&format.format_id) {
+            return Ok(video_info.url.clone());
+        }
+        let direct_url = self
+            .extractor
+            .get_direct_url(&video_info.url, &format.format_id)
+            .await?;
+
+        // HLS Check
+        if direct_url.contains(".m3u8") || direct_url.contains("/manifest") {
+            Ok(video_info.url.clone())
+        } else {
+            Ok(direct_url)
+        }
+    }
+}
+
+/// Choose the format to download.
+///
+/// `format_id` is a yt-dlp format selector: an exact id, a `video+audio`
+/// pair or a full expression such as `bv*[height<=1080]+ba/b`, evaluated
+/// natively so the pick matches what yt-dlp would download. Without one,
+/// [`FormatSelector::native_default`] prefers the best single progressive
+/// (video+audio) format and falls back to the best available one — direct
+/// media files and DASH video/audio-split sources — rather than failing, so
+/// the engine/yt-dlp path can still fetch it. (Returning an error here is what
+/// previously stopped the GUI from ever starting a download.)
+///
+/// `preferences` sort the candidates; without an expression their selector
+/// (codecs, audio languages) is tried before the default.
+pub(crate) fn select_format(
+    video_info: &VideoInfo,
+    format_id: Option<String>,
+    preferences: &FormatPreferences,
+) -> Result<Format, String> {
+    let sort = preferences.format_sort();
+    match format_id {
+        Some(expression) => {
+            let selector: FormatSelector = expression.parse()?;
+            let unknown = selector.unknown_ids(&video_info.formats);
+            if !unknown.is_empty() {
+                warn!(
+                    "Format {} names id(s) {} that {} does not list",
+                    expression,
+                    unknown.join(", "),
+                    video_info.url
+                );
+            }
+            selector
+                .pick(&video_info.formats, &sort)
+                .ok_or_else(|| format!("Format not found: {expression}"))
+        }
+        None => preferences
+            .selector()
+            .and_then(|selector| selector.pick(&video_info.formats, &sort))
+            .or_else(|| {
+                preferences
+                    .native_fallback()
+                    .pick(&video_info.formats, &sort)
+            })
+            .or_else(|| video_info.formats.first().cloned())
+            .ok_or_else(|| "No downloadable format found".to_string()),
+    }
+}
+
+/// Poll the queue, publish status events and persist status transitions to
+/// the `downloads` table. Progress is pushed separately by `progress_loop`.
+async fn monitor_loop(qm: Arc<QueueManager>, events: EventBus, db_manager: Arc<DatabaseManager>) {
+    let mut last_statuses = std::collections::HashMap::new();
+
+    loop {
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+        let tasks = qm.get_all_tasks().await;
+
+        for task in tasks {
+            // Check status change
+            let last_status = last_statuses
+                .get(&task.id)
+                .cloned()
+                .unwrap_or(TaskStatus::Queued);
+
+            if task.status != last_status {
+                let (status_str, completed_at, error_message) = task_status_db_fields(&task.status);
+
+                events.send(BackendEvent::TaskStatusUpdated {
+                    task_id: task.id.clone(),
+                    status: status_str.clone(),
+                });
+
+                if matches!(task.status, TaskStatus::Completed) {
+                    events.send(BackendEvent::DownloadCompleted {
+                        task_id: task.id.clone(),
+                        file_path: Some(task.output_path.to_string_lossy().to_string()),
+                    });
+                }
+                if let TaskStatus::Failed(e) = &task.status {
+                    events.send(BackendEvent::DownloadFailed {
+                        task_id: task.id.clone(),
+                        error: e.clone(),
+                    });
+                }
+
+                // Persist the transition to the `downloads` history table.
+                // Best-effort: the live queue (via `last_statuses`/`qm`)
+                // remains the runtime authority for this task's state
+                // regardless of whether this write succeeds — a failure
+                // here must never affect the download itself.
+                let record = DownloadRecord {
+                    id: task.id.clone(),
+                    url: task.video_info.url.clone(),
+                    title: task.video_info.title.clone(),
+                    output_path: task.output_path.clone(),
+                    file_size: task.format.filesize,
+                    status: status_str,
+                    created_at: task.added_at,
+                    completed_at,
+                    error_message,
+                };
+                if let Err(e) = db_manager.save_download(&record).await {
+                    warn!(
+                        "Failed to persist download history update for {}: {}",
+                        task.id, e
+                    );
+                }
+
+                last_statuses.insert(task.id.clone(), task.status.clone());
+            }
+        }
+    }
+}
+
+/// Publish engine progress as `DownloadProgress` events the moment it is
+/// reported — no polling, and no back-pressure on the transfer: if this
+/// falls behind, the queue's broadcast makes it skip ahead.
+async fn progress_loop(
+    qm: Arc<QueueManager>,
+    mut progress: broadcast::Receiver<TaskProgress>,
+    events: EventBus,
+) {
+    loop {
+        let update = match progress.recv().await {
+            Ok(update) => update,
+            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => break,
+        };
+
+        // Send Progress only while the task is actually active — never for
+        // terminal states (Completed/Failed/Cancelled), otherwise a late
+        // progress event would flip a finished row back to "Downloading" in
+        // the GUI.
+        let active = matches!(
+            qm.task_status(&update.task_id).await,
+            Some(
+                TaskStatus::Downloading
+                    | TaskStatus::Queued
+                    | TaskStatus::Waiting
+                    | TaskStatus::Paused
+            )
+        );
+        if !active {
+            continue;
+        }
+
+        let p = &update.progress;
+        let data = DownloadProgressData {
+            progress: p.percentage() as f32,
+            speed: p.speed,
+            downloaded: p.downloaded_bytes,
+            total: p.total_bytes,
+            eta: p.eta.map(|d| d.as_secs()),
+            live: match p.status {
+                DownloadStatus::Waiting => Some(LiveProgress::Waiting {
+                    starts_at: p.starts_at,
+                }),
+                DownloadStatus::Recording => Some(LiveProgress::Recording {
+                    elapsed: p.elapsed.map_or(0, |d| d.as_secs()),
+                }),
+                _ => None,
+            },
+        };
+        events.send(BackendEvent::DownloadProgress {
+            task_id: update.task_id,
+            data,
+        });
+    }
+}
+
+/// Stand-in fallback when yt-dlp is not installed: native extractors keep
+/// working, and anything that needs yt-dlp fails with `YtDlpNotFound`.
+struct MissingYtDlp;
+
+#[async_trait::async_trait]
+impl Extractor for MissingYtDlp {
+    fn id(&self) -> &str {
+        "ytdlp-missing"
+    }
+
+    fn supports(&self, _url: &str) -> bool {
+        true
+    }
+
+    async fn extract_info(&self, _url: &str) -> Result<VideoInfo> {
+        Err(RustloaderError::YtDlpNotFound.into())
+    }
+
+    async fn get_direct_url(&self, _url: &str, _format_id: &str) -> Result<String> {
+        Err(RustloaderError::YtDlpNotFound.into())
+    }
+
+    async fn search(&self, _query: &SearchQuery) -> Result<SearchPage> {
+        Err(RustloaderError::YtDlpNotFound.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+
+    /// Fallback that answers every URL with a fixed single-format video and
+    /// never touches the network.
+    struct StubExtractor;
+
+    #[async_trait]
+    impl Extractor for StubExtractor {
+        fn id(&self) -> &str {
+            "stub"
+        }
+
+        fn supports(&self, _url: &str) -> bool {
+            true
+        }
+
+        async fn extract_info(&self, url: &str) -> Result<VideoInfo> {
+            Ok(info_at(url))
+        }
+
+        async fn get_direct_url(&self, _url: &str, _format_id: &str) -> Result<String> {
+            Ok("http://127.0.0.1:9/clip.mp4".to_string())
+        }
+    }
+
+    fn info_at(url: &str) -> VideoInfo {
+        VideoInfo {
+            id: "clip".to_string(),
+            title: "Clip".to_string(),
+            url: url.to_string(),
+            formats: vec![Format {
+                format_id: "0".to_string(),
+                url: "http://127.0.0.1:9/clip.mp4".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    async fn stub_backend(dir: &Path) -> Rustloader {
+        Rustloader::builder()
+            .data_dir(dir.join("data"))
+            .library_dir(dir.join("library"))
+            .fallback_extractor(Arc::new(StubExtractor))
+            .build()
+            .await
+            .expect("facade builds without yt-dlp when a fallback is given")
+    }
+
+    #[tokio::test]
+    async fn builder_wires_a_working_backend_from_explicit_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let rl = stub_backend(dir.path()).await;
+
+        assert!(dir.path().join("data").join("rustloader.db").exists());
+        assert!(dir.path().join("library").exists());
+
+        let info = rl.extract("https://example.com/watch/clip").await.unwrap();
+        assert_eq!(info.title, "Clip");
+    }
+
+    /// Fallback that counts how often it actually extracts.
+    struct CountingExtractor(std::sync::atomic::AtomicUsize);
+
+    #[async_trait]
+    impl Extractor for CountingExtractor {
+        fn id(&self) -> &str {
+            "counting"
+        }
+
+        fn supports(&self, _url: &str) -> bool {
+            true
+        }
+
+        async fn extract_info(&self, url: &str) -> Result<VideoInfo> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Ok(info_at(url))
+        }
+
+        async fn get_direct_url(&self, _url: &str, _format_id: &str) -> Result<String> {
+            Ok("http://127.0.0.1:9/clip.mp4".to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn extraction_results_are_cached_until_refreshed() {
+        let dir = tempfile::tempdir().unwrap();
+        let counter = Arc::new(CountingExtractor(Default::default()));
+        let rl = Rustloader::builder()
+            .data_dir(dir.path().join("data"))
+            .library_dir(dir.path().join("library"))
+            .fallback_extractor(counter.clone())
+            .build()
+            .await
+            .unwrap();
+        let calls = || counter.0.load(Ordering::SeqCst);
+
+        rl.extract("https://example.com/watch/clip").await.unwrap();
+        rl.extract("https://example.com/watch/clip/?utm_source=feed#t=1")
+            .await
+            .unwrap();
+        assert_eq!(calls(), 1, "second extract is served from the cache");
+
+        rl.refresh("https://example.com/watch/clip").await.unwrap();
+        assert_eq!(calls(), 2, "refresh always re-extracts");
+
+        let uncached = Rustloader::builder()
+            .data_dir(dir.path().join("data2"))
+            .library_dir(dir.path().join("library"))
+            .fallback_extractor(counter.clone())
+            .extraction_cache(false)
+            .build()
+            .await
+            .unwrap();
+        uncached
+            .extract("https://example.com/watch/clip")
+            .await
+            .unwrap();
+        uncached
+            .extract("https://example.com/watch/clip")
+            .await
+            .unwrap();
+        assert_eq!(calls(), 4);
+    }
+
+    /// Fallback that never finishes extracting URLs containing `slow`.
+    struct SlowExtractor;
+
+    #[async_trait]
+    impl Extractor for SlowExtractor {
+        fn id(&self) -> &str {
+            "slow"
+        }
+
+        fn supports(&self, _url: &str) -> bool {
+            true
+        }
+
+        async fn extract_info(&self, url: &str) -> Result<VideoInfo> {
+            if url.contains("slow") {
+                std::future::pending::<()>().await;
+            }
+            Ok(info_at(url))
+        }
+
+        async fn get_direct_url(&self, _url: &str, _format_id: &str) -> Result<String> {
+            Ok("http://127.0.0.1:9/clip.mp4".to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn extraction_jobs_are_limited_tagged_and_cancellable() {
+        let dir = tempfile::tempdir().unwrap();
+        let rl = Rustloader::builder()
+            .data_dir(dir.path().join("data"))
+            .library_dir(dir.path().join("library"))
+            .fallback_extractor(Arc::new(SlowExtractor))
+            .build()
+            .await
+            .unwrap();
+        let mut events = rl.subscribe();
+
+        for i in 0..MAX_CONCURRENT_EXTRACTIONS {
+            rl.start_extraction(
+                format!("slow{i}"),
+                format!("https://example.com/slow/{i}"),
+                false,
+            );
+        }
+        rl.start_extraction("fast".into(), "https://example.com/fast".into(), false);
+
+        // Every slot is taken, so the last job waits.
+        let mut queued = false;
+        while !queued {
+            match events.recv().await.unwrap() {
+                BackendEvent::ExtractionProgress { id, stage } => {
+                    queued = id == "fast" && stage == ExtractionStage::Queued;
+                }
+                BackendEvent::ExtractionCompleted { id, .. } => panic!("{id} completed"),
+                _ => {}
+            }
+        }
+
+        assert!(rl.cancel_extraction("slow0"));
+        assert!(!rl.cancel_extraction("slow0"), "already cancelled");
+        let mut cancelled = false;
+        loop {
+            match events.recv().await.unwrap() {
+                BackendEvent::ExtractionCancelled { id } => {
+                    assert_eq!(id, "slow0");
+                    cancelled = true;
+                }
+                BackendEvent::ExtractionCompleted { id, result } => {
+                    assert_eq!(id, "fast");
+                    assert_eq!(result.unwrap().url, "https://example.com/fast");
+                    break;
+                }
+                _ => {}
+            }
+        }
+        assert!(cancelled);
+        assert!(!rl.cancel_extraction("fast"), "finished jobs are forgotten");
+    }
+
+    #[tokio::test]
+    async fn enqueue_queues_records_history_and_publishes_started() {
+        let dir = tempfile::tempdir().unwrap();
+        let rl = stub_backend(dir.path()).await;
+        let mut events = rl.subscribe();
+
+        let info = rl.extract("https://example.com/watch/clip").await.unwrap();
+        let task_id = rl
+            .enqueue(info, dir.path().join("clip.mp4"), None)
+            .await
+            .unwrap();
+
+        let tasks = rl.tasks().await;
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].id, task_id);
+        assert_eq!(tasks[0].format.url, "http://127.0.0.1:9/clip.mp4");
+
+        let history = rl.history().await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].status, "Queued");
+
+        match events.recv().await.unwrap() {
+            BackendEvent::DownloadStarted { task_id: id, .. } => assert_eq!(id, task_id),
+            other => panic!("expected DownloadStarted, got {other:?}"),
+        }
+    }
+
+    /// Lists a three-entry flat playlist; the second entry cannot be
+    /// extracted.
+    struct PlaylistStub;
+
+    #[async_trait]
+    impl Extractor for PlaylistStub {
+        fn id(&self) -> &str {
+            "playlist-stub"
+        }
+
+        fn supports(&self, _url: &str) -> bool {
+            true
+        }
+
+        async fn extract_info(&self, url: &str) -> Result<VideoInfo> {
+            if url.ends_with("/2") {
+                return Err(RustloaderError::ExtractionError("private video".into()).into());
+            }
+            Ok(info_at(url))
+        }
+
+        async fn extract_playlist(&self, _url: &str) -> Result<Vec<VideoInfo>> {
+            Ok((1..=3)
+                .map(|i| VideoInfo {
+                    title: format!("Entry {i}"),
+                    url: format!("https://example.com/watch/{i}"),
+                    playlist_title: Some("Mix".to_string()),
+                    ..Default::default()
+                })
+                .collect())
+        }
+
+        async fn get_direct_url(&self, _url: &str, _format_id: &str) -> Result<String> {
+            Ok("http://127.0.0.1:9/clip.mp4".to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn playlist_entries_queue_as_one_batch_skipping_failures() {
+        let dir = tempfile::tempdir().unwrap();
+        let rl = Rustloader::builder()
+            .data_dir(dir.path().join("data"))
+            .library_dir(dir.path().join("library"))
+            .fallback_extractor(Arc::new(PlaylistStub))
+            .build()
+            .await
+            .unwrap();
+        let mut events = rl.subscribe();
+
+        let playlist = rl
+            .expand_playlist("https://example.com/playlist")
+            .await
+            .unwrap();
+        assert_eq!(playlist.title, "Mix");
+        assert_eq!(playlist.entries.len(), 3);
+
+        let task_ids = rl
+            .enqueue_playlist(&playlist, &[1, 2, 3], dir.path(), ArchivePolicy::Skip)
+            .await
+            .unwrap();
+        assert_eq!(task_ids.len(), 2, "the unextractable entry is skipped");
+
+        let tasks = rl.tasks().await;
+        let batches: Vec<_> = tasks.iter().filter_map(|t| t.batch.clone()).collect();
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].id, batches[1].id);
+        assert_eq!(batches.iter().map(|b| b.index).collect::<Vec<_>>(), [1, 3]);
+        assert!(batches.iter().all(|b| b.name == "Mix"));
+
+        let mut skipped = false;
+        while let Some(event) = events.try_recv() {
+            match event {
+                BackendEvent::DownloadStarted { batch, .. } => assert!(batch.is_some()),
+                BackendEvent::Error(e) => skipped |= e.contains("Entry 2"),
+                _ => {}
+            }
+        }
+        assert!(skipped, "the skipped entry is reported");
+
+        let err = rl
+            .enqueue_playlist(&playlist, &[2], dir.path(), ArchivePolicy::Skip)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Mix"));
+    }
+
+    #[tokio::test]
+    async fn pasted_urls_queue_in_order_as_one_batch() {
+        let dir = tempfile::tempdir().unwrap();
+        let rl = Rustloader::builder()
+            .data_dir(dir.path().join("data"))
+            .library_dir(dir.path().join("library"))
+            .fallback_extractor(Arc::new(PlaylistStub))
+            .build()
+            .await
+            .unwrap();
+
+        let urls: Vec<String> = (1..=3)
+            .map(|i| format!("https://example.com/watch/{i}"))
+            .collect();
+        let task_ids = rl.enqueue_urls(&urls, dir.path()).await.unwrap();
+        assert_eq!(task_ids.len(), 2);
+
+        let tasks = rl.tasks().await;
+        let batches: Vec<_> = tasks.iter().filter_map(|t| t.batch.clone()).collect();
+        assert_eq!(batches.iter().map(|b| b.index).collect::<Vec<_>>(), [1, 3]);
+        assert!(batches.iter().all(|b| b.name == "3 imported URLs"));
+    }
+
+    /// A channel listing whatever entry numbers `listed` holds.
+    struct ChannelStub {
+        listed: std::sync::Mutex<Vec<u32>>,
+        /// Entries that never extract, like a private video.
+        unavailable: Vec<u32>,
+    }
+
+    #[async_trait]
+    impl Extractor for ChannelStub {
+        fn id(&self) -> &str {
+            "channel-stub"
+        }
+
+        fn supports(&self, _url: &str) -> bool {
+            true
+        }
+
+        async fn extract_info(&self, url: &str) -> Result<VideoInfo> {
+            if self
+                .unavailable
+                .iter()
+                .any(|i| url.ends_with(&format!("/watch/{i}")))
+            {
+                return Err(RustloaderError::ExtractionError("Private video".into()).into());
+            }
+            Ok(info_at(url))
+        }
+
+        async fn extract_playlist(&self, _url: &str) -> Result<Vec<VideoInfo>> {
+            Ok(self
+                .listed
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|i| VideoInfo {
+                    id: format!("v{i}"),
+                    title: format!("Upload {i}"),
+                    url: format!("https://example.com/watch/{i}"),
+                    playlist_title: Some("Channel".to_string()),
+                    ..Default::default()
+                })
+                .collect())
+        }
+
+        async fn get_direct_url(&self, _url: &str, _format_id: &str) -> Result<String> {
+            Ok("http://127.0.0.1:9/clip.mp4".to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn subscriptions_queue_only_new_items_and_mirror_removals() {
+        let dir = tempfile::tempdir().unwrap();
+        let stub = Arc::new(ChannelStub {
+            listed: std::sync::Mutex::new(vec![1, 2]),
+            unavailable: Vec::new(),
+        });
+        let rl = Rustloader::builder()
+            .data_dir(dir.path().join("data"))
+            .library_dir(dir.path().join("library"))
+            .fallback_extractor(stub.clone())
+            .build()
+            .await
+            .unwrap();
+        let set_listed = |ids: &[u32]| *stub.listed.lock().unwrap() = ids.to_vec();
+
+        let subscription = Subscription::new("https://example.com/@channel")
+            .with_mirror(true)
+            .with_target_dir(dir.path().join("subs"));
+        let report = rl.add_subscription(subscription, false).await.unwrap();
+        assert_eq!((report.skipped, report.queued), (2, 0));
+        assert!(
+            rl.tasks().await.is_empty(),
+            "existing uploads are only marked seen"
+        );
+        let subscription = rl.subscriptions().await.unwrap().remove(0);
+        assert_eq!(subscription.name, "Channel");
+        assert!(subscription.last_checked.is_some());
+
+        set_listed(&[2, 3]);
+        let report = rl.check_subscription(&subscription.id).await.unwrap();
+        assert_eq!((report.queued, report.removed), (1, 1));
+        let tasks = rl.tasks().await;
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].video_info.url, "https://example.com/watch/3");
+        assert_eq!(tasks[0].target_dir, Some(dir.path().join("subs")));
+        assert_eq!(tasks[0].batch.as_ref().unwrap().name, "Channel");
+
+        set_listed(&[1, 2, 3]);
+        let report = rl.check_subscription(&subscription.id).await.unwrap();
+        assert_eq!((report.queued, report.restored), (0, 1));
+        let items = rl.subscription_items(&subscription.id).await.unwrap();
+        assert_eq!(items.len(), 3);
+        assert!(items.iter().all(|i| i.removed_at.is_none()));
+
+        rl.remove_subscription(&subscription.id).await.unwrap();
+        assert!(rl.subscriptions().await.unwrap().is_empty());
+        assert!(rl
+            .subscription_items(&subscription.id)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(rl.check_subscription(&subscription.id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn subscription_entries_that_never_extract_are_given_up_on() {
+        let dir = tempfile::tempdir().unwrap();
+        let stub = Arc::new(ChannelStub {
+            listed: std::sync::Mutex::new(vec![1]),
+            unavailable: vec![2],
+        });
+        let rl = Rustloader::builder()
+            .data_dir(dir.path().join("data"))
+            .library_dir(dir.path().join("library"))
+            .fallback_extractor(stub.clone())
+            .build()
+            .await
+            .unwrap();
+        let subscription = Subscription::new("https://example.com/@channel");
+        rl.add_subscription(subscription, false).await.unwrap();
+        let id = rl.subscriptions().await.unwrap().remove(0).id;
+
+        *stub.listed.lock().unwrap() = vec![1, 2];
+        for _ in 0..MAX_ENTRY_ATTEMPTS {
+            let report = rl.check_subscription(&id).await.unwrap();
+            assert_eq!((report.queued, report.failed), (0, 1));
+        }
+        // Recorded as seen after the last attempt: no more retries.
+        let report = rl.check_subscription(&id).await.unwrap();
+        assert_eq!((report.queued, report.failed), (0, 0));
+        assert_eq!(rl.subscription_items(&id).await.unwrap().len(), 2);
+        assert!(rl.tasks().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn archived_videos_are_skipped_asked_about_or_forced() {
+        let dir = tempfile::tempdir().unwrap();
+        let rl = stub_backend(dir.path()).await;
+        let info = rl.extract("https://example.com/watch/clip").await.unwrap();
+        assert!(rl.archive().record(&info).await.unwrap());
+
+        let err = rl
+            .enqueue(info.clone(), dir.path().join("clip.mp4"), None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Already downloaded"));
+        let asked = rl
+            .enqueue_with(
+                info.clone(),
+                dir.path().join("clip.mp4"),
+                None,
+                ArchivePolicy::Ask,
+            )
+            .await
+            .unwrap();
+        assert!(matches!(asked, AddOutcome::NeedsConfirmation(_)));
+        assert!(rl.tasks().await.is_empty());
+
+        let forced = rl
+            .enqueue_with(
+                info,
+                dir.path().join("clip.mp4"),
+                None,
+                ArchivePolicy::Force,
+            )
+            .await
+            .unwrap();
+        assert!(matches!(forced, AddOutcome::Queued(_)));
+        assert_eq!(rl.tasks().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn playlists_mark_and_leave_out_archived_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let rl = Rustloader::builder()
+            .data_dir(dir.path().join("data"))
+            .library_dir(dir.path().join("library"))
+            .fallback_extractor(Arc::new(PlaylistStub))
+            .build()
+            .await
+            .unwrap();
+        let playlist = rl
+            .expand_playlist("https://example.com/playlist")
+            .await
+            .unwrap();
+        assert!(playlist.archived.is_empty());
+
+        // Flat entries have no ID; the extracted video does.
+        rl.archive()
+            .record(&rl.extract("https://example.com/watch/1").await.unwrap())
+            .await
+            .unwrap();
+        let task_ids = rl
+            .enqueue_playlist(&playlist, &[1], dir.path(), ArchivePolicy::Skip)
+            .await
+            .unwrap();
+        assert!(
+            task_ids.is_empty(),
+            "all selected entries were downloaded before"
+        );
+        let task_ids = rl
+            .enqueue_playlist(&playlist, &[1], dir.path(), ArchivePolicy::Force)
+            .await
+            .unwrap();
+        assert_eq!(task_ids.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn library_metadata_is_backfilled_into_the_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let library = dir.path().join("library");
+        let metadata = crate::utils::VideoMetadata {
+            video_id: "dQw4w9WgXcQ".to_string(),
+            title: "Old download".to_string(),
+            url: "https://www.youtube.com/watch?v=dQw4w9WgXcQ".to_string(),
+            source_platform: "YouTube".to_string(),
+            duration: None,
+            resolution: "1080p".to_string(),
+            format: "mp4".to_string(),
+            file_size: 0,
+            download_date: Utc::now(),
+            channel: None,
+            uploader: None,
+            description: None,
+            thumbnail_url: None,
+            quality_tier: "FullHD".to_string(),
+            content_type: "Video".to_string(),
+            tags: Vec::new(),
+            favorite: false,
+            watch_count: 0,
+            last_accessed: Utc::now(),
+        };
+        MetadataManager::new(&library)
+            .save_metadata(&metadata.video_id, &metadata)
+            .await
+            .unwrap();
+
+        let rl = Rustloader::builder()
+            .data_dir(dir.path().join("data"))
+            .library_dir(&library)
+            .fallback_extractor(Arc::new(StubExtractor))
+            .build()
+            .await
+            .unwrap();
+        let flat_entry = VideoInfo {
+            id: "dQw4w9WgXcQ".to_string(),
+            url: metadata.url.clone(),
+            ..Default::default()
+        };
+        let record = rl.archive().lookup(&flat_entry).await.unwrap().unwrap();
+        assert_eq!(record.title, "Old download");
+    }
+
+    #[tokio::test]
+    async fn enqueue_with_unknown_format_id_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let rl = stub_backend(dir.path()).await;
+
+        let info = rl.extract("https://example.com/watch/clip").await.unwrap();
+        let err = rl
+            .enqueue(info, dir.path().join("clip.mp4"), Some("nope".to_string()))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Format not found"));
+        assert!(rl.tasks().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn picked_formats_are_carried_to_the_task() {
+        let dir = tempfile::tempdir().unwrap();
+        let rl = stub_backend(dir.path()).await;
+
+        let split = |url: &str| {
+            let mut info = info_at(url);
+            info.formats
+                .push(fmt("v", Some("avc1"), Some("none"), 1920, 1080));
+            info.formats
+                .push(fmt("a", Some("none"), Some("mp4a"), 0, 0));
+            info
+        };
+        let page = "https://example.com/watch/merged";
+        rl.enqueue(split(page), dir.path().join("m.mp4"), Some("v+a".into()))
+            .await
+            .unwrap();
+        rl.enqueue(
+            split("https://example.com/watch/single"),
+            dir.path().join("s.mp4"),
+            Some("a".into()),
+        )
+        .await
+        .unwrap();
+        rl.enqueue(
+            info_at("https://example.com/watch/auto"),
+            dir.path().join("x.mp4"),
+            None,
+        )
+        .await
+        .unwrap();
+
+        let tasks = rl.tasks().await;
+        let task = |url: &str| {
+            tasks
+                .iter()
+                .find(|t| t.video_info.url == url)
+                .expect("task queued")
+        };
+        // A merge has no direct URL: yt-dlp gets the page and `-f v+a`.
+        let merged = task(page);
+        assert_eq!(merged.requested_format.as_deref(), Some("v+a"));
+        assert_eq!(merged.format.url, page);
+        assert_eq!(merged.format.acodec.as_deref(), Some("mp4a"));
+        // A single pick resolves to its direct URL for the native engine.
+        let single = task("https://example.com/watch/single");
+        assert_eq!(single.requested_format.as_deref(), Some("a"));
+        assert_eq!(single.format.url, "http://127.0.0.1:9/clip.mp4");
+        assert_eq!(
+            task("https://example.com/watch/auto").requested_format,
+            None
+        );
+    }
+
+    #[test]
+    fn task_status_db_fields_sets_completed_at_only_for_terminal_states() {
+        let (status, completed_at, error) = task_status_db_fields(&TaskStatus::Queued);
+        assert_eq!(status, "Queued");
+        assert!(completed_at.is_none());
+        assert!(error.is_none());
+
+        let (status, completed_at, error) = task_status_db_fields(&TaskStatus::Downloading);
+        assert_eq!(status, "Downloading");
+        assert!(completed_at.is_none());
+        assert!(error.is_none());
+
+        let (status, completed_at, error) = task_status_db_fields(&TaskStatus::Paused);
+        assert_eq!(status, "Paused");
+        assert!(completed_at.is_none());
+        assert!(error.is_none());
+
+        let (status, completed_at, error) = task_status_db_fields(&TaskStatus::Completed);
+        assert_eq!(status, "Completed");
+        assert!(completed_at.is_some());
+        assert!(error.is_none());
+
+        let (status, completed_at, error) = task_status_db_fields(&TaskStatus::Cancelled);
+        assert_eq!(status, "Cancelled");
+        assert!(completed_at.is_some());
+        assert!(error.is_none());
+    }
+
+    #[test]
+    fn task_status_db_fields_carries_the_failure_message() {
+        let (status, completed_at, error) =
+            task_status_db_fields(&TaskStatus::Failed("boom".to_string()));
+        assert_eq!(status, "Failed");
+        assert!(completed_at.is_some());
+        assert_eq!(error.as_deref(), Some("boom"));
+    }
+
+    fn fmt(id: &str, vcodec: Option<&str>, acodec: Option<&str>, w: u32, h: u32) -> Format {
+        Format {
+            format_id: id.to_string(),
+            vcodec: vcodec.map(str::to_string),
+            acodec: acodec.map(str::to_string),
+            width: Some(w),
+            height: Some(h),
+            url: format!("https://example.com/{id}"),
+            ..Default::default()
+        }
+    }
+
+    fn info(formats: Vec<Format>) -> VideoInfo {
+        VideoInfo {
+            title: "t".to_string(),
+            url: "https://example.com/page".to_string(),
+            formats,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn explicit_format_id_is_returned() {
+        let vi = info(vec![fmt("18", Some("h264"), Some("aac"), 640, 360)]);
+        let f = select_format(&vi, Some("18".to_string()), &FormatPreferences::default()).unwrap();
+        assert_eq!(f.format_id, "18");
+    }
+
+    #[test]
+    fn prefers_best_progressive_format() {
+        let vi = info(vec![
+            fmt("low", Some("h264"), Some("aac"), 640, 360),
+            fmt("high", Some("h264"), Some("aac"), 1920, 1080),
+            fmt("videoonly", Some("vp9"), Some("none"), 3840, 2160),
+        ]);
+        let f = select_format(&vi, None, &FormatPreferences::default()).unwrap();
+        assert_eq!(f.format_id, "high"); // best *progressive*, not the 4k video-only
+    }
+
+    #[test]
+    fn direct_file_single_format_no_codecs_now_selectable() {
+        // A direct .mp4 yields one format with no codec info — previously this
+        // returned Err("No combined format found") and the GUI never downloaded.
+        let vi = info(vec![Format {
+            format_id: "0".to_string(),
+            url: "https://example.com/video.mp4".to_string(),
+            ..Default::default()
+        }]);
+        let f = select_format(&vi, None, &FormatPreferences::default())
+            .expect("must pick a format, not error");
+        assert_eq!(f.format_id, "0");
+    }
+
+    #[test]
+    fn dash_split_falls_back_to_best_available() {
+        // No single progressive format (video-only + audio-only). Must fall back
+        // rather than fail.
+        let vi = info(vec![
+            fmt("video", Some("vp9"), Some("none"), 1920, 1080),
+            fmt("audio", Some("none"), Some("mp4a"), 0, 0),
+        ]);
+        let f = select_format(&vi, None, &FormatPreferences::default())
+            .expect("must fall back, not error");
+        assert_eq!(f.format_id, "video"); // best by resolution
+    }
+
+    #[test]
+    fn preferences_steer_the_native_choice() {
+        let vi = info(vec![
+            fmt("low", Some("avc1.42001E"), Some("mp4a.40.2"), 640, 360),
+            fmt("high", Some("avc1.64001F"), Some("mp4a.40.2"), 1280, 720),
+            fmt("vp9", Some("vp9"), Some("none"), 1920, 1080),
+            fmt("av1", Some("av01.0.08M.08"), Some("none"), 1920, 1080),
+            fmt("audio", Some("none"), Some("opus"), 0, 0),
+        ]);
+        let capped = FormatPreferences::default().with_max_height(480);
+        let f = select_format(&vi, None, &capped).unwrap();
+        assert_eq!(f.format_id, "low");
+
+        let no_av1 = FormatPreferences::default().with_avoid_codecs(vec!["av1".to_string()]);
+        let f = select_format(&vi, None, &no_av1).unwrap();
+        assert_eq!(f.format_id, "vp9+audio");
+
+        // Without audio to merge the codec selector finds nothing; the
+        // fallback still skips AV1 rather than taking the best progressive.
+        let video_only = info(vec![
+            fmt("av1", Some("av01.0.08M.08"), Some("mp4a.40.2"), 1920, 1080),
+            fmt("vp9", Some("vp9"), Some("none"), 1280, 720),
+        ]);
+        let f = select_format(&video_only, None, &no_av1).unwrap();
+        assert_eq!(f.format_id, "vp9");
+        let f = select_format(&video_only, None, &FormatPreferences::default()).unwrap();
+        assert_eq!(f.format_id, "av1");
+    }
+
+    #[test]
+    fn empty_formats_is_an_error() {
+        let vi = info(vec![]);
+        assert!(select_format(&vi, None, &FormatPreferences::default()).is_err());
+    }
+}
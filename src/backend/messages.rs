@@ -1,26 +1,77 @@
-use crate::extractor::VideoInfo;
+use super::subscriptions::{Subscription, SyncReport};
+use crate::database::ArchiveRecord;
+use crate::extractor::{
+    ExtractionStage, ExtractorStats, Playlist, SearchPage, SearchQuery, VideoInfo,
+};
 use crate::gui::DownloadProgressData;
+use crate::queue::{ArchivePolicy, TaskBatch};
 use std::path::PathBuf;
 
 /// Commands sent from GUI to Backend
 #[derive(Debug, Clone)]
 pub enum BackendCommand {
+    /// Extract `url` as job `id`, alongside any other running extractions;
+    /// answered with `ExtractionProgress` and `ExtractionCompleted` events
+    /// carrying the same `id`.
     ExtractInfo {
+        id: String,
         url: String,
     },
+    /// Like `ExtractInfo`, but ignores any cached result.
+    RefreshInfo {
+        id: String,
+        url: String,
+    },
+    /// Stop extraction job `id`; answered with `ExtractionCancelled`.
+    CancelExtraction(String),
+    Search {
+        query: SearchQuery,
+    },
+    /// List a playlist's entries; answered with `PlaylistExpanded`.
+    ExpandPlaylist {
+        url: String,
+    },
+    /// Queue the entries at these 1-based indices as one batch.
+    EnqueuePlaylist {
+        playlist: Box<Playlist>,
+        indices: Vec<usize>,
+        output_dir: PathBuf,
+        archive: ArchivePolicy,
+    },
+    /// Queue several URLs at once (a pasted list) as one batch.
+    EnqueueUrls {
+        urls: Vec<String>,
+        output_dir: PathBuf,
+    },
     StartDownload {
         // Boxed: VideoInfo is large; boxing keeps the enum variants similar in
         // size (clippy::large_enum_variant).
         video_info: Box<VideoInfo>,
         output_path: PathBuf,
         format_id: Option<String>,
+        /// `Ask` answers an archived video with `AlreadyDownloaded`.
+        archive: ArchivePolicy,
     },
     PauseDownload(String),
     ResumeDownload(String),
     CancelDownload(String),
+    /// End a live recording, keeping what was recorded.
+    StopRecording(String),
     RemoveTask(String),
     ClearCompleted,
     ResumeAll,
+    /// Ask for an `ExtractorDiagnostics` event.
+    RequestDiagnostics,
+    // Subscriptions (each change is answered with `SubscriptionsLoaded`)
+    ListSubscriptions,
+    AddSubscription {
+        subscription: Box<Subscription>,
+        /// Queue everything listed now, not just later uploads.
+        download_existing: bool,
+    },
+    UpdateSubscription(Box<Subscription>),
+    RemoveSubscription(String),
+    CheckSubscription(String),
     // System
     Shutdown,
 }
@@ -29,18 +80,53 @@ pub enum BackendCommand {
 #[derive(Debug, Clone)]
 pub enum BackendEvent {
     // Extraction
-    ExtractionStarted,
-    ExtractionCompleted(Result<VideoInfo, String>),
+    ExtractionProgress {
+        id: String,
+        stage: ExtractionStage,
+    },
+    ExtractionCompleted {
+        id: String,
+        result: Result<VideoInfo, String>,
+    },
+    ExtractionCancelled {
+        id: String,
+    },
+
+    // Search
+    SearchCompleted(Result<SearchPage, String>),
+
+    // Playlists
+    PlaylistExpanded(Result<Playlist, String>),
+
+    // Subscriptions
+    SubscriptionsLoaded(Result<Vec<Subscription>, String>),
+    SubscriptionChecked(SyncReport),
 
     // Download Life-cycle
+    /// A `StartDownload` with `ArchivePolicy::Ask` hit a video that was
+    /// downloaded before; nothing was queued. Send it again with `Force` to
+    /// download anyway.
+    AlreadyDownloaded {
+        video_info: Box<VideoInfo>,
+        output_path: PathBuf,
+        format_id: Option<String>,
+        record: ArchiveRecord,
+    },
     DownloadStarted {
         task_id: String,
         video_info: VideoInfo,
+        /// Set for tasks expanded from a playlist.
+        batch: Option<TaskBatch>,
     },
     DownloadProgress {
         task_id: String,
         data: DownloadProgressData,
     },
+    /// The task's thumbnail is in the cache at `path`.
+    ThumbnailReady {
+        task_id: String,
+        path: PathBuf,
+    },
     DownloadCompleted {
         task_id: String,
         /// Final on-disk path after organization, so the GUI's Open File /
@@ -59,5 +145,18 @@ pub enum BackendEvent {
     },
 
     // System
+    /// Per-extractor stats, in chain order.
+    ExtractorDiagnostics(Vec<ExtractorStats>),
     Error(String),
 }
+
+impl BackendEvent {
+    /// Progress updates, which a lagging subscriber may skip: a later one
+    /// supersedes them.
+    pub fn is_progress(&self) -> bool {
+        matches!(
+            self,
+            Self::DownloadProgress { .. } | Self::ExtractionProgress { .. }
+        )
+    }
+}
@@ -1,5 +1,11 @@
 pub mod actor;
+pub mod events;
+pub mod facade;
 pub mod messages;
+pub mod subscriptions;
 
 pub use actor::BackendActor;
+pub use events::{EventBus, EventReceiver};
+pub use facade::{Rustloader, RustloaderBuilder};
 pub use messages::{BackendCommand, BackendEvent};
+pub use subscriptions::{MediaProfile, Subscription, SyncReport};
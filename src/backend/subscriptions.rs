@@ -0,0 +1,420 @@
+//! Channel and playlist subscriptions.
+//!
+//! A subscription is a channel or playlist URL polled on its own interval
+//! with `extract_playlist`. The IDs of entries already seen are stored per
+//! subscription, so a check only queues what is new. In mirror mode, entries
+//! that vanish from the source are marked removed (and unmarked if they come
+//! back); downloaded files are never deleted.
+
+use crate::database::{SubscriptionItemRecord, SubscriptionRecord};
+use crate::extractor::{Format, FormatSelector, FormatSort, VideoInfo};
+use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// How many checks in a row may fail to extract a new entry before it is
+/// recorded as seen anyway. Private, members-only and removed videos never
+/// extract, and would otherwise be retried and reported on every check.
+pub const MAX_ENTRY_ATTEMPTS: u32 = 3;
+
+/// What a subscription downloads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MediaProfile {
+    #[default]
+    Video,
+    /// The best audio-only format.
+    Audio,
+}
+
+impl MediaProfile {
+    pub const ALL: [MediaProfile; 2] = [MediaProfile::Video, MediaProfile::Audio];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            MediaProfile::Video => "video",
+            MediaProfile::Audio => "audio",
+        }
+    }
+}
+
+impl fmt::Display for MediaProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            MediaProfile::Video => "Video",
+            MediaProfile::Audio => "Audio only",
+        })
+    }
+}
+
+impl FromStr for MediaProfile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "video" => Ok(MediaProfile::Video),
+            "audio" => Ok(MediaProfile::Audio),
+            _ => Err(format!(
+                "unknown profile '{s}' (expected 'video' or 'audio')"
+            )),
+        }
+    }
+}
+
+/// A followed channel or playlist and how its new items are downloaded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Subscription {
+    pub id: String,
+    pub url: String,
+    pub name: String,
+    /// Highest video height to pick (e.g. 1080); `None` is the best there is.
+    pub quality: Option<u32>,
+    pub profile: MediaProfile,
+    /// Where finished files go; `None` organizes them into the library.
+    pub target_dir: Option<PathBuf>,
+    /// Also track entries that disappear from the source.
+    pub mirror: bool,
+    pub interval: Duration,
+    pub enabled: bool,
+    pub last_checked: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Subscription {
+    pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(60 * 60);
+    /// Polling more often than this only gets the source rate-limiting us.
+    pub const MIN_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+    /// An enabled video subscription checked hourly, named after its URL
+    /// until the first check finds a title.
+    pub fn new(url: impl Into<String>) -> Self {
+        let url = url.into();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            name: url.clone(),
+            url,
+            quality: None,
+            profile: MediaProfile::default(),
+            target_dir: None,
+            mirror: false,
+            interval: Self::DEFAULT_INTERVAL,
+            enabled: true,
+            last_checked: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    pub fn with_quality(mut self, height: u32) -> Self {
+        self.quality = Some(height);
+        self
+    }
+
+    pub fn with_profile(mut self, profile: MediaProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    pub fn with_target_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.target_dir = Some(dir.into());
+        self
+    }
+
+    pub fn with_mirror(mut self, mirror: bool) -> Self {
+        self.mirror = mirror;
+        self
+    }
+
+    /// Clamped to [`MIN_INTERVAL`](Self::MIN_INTERVAL).
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval.max(Self::MIN_INTERVAL);
+        self
+    }
+
+    /// Whether an enabled subscription is due for a check at `now`.
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.enabled
+            && self.last_checked.is_none_or(|last| {
+                now.signed_duration_since(last).to_std().unwrap_or_default() >= self.interval
+            })
+    }
+
+    /// The `-f` selector this subscription downloads with: the best video
+    /// (merged with the best audio) within the quality cap, falling back to
+    /// the best progressive format under it, or the best audio in whatever
+    /// container it comes in.
+    pub fn format_selector(&self) -> FormatSelector {
+        let expression = match self.profile {
+            MediaProfile::Video => {
+                let cap = self
+                    .quality
+                    .map(|height| format!("[height<=?{height}]"))
+                    .unwrap_or_default();
+                format!("bv*{cap}+ba/b{cap}")
+            }
+            MediaProfile::Audio => "ba/ba*".to_string(),
+        };
+        expression.parse().expect("subscription selectors parse")
+    }
+
+    /// The format this subscription wants from `info` (a merge of several for
+    /// split streams), or `None` to leave the choice to the default
+    /// selection.
+    pub fn pick_format(&self, info: &VideoInfo, sort: &FormatSort) -> Option<Format> {
+        self.format_selector().pick(&info.formats, sort)
+    }
+
+    pub fn to_record(&self) -> SubscriptionRecord {
+        SubscriptionRecord {
+            id: self.id.clone(),
+            url: self.url.clone(),
+            name: self.name.clone(),
+            quality: self.quality,
+            profile: self.profile.name().to_string(),
+            target_dir: self.target_dir.clone(),
+            mirror: self.mirror,
+            interval_secs: self.interval.as_secs(),
+            enabled: self.enabled,
+            last_checked: self.last_checked,
+            created_at: self.created_at,
+        }
+    }
+
+    /// An unknown stored profile reads back as video.
+    pub fn from_record(record: SubscriptionRecord) -> Self {
+        Self {
+            id: record.id,
+            url: record.url,
+            name: record.name,
+            quality: record.quality,
+            profile: record.profile.parse().unwrap_or_default(),
+            target_dir: record.target_dir,
+            mirror: record.mirror,
+            interval: Duration::from_secs(record.interval_secs),
+            enabled: record.enabled,
+            last_checked: record.last_checked,
+            created_at: record.created_at,
+        }
+    }
+}
+
+/// The key an entry is tracked under: its ID, or its URL for sources that
+/// list entries without one.
+pub fn entry_key(entry: &VideoInfo) -> String {
+    if entry.id.is_empty() {
+        entry.url.clone()
+    } else {
+        entry.id.clone()
+    }
+}
+
+/// What one check of a subscription has to do.
+#[derive(Debug, Default)]
+pub struct SyncPlan {
+    /// Entries never seen before, with their 1-based playlist position.
+    pub new: Vec<(usize, VideoInfo)>,
+    /// Seen entries gone from the source (mirror mode only).
+    pub removed: Vec<String>,
+    /// Entries marked removed that are listed again (mirror mode only).
+    pub restored: Vec<SubscriptionItemRecord>,
+}
+
+/// Compare the current listing with what the subscription has seen.
+pub fn plan_sync(seen: &[SubscriptionItemRecord], entries: &[VideoInfo], mirror: bool) -> SyncPlan {
+    let seen_keys: HashSet<&str> = seen.iter().map(|i| i.video_id.as_str()).collect();
+    let listed: HashSet<String> = entries.iter().map(entry_key).collect();
+
+    let mut plan = SyncPlan::default();
+    let mut planned = HashSet::new();
+    for (i, entry) in entries.iter().enumerate() {
+        let key = entry_key(entry);
+        // A listing can repeat an entry; queue it once.
+        if !seen_keys.contains(key.as_str()) && planned.insert(key) {
+            plan.new.push((i + 1, entry.clone()));
+        }
+    }
+    if mirror {
+        for item in seen {
+            match (listed.contains(&item.video_id), item.removed_at.is_some()) {
+                (false, false) => plan.removed.push(item.video_id.clone()),
+                (true, true) => plan.restored.push(item.clone()),
+                _ => {}
+            }
+        }
+    }
+    plan
+}
+
+/// The outcome of one subscription check.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SyncReport {
+    pub subscription_id: String,
+    pub name: String,
+    /// Entries currently listed by the source.
+    pub listed: usize,
+    /// New entries queued for download.
+    pub queued: usize,
+    /// New entries that could not be queued (retried on the next check, up
+    /// to [`MAX_ENTRY_ATTEMPTS`] times when they fail to extract).
+    pub failed: usize,
+    /// New entries recorded as seen without downloading (first check of a
+    /// subscription that skips its back catalogue).
+    pub skipped: usize,
+    pub removed: usize,
+    pub restored: usize,
+}
+
+impl fmt::Display for SyncReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} new", self.name, self.queued)?;
+        if self.failed > 0 {
+            write!(f, ", {} failed", self.failed)?;
+        }
+        if self.skipped > 0 {
+            write!(f, ", {} marked seen", self.skipped)?;
+        }
+        if self.removed > 0 {
+            write!(f, ", {} removed upstream", self.removed)?;
+        }
+        if self.restored > 0 {
+            write!(f, ", {} back upstream", self.restored)?;
+        }
+        write!(f, " ({} listed)", self.listed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str) -> VideoInfo {
+        VideoInfo {
+            id: id.to_string(),
+            title: format!("Video {id}"),
+            url: format!("https://example.com/watch/{id}"),
+            ..Default::default()
+        }
+    }
+
+    fn seen(id: &str, removed: bool) -> SubscriptionItemRecord {
+        SubscriptionItemRecord {
+            subscription_id: "sub".to_string(),
+            video_id: id.to_string(),
+            url: format!("https://example.com/watch/{id}"),
+            title: format!("Video {id}"),
+            first_seen: Utc::now(),
+            removed_at: removed.then(Utc::now),
+        }
+    }
+
+    #[test]
+    fn only_unseen_entries_are_planned() {
+        let entries = [entry("c"), entry("b"), entry("a"), entry("c")];
+        let plan = plan_sync(&[seen("a", false)], &entries, false);
+        let new: Vec<_> = plan.new.iter().map(|(i, e)| (*i, e.id.as_str())).collect();
+        assert_eq!(new, [(1, "c"), (2, "b")]);
+        assert!(plan.removed.is_empty() && plan.restored.is_empty());
+    }
+
+    #[test]
+    fn mirror_mode_tracks_removals_and_returns() {
+        let history = [seen("a", false), seen("b", false), seen("c", true)];
+        let entries = [entry("a"), entry("c")];
+
+        let plain = plan_sync(&history, &entries, false);
+        assert!(plain.removed.is_empty() && plain.restored.is_empty());
+
+        let mirrored = plan_sync(&history, &entries, true);
+        assert!(mirrored.new.is_empty());
+        assert_eq!(mirrored.removed, ["b"]);
+        assert_eq!(mirrored.restored.len(), 1);
+        assert_eq!(mirrored.restored[0].video_id, "c");
+    }
+
+    #[test]
+    fn due_after_the_interval_unless_disabled() {
+        let now = Utc::now();
+        let mut sub =
+            Subscription::new("https://example.com/c/chan").with_interval(Duration::from_secs(10));
+        assert_eq!(sub.interval, Subscription::MIN_INTERVAL, "clamped");
+        assert!(sub.is_due(now), "never checked");
+
+        sub.last_checked = Some(now - chrono::Duration::minutes(4));
+        assert!(!sub.is_due(now));
+        sub.last_checked = Some(now - chrono::Duration::minutes(6));
+        assert!(sub.is_due(now));
+        sub.enabled = false;
+        assert!(!sub.is_due(now));
+    }
+
+    #[test]
+    fn formats_follow_quality_and_profile() {
+        let fmt =
+            |id: &str, ext: &str, v: &str, a: &str, h: Option<u32>, abr: Option<f32>| Format {
+                format_id: id.to_string(),
+                ext: ext.to_string(),
+                vcodec: Some(v.to_string()),
+                acodec: Some(a.to_string()),
+                height: h,
+                abr,
+                ..Default::default()
+            };
+        // YouTube lists one progressive format; everything above 360p is a
+        // split stream.
+        let info = VideoInfo {
+            formats: vec![
+                fmt("18", "mp4", "avc1", "mp4a", Some(360), None),
+                fmt("136", "mp4", "avc1", "none", Some(720), None),
+                fmt("137", "mp4", "avc1", "none", Some(1080), None),
+                fmt("140", "m4a", "none", "mp4a", None, Some(128.0)),
+                fmt("251", "webm", "none", "opus", None, Some(160.0)),
+            ],
+            ..Default::default()
+        };
+        let sort = FormatSort::default();
+        let pick = |sub: Subscription| sub.pick_format(&info, &sort).map(|f| f.format_id);
+        let sub = Subscription::new("u");
+        assert_eq!(pick(sub.clone()).as_deref(), Some("137+251"));
+        assert_eq!(
+            pick(sub.clone().with_quality(720)).as_deref(),
+            Some("136+251")
+        );
+        assert_eq!(
+            pick(sub.clone().with_quality(480)).as_deref(),
+            Some("18+251")
+        );
+        assert_eq!(
+            sub.clone().with_quality(720).format_selector().to_string(),
+            "bv*[height<=?720]+ba/b[height<=?720]"
+        );
+
+        let audio = sub
+            .clone()
+            .with_profile(MediaProfile::Audio)
+            .pick_format(&info, &sort)
+            .unwrap();
+        assert_eq!(audio.format_id, "251");
+        assert_eq!(audio.ext, "webm", "opus stays in its own container");
+        assert!(sub.pick_format(&VideoInfo::default(), &sort).is_none());
+    }
+
+    #[test]
+    fn records_round_trip() {
+        let sub = Subscription::new("https://example.com/c/chan")
+            .with_name("Chan")
+            .with_quality(480)
+            .with_profile(MediaProfile::Audio)
+            .with_target_dir("/music/chan")
+            .with_mirror(true);
+        assert_eq!(Subscription::from_record(sub.to_record()), sub);
+        assert_eq!("Audio".parse(), Ok(MediaProfile::Audio));
+        assert!("podcast".parse::<MediaProfile>().is_err());
+    }
+}
@@ -3,7 +3,8 @@
 //! The CLI is a thin front-end over Rustloader's existing engine: it parses
 //! ergonomic flags (ported from the legacy `rustloader2` CLI surface,
 //! `src/cli.rs` in that repo) and drives the same [`DownloadEngine`] /
-//! [`HybridExtractor`] the GUI uses. It deliberately does **not** implement its
+//! [`HybridExtractor`](crate::extractor::HybridExtractor) the GUI uses (built
+//! through [`Rustloader::builder`]). It deliberately does **not** implement its
 //! own download logic — every flag maps onto options consumed by the existing
 //! engine yt-dlp path (see [`crate::downloader::build_ytdlp_args`]).
 //!
@@ -11,17 +12,36 @@
 //! supplied it runs a single headless download.
 
 use std::path::PathBuf;
-use std::sync::Arc;
 
 use anyhow::{Context, Result};
-use clap::Parser;
+use chrono::NaiveDate;
+use clap::{Args, Parser, Subcommand};
+use regex::Regex;
+use tokio_util::sync::CancellationToken;
 
+use crate::backend::{BackendEvent, MediaProfile, Rustloader, Subscription};
+use crate::database::{initialize_database, DatabaseManager};
+use crate::downloader::thumbnail::embed_cover_art;
 use crate::downloader::{
-    build_ytdlp_args, ytdlp_output_template, DownloadEngine, DownloadProgress, YtDlpOptions,
+    build_ytdlp_args, chapters, live, ytdlp_output_template, CallbackSink, ChapterMode,
+    DownloadEngine, DownloadProgress, DownloadStatus, LiveOptions, ThumbnailCache, YtDlpOptions,
 };
-use crate::extractor::ytdlp::find_aria2c;
-use crate::extractor::{HybridExtractor, YtDlpExtractor};
-use crate::utils;
+use crate::extractor::chapters::format_timestamp;
+use crate::extractor::formats;
+use crate::extractor::playlist::{parse_date, parse_duration, parse_index_ranges};
+use crate::extractor::preferences::parse_video_codec;
+use crate::extractor::search::result_details;
+use crate::extractor::subtitles;
+use crate::extractor::ytdlp::{find_aria2c, find_ffmpeg, find_ytdlp};
+use crate::extractor::{
+    CachePolicy, Container, DynamicRange, ExtractionCache, FormatPreferences, FormatSelector,
+    FormatSort, HybridExtractor, Playlist, PlaylistSelection, SearchProvider, SearchQuery,
+    SubtitleFormat, SubtitleOptions, VideoInfo,
+};
+use crate::queue::{ArchivePolicy, DownloadArchive, TaskStatus};
+use crate::utils::cookies;
+use crate::utils::tools::{parse_public_key, Tool, ToolManager, ToolSource};
+use crate::utils::{self, sanitize_filename, CookieJar};
 
 /// Rustloader entry arguments (GUI by default, CLI when a URL is given).
 #[derive(Parser, Debug, Default)]
@@ -34,14 +54,52 @@ pub struct Cli {
     /// URL of the video to download. If omitted, the GUI is launched.
     pub url: Option<String>,
 
-    /// Maximum video quality / height.
-    #[arg(short = 'q', long, value_parser = ["480", "720", "1080"])]
+    /// Maximum video height: 144, 240, 360, 480, 720, 1080, 1440, 2160 or
+    /// 4320, optionally with a `p` (`1440p`), or `2k`/`4k`/`8k`.
+    #[arg(short = 'q', long, value_parser = parse_height)]
     pub quality: Option<String>,
 
-    /// Output type: `mp4` (video, default) or `mp3` (audio only).
-    #[arg(short = 'f', long, value_parser = ["mp4", "mp3"])]
+    /// Video codecs to prefer, best first (e.g. `h264,vp9`). A listed codec
+    /// wins over resolution.
+    #[arg(long = "codec", value_name = "CODECS", value_delimiter = ',', value_parser = parse_video_codec)]
+    pub codecs: Vec<String>,
+
+    /// Video codecs never to download (e.g. `av1`).
+    #[arg(long = "avoid-codec", value_name = "CODECS", value_delimiter = ',', value_parser = parse_video_codec)]
+    pub avoid_codecs: Vec<String>,
+
+    /// Container for merged and remuxed videos: mp4, mkv or webm.
+    #[arg(long, value_name = "CONTAINER")]
+    pub container: Option<Container>,
+
+    /// Prefer SDR or HDR video.
+    #[arg(long, value_name = "RANGE")]
+    pub dynamic_range: Option<DynamicRange>,
+
+    /// Audio languages to prefer, best first (e.g. `es,en`).
+    #[arg(long = "audio-lang", value_name = "LANGS", value_delimiter = ',')]
+    pub audio_languages: Vec<String>,
+
+    /// Output type: `mp4` (video, default) or `mp3` (audio only) — or a
+    /// yt-dlp format selector such as `bv*[height<=1440]+ba/b`, with
+    /// `[filters]`, `+` merges and `/` fallbacks.
+    #[arg(short = 'f', long, value_parser = parse_format_arg)]
     pub format: Option<String>,
 
+    /// Format sorting for selectors, e.g. `res:1080,vcodec:h264,+size`.
+    #[arg(short = 'S', long, value_name = "KEYS")]
+    pub format_sort: Option<FormatSort>,
+
+    /// Download exactly this format (see `--list-formats`); `VIDEO+AUDIO`
+    /// merges a video-only and an audio-only stream. Overrides `-q`.
+    #[arg(long, value_name = "ID")]
+    pub format_id: Option<String>,
+
+    /// List the video's formats (resolution, codecs, fps, bitrate, size)
+    /// and exit without downloading.
+    #[arg(long, conflicts_with_all = ["playlist", "dry_run"])]
+    pub list_formats: bool,
+
     /// Start time of a clip section (e.g. 00:01:00).
     #[arg(short = 's', long)]
     pub start_time: Option<String>,
@@ -54,10 +112,94 @@ pub struct Cli {
     #[arg(long = "subs")]
     pub subs: bool,
 
-    /// Download the entire playlist instead of a single video.
+    /// Subtitle languages as yt-dlp patterns (e.g. `en,es.*`; default all).
+    /// Implies --subs.
+    #[arg(long = "sub-langs", value_name = "LANGS", value_delimiter = ',')]
+    pub sub_langs: Vec<String>,
+
+    /// Also download auto-generated captions. Implies --subs.
+    #[arg(long = "auto-subs")]
+    pub auto_subs: bool,
+
+    /// Convert subtitles to srt, vtt or ass. Implies --subs.
+    #[arg(long = "sub-format", value_name = "FORMAT")]
+    pub sub_format: Option<SubtitleFormat>,
+
+    /// Embed subtitles into the video instead of writing sidecar files.
+    /// Implies --subs.
+    #[arg(long = "embed-subs")]
+    pub embed_subs: bool,
+
+    /// List the video's subtitle tracks, including auto-generated ones, and
+    /// exit without downloading.
+    #[arg(long, conflicts_with_all = ["playlist", "dry_run", "list_formats"])]
+    pub list_subs: bool,
+
+    /// Don't fetch the thumbnail; by default it is embedded as cover art
+    /// (mp3, m4a, mp4, mkv) and saved next to the file as `<name>.jpg`.
+    #[arg(long = "no-thumbnail")]
+    pub no_thumbnail: bool,
+
+    /// Write the video's chapters (from the site, or from timestamps in its
+    /// description) into the file as chapter markers.
+    #[arg(long = "embed-chapters", conflicts_with = "split_chapters")]
+    pub embed_chapters: bool,
+
+    /// Cut the video into one file per chapter, named after the chapters,
+    /// in a folder named after the video.
+    #[arg(long = "split-chapters")]
+    pub split_chapters: bool,
+
+    /// Record a live stream from the beginning of the broadcast when the
+    /// site keeps it, instead of from now.
+    #[arg(long = "live-from-start")]
+    pub live_from_start: bool,
+
+    /// Fail on a scheduled stream or premiere instead of waiting for it to
+    /// start.
+    #[arg(long = "no-wait")]
+    pub no_wait: bool,
+
+    /// Stop recording a live stream after this long (seconds or h:mm:ss).
+    #[arg(long = "record-for", value_name = "DURATION", value_parser = parse_duration)]
+    pub record_for: Option<u64>,
+
+    /// Stop recording a live stream once the file reaches this size
+    /// (e.g. 500M, 4G).
+    #[arg(long = "record-max-size", value_name = "SIZE", value_parser = live::parse_size)]
+    pub record_max_size: Option<u64>,
+
+    /// Treat the URL as a playlist: list its entries and download each one
+    /// separately (narrowed down by the --items/--*-duration/--date-*/
+    /// --match-title filters).
     #[arg(short = 'p', long)]
     pub playlist: bool,
 
+    /// Playlist entries to download, by 1-based index (e.g. `1-10,15,20-`).
+    #[arg(long, value_name = "SPEC", requires = "playlist")]
+    pub items: Option<String>,
+
+    /// Skip playlist entries shorter than this (seconds or m:ss).
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration, requires = "playlist")]
+    pub min_duration: Option<u64>,
+
+    /// Skip playlist entries longer than this (seconds or m:ss).
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration, requires = "playlist")]
+    pub max_duration: Option<u64>,
+
+    /// Only playlist entries uploaded on or after this date (YYYYMMDD or
+    /// YYYY-MM-DD).
+    #[arg(long, value_name = "DATE", value_parser = parse_date, requires = "playlist")]
+    pub date_after: Option<NaiveDate>,
+
+    /// Only playlist entries uploaded on or before this date.
+    #[arg(long, value_name = "DATE", value_parser = parse_date, requires = "playlist")]
+    pub date_before: Option<NaiveDate>,
+
+    /// Only playlist entries whose title matches this regular expression.
+    #[arg(long, value_name = "REGEX", value_parser = Regex::new, requires = "playlist")]
+    pub match_title: Option<Regex>,
+
     /// Directory to save into (defaults to the system Downloads folder).
     #[arg(short = 'o', long)]
     pub output_dir: Option<PathBuf>,
@@ -77,8 +219,14 @@ pub struct Cli {
 
     /// Read cookies from this browser for sites that require authentication
     /// (e.g. YouTube's "Sign in to confirm you're not a bot"). One of:
-    /// chrome, firefox, safari, edge, brave, chromium, opera, vivaldi.
-    #[arg(long = "cookies-from-browser", value_name = "BROWSER")]
+    /// chrome, firefox, safari, edge, brave, chromium, opera, vivaldi,
+    /// optionally followed by `:PROFILE` (a profile name or path; see
+    /// `rustloader browsers`).
+    #[arg(
+        long = "cookies-from-browser",
+        value_name = "BROWSER[:PROFILE]",
+        value_parser = cookies::parse_browser_source
+    )]
     pub cookies_from_browser: Option<String>,
 
     /// Path to a Netscape-format cookies.txt file passed to yt-dlp via
@@ -97,9 +245,250 @@ pub struct Cli {
     /// bundled with Rustloader).
     #[arg(long = "experimental-aria2c")]
     pub experimental_aria2c: bool,
+
+    /// Always re-extract instead of reusing a cached extraction result (the
+    /// fresh result still updates the cache).
+    #[arg(long = "no-cache")]
+    pub no_cache: bool,
+
+    /// What to do with a video the download archive already has: skip it,
+    /// ask, or force a new download.
+    #[arg(long = "archive-mode", default_value = "skip")]
+    pub archive_mode: ArchivePolicy,
+
+    #[command(subcommand)]
+    pub command: Option<CliCommand>,
+}
+
+/// Subcommands. Without one, a URL argument runs a single download.
+#[derive(Subcommand, Debug, Clone, PartialEq)]
+pub enum CliCommand {
+    /// Search a site and list matching videos with their URLs.
+    Search(SearchArgs),
+    /// Follow channels and playlists and download their new items.
+    #[command(subcommand)]
+    Subscriptions(SubscriptionCommand),
+    /// Manage the archive of already-downloaded videos.
+    #[command(subcommand)]
+    Archive(ArchiveCommand),
+    /// Install, update or roll back Rustloader's own yt-dlp and ffmpeg.
+    #[command(subcommand)]
+    Tools(ToolsCommand),
+    /// List browsers and profiles that `--cookies-from-browser` can read.
+    Browsers,
+}
+
+/// `rustloader tools ...`
+#[derive(Subcommand, Debug, Clone, PartialEq)]
+pub enum ToolsCommand {
+    /// Show which yt-dlp and ffmpeg are used, and their versions.
+    Status,
+    /// Install the latest (or a pinned) release of yt-dlp or ffmpeg.
+    Update(ToolUpdateArgs),
+    /// Go back to the version the last update replaced.
+    Rollback {
+        /// yt-dlp or ffmpeg.
+        #[arg(default_value = "yt-dlp")]
+        tool: Tool,
+    },
+}
+
+/// Arguments of `rustloader tools update`.
+#[derive(Args, Debug, Clone, PartialEq)]
+pub struct ToolUpdateArgs {
+    /// yt-dlp or ffmpeg.
+    #[arg(default_value = "yt-dlp")]
+    pub tool: Tool,
+
+    /// Release to install, e.g. 2026.09.01 (default: the latest).
+    #[arg(long)]
+    pub version: Option<String>,
+
+    /// Release server to install from. yt-dlp defaults to its GitHub
+    /// releases; ffmpeg has no default.
+    #[arg(long, value_name = "URL")]
+    pub from: Option<String>,
+
+    /// Hex-encoded Ed25519 key the release checksums must be signed with.
+    #[arg(long = "public-key", value_name = "HEX")]
+    pub public_key: Option<String>,
+
+    /// Install even though the release checksums are not signed by a
+    /// pinned key, trusting them over HTTPS alone. yt-dlp's GPG-signed
+    /// releases need this.
+    #[arg(long)]
+    pub insecure: bool,
+}
+
+impl ToolUpdateArgs {
+    /// Where to install from.
+    pub fn source(&self) -> Result<ToolSource> {
+        let mut source = match (&self.from, self.tool) {
+            (Some(url), _) => ToolSource::new(url.clone()),
+            (None, Tool::YtDlp) => ToolSource::ytdlp(),
+            (None, Tool::Ffmpeg) => {
+                anyhow::bail!("ffmpeg has no default release server; pass --from URL")
+            }
+        };
+        if let Some(key) = &self.public_key {
+            source = source.with_public_key(parse_public_key(key).map_err(anyhow::Error::msg)?);
+        }
+        if self.insecure {
+            source = source.insecure();
+        }
+        Ok(source)
+    }
+}
+
+/// `rustloader archive ...`
+#[derive(Subcommand, Debug, Clone, PartialEq)]
+pub enum ArchiveCommand {
+    /// Merge a yt-dlp `--download-archive` file into the archive.
+    Import { file: PathBuf },
+    /// Write the archive as a yt-dlp `--download-archive` file.
+    Export { file: PathBuf },
+}
+
+/// `rustloader subscriptions ...`
+#[derive(Subcommand, Debug, Clone, PartialEq)]
+pub enum SubscriptionCommand {
+    /// Follow a channel or playlist. Only items uploaded from now on are
+    /// downloaded unless `--download-existing` is given.
+    Add(SubscriptionAddArgs),
+    /// List subscriptions.
+    List,
+    /// Stop following a subscription (downloaded files are kept).
+    Remove {
+        /// Subscription id (or a unique prefix of it).
+        id: String,
+    },
+    /// Check now and download new items: one subscription, or all enabled ones.
+    Check {
+        /// Subscription id (or a unique prefix of it).
+        id: Option<String>,
+    },
+    /// Keep running, checking each subscription on its own interval.
+    Watch,
+}
+
+/// Arguments of `rustloader subscriptions add`.
+#[derive(Args, Debug, Clone, PartialEq)]
+pub struct SubscriptionAddArgs {
+    /// Channel or playlist URL.
+    pub url: String,
+
+    /// Display name (defaults to the playlist or channel title).
+    #[arg(long)]
+    pub name: Option<String>,
+
+    /// Highest video height to download, e.g. 1080.
+    #[arg(short = 'q', long)]
+    pub quality: Option<u32>,
+
+    /// What to download: video or audio.
+    #[arg(long, default_value = "video")]
+    pub profile: MediaProfile,
+
+    /// Save files here as-is instead of organizing them into the library.
+    #[arg(long = "target-dir")]
+    pub target_dir: Option<PathBuf>,
+
+    /// Also track items that disappear from the source.
+    #[arg(long)]
+    pub mirror: bool,
+
+    /// Minutes between checks (at least 5).
+    #[arg(long = "interval-minutes", default_value_t = 60)]
+    pub interval_minutes: u64,
+
+    /// Download everything listed now, not only later uploads.
+    #[arg(long = "download-existing")]
+    pub download_existing: bool,
+}
+
+impl SubscriptionAddArgs {
+    /// The subscription these arguments describe.
+    pub fn to_subscription(&self) -> Subscription {
+        let mut subscription = Subscription::new(self.url.clone())
+            .with_profile(self.profile)
+            .with_mirror(self.mirror)
+            .with_interval(std::time::Duration::from_secs(self.interval_minutes * 60));
+        if let Some(name) = &self.name {
+            subscription = subscription.with_name(name.clone());
+        }
+        if let Some(quality) = self.quality {
+            subscription = subscription.with_quality(quality);
+        }
+        if let Some(dir) = &self.target_dir {
+            subscription = subscription.with_target_dir(dir.clone());
+        }
+        subscription
+    }
+}
+
+/// Arguments of `rustloader search`.
+#[derive(Args, Debug, Clone, PartialEq)]
+pub struct SearchArgs {
+    /// Search terms.
+    #[arg(required = true, num_args = 1..)]
+    pub terms: Vec<String>,
+
+    /// Site to search: youtube, youtube-date, soundcloud, bilibili or niconico.
+    #[arg(long, default_value = "youtube")]
+    pub provider: SearchProvider,
+
+    /// Page of results to show (1-based).
+    #[arg(long, default_value_t = 1)]
+    pub page: usize,
+
+    /// Results per page.
+    #[arg(short = 'n', long, default_value_t = SearchQuery::DEFAULT_PAGE_SIZE)]
+    pub limit: usize,
+
+    /// Resolve every hit fully (slower) instead of the fast flat listing.
+    #[arg(long)]
+    pub full: bool,
+}
+
+impl SearchArgs {
+    /// The backend query for these arguments.
+    pub fn to_query(&self) -> SearchQuery {
+        SearchQuery::new(self.terms.join(" "))
+            .with_provider(self.provider)
+            .with_page(self.page)
+            .with_page_size(self.limit)
+            .with_flat(!self.full)
+    }
 }
 
 impl Cli {
+    /// Whether to download `info` given the archive and `--archive-mode`;
+    /// `ask` prompts on the terminal. A failed lookup downloads.
+    async fn wants_download(&self, archive: &DownloadArchive, info: &VideoInfo) -> bool {
+        if self.archive_mode == ArchivePolicy::Force {
+            return true;
+        }
+        let record = match archive.lookup(info).await {
+            Ok(Some(record)) => record,
+            Ok(None) => return true,
+            Err(e) => {
+                tracing::warn!("Download archive lookup failed: {e:#}");
+                return true;
+            }
+        };
+        if self.archive_mode == ArchivePolicy::Skip {
+            return false;
+        }
+        eprint!(
+            "\"{}\" was already downloaded on {}. Download again? [y/N] ",
+            info.title,
+            record.added_at.format("%Y-%m-%d")
+        );
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer).is_ok()
+            && matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+    }
+
     /// The URL to download, if the binary was invoked in CLI mode.
     pub fn target_url(&self) -> Option<&str> {
         self.url.as_deref().or(self.test_download.as_deref())
@@ -113,9 +502,67 @@ impl Cli {
         )
     }
 
-    /// True when the binary should run a headless download rather than the GUI.
+    /// True when the binary should run headless (a download or a
+    /// subcommand) rather than the GUI.
     pub fn is_cli_mode(&self) -> bool {
-        self.target_url().is_some()
+        self.target_url().is_some() || self.command.is_some()
+    }
+
+    /// The `-f` selector, unless it is one of the `mp4`/`mp3` output types.
+    pub fn format_selector(&self) -> Option<FormatSelector> {
+        self.format
+            .as_deref()
+            .filter(|f| !OUTPUT_TYPES.contains(f))
+            .and_then(|f| f.parse().ok())
+    }
+
+    /// The codec, container, HDR and audio-language flags. `-q` is the
+    /// maximum height and stays in [`YtDlpOptions::quality`].
+    pub fn format_preferences(&self) -> FormatPreferences {
+        FormatPreferences {
+            max_height: None,
+            video_codecs: self.codecs.clone(),
+            avoid_codecs: self.avoid_codecs.clone(),
+            container: self.container,
+            dynamic_range: self.dynamic_range,
+            audio_languages: self.audio_languages.clone(),
+        }
+    }
+
+    /// The subtitle tracks, format and embedding the flags ask for.
+    pub fn subtitle_options(&self) -> SubtitleOptions {
+        SubtitleOptions {
+            languages: self.sub_langs.clone(),
+            auto_generated: self.auto_subs,
+            format: self.sub_format,
+            embed: self.embed_subs,
+        }
+    }
+
+    /// What --embed-chapters/--split-chapters ask for.
+    pub fn chapter_mode(&self) -> ChapterMode {
+        if self.split_chapters {
+            ChapterMode::Split
+        } else if self.embed_chapters {
+            ChapterMode::Embed
+        } else {
+            ChapterMode::None
+        }
+    }
+
+    /// What the live-stream flags ask for.
+    pub fn live_options(&self) -> LiveOptions {
+        LiveOptions {
+            from_start: self.live_from_start,
+            wait_for_start: !self.no_wait,
+            max_duration: self.record_for,
+            max_size: self.record_max_size,
+        }
+    }
+
+    /// `--subs`, or any subtitle flag that implies it.
+    pub fn wants_subtitles(&self) -> bool {
+        self.subs || self.subtitle_options() != SubtitleOptions::default()
     }
 
     /// Translate the parsed flags into engine-level yt-dlp options.
@@ -129,7 +576,8 @@ impl Cli {
             } else {
                 None
             },
-            subtitles: self.subs,
+            subtitles: self.wants_subtitles(),
+            subtitle_options: self.subtitle_options(),
             playlist: self.playlist,
             start_time: self.start_time.clone(),
             end_time: self.end_time.clone(),
@@ -140,6 +588,12 @@ impl Cli {
             // this is `false`, so the resolved args are unchanged from
             // before this option existed.
             use_aria2c: self.experimental_aria2c,
+            format_id: self.format_id.clone(),
+            format_selector: self.format_selector(),
+            format_sort: self.format_sort.clone(),
+            preferences: self.format_preferences(),
+            // Live recordings add their flags in `DownloadEngine::record_live`.
+            live: None,
         }
     }
 
@@ -170,14 +624,49 @@ impl Cli {
         dir.join(format!("{}.{}", sanitize_filename(title), ext))
     }
 
-    /// A heads-up note when yt-dlp-only options (`-q`, `-f mp3`, `--subs`, clip
-    /// section) are set but `url` is a direct media file — which the engine
-    /// downloads as-is, ignoring those options. Returns `None` when the URL
+    /// Where one playlist entry is saved: a folder named after the playlist,
+    /// then the usual sanitized `<title>.<ext>`.
+    pub fn playlist_entry_path(&self, playlist_title: &str, title: &str) -> PathBuf {
+        let dir = self
+            .output_dir
+            .clone()
+            .unwrap_or_else(utils::get_downloads_dir)
+            .join(sanitize_filename(playlist_title));
+        let ext = if self.format.as_deref() == Some("mp3") {
+            "mp3"
+        } else {
+            "mp4"
+        };
+        dir.join(format!("{}.{}", sanitize_filename(title), ext))
+    }
+
+    /// The playlist entries to keep, from the selection flags.
+    pub fn playlist_selection(&self) -> Result<PlaylistSelection> {
+        let mut selection = PlaylistSelection::all();
+        if let Some(items) = &self.items {
+            let ranges =
+                parse_index_ranges(items).map_err(|e| anyhow::anyhow!("invalid --items: {e}"))?;
+            selection = selection.with_items(ranges);
+        }
+        selection.min_duration = self.min_duration;
+        selection.max_duration = self.max_duration;
+        selection.date_after = self.date_after;
+        selection.date_before = self.date_before;
+        selection.title_pattern = self.match_title.clone();
+        Ok(selection)
+    }
+
+    /// A heads-up note when yt-dlp-only options (`-q`, `-f mp3`, `--format-id`,
+    /// `--subs`, clip section) are set but `url` is a direct media file —
+    /// which the engine downloads as-is, ignoring those options. Returns `None` when the URL
     /// routes through yt-dlp (which honors them) or no such options are set.
     pub fn ignored_options_note(&self, url: &str) -> Option<String> {
         let has_ytdlp_only = self.quality.is_some()
+            || self.format_id.is_some()
+            || self.format_selector().is_some()
+            || !self.format_preferences().is_empty()
             || self.format.as_deref() == Some("mp3")
-            || self.subs
+            || self.wants_subtitles()
             || self.start_time.is_some()
             || self.end_time.is_some();
         if !has_ytdlp_only {
@@ -202,7 +691,7 @@ impl Cli {
         let path = lower.split(['?', '#']).next().unwrap_or(&lower);
         if MEDIA_EXTS.iter().any(|e| path.ends_with(e)) {
             Some(
-                "-q/-f/--subs/section flags apply to streaming-site downloads (yt-dlp); this \
+                "-q/-f/--format-id/--subs/section flags apply to streaming-site downloads (yt-dlp); this \
                  looks like a direct file URL, which is downloaded as-is and ignores them."
                     .to_string(),
             )
@@ -212,29 +701,64 @@ impl Cli {
     }
 }
 
-/// Sanitize a video title into a safe single-path-component filename.
-pub fn sanitize_filename(title: &str) -> String {
-    let cleaned: String = title
-        .chars()
-        .map(|c| match c {
-            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' | '\0' => '_',
-            c if c.is_control() => '_',
-            c => c,
-        })
-        .collect();
-    let trimmed = cleaned.trim().trim_matches('.').trim();
-    if trimmed.is_empty() {
-        "rustloader_download".to_string()
-    } else {
-        trimmed.chars().take(180).collect()
+/// The heights `-q` accepts.
+const HEIGHTS: [u32; 9] = [144, 240, 360, 480, 720, 1080, 1440, 2160, 4320];
+
+/// `-q`: a standard height, `1440p`, or `2k`/`4k`/`8k`.
+fn parse_height(value: &str) -> Result<String, String> {
+    let lower = value.trim().to_ascii_lowercase();
+    let height = match lower.as_str() {
+        "2k" => 1440,
+        "4k" => 2160,
+        "8k" => 4320,
+        other => other
+            .trim_end_matches('p')
+            .parse::<u32>()
+            .map_err(|_| format!("'{value}' is not a video height (e.g. 720, 1440p, 4k)"))?,
+    };
+    if !HEIGHTS.contains(&height) {
+        let heights: Vec<String> = HEIGHTS.iter().map(u32::to_string).collect();
+        return Err(format!("{height} is not one of {}", heights.join(", ")));
     }
+    Ok(height.to_string())
+}
+
+/// The `-f` values that pick an output type rather than a format.
+const OUTPUT_TYPES: [&str; 2] = ["mp4", "mp3"];
+
+/// `-f`: an output type or a format selector that parses.
+fn parse_format_arg(value: &str) -> Result<String, String> {
+    if !OUTPUT_TYPES.contains(&value) {
+        value.parse::<FormatSelector>()?;
+    }
+    Ok(value.to_string())
 }
 
 /// Run a single headless download (or print the plan when `--dry-run`).
 ///
-/// This drives A's existing engine: it builds the same [`HybridExtractor`] the
-/// backend uses and calls [`DownloadEngine::download`].
+/// This drives A's existing engine: it builds the same extractor registry the
+/// backend uses ([`RustloaderBuilder::build_extractor`]) and calls
+/// [`DownloadEngine::download`].
+///
+/// [`RustloaderBuilder::build_extractor`]: crate::backend::RustloaderBuilder::build_extractor
 pub async fn run(cli: &Cli) -> Result<()> {
+    if let Some(CliCommand::Search(args)) = &cli.command {
+        return run_search(cli, args).await;
+    }
+    if let Some(CliCommand::Subscriptions(command)) = &cli.command {
+        return run_subscriptions(cli, command).await;
+    }
+    if let Some(CliCommand::Archive(command)) = &cli.command {
+        return run_archive(command).await;
+    }
+    if let Some(CliCommand::Tools(command)) = &cli.command {
+        return run_tools(command).await;
+    }
+    if let Some(CliCommand::Browsers) = &cli.command {
+        run_browsers();
+        return Ok(());
+    }
+
     let url = cli
         .target_url()
         .context("no URL provided for CLI download")?
@@ -256,36 +780,120 @@ pub async fn run(cli: &Cli) -> Result<()> {
         println!("  url:    {url}");
         println!("  output: {output_template}");
         println!("  engine: DownloadEngine::download (yt-dlp path)");
+        println!("  archive: {} videos already downloaded", cli.archive_mode);
         println!("  yt-dlp: yt-dlp {}", args.join(" "));
+        if cli.playlist {
+            cli.playlist_selection()?;
+            println!("  playlist: listed first; each selected entry downloads separately");
+        }
         return Ok(());
     }
 
     // Build the same hybrid extractor the backend uses: native extractors with
-    // a yt-dlp fallback.
-    let ytdlp = Arc::new(
-        YtDlpExtractor::new()
-            .context("failed to initialise yt-dlp extractor")?
-            .with_cookies(cli.cookie_config()),
-    );
-    let extractor = HybridExtractor::new(Vec::new(), ytdlp);
+    // a yt-dlp fallback. Its direct-link probe and the engine's own requests
+    // share one cookie jar.
+    let cookie_jar = std::sync::Arc::new(CookieJar::load(&cli.cookie_config()).await);
+    let mut extractor = Rustloader::builder()
+        .cookies(cli.cookie_config())
+        .cookie_jar(std::sync::Arc::clone(&cookie_jar))
+        .build_extractor()
+        .context("failed to initialise yt-dlp extractor")?;
+    // Share the GUI's extraction cache and download archive. Without the
+    // database the download still works, it just extracts from scratch and
+    // cannot skip anything already downloaded.
+    let archive = match open_database().await {
+        Ok(db) => {
+            extractor = extractor.with_cache(ExtractionCache::new(
+                std::sync::Arc::clone(&db),
+                CachePolicy::default(),
+            ));
+            Some(DownloadArchive::new(db))
+        }
+        Err(e) => {
+            tracing::warn!("Database unavailable ({e:#}); extracting uncached, no archive");
+            None
+        }
+    };
 
-    // Resolve a title for the output filename (best-effort; playlists skip this
-    // and let yt-dlp name each entry via the output template).
-    let title = if cli.playlist {
-        "playlist".to_string()
+    if cli.playlist {
+        return run_playlist(cli, &extractor, archive.as_ref(), &url, options, cookie_jar).await;
+    }
+    if cli.list_formats {
+        let info = extractor.extract_info(&url).await?;
+        println!("Formats of \"{}\":", info.title);
+        print!("{}", formats::format_table(&info));
+        println!("Download one with --format-id ID, or merge two with --format-id VIDEO+AUDIO.");
+        return Ok(());
+    }
+    if cli.list_subs {
+        let info = extractor.extract_info(&url).await?;
+        if subtitles::subtitle_tracks(&info).is_empty() {
+            println!("\"{}\" has no subtitles.", info.title);
+        } else {
+            println!("Subtitles of \"{}\":", info.title);
+            print!("{}", subtitles::subtitle_table(&info));
+            println!("Download them with --sub-langs LANGS (add --auto-subs for auto-generated).");
+        }
+        return Ok(());
+    }
+
+    // Resolve a title for the output filename (best-effort).
+    let info = if cli.no_cache {
+        extractor.refresh_info(&url).await
     } else {
-        match extractor.extract_info(&url).await {
-            Ok(info) => info.title,
-            Err(e) => {
-                tracing::warn!("Could not extract video info ({e}); using a generic filename");
-                "rustloader_download".to_string()
-            }
+        extractor.extract_info(&url).await
+    };
+    let info = match info {
+        Ok(info) => Some(info),
+        Err(e) => {
+            tracing::warn!("Could not extract video info ({e}); using a generic filename");
+            None
         }
     };
+    // Catch a mistyped --format-id before yt-dlp does. Sources that list no
+    // formats (direct files) are left to the engine.
+    if let (Some(format_id), Some(info)) = (&cli.format_id, &info) {
+        if !info.formats.is_empty() {
+            formats::resolve(info, format_id)
+                .map_err(|e| anyhow::anyhow!("{e} (see --list-formats for the available IDs)"))?;
+        }
+    }
+    // Show what the selector picks from the listed formats; yt-dlp evaluates
+    // the same expression (over its possibly longer list) when downloading.
+    let preferences = options.effective_preferences();
+    if let (Some(selector), Some(info)) = (
+        cli.format_selector().or_else(|| preferences.selector()),
+        &info,
+    ) {
+        let sort = cli
+            .format_sort
+            .clone()
+            .unwrap_or_default()
+            .then(&preferences.format_sort());
+        if let Some(format) = selector.pick(&info.formats, &sort) {
+            println!(
+                "Format: {} ({})",
+                format.format_id,
+                formats::describe(&format)
+            );
+        }
+    }
+    if let (Some(archive), Some(info)) = (&archive, &info) {
+        if !cli.wants_download(archive, info).await {
+            println!("Skipped: {} was already downloaded.", info.title);
+            return Ok(());
+        }
+    }
+    let title = info
+        .as_ref()
+        .map(|info| info.title.clone())
+        .unwrap_or_else(|| "rustloader_download".to_string());
     let output_path = cli.output_path(&title);
 
     // Configure the existing engine with the CLI-derived options and run it.
-    let engine = DownloadEngine::default().with_ytdlp_options(options);
+    let engine = DownloadEngine::default()
+        .with_cookie_jar(cookie_jar)
+        .with_ytdlp_options(options);
 
     // Heads-up rather than silent no-op: -q/-f/--subs/section flags only affect
     // the yt-dlp (streaming-site) path; a direct media-file URL is downloaded
@@ -294,33 +902,561 @@ pub async fn run(cli: &Cli) -> Result<()> {
         eprintln!("⚠️  {note}");
     }
 
-    let (progress_tx, mut progress_rx) = tokio::sync::mpsc::channel::<DownloadProgress>(100);
-    tokio::spawn(async move {
-        while let Some(p) = progress_rx.recv().await {
+    let final_path = match info.as_ref().filter(|info| info.live().needs_recording()) {
+        Some(info) => {
+            let engine = engine.with_live_options(cli.live_options());
+            println!("Recording {url} -> {}", output_path.display());
+            record_one(&engine, info, &output_path, cli.format_id.as_deref()).await?
+        }
+        None => {
+            println!("Downloading {url} -> {}", output_path.display());
+            download_one(&engine, &url, &output_path).await?
+        }
+    };
+    if let Some(info) = &info {
+        let chapters = apply_chapters(cli.chapter_mode(), info, &final_path).await;
+        if !cli.no_thumbnail {
+            if chapters.is_empty() {
+                add_thumbnail(info, &final_path, None).await;
+            }
+            for part in &chapters {
+                add_thumbnail(info, part, Some("folder.jpg")).await;
+            }
+        }
+        if let Some(dir) = chapters.first().and_then(|part| part.parent()) {
+            println!(
+                "Split into {} chapters in {}",
+                chapters.len(),
+                dir.display()
+            );
+        }
+    }
+    if let (Some(archive), Some(info)) = (&archive, &info) {
+        record_in_archive(archive, info).await;
+    }
+    // The engine finalizes the extension from the actual content, so the
+    // saved path can differ from the provisional one printed above.
+    println!("Done. Saved to {}", final_path.display());
+    Ok(())
+}
+
+/// `-p`: list the playlist, apply the selection flags and download each kept
+/// entry on its own, so every entry gets its own progress and file name. A
+/// failed entry does not stop the rest.
+async fn run_playlist(
+    cli: &Cli,
+    extractor: &HybridExtractor,
+    archive: Option<&DownloadArchive>,
+    url: &str,
+    mut options: YtDlpOptions,
+    cookie_jar: std::sync::Arc<CookieJar>,
+) -> Result<()> {
+    let selection = cli.playlist_selection()?;
+    let playlist = Playlist::new(url, extractor.extract_playlist(url).await?);
+    let indices = selection.apply(&playlist.entries);
+    if indices.is_empty() {
+        println!(
+            "No entries of \"{}\" ({} in total) match the selection.",
+            playlist.title,
+            playlist.entries.len()
+        );
+        return Ok(());
+    }
+    println!(
+        "Playlist \"{}\": downloading {} of {} entries",
+        playlist.title,
+        indices.len(),
+        playlist.entries.len()
+    );
+
+    // Each entry is a single video; yt-dlp must not expand it again.
+    options.playlist = false;
+    let engine = DownloadEngine::default()
+        .with_cookie_jar(cookie_jar)
+        .with_ytdlp_options(options);
+
+    let mut failed = 0;
+    let mut skipped = 0;
+    for (n, &index) in indices.iter().enumerate() {
+        let Some(entry) = playlist.entry(index) else {
+            continue;
+        };
+        println!("[{}/{}] #{index} {}", n + 1, indices.len(), entry.title);
+        if let Some(archive) = archive {
+            if !cli.wants_download(archive, entry).await {
+                skipped += 1;
+                println!("Skipped: already downloaded");
+                continue;
+            }
+        }
+        let output_path = cli.playlist_entry_path(&playlist.title, &entry.title);
+        match download_one(&engine, &entry.url, &output_path).await {
+            Ok(path) => {
+                println!("Saved to {}", path.display());
+                // Flat playlist listings carry no chapters; look them up.
+                let full;
+                let entry_info = if cli.chapter_mode() != ChapterMode::None {
+                    full = extractor.extract_info(&entry.url).await.ok();
+                    full.as_ref().unwrap_or(entry)
+                } else {
+                    entry
+                };
+                let chapters = apply_chapters(cli.chapter_mode(), entry_info, &path).await;
+                if !chapters.is_empty() {
+                    println!("Split into {} chapters", chapters.len());
+                }
+                if !cli.no_thumbnail {
+                    if chapters.is_empty() {
+                        add_thumbnail(entry, &path, Some("folder.jpg")).await;
+                    }
+                    for part in &chapters {
+                        add_thumbnail(entry, part, Some("folder.jpg")).await;
+                    }
+                }
+                if let Some(archive) = archive {
+                    record_in_archive(archive, entry).await;
+                }
+            }
+            Err(e) => {
+                failed += 1;
+                eprintln!("❌ #{index} failed: {e}");
+            }
+        }
+    }
+    if failed > 0 {
+        anyhow::bail!("{failed} of {} playlist entries failed", indices.len());
+    }
+    println!(
+        "Done. {} entries saved, {skipped} already downloaded.",
+        indices.len() - skipped
+    );
+    Ok(())
+}
+
+/// `--embed-chapters`/`--split-chapters` on a downloaded `file`. Returns
+/// the chapter files when it was split (into a folder named after the file,
+/// which is removed), otherwise nothing. Best-effort: a video without
+/// chapters, or a missing ffmpeg, leaves the file as it is.
+async fn apply_chapters(
+    mode: ChapterMode,
+    info: &VideoInfo,
+    file: &std::path::Path,
+) -> Vec<PathBuf> {
+    if info.chapters.is_empty() {
+        if mode != ChapterMode::None {
             println!(
-                "Progress: {:.1}%  {:.2} MB/s  [{:?}]",
-                p.percentage() * 100.0,
-                p.speed / 1024.0 / 1024.0,
-                p.status
+                "\"{}\" has no chapters; keeping it as one file.",
+                info.title
             );
         }
+        return Vec::new();
+    }
+    match mode {
+        ChapterMode::None => Vec::new(),
+        ChapterMode::Embed => {
+            match chapters::embed_chapters(file, &info.chapters, info.duration).await {
+                Ok(true) => println!("Embedded {} chapters", info.chapters.len()),
+                Ok(false) => eprintln!("⚠️  ffmpeg is needed to embed chapters"),
+                Err(e) => eprintln!("⚠️  Could not embed chapters: {e:#}"),
+            }
+            Vec::new()
+        }
+        ChapterMode::Split => {
+            match chapters::split_chapters(file, info, &file.with_extension("")).await {
+                Ok(parts) if !parts.is_empty() => {
+                    let _ = tokio::fs::remove_file(file).await;
+                    parts
+                }
+                Ok(_) => {
+                    eprintln!("⚠️  ffmpeg is needed to split chapters");
+                    Vec::new()
+                }
+                Err(e) => {
+                    eprintln!("⚠️  Could not split chapters: {e:#}");
+                    Vec::new()
+                }
+            }
+        }
+    }
+}
+
+/// Embed the thumbnail of `info` into `file` as cover art and save it as
+/// `<name>.jpg` next to it — and as `collection_art` in the same folder, if
+/// that is not there yet. Best-effort, like the queue's.
+async fn add_thumbnail(info: &VideoInfo, file: &std::path::Path, collection_art: Option<&str>) {
+    let cache = ThumbnailCache::new(std::env::temp_dir().join("rustloader-thumbnails"));
+    let key = uuid::Uuid::new_v4().to_string();
+    let cover = match cache.fetch(&key, info).await {
+        Ok(cover) => cover,
+        Err(e) => {
+            tracing::debug!("No thumbnail: {e:#}");
+            return;
+        }
+    };
+    if let Err(e) = embed_cover_art(file, &cover).await {
+        eprintln!("⚠️  Could not embed cover art: {e:#}");
+    }
+    utils::FileOrganizer::write_thumbnail_sidecar(file, &cover).await;
+    if let (Some(name), Some(dir)) = (collection_art, file.parent()) {
+        let art = dir.join(name);
+        if !art.exists() {
+            let _ = tokio::fs::copy(&cover, &art).await;
+        }
+    }
+    cache.remove(&key).await;
+}
+
+/// Download one URL with console progress; returns the real saved path.
+async fn download_one(
+    engine: &DownloadEngine,
+    url: &str,
+    output_path: &std::path::Path,
+) -> Result<PathBuf> {
+    let progress = CallbackSink::new(|p: &DownloadProgress| {
+        println!(
+            "Progress: {:.1}%  {:.2} MB/s  [{:?}]",
+            p.percentage() * 100.0,
+            p.speed / 1024.0 / 1024.0,
+            p.status
+        );
     });
 
-    println!("Downloading {url} -> {}", output_path.display());
-    let final_path = engine
-        .download(&url, &output_path, progress_tx)
+    engine
+        .download(url, output_path, progress)
         .await
         .map_err(|e| {
             // Keep the raw error in the logs; show the user a friendly message.
             tracing::debug!("download failed (raw): {e:#}");
             anyhow::anyhow!("{}", crate::utils::make_error_user_friendly(&e.to_string()))
-        })?;
-    // The engine finalizes the extension from the actual content, so the
-    // saved path can differ from the provisional one printed above.
-    println!("Done. Saved to {}", final_path.display());
+        })
+}
+
+/// Record a live stream (waiting for it first if it is scheduled) with
+/// console progress until it ends, a limit is reached or Ctrl+C is pressed.
+async fn record_one(
+    engine: &DownloadEngine,
+    info: &VideoInfo,
+    output_path: &std::path::Path,
+    format_id: Option<&str>,
+) -> Result<PathBuf> {
+    let progress = CallbackSink::new(|p: &DownloadProgress| match p.status {
+        DownloadStatus::Waiting => match p.starts_at {
+            Some(at) => println!(
+                "Waiting for the stream to start at {}",
+                at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")
+            ),
+            None => println!("Waiting for the stream to start..."),
+        },
+        DownloadStatus::Recording => println!(
+            "Recording: {}  {:.1} MB  {:.2} MB/s",
+            format_timestamp(p.elapsed.unwrap_or_default().as_secs_f64()),
+            p.downloaded_bytes as f64 / 1024.0 / 1024.0,
+            p.speed / 1024.0 / 1024.0
+        ),
+        _ => {}
+    });
+
+    // Ctrl+C ends the recording cleanly: what was recorded is kept.
+    let stop = CancellationToken::new();
+    let on_ctrl_c = stop.clone();
+    let ctrl_c = tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            println!("Stopping the recording...");
+            on_ctrl_c.cancel();
+        }
+    });
+    let result = engine
+        .record_live(info, output_path, format_id, stop, progress)
+        .await;
+    ctrl_c.abort();
+    result.map_err(|e| {
+        tracing::debug!("recording failed (raw): {e:#}");
+        anyhow::anyhow!("{}", crate::utils::make_error_user_friendly(&e.to_string()))
+    })
+}
+
+/// `rustloader search`: print one page of hits, numbered across pages.
+async fn run_search(cli: &Cli, args: &SearchArgs) -> Result<()> {
+    let extractor = Rustloader::builder()
+        .cookies(cli.cookie_config())
+        .build_extractor()
+        .context("failed to initialise extractors")?;
+    let query = args.to_query();
+    let page = extractor.search(&query).await?;
+
+    if page.results.is_empty() {
+        println!("No results for \"{}\" on {}.", query.terms, query.provider);
+        return Ok(());
+    }
+    println!(
+        "{} results for \"{}\" (page {}):",
+        query.provider, query.terms, page.page
+    );
+    for (i, info) in page.results.iter().enumerate() {
+        println!("{:>4}. {}", query.first_index() + i, info.title);
+        let details = result_details(info);
+        if !details.is_empty() {
+            println!("      {details}");
+        }
+        println!("      {}", info.url);
+    }
+    if page.has_more {
+        println!("More: rustloader search --page {} ...", page.page + 1);
+    }
+    Ok(())
+}
+
+/// `rustloader subscriptions`: manage subscriptions in the GUI's database
+/// and, for `check` / `watch`, download what they queue.
+async fn run_subscriptions(cli: &Cli, command: &SubscriptionCommand) -> Result<()> {
+    let rustloader = std::sync::Arc::new(
+        Rustloader::builder()
+            .cookies(cli.cookie_config())
+            .database_path(utils::get_database_path())
+            .build()
+            .await
+            .context("failed to initialise the backend")?,
+    );
+
+    match command {
+        SubscriptionCommand::Add(args) => {
+            rustloader.start().await;
+            let report = rustloader
+                .add_subscription(args.to_subscription(), args.download_existing)
+                .await?;
+            println!("Subscribed: {report}");
+            wait_for_downloads(&rustloader).await;
+        }
+        SubscriptionCommand::List => {
+            let subscriptions = rustloader.subscriptions().await?;
+            if subscriptions.is_empty() {
+                println!("No subscriptions.");
+            }
+            for subscription in subscriptions {
+                let last_checked = subscription
+                    .last_checked
+                    .map(|at| at.format("%Y-%m-%d %H:%M").to_string())
+                    .unwrap_or_else(|| "never".to_string());
+                println!(
+                    "{}  {}{}",
+                    &subscription.id[..8],
+                    subscription.name,
+                    if subscription.enabled {
+                        ""
+                    } else {
+                        " (disabled)"
+                    }
+                );
+                println!("          {}", subscription.url);
+                println!(
+                    "          {} · every {} min · last checked {}{}",
+                    subscription.profile,
+                    subscription.interval.as_secs() / 60,
+                    last_checked,
+                    if subscription.mirror {
+                        " · mirror"
+                    } else {
+                        ""
+                    }
+                );
+            }
+        }
+        SubscriptionCommand::Remove { id } => {
+            let subscription = find_subscription(&rustloader, id).await?;
+            rustloader.remove_subscription(&subscription.id).await?;
+            println!("Removed {}", subscription.name);
+        }
+        SubscriptionCommand::Check { id } => {
+            rustloader.start().await;
+            let subscriptions = match id {
+                Some(id) => vec![find_subscription(&rustloader, id).await?],
+                None => rustloader
+                    .subscriptions()
+                    .await?
+                    .into_iter()
+                    .filter(|s| s.enabled)
+                    .collect(),
+            };
+            for subscription in subscriptions {
+                match rustloader.check_subscription(&subscription.id).await {
+                    Ok(report) => println!("{report}"),
+                    Err(e) => eprintln!("⚠️  {e:#}"),
+                }
+            }
+            wait_for_downloads(&rustloader).await;
+        }
+        SubscriptionCommand::Watch => {
+            rustloader.start().await;
+            let mut events = rustloader.subscribe();
+            println!("Watching subscriptions (Ctrl+C to stop)...");
+            tokio::spawn(
+                std::sync::Arc::clone(&rustloader)
+                    .watch_subscriptions(std::time::Duration::from_secs(60)),
+            );
+            while let Some(event) = events.recv().await {
+                match event {
+                    BackendEvent::SubscriptionChecked(report) => println!("{report}"),
+                    event => print_download_event(&event),
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The subscription whose id is `id` or starts with it (as `list` prints).
+async fn find_subscription(rustloader: &Rustloader, id: &str) -> Result<Subscription> {
+    let mut matches: Vec<_> = rustloader
+        .subscriptions()
+        .await?
+        .into_iter()
+        .filter(|s| s.id.starts_with(id))
+        .collect();
+    match matches.len() {
+        0 => anyhow::bail!("no subscription with id {id}"),
+        1 => Ok(matches.remove(0)),
+        _ => anyhow::bail!("subscription id {id} is ambiguous"),
+    }
+}
+
+/// Report finished downloads until nothing is queued or running.
+async fn wait_for_downloads(rustloader: &Rustloader) {
+    let mut events = rustloader.subscribe();
+    loop {
+        let pending = rustloader.tasks().await.iter().any(|t| {
+            matches!(
+                t.status,
+                TaskStatus::Queued | TaskStatus::Waiting | TaskStatus::Downloading
+            )
+        });
+        if !pending {
+            break;
+        }
+        while let Some(event) = events.try_recv() {
+            print_download_event(&event);
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    }
+    while let Some(event) = events.try_recv() {
+        print_download_event(&event);
+    }
+}
+
+fn print_download_event(event: &BackendEvent) {
+    match event {
+        BackendEvent::DownloadCompleted {
+            file_path: Some(path),
+            ..
+        } => println!("  ✓ {path}"),
+        BackendEvent::DownloadFailed { error, .. } => eprintln!("  ✗ {error}"),
+        _ => {}
+    }
+}
+
+/// `rustloader archive`: move entries between the archive and yt-dlp
+/// archive files.
+async fn run_archive(command: &ArchiveCommand) -> Result<()> {
+    let archive = DownloadArchive::new(open_database().await?);
+    match command {
+        ArchiveCommand::Import { file } => {
+            let added = archive.import_ytdlp(file).await?;
+            println!("Imported {added} new entries from {}", file.display());
+        }
+        ArchiveCommand::Export { file } => {
+            let written = archive.export_ytdlp(file).await?;
+            println!("Exported {written} entries to {}", file.display());
+        }
+    }
     Ok(())
 }
 
+/// `rustloader tools`: manage the yt-dlp and ffmpeg Rustloader installs.
+/// `rustloader browsers`: each detected browser with the
+/// `--cookies-from-browser` value for each of its profiles.
+fn run_browsers() {
+    let browsers = cookies::detect_browser_profiles();
+    if browsers.is_empty() {
+        println!("No supported browsers found.");
+        return;
+    }
+    for browser in &browsers {
+        println!("{}", browser.name);
+        for profile in &browser.profiles {
+            println!(
+                "  {:<32} --cookies-from-browser {}",
+                profile.label(),
+                profile.cookie_source(&browser.name)
+            );
+        }
+    }
+}
+
+async fn run_tools(command: &ToolsCommand) -> Result<()> {
+    let manager = ToolManager::default();
+    match command {
+        ToolsCommand::Status => {
+            for tool in Tool::ALL {
+                let used = match tool {
+                    Tool::YtDlp => find_ytdlp(),
+                    Tool::Ffmpeg => find_ffmpeg(),
+                };
+                match manager.installed(tool) {
+                    Some(installed) => {
+                        println!("{tool}: {} (managed)", installed.version);
+                        if let Some(previous) = manager.previous_version(tool) {
+                            println!("      rollback to {previous} available");
+                        }
+                    }
+                    None => println!("{tool}: not managed"),
+                }
+                match used {
+                    Some(path) => println!("      using {}", path.display()),
+                    None => println!("      not found"),
+                }
+            }
+        }
+        ToolsCommand::Update(args) => {
+            let source = args.source()?;
+            println!("Installing {} from {}...", args.tool, source.base_url);
+            let installed = manager
+                .install(args.tool, &source, args.version.as_deref())
+                .await?;
+            println!(
+                "Installed {} {} at {}",
+                args.tool,
+                installed.version,
+                installed.path.display()
+            );
+        }
+        ToolsCommand::Rollback { tool } => {
+            let installed = manager.rollback(*tool).await?;
+            println!("Rolled {tool} back to {}", installed.version);
+        }
+    }
+    Ok(())
+}
+
+/// Record a finished download in the archive. Failing to is only a warning:
+/// the file is saved either way.
+async fn record_in_archive(archive: &DownloadArchive, info: &VideoInfo) {
+    if let Err(e) = archive.record(info).await {
+        tracing::warn!(
+            "Could not record {} in the download archive: {e:#}",
+            info.title
+        );
+    }
+}
+
+/// Open the application database the GUI uses.
+async fn open_database() -> Result<std::sync::Arc<DatabaseManager>> {
+    let path = utils::get_database_path();
+    let url = format!("sqlite://{}?mode=rwc", path.to_string_lossy());
+    let pool = initialize_database(&url)
+        .await
+        .with_context(|| format!("failed to open database {}", path.display()))?;
+    Ok(std::sync::Arc::new(DatabaseManager::new(pool)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -351,6 +1487,25 @@ mod tests {
         assert!(cli.ignored_options_note("https://h/clip.mp4").is_none());
     }
 
+    #[test]
+    fn cookies_from_browser_takes_a_profile() {
+        let cli = Cli::try_parse_from([
+            "rustloader",
+            "URL",
+            "--cookies-from-browser",
+            "firefox:abcd.default-release",
+        ])
+        .unwrap();
+        assert_eq!(
+            cli.cookie_config().from_browser.as_deref(),
+            Some("firefox:abcd.default-release")
+        );
+        assert!(
+            Cli::try_parse_from(["rustloader", "URL", "--cookies-from-browser", "netscape"])
+                .is_err()
+        );
+    }
+
     #[test]
     fn url_is_cli_mode() {
         let cli = Cli::try_parse_from(["rustloader", "https://youtu.be/abc"]).unwrap();
@@ -390,6 +1545,21 @@ mod tests {
         assert_eq!(opts.audio_bitrate.as_deref(), Some("128K"));
     }
 
+    #[test]
+    fn format_id_and_list_formats_parse() {
+        let cli = Cli::try_parse_from(["rustloader", "URL", "-q", "720", "--format-id", "137+140"])
+            .unwrap();
+        let opts = cli.to_ytdlp_options();
+        assert_eq!(opts.format_id.as_deref(), Some("137+140"));
+        let args = build_ytdlp_args(&opts, "URL", "/out.mp4", false);
+        assert!(args.windows(2).any(|w| w == ["-f", "137+140"]));
+
+        let cli = Cli::try_parse_from(["rustloader", "URL", "--list-formats"]).unwrap();
+        assert!(cli.list_formats);
+        assert!(cli.to_ytdlp_options().format_id.is_none());
+        assert!(Cli::try_parse_from(["rustloader", "URL", "--list-formats", "-p"]).is_err());
+    }
+
     #[test]
     fn use_aria2c_defaults_false_without_the_flag() {
         let cli = Cli::try_parse_from(["rustloader", "URL"]).unwrap();
@@ -404,6 +1574,227 @@ mod tests {
         assert!(cli.to_ytdlp_options().use_aria2c);
     }
 
+    #[test]
+    fn chapter_flags_pick_a_mode() {
+        let cli = Cli::try_parse_from(["rustloader", "URL"]).unwrap();
+        assert_eq!(cli.chapter_mode(), ChapterMode::None);
+        let cli = Cli::try_parse_from(["rustloader", "URL", "--split-chapters"]).unwrap();
+        assert_eq!(cli.chapter_mode(), ChapterMode::Split);
+        assert!(
+            Cli::try_parse_from(["rustloader", "URL", "--split-chapters", "--embed-chapters"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn live_flags_build_live_options() {
+        let cli = Cli::try_parse_from(["rustloader", "URL"]).unwrap();
+        assert_eq!(cli.live_options(), LiveOptions::default());
+        let cli = Cli::try_parse_from([
+            "rustloader",
+            "URL",
+            "--live-from-start",
+            "--no-wait",
+            "--record-for",
+            "1:30:00",
+            "--record-max-size",
+            "2G",
+        ])
+        .unwrap();
+        let live = cli.live_options();
+        assert!(live.from_start && !live.wait_for_start);
+        assert_eq!(live.max_duration, Some(5400));
+        assert_eq!(live.max_size, Some(2 * 1024 * 1024 * 1024));
+        assert!(Cli::try_parse_from(["rustloader", "URL", "--record-max-size", "lots"]).is_err());
+    }
+
+    #[test]
+    fn tools_update_defaults_to_ytdlp_releases() {
+        let cli = Cli::try_parse_from(["rustloader", "tools", "update"]).unwrap();
+        assert!(cli.is_cli_mode());
+        let Some(CliCommand::Tools(ToolsCommand::Update(args))) = &cli.command else {
+            panic!("expected tools update, got {:?}", cli.command);
+        };
+        assert_eq!(args.tool, Tool::YtDlp);
+        assert_eq!(args.source().unwrap(), ToolSource::ytdlp());
+        assert!(!args.source().unwrap().allow_unsigned);
+
+        let cli = Cli::try_parse_from(["rustloader", "tools", "update", "--insecure"]).unwrap();
+        let Some(CliCommand::Tools(ToolsCommand::Update(args))) = &cli.command else {
+            panic!("expected tools update");
+        };
+        assert_eq!(args.source().unwrap(), ToolSource::ytdlp().insecure());
+
+        let cli = Cli::try_parse_from([
+            "rustloader",
+            "tools",
+            "update",
+            "ffmpeg",
+            "--from",
+            "https://mirror.example/ffmpeg/",
+            "--public-key",
+            &"0f".repeat(32),
+        ])
+        .unwrap();
+        let Some(CliCommand::Tools(ToolsCommand::Update(args))) = &cli.command else {
+            panic!("expected tools update");
+        };
+        let source = args.source().unwrap();
+        assert_eq!(source.base_url, "https://mirror.example/ffmpeg");
+        assert_eq!(source.public_key, Some(vec![0x0f; 32]));
+
+        let cli = Cli::try_parse_from(["rustloader", "tools", "update", "ffmpeg"]).unwrap();
+        let Some(CliCommand::Tools(ToolsCommand::Update(args))) = &cli.command else {
+            panic!("expected tools update");
+        };
+        assert!(args.source().is_err());
+        assert!(Cli::try_parse_from(["rustloader", "tools", "rollback", "aria2c"]).is_err());
+    }
+
+    #[test]
+    fn no_cache_flag_parses() {
+        let cli = Cli::try_parse_from(["rustloader", "URL"]).unwrap();
+        assert!(!cli.no_cache);
+        let cli = Cli::try_parse_from(["rustloader", "URL", "--no-cache"]).unwrap();
+        assert!(cli.no_cache);
+    }
+
+    #[test]
+    fn search_subcommand_parses_into_a_query() {
+        let cli = Cli::try_parse_from([
+            "rustloader",
+            "search",
+            "lofi",
+            "beats",
+            "--provider",
+            "soundcloud",
+            "--page",
+            "2",
+            "-n",
+            "5",
+        ])
+        .unwrap();
+        assert!(cli.is_cli_mode());
+        assert!(cli.target_url().is_none());
+        let Some(CliCommand::Search(args)) = &cli.command else {
+            panic!("expected the search subcommand");
+        };
+        let query = args.to_query();
+        assert_eq!(query.terms, "lofi beats");
+        assert_eq!(query.provider, SearchProvider::SoundCloud);
+        assert_eq!((query.page, query.page_size, query.flat), (2, 5, true));
+
+        // A URL is still a plain download, not a subcommand.
+        let cli = Cli::try_parse_from(["rustloader", "https://youtu.be/abc"]).unwrap();
+        assert!(cli.command.is_none());
+        assert!(Cli::try_parse_from(["rustloader", "search", "x", "--provider", "vimeo"]).is_err());
+    }
+
+    #[test]
+    fn subscriptions_subcommands_parse() {
+        let cli = Cli::try_parse_from([
+            "rustloader",
+            "subscriptions",
+            "add",
+            "https://www.youtube.com/@channel/videos",
+            "-q",
+            "720",
+            "--profile",
+            "audio",
+            "--target-dir",
+            "/music",
+            "--mirror",
+            "--interval-minutes",
+            "1",
+        ])
+        .unwrap();
+        assert!(cli.is_cli_mode());
+        let Some(CliCommand::Subscriptions(SubscriptionCommand::Add(args))) = &cli.command else {
+            panic!("expected subscriptions add");
+        };
+        let subscription = args.to_subscription();
+        assert_eq!(subscription.name, subscription.url);
+        assert_eq!(subscription.quality, Some(720));
+        assert_eq!(subscription.profile, MediaProfile::Audio);
+        assert_eq!(subscription.target_dir, Some(PathBuf::from("/music")));
+        assert!(subscription.mirror && !args.download_existing);
+        // Clamped to the minimum interval.
+        assert_eq!(subscription.interval, Subscription::MIN_INTERVAL);
+
+        let cli = Cli::try_parse_from(["rustloader", "subscriptions", "check"]).unwrap();
+        assert_eq!(
+            cli.command,
+            Some(CliCommand::Subscriptions(SubscriptionCommand::Check {
+                id: None
+            }))
+        );
+        assert!(Cli::try_parse_from(["rustloader", "subscriptions", "remove"]).is_err());
+        assert!(Cli::try_parse_from([
+            "rustloader",
+            "subscriptions",
+            "add",
+            "URL",
+            "--profile",
+            "podcast"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn archive_mode_and_subcommands_parse() {
+        let cli = Cli::try_parse_from(["rustloader", "URL"]).unwrap();
+        assert_eq!(cli.archive_mode, ArchivePolicy::Skip);
+        let cli = Cli::try_parse_from(["rustloader", "URL", "--archive-mode", "ask"]).unwrap();
+        assert_eq!(cli.archive_mode, ArchivePolicy::Ask);
+        assert!(Cli::try_parse_from(["rustloader", "URL", "--archive-mode", "never"]).is_err());
+
+        let cli =
+            Cli::try_parse_from(["rustloader", "archive", "import", "yt-dlp-archive.txt"]).unwrap();
+        assert!(cli.is_cli_mode());
+        assert_eq!(
+            cli.command,
+            Some(CliCommand::Archive(ArchiveCommand::Import {
+                file: PathBuf::from("yt-dlp-archive.txt")
+            }))
+        );
+        assert!(Cli::try_parse_from(["rustloader", "archive", "export"]).is_err());
+    }
+
+    #[test]
+    fn playlist_filters_parse_into_a_selection() {
+        let cli = Cli::try_parse_from([
+            "rustloader",
+            "URL",
+            "-p",
+            "-o",
+            "/tmp",
+            "--items",
+            "1-10,15",
+            "--min-duration",
+            "1:00",
+            "--date-after",
+            "2024-01-01",
+            "--match-title",
+            "(?i)live",
+        ])
+        .unwrap();
+        let selection = cli.playlist_selection().unwrap();
+        assert_eq!(selection.items.as_ref().map(Vec::len), Some(2));
+        assert_eq!(selection.min_duration, Some(60));
+        assert_eq!(selection.date_after, parse_date("20240101").ok());
+        assert!(selection.title_pattern.unwrap().is_match("Part 3 (Live)"));
+        assert_eq!(
+            cli.playlist_entry_path("Mix: Vol/1", "Intro"),
+            PathBuf::from("/tmp").join("Mix_ Vol_1").join("Intro.mp4")
+        );
+
+        // The filters only make sense with -p, and must be well-formed.
+        assert!(Cli::try_parse_from(["rustloader", "URL", "--items", "1-3"]).is_err());
+        assert!(Cli::try_parse_from(["rustloader", "URL", "-p", "--date-after", "May"]).is_err());
+        let bad = Cli::try_parse_from(["rustloader", "URL", "-p", "--items", "3-1"]).unwrap();
+        assert!(bad.playlist_selection().is_err());
+    }
+
     #[test]
     fn rejects_invalid_quality() {
         assert!(Cli::try_parse_from(["rustloader", "URL", "-q", "4000"]).is_err());
@@ -411,13 +1802,112 @@ mod tests {
 
     #[test]
     fn rejects_invalid_format() {
+        for bad in ["best[height<<720]", "bv+", "bv[colour=red]", "mkv"] {
+            assert!(Cli::try_parse_from(["rustloader", "URL", "-f", bad]).is_err());
+        }
         assert!(Cli::try_parse_from(["rustloader", "URL", "-f", "avi"]).is_err());
+        assert!(Cli::try_parse_from(["rustloader", "URL", "-S", "loudness"]).is_err());
+    }
+
+    #[test]
+    fn subtitle_flags_imply_subs() {
+        let cli = Cli::try_parse_from(["rustloader", "URL"]).unwrap();
+        assert!(!cli.to_ytdlp_options().subtitles);
+
+        let cli = Cli::try_parse_from([
+            "rustloader",
+            "URL",
+            "--sub-langs",
+            "en,es.*",
+            "--auto-subs",
+            "--sub-format",
+            "srt",
+            "--embed-subs",
+        ])
+        .unwrap();
+        let opts = cli.to_ytdlp_options();
+        assert!(opts.subtitles);
+        assert_eq!(
+            opts.subtitle_options,
+            SubtitleOptions::default()
+                .with_languages(vec!["en".to_string(), "es.*".to_string()])
+                .with_auto_generated(true)
+                .with_format(SubtitleFormat::Srt)
+                .with_embed(true)
+        );
+
+        assert!(Cli::try_parse_from(["rustloader", "URL", "--sub-format", "sub"]).is_err());
+        assert!(
+            Cli::try_parse_from(["rustloader", "URL", "--list-subs", "--list-formats"]).is_err()
+        );
     }
 
     #[test]
-    fn sanitize_filename_strips_separators() {
-        assert_eq!(sanitize_filename("a/b:c?"), "a_b_c_");
-        assert_eq!(sanitize_filename("   "), "rustloader_download");
+    fn heights_and_format_preferences_parse() {
+        for (flag, height) in [("1440", 1440), ("1440p", 1440), ("4K", 2160), ("8k", 4320)] {
+            let cli = Cli::try_parse_from(["rustloader", "URL", "-q", flag]).unwrap();
+            assert_eq!(cli.to_ytdlp_options().quality, Some(height), "{flag}");
+        }
+
+        let cli = Cli::try_parse_from([
+            "rustloader",
+            "URL",
+            "--codec",
+            "avc1,vp9",
+            "--avoid-codec",
+            "av1",
+            "--container",
+            "mkv",
+            "--dynamic-range",
+            "sdr",
+            "--audio-lang",
+            "es,en",
+        ])
+        .unwrap();
+        let prefs = cli.to_ytdlp_options().preferences;
+        assert_eq!(prefs.video_codecs, ["h264", "vp9"]);
+        assert_eq!(prefs.avoid_codecs, ["av1"]);
+        assert_eq!(prefs.container, Some(Container::Mkv));
+        assert_eq!(prefs.dynamic_range, Some(DynamicRange::Sdr));
+        assert_eq!(prefs.audio_languages, ["es", "en"]);
+        assert!(cli
+            .ignored_options_note("https://example.com/a.mp4")
+            .is_some());
+
+        for bad in [
+            ["--codec", "divx"],
+            ["--container", "avi"],
+            ["--dynamic-range", "dolby"],
+        ] {
+            assert!(Cli::try_parse_from(["rustloader", "URL", bad[0], bad[1]]).is_err());
+        }
+    }
+
+    #[test]
+    fn format_selector_and_sort_reach_ytdlp() {
+        let cli = Cli::try_parse_from([
+            "rustloader",
+            "URL",
+            "-f",
+            "bv*[height<=1440]+ba/b",
+            "-S",
+            "vcodec:h264,+size",
+        ])
+        .unwrap();
+        assert_eq!(
+            cli.format_selector().unwrap().to_string(),
+            "bv*[height<=1440]+ba/b"
+        );
+        let args = build_ytdlp_args(&cli.to_ytdlp_options(), "URL", "/out.mp4", false);
+        assert!(args
+            .windows(2)
+            .any(|w| w == ["-f", "bv*[height<=1440]+ba/b"]));
+        assert!(args.windows(2).any(|w| w == ["-S", "vcodec:h264,+size"]));
+
+        // The output types keep their meaning.
+        let cli = Cli::try_parse_from(["rustloader", "URL", "-f", "mp3"]).unwrap();
+        assert!(cli.format_selector().is_none());
+        assert!(cli.to_ytdlp_options().audio_only);
     }
 
     #[test]
@@ -5,5 +5,8 @@ pub mod schema;
 
 // Re-export for convenience
 #[allow(unused_imports)] // Exposed for external callers; may be unused internally
-pub use operations::{DatabaseManager, DownloadRecord, SettingsRecord};
+pub use operations::{
+    ArchiveRecord, CachedExtractionRecord, DatabaseManager, DownloadRecord, SettingsRecord,
+    SubscriptionItemRecord, SubscriptionRecord,
+};
 pub use schema::initialize_database;
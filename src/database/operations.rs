@@ -168,6 +168,307 @@ impl DatabaseManager {
         Ok(row.map(|r| r.get("value")))
     }
 
+    /// Save (or replace) a cached extraction result
+    pub async fn save_cached_extraction(&self, record: &CachedExtractionRecord) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO extraction_cache
+            (cache_key, url, extractor, info_json, fetched_at)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&record.cache_key)
+        .bind(&record.url)
+        .bind(&record.extractor)
+        .bind(&record.info_json)
+        .bind(record.fetched_at)
+        .execute(&self.pool)
+        .await?;
+
+        debug!("Cached extraction: {}", record.cache_key);
+        Ok(())
+    }
+
+    /// Get cached extraction result by cache key
+    pub async fn get_cached_extraction(
+        &self,
+        cache_key: &str,
+    ) -> Result<Option<CachedExtractionRecord>> {
+        let row = sqlx::query("SELECT * FROM extraction_cache WHERE cache_key = ?")
+            .bind(cache_key)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|row| CachedExtractionRecord {
+            cache_key: row.get("cache_key"),
+            url: row.get("url"),
+            extractor: row.get("extractor"),
+            info_json: row.get("info_json"),
+            fetched_at: row.get("fetched_at"),
+        }))
+    }
+
+    /// Delete a cached extraction result
+    pub async fn delete_cached_extraction(&self, cache_key: &str) -> Result<()> {
+        sqlx::query("DELETE FROM extraction_cache WHERE cache_key = ?")
+            .bind(cache_key)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Delete cached extraction results fetched before `cutoff`; returns how
+    /// many were removed
+    pub async fn prune_cached_extractions(&self, cutoff: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM extraction_cache WHERE fetched_at < ?")
+            .bind(cutoff)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Save (or replace) a subscription
+    pub async fn save_subscription(&self, record: &SubscriptionRecord) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO subscriptions
+            (id, url, name, quality, profile, target_dir, mirror, interval_secs, enabled,
+             last_checked, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&record.id)
+        .bind(&record.url)
+        .bind(&record.name)
+        .bind(record.quality.map(|v| v as i64))
+        .bind(&record.profile)
+        .bind(
+            record
+                .target_dir
+                .as_ref()
+                .map(|p| p.to_string_lossy().into_owned()),
+        )
+        .bind(record.mirror)
+        .bind(record.interval_secs as i64)
+        .bind(record.enabled)
+        .bind(record.last_checked)
+        .bind(record.created_at)
+        .execute(&self.pool)
+        .await?;
+
+        debug!("Saved subscription: {}", record.id);
+        Ok(())
+    }
+
+    /// Get subscription by ID
+    pub async fn get_subscription(&self, id: &str) -> Result<Option<SubscriptionRecord>> {
+        let row = sqlx::query("SELECT * FROM subscriptions WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(row.map(row_into_subscription_record))
+    }
+
+    /// Get all subscriptions, oldest first
+    pub async fn get_all_subscriptions(&self) -> Result<Vec<SubscriptionRecord>> {
+        let rows = sqlx::query("SELECT * FROM subscriptions ORDER BY created_at")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows.into_iter().map(row_into_subscription_record).collect())
+    }
+
+    /// Record when a subscription was last checked
+    pub async fn mark_subscription_checked(&self, id: &str, at: DateTime<Utc>) -> Result<()> {
+        sqlx::query("UPDATE subscriptions SET last_checked = ? WHERE id = ?")
+            .bind(at)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Delete a subscription and everything it has seen
+    pub async fn delete_subscription(&self, id: &str) -> Result<()> {
+        sqlx::query("DELETE FROM subscription_items WHERE subscription_id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        sqlx::query("DELETE FROM subscription_failures WHERE subscription_id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        sqlx::query("DELETE FROM subscriptions WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Record a seen subscription entry; a previously removed entry is
+    /// marked present again
+    pub async fn save_subscription_item(&self, item: &SubscriptionItemRecord) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO subscription_items
+            (subscription_id, video_id, url, title, first_seen, removed_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            ON CONFLICT (subscription_id, video_id)
+            DO UPDATE SET url = excluded.url, title = excluded.title,
+                          removed_at = excluded.removed_at
+            "#,
+        )
+        .bind(&item.subscription_id)
+        .bind(&item.video_id)
+        .bind(&item.url)
+        .bind(&item.title)
+        .bind(item.first_seen)
+        .bind(item.removed_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Mark entries as gone from their subscription's source
+    pub async fn mark_subscription_items_removed(
+        &self,
+        subscription_id: &str,
+        video_ids: &[String],
+        at: DateTime<Utc>,
+    ) -> Result<()> {
+        for video_id in video_ids {
+            sqlx::query(
+                "UPDATE subscription_items SET removed_at = ? \
+                 WHERE subscription_id = ? AND video_id = ?",
+            )
+            .bind(at)
+            .bind(subscription_id)
+            .bind(video_id)
+            .execute(&self.pool)
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Count a failed attempt at a subscription entry; returns how many
+    /// attempts at it have failed so far
+    pub async fn record_subscription_failure(
+        &self,
+        subscription_id: &str,
+        video_id: &str,
+        error: &str,
+        at: DateTime<Utc>,
+    ) -> Result<u32> {
+        let row = sqlx::query(
+            r#"
+            INSERT INTO subscription_failures
+            (subscription_id, video_id, attempts, last_error, last_attempt)
+            VALUES (?, ?, 1, ?, ?)
+            ON CONFLICT (subscription_id, video_id)
+            DO UPDATE SET attempts = attempts + 1, last_error = excluded.last_error,
+                          last_attempt = excluded.last_attempt
+            RETURNING attempts
+            "#,
+        )
+        .bind(subscription_id)
+        .bind(video_id)
+        .bind(error)
+        .bind(at)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(u32::try_from(row.get::<i64, _>("attempts")).unwrap_or(u32::MAX))
+    }
+
+    /// Forget the failed attempts at a subscription entry
+    pub async fn clear_subscription_failures(
+        &self,
+        subscription_id: &str,
+        video_id: &str,
+    ) -> Result<()> {
+        sqlx::query("DELETE FROM subscription_failures WHERE subscription_id = ? AND video_id = ?")
+            .bind(subscription_id)
+            .bind(video_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Get every entry a subscription has seen, in first-seen order
+    pub async fn get_subscription_items(
+        &self,
+        subscription_id: &str,
+    ) -> Result<Vec<SubscriptionItemRecord>> {
+        let rows = sqlx::query(
+            "SELECT * FROM subscription_items WHERE subscription_id = ? ORDER BY first_seen",
+        )
+        .bind(subscription_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| SubscriptionItemRecord {
+                subscription_id: row.get("subscription_id"),
+                video_id: row.get("video_id"),
+                url: row.get("url"),
+                title: row.get("title"),
+                first_seen: row.get("first_seen"),
+                removed_at: row.get("removed_at"),
+            })
+            .collect())
+    }
+
+    /// Record a downloaded video. An entry that is already archived keeps its
+    /// original `added_at`.
+    pub async fn save_archive_entry(&self, entry: &ArchiveRecord) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO download_archive (extractor, video_id, title, url, added_at)
+            VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT (extractor, video_id) DO NOTHING
+            "#,
+        )
+        .bind(&entry.extractor)
+        .bind(&entry.video_id)
+        .bind(&entry.title)
+        .bind(&entry.url)
+        .bind(entry.added_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Get an archived video
+    pub async fn get_archive_entry(
+        &self,
+        extractor: &str,
+        video_id: &str,
+    ) -> Result<Option<ArchiveRecord>> {
+        let row =
+            sqlx::query("SELECT * FROM download_archive WHERE extractor = ? AND video_id = ?")
+                .bind(extractor)
+                .bind(video_id)
+                .fetch_optional(&self.pool)
+                .await?;
+        Ok(row.map(row_into_archive_record))
+    }
+
+    /// Get every archived video, oldest first
+    pub async fn get_archive_entries(&self) -> Result<Vec<ArchiveRecord>> {
+        let rows = sqlx::query("SELECT * FROM download_archive ORDER BY added_at")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows.into_iter().map(row_into_archive_record).collect())
+    }
+
+    /// Forget an archived video so it can be downloaded again
+    pub async fn delete_archive_entry(&self, extractor: &str, video_id: &str) -> Result<()> {
+        sqlx::query("DELETE FROM download_archive WHERE extractor = ? AND video_id = ?")
+            .bind(extractor)
+            .bind(video_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
     /// Get all settings
     pub async fn get_all_settings(&self) -> Result<Vec<SettingsRecord>> {
         let rows = sqlx::query("SELECT * FROM settings")
@@ -186,6 +487,66 @@ impl DatabaseManager {
     }
 }
 
+/// Cached extraction result
+#[derive(Debug, Clone)]
+pub struct CachedExtractionRecord {
+    pub cache_key: String,
+    pub url: String,
+    pub extractor: Option<String>,
+    pub info_json: String,
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// Subscription record
+#[derive(Debug, Clone)]
+pub struct SubscriptionRecord {
+    pub id: String,
+    pub url: String,
+    pub name: String,
+    pub quality: Option<u32>,
+    pub profile: String,
+    pub target_dir: Option<PathBuf>,
+    pub mirror: bool,
+    pub interval_secs: u64,
+    pub enabled: bool,
+    pub last_checked: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// An entry a subscription has seen
+#[derive(Debug, Clone)]
+pub struct SubscriptionItemRecord {
+    pub subscription_id: String,
+    pub video_id: String,
+    pub url: String,
+    pub title: String,
+    pub first_seen: DateTime<Utc>,
+    /// Set (in mirror mode) once the entry disappeared from the source.
+    pub removed_at: Option<DateTime<Utc>>,
+}
+
+/// A video in the download archive
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchiveRecord {
+    /// Lower-case extractor key, e.g. `youtube`.
+    pub extractor: String,
+    pub video_id: String,
+    /// Empty for entries imported from a yt-dlp archive file.
+    pub title: String,
+    pub url: String,
+    pub added_at: DateTime<Utc>,
+}
+
+fn row_into_archive_record(row: sqlx::sqlite::SqliteRow) -> ArchiveRecord {
+    ArchiveRecord {
+        extractor: row.get("extractor"),
+        video_id: row.get("video_id"),
+        title: row.get("title"),
+        url: row.get("url"),
+        added_at: row.get("added_at"),
+    }
+}
+
 /// Download record
 #[derive(Debug, Clone)]
 pub struct DownloadRecord {
@@ -233,6 +594,25 @@ fn row_into_download_record(row: sqlx::sqlite::SqliteRow) -> Result<DownloadReco
     })
 }
 
+/// Convert database row to subscription record
+fn row_into_subscription_record(row: sqlx::sqlite::SqliteRow) -> SubscriptionRecord {
+    SubscriptionRecord {
+        id: row.get("id"),
+        url: row.get("url"),
+        name: row.get("name"),
+        quality: row.get::<Option<i64>, _>("quality").map(|v| v as u32),
+        profile: row.get("profile"),
+        target_dir: row
+            .get::<Option<String>, _>("target_dir")
+            .map(PathBuf::from),
+        mirror: row.get("mirror"),
+        interval_secs: row.get::<i64, _>("interval_secs") as u64,
+        enabled: row.get("enabled"),
+        last_checked: row.get("last_checked"),
+        created_at: row.get("created_at"),
+    }
+}
+
 /// Convert database row to segment record
 fn row_into_segment_record(row: sqlx::sqlite::SqliteRow) -> Result<SegmentRecord> {
     Ok(SegmentRecord {
@@ -416,4 +796,145 @@ mod tests {
             .await
             .expect("delete_download on a missing id must not error");
     }
+
+    #[tokio::test]
+    async fn cached_extractions_replace_and_prune_by_age() {
+        let db_url = fresh_db_url("extraction-cache").await;
+        let pool = initialize_database(&db_url).await.expect("init db");
+        let db = DatabaseManager::new(pool);
+
+        let old = CachedExtractionRecord {
+            cache_key: "youtube:dQw4w9WgXcQ".to_string(),
+            url: "https://www.youtube.com/watch?v=dQw4w9WgXcQ".to_string(),
+            extractor: Some("youtube".to_string()),
+            info_json: r#"{"id":"dQw4w9WgXcQ","title":"old"}"#.to_string(),
+            fetched_at: Utc::now() - chrono::Duration::days(3),
+        };
+        db.save_cached_extraction(&old).await.expect("save old");
+
+        let mut fresh = old.clone();
+        fresh.cache_key = "https://example.com/a".to_string();
+        fresh.fetched_at = Utc::now();
+        db.save_cached_extraction(&fresh).await.expect("save fresh");
+
+        // Same key replaces in place.
+        let mut replaced = old.clone();
+        replaced.info_json = r#"{"id":"dQw4w9WgXcQ","title":"new"}"#.to_string();
+        db.save_cached_extraction(&replaced)
+            .await
+            .expect("replace old");
+        let row = db
+            .get_cached_extraction(&old.cache_key)
+            .await
+            .expect("get")
+            .expect("row exists");
+        assert!(row.info_json.contains("new"));
+
+        let pruned = db
+            .prune_cached_extractions(Utc::now() - chrono::Duration::days(1))
+            .await
+            .expect("prune");
+        assert_eq!(pruned, 1);
+        assert!(db
+            .get_cached_extraction(&old.cache_key)
+            .await
+            .expect("get")
+            .is_none());
+
+        db.delete_cached_extraction(&fresh.cache_key)
+            .await
+            .expect("delete");
+        assert!(db
+            .get_cached_extraction(&fresh.cache_key)
+            .await
+            .expect("get")
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn subscriptions_round_trip_and_track_removed_items() {
+        let db_url = fresh_db_url("subscriptions").await;
+        let pool = initialize_database(&db_url).await.expect("init db");
+        let db = DatabaseManager::new(pool);
+
+        let mut sub = SubscriptionRecord {
+            id: "sub-1".to_string(),
+            url: "https://www.youtube.com/@channel/videos".to_string(),
+            name: "Channel".to_string(),
+            quality: Some(1080),
+            profile: "audio".to_string(),
+            target_dir: Some(PathBuf::from("/music/channel")),
+            mirror: true,
+            interval_secs: 3600,
+            enabled: true,
+            last_checked: None,
+            created_at: Utc::now(),
+        };
+        db.save_subscription(&sub).await.expect("save");
+        sub.enabled = false;
+        db.save_subscription(&sub).await.expect("update in place");
+        let stored = db.get_all_subscriptions().await.expect("list");
+        assert_eq!(stored.len(), 1);
+        assert!(!stored[0].enabled);
+        assert_eq!(stored[0].quality, Some(1080));
+        assert_eq!(stored[0].target_dir, sub.target_dir);
+
+        let checked = Utc::now();
+        db.mark_subscription_checked(&sub.id, checked)
+            .await
+            .expect("mark checked");
+        let stored = db.get_subscription(&sub.id).await.expect("get").unwrap();
+        assert_eq!(
+            stored.last_checked.map(|t| t.timestamp()),
+            Some(checked.timestamp())
+        );
+
+        for video_id in ["a", "b"] {
+            db.save_subscription_item(&SubscriptionItemRecord {
+                subscription_id: sub.id.clone(),
+                video_id: video_id.to_string(),
+                url: format!("https://example.com/{video_id}"),
+                title: video_id.to_uppercase(),
+                first_seen: Utc::now(),
+                removed_at: None,
+            })
+            .await
+            .expect("save item");
+        }
+        db.mark_subscription_items_removed(&sub.id, &["b".to_string()], Utc::now())
+            .await
+            .expect("mark removed");
+        let items = db.get_subscription_items(&sub.id).await.expect("items");
+        assert_eq!(items.len(), 2);
+        let removed: Vec<_> = items
+            .iter()
+            .filter(|i| i.removed_at.is_some())
+            .map(|i| i.video_id.as_str())
+            .collect();
+        assert_eq!(removed, ["b"]);
+
+        for expected in 1..=2 {
+            let attempts = db
+                .record_subscription_failure(&sub.id, "c", "Video unavailable", Utc::now())
+                .await
+                .expect("record failure");
+            assert_eq!(attempts, expected);
+        }
+        db.clear_subscription_failures(&sub.id, "c")
+            .await
+            .expect("clear failures");
+        let attempts = db
+            .record_subscription_failure(&sub.id, "c", "Video unavailable", Utc::now())
+            .await
+            .expect("record failure");
+        assert_eq!(attempts, 1);
+
+        db.delete_subscription(&sub.id).await.expect("delete");
+        assert!(db.get_subscription(&sub.id).await.expect("get").is_none());
+        assert!(db
+            .get_subscription_items(&sub.id)
+            .await
+            .expect("items")
+            .is_empty());
+    }
 }
@@ -73,6 +73,95 @@ async fn create_tables(pool: &Pool<Sqlite>) -> Result<()> {
     .execute(pool)
     .await?;
 
+    // Create extraction cache table (VideoInfo JSON keyed by normalized URL)
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS extraction_cache (
+            cache_key TEXT PRIMARY KEY,
+            url TEXT NOT NULL,
+            extractor TEXT,
+            info_json TEXT NOT NULL,
+            fetched_at DATETIME NOT NULL
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    // Create subscriptions table (channels/playlists polled for new items)
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS subscriptions (
+            id TEXT PRIMARY KEY,
+            url TEXT NOT NULL UNIQUE,
+            name TEXT NOT NULL,
+            quality INTEGER,
+            profile TEXT NOT NULL,
+            target_dir TEXT,
+            mirror BOOLEAN NOT NULL DEFAULT FALSE,
+            interval_secs INTEGER NOT NULL,
+            enabled BOOLEAN NOT NULL DEFAULT TRUE,
+            last_checked DATETIME,
+            created_at DATETIME NOT NULL
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    // Create subscription_items table (entries a subscription has seen)
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS subscription_items (
+            subscription_id TEXT NOT NULL,
+            video_id TEXT NOT NULL,
+            url TEXT NOT NULL,
+            title TEXT NOT NULL,
+            first_seen DATETIME NOT NULL,
+            removed_at DATETIME,
+            PRIMARY KEY (subscription_id, video_id),
+            FOREIGN KEY (subscription_id) REFERENCES subscriptions(id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    // Create subscription_failures table (entries that failed to extract, so
+    // a check gives up on them after a few attempts)
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS subscription_failures (
+            subscription_id TEXT NOT NULL,
+            video_id TEXT NOT NULL,
+            attempts INTEGER NOT NULL,
+            last_error TEXT NOT NULL,
+            last_attempt DATETIME NOT NULL,
+            PRIMARY KEY (subscription_id, video_id),
+            FOREIGN KEY (subscription_id) REFERENCES subscriptions(id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    // Create download_archive table (videos already downloaded, keyed like
+    // yt-dlp's --download-archive lines)
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS download_archive (
+            extractor TEXT NOT NULL,
+            video_id TEXT NOT NULL,
+            title TEXT NOT NULL,
+            url TEXT NOT NULL,
+            added_at DATETIME NOT NULL,
+            PRIMARY KEY (extractor, video_id)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
     // Create indexes
     sqlx::query("CREATE INDEX IF NOT EXISTS idx_downloads_status ON downloads(status)")
         .execute(pool)
@@ -0,0 +1,310 @@
+//! Chapter post-processing: writing a video's chapters into the finished
+//! file as chapter markers, or cutting the file into one file per chapter.
+//!
+//! Both copy streams with ffmpeg, never re-encode. Splitting on a stream
+//! copy cuts at the keyframe nearest each chapter start, which is what
+//! players do too when seeking to a chapter.
+
+use super::ffmpeg;
+use crate::extractor::{Chapter, VideoInfo};
+use crate::utils::FileOrganizer;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use tokio::fs;
+
+/// What to do with a video's chapters once it is downloaded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChapterMode {
+    /// Leave the file as downloaded.
+    #[default]
+    None,
+    /// Write the chapters into the file as chapter markers.
+    Embed,
+    /// Cut the file into one file per chapter.
+    Split,
+}
+
+impl ChapterMode {
+    pub const ALL: [ChapterMode; 3] = [ChapterMode::None, ChapterMode::Embed, ChapterMode::Split];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ChapterMode::None => "none",
+            ChapterMode::Embed => "embed",
+            ChapterMode::Split => "split",
+        }
+    }
+}
+
+impl fmt::Display for ChapterMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ChapterMode::None => "Keep as downloaded",
+            ChapterMode::Embed => "Embed chapter markers",
+            ChapterMode::Split => "One file per chapter",
+        })
+    }
+}
+
+impl FromStr for ChapterMode {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        ChapterMode::ALL
+            .into_iter()
+            .find(|m| m.as_str().eq_ignore_ascii_case(value.trim()))
+            .ok_or_else(|| format!("unknown chapter mode '{value}' (one of none, embed, split)"))
+    }
+}
+
+/// Where `chapter` ends: its own end, the next chapter's start, or the end
+/// of the video (`None` if that is unknown too).
+fn chapter_end(chapters: &[Chapter], index: usize, duration: Option<u64>) -> Option<f64> {
+    chapters[index]
+        .end_time
+        .or_else(|| chapters.get(index + 1).map(|next| next.start_time))
+        .or_else(|| duration.map(|d| d as f64))
+}
+
+/// An ffmpeg metadata file (`;FFMETADATA1`) listing `chapters`.
+pub fn ffmetadata(chapters: &[Chapter], duration: Option<u64>) -> String {
+    let escape = |value: &str| {
+        value.chars().fold(String::new(), |mut out, c| {
+            if matches!(c, '=' | ';' | '#' | '\\' | '\n') {
+                out.push('\\');
+            }
+            out.push(c);
+            out
+        })
+    };
+    let mut meta = String::from(";FFMETADATA1\n");
+    for (index, chapter) in chapters.iter().enumerate() {
+        let start = (chapter.start_time * 1000.0) as u64;
+        // A last chapter of unknown length gets a nominal minute; ffmpeg
+        // clamps it to the end of the file.
+        let end = chapter_end(chapters, index, duration)
+            .map_or(start + 60_000, |end| (end * 1000.0) as u64);
+        meta.push_str(&format!(
+            "[CHAPTER]\nTIMEBASE=1/1000\nSTART={start}\nEND={end}\ntitle={}\n",
+            escape(&chapter.title)
+        ));
+    }
+    meta
+}
+
+/// The ffmpeg arguments that copy `media` to `output` with the chapters of
+/// the `metadata` file.
+pub fn embed_args(media: &Path, metadata: &Path, output: &Path) -> Vec<String> {
+    let media = media.to_string_lossy().to_string();
+    let metadata = metadata.to_string_lossy().to_string();
+    let output = output.to_string_lossy().to_string();
+    [
+        "-y",
+        "-loglevel",
+        "error",
+        "-i",
+        &media,
+        "-i",
+        &metadata,
+        "-map",
+        "0",
+        "-map_metadata",
+        "0",
+        "-map_chapters",
+        "1",
+        "-c",
+        "copy",
+        &output,
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect()
+}
+
+/// The ffmpeg arguments that copy `start..end` of `media` to `output`,
+/// without the source's chapter markers.
+pub fn split_args(media: &Path, start: f64, end: Option<f64>, output: &Path) -> Vec<String> {
+    let mut args: Vec<String> = vec![
+        "-y".into(),
+        "-loglevel".into(),
+        "error".into(),
+        "-ss".into(),
+        format!("{start:.3}"),
+        "-i".into(),
+        media.to_string_lossy().to_string(),
+    ];
+    if let Some(end) = end {
+        args.extend(["-t".into(), format!("{:.3}", end - start)]);
+    }
+    args.extend(
+        ["-map", "0", "-map_chapters", "-1", "-c", "copy"]
+            .iter()
+            .map(|s| s.to_string()),
+    );
+    args.push(output.to_string_lossy().to_string());
+    args
+}
+
+/// `03 - Title`: the file name (without extension) of chapter `index`,
+/// numbered so the files sort in playback order.
+pub fn chapter_file_stem(chapters: &[Chapter], index: usize) -> String {
+    let width = chapters.len().to_string().len().max(2);
+    let title = chapters[index].title.trim();
+    let title = if title.is_empty() {
+        format!("Chapter {}", index + 1)
+    } else {
+        FileOrganizer::sanitize_filename(title)
+    };
+    format!("{:0width$} - {}", index + 1, title)
+}
+
+/// The video of one chapter of `info`: titled from the chapter, so the
+/// organizer names its file after it.
+pub fn chapter_info(info: &VideoInfo, index: usize) -> VideoInfo {
+    let chapter = &info.chapters[index];
+    let start = chapter.start_time;
+    let end = chapter_end(&info.chapters, index, info.duration);
+    VideoInfo {
+        title: chapter_file_stem(&info.chapters, index),
+        duration: end.map(|end| (end - start).max(0.0) as u64),
+        chapters: Vec::new(),
+        ..info.clone()
+    }
+}
+
+/// Write `chapters` into `media` as chapter markers, in place. `Ok(false)`
+/// when there are none or ffmpeg is not installed; the file is untouched
+/// then, and also when ffmpeg fails.
+pub async fn embed_chapters(
+    media: &Path,
+    chapters: &[Chapter],
+    duration: Option<u64>,
+) -> Result<bool> {
+    let Some(ext) = media.extension().and_then(|e| e.to_str()) else {
+        return Ok(false);
+    };
+    if chapters.is_empty() {
+        return Ok(false);
+    }
+    let metadata = media.with_extension("chapters.txt");
+    let output = media.with_extension(format!("chapters.{ext}"));
+    fs::write(&metadata, ffmetadata(chapters, duration)).await?;
+    let result = ffmpeg::run(&embed_args(media, &metadata, &output)).await;
+    let _ = fs::remove_file(&metadata).await;
+    match result {
+        Ok(true) => {}
+        Ok(false) => return Ok(false),
+        Err(e) => {
+            let _ = fs::remove_file(&output).await;
+            anyhow::bail!("ffmpeg could not embed chapters: {e}");
+        }
+    }
+    fs::rename(&output, media).await?;
+    Ok(true)
+}
+
+/// Cut `media` into one file per chapter of `info`, named by
+/// [`chapter_file_stem`] in `dir`. The original is left in place. Empty
+/// when there are no chapters or ffmpeg is not installed.
+pub async fn split_chapters(media: &Path, info: &VideoInfo, dir: &Path) -> Result<Vec<PathBuf>> {
+    if info.chapters.is_empty() {
+        return Ok(Vec::new());
+    }
+    let ext = media
+        .extension()
+        .and_then(|e| e.to_str())
+        .context("Downloaded file has no extension")?;
+    fs::create_dir_all(dir).await?;
+
+    let mut parts = Vec::with_capacity(info.chapters.len());
+    for (index, chapter) in info.chapters.iter().enumerate() {
+        let output = dir.join(format!(
+            "{}.{ext}",
+            chapter_file_stem(&info.chapters, index)
+        ));
+        let end = chapter_end(&info.chapters, index, info.duration);
+        let result = ffmpeg::run(&split_args(media, chapter.start_time, end, &output)).await;
+        if !matches!(result, Ok(true)) {
+            // No half-split videos: drop what was cut so far.
+            for part in parts.iter().chain([&output]) {
+                let _ = fs::remove_file(part).await;
+            }
+            let _ = fs::remove_dir(dir).await;
+            return match result {
+                Err(e) => Err(e.context(format!(
+                    "ffmpeg could not split chapter '{}'",
+                    chapter.title
+                ))),
+                _ => Ok(Vec::new()),
+            };
+        }
+        parts.push(output);
+    }
+    Ok(parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chapters() -> Vec<Chapter> {
+        ["Intro", "Talk: Part 1", ""]
+            .iter()
+            .zip([0.0, 90.5, 1800.0])
+            .map(|(title, start_time)| Chapter {
+                start_time,
+                end_time: None,
+                title: title.to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn ffmetadata_lists_every_chapter() {
+        let meta = ffmetadata(&chapters(), Some(2000));
+        assert!(meta.starts_with(";FFMETADATA1\n"));
+        assert_eq!(meta.matches("[CHAPTER]").count(), 3);
+        assert!(meta.contains("START=90500\nEND=1800000\ntitle=Talk: Part 1\n"));
+        assert!(meta.contains("START=1800000\nEND=2000000\n"));
+
+        let tricky = [Chapter {
+            title: "a=b; #1".to_string(),
+            ..Default::default()
+        }];
+        assert!(ffmetadata(&tricky, None).contains("title=a\\=b\\; \\#1\n"));
+    }
+
+    #[test]
+    fn ffmpeg_args_copy_streams() {
+        let embed = embed_args(Path::new("a.mkv"), Path::new("a.txt"), Path::new("o.mkv"));
+        assert!(embed.windows(2).any(|w| w == ["-map_chapters", "1"]));
+        assert!(embed.windows(2).any(|w| w == ["-c", "copy"]));
+
+        let split = split_args(Path::new("a.mkv"), 90.5, Some(1800.0), Path::new("o.mkv"));
+        assert!(split.windows(2).any(|w| w == ["-ss", "90.500"]));
+        assert!(split.windows(2).any(|w| w == ["-t", "1709.500"]));
+        let last = split_args(Path::new("a.mkv"), 1800.0, None, Path::new("o.mkv"));
+        assert!(!last.iter().any(|a| a == "-t"));
+    }
+
+    #[test]
+    fn chapter_files_are_numbered_and_titled() {
+        let info = VideoInfo {
+            title: "Lecture".to_string(),
+            duration: Some(2000),
+            chapters: chapters(),
+            ..Default::default()
+        };
+        let second = chapter_info(&info, 1);
+        assert_eq!(second.title, "02 - Talk_ Part 1");
+        assert_eq!(second.duration, Some(1709));
+        assert!(second.chapters.is_empty());
+        assert_eq!(chapter_info(&info, 2).title, "03 - Chapter 3");
+        assert_eq!("Split".parse::<ChapterMode>(), Ok(ChapterMode::Split));
+        assert!("both".parse::<ChapterMode>().is_err());
+    }
+}
@@ -7,6 +7,7 @@
     unused_assignments
 )]
 
+use crate::downloader::live::{self, live_args, LiveOptions};
 use crate::downloader::merger::{cleanup_segments, merge_segments, MergeProgress};
 // progress types already imported above
 use crate::downloader::progress::{
@@ -16,7 +17,10 @@ use crate::downloader::resume_guard::{
     read_sidecar, remove_sidecar, sidecar_path, write_sidecar, ResumeIdentity,
 };
 use crate::downloader::segment::{calculate_segments, download_segment, SegmentProgress};
-use crate::extractor::ytdlp::find_aria2c;
+use crate::downloader::sink::{ProgressSink, SharedProgressSink};
+use crate::extractor::ytdlp::{find_aria2c, find_ytdlp};
+use crate::extractor::{FormatPreferences, FormatSelector, FormatSort, SubtitleOptions, VideoInfo};
+use crate::utils::{CookieJar, RustloaderError};
 use anyhow::Result;
 use futures::stream::{self, StreamExt};
 use reqwest::Client;
@@ -30,6 +34,7 @@ use tokio::process::Command as AsyncCommand;
 use tokio::sync::{mpsc, Mutex};
 use tokio::time::sleep;
 use tokio::time::{timeout, Duration as TokioDuration};
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
 
 fn parse_yt_dlp_progress(line: &str) -> Option<(f64, f64, u64)> {
@@ -146,8 +151,11 @@ pub struct YtDlpOptions {
     pub audio_only: bool,
     /// Audio format when extracting audio (e.g. "mp3").
     pub audio_format: Option<String>,
-    /// Download subtitles (`--write-subs --sub-langs all`).
+    /// Download subtitles (`--write-subs`), as `subtitle_options` says.
     pub subtitles: bool,
+    /// Languages, auto-generated captions, conversion and embedding. The
+    /// default takes every uploaded track as the site serves it.
+    pub subtitle_options: SubtitleOptions,
     /// Download the whole playlist (`--yes-playlist`).
     pub playlist: bool,
     /// Clip start time (e.g. 00:01:00).
@@ -170,6 +178,31 @@ pub struct YtDlpOptions {
     /// yt-dlp's `ExternalFD` — see `build_ytdlp_args`'s doc comment), so this
     /// stays opt-in until that's addressed.
     pub use_aria2c: bool,
+    /// Exact yt-dlp format selection (`-f`), e.g. `18` or `137+140` to merge
+    /// a video and an audio stream. Takes precedence over `quality`.
+    pub format_id: Option<String>,
+    /// A full `-f` expression (e.g. `bv*[height<=1440]+ba/b`). Used when no
+    /// `format_id` is set; takes precedence over `quality`.
+    pub format_selector: Option<FormatSelector>,
+    /// Format sorting passed as `-S` (e.g. `res:1080,+size`).
+    pub format_sort: Option<FormatSort>,
+    /// Height, codec, container, HDR and audio-language preferences. Added
+    /// to `-S` after `format_sort`; their selector applies when neither
+    /// `format_id` nor `format_selector` is set.
+    pub preferences: FormatPreferences,
+    /// Set when recording a live stream (see [`live::record`]).
+    pub live: Option<LiveOptions>,
+}
+
+impl YtDlpOptions {
+    /// [`preferences`](Self::preferences) with `quality` as the maximum
+    /// height when it is set.
+    pub fn effective_preferences(&self) -> FormatPreferences {
+        FormatPreferences {
+            max_height: self.quality.or(self.preferences.max_height),
+            ..self.preferences.clone()
+        }
+    }
 }
 
 /// Build the yt-dlp argument vector for the given options, URL and output path.
@@ -202,6 +235,7 @@ pub fn build_ytdlp_args(
     aria2c_available: bool,
 ) -> Vec<String> {
     let mut args: Vec<String> = Vec::new();
+    let preferences = opts.effective_preferences();
 
     if opts.audio_only {
         args.push("-x".to_string());
@@ -209,19 +243,53 @@ pub fn build_ytdlp_args(
             args.push("--audio-format".to_string());
             args.push(fmt.clone());
         }
+        if let Some(selector) = opts.format_id.clone().or_else(|| {
+            opts.format_selector
+                .clone()
+                .or_else(|| preferences.audio_selector())
+                .map(|selector| selector.to_string())
+        }) {
+            args.push("-f".to_string());
+            args.push(selector);
+        }
     } else {
-        let selector = match opts.quality {
-            Some(h) => {
+        let selector = match (
+            &opts.format_id,
+            opts.format_selector
+                .clone()
+                .or_else(|| preferences.selector()),
+            opts.quality,
+        ) {
+            (Some(id), _, _) => id.clone(),
+            (None, Some(selector), _) => selector.to_string(),
+            (None, None, Some(h)) => {
                 format!("bestvideo[height<={h}]+bestaudio/best[height<={h}]/best")
             }
             // A bare `best` makes yt-dlp reject HLS master playlists with
             // "Requested format is not available". `bestvideo*+bestaudio/best`
             // resolves HLS/DASH variants (merging video+audio when split) and
             // still falls back to a single progressive stream via `/best`.
-            None => "bestvideo*+bestaudio/best".to_string(),
+            (None, None, None) => "bestvideo*+bestaudio/best".to_string(),
         };
         args.push("-f".to_string());
         args.push(selector);
+
+        if let Some(container) = preferences.container {
+            args.push("--merge-output-format".to_string());
+            args.push(container.to_string());
+            args.push("--remux-video".to_string());
+            args.push(container.to_string());
+        }
+    }
+
+    let sort = opts
+        .format_sort
+        .clone()
+        .unwrap_or_default()
+        .then(&preferences.format_sort());
+    if !sort.is_empty() {
+        args.push("-S".to_string());
+        args.push(sort.to_string());
     }
 
     if let Some(bitrate) = &opts.audio_bitrate {
@@ -230,15 +298,31 @@ pub fn build_ytdlp_args(
     }
 
     if opts.subtitles {
+        let subs = &opts.subtitle_options;
         args.push("--write-subs".to_string());
+        if subs.auto_generated {
+            args.push("--write-auto-subs".to_string());
+        }
         args.push("--sub-langs".to_string());
-        args.push("all".to_string());
+        args.push(subs.sub_langs());
+        if let Some(format) = subs.format {
+            args.push("--convert-subs".to_string());
+            args.push(format.to_string());
+        }
+        // Audio files have nowhere to embed them.
+        if subs.embed && !opts.audio_only {
+            args.push("--embed-subs".to_string());
+        }
     }
 
     if opts.playlist {
         args.push("--yes-playlist".to_string());
     }
 
+    if let Some(live) = &opts.live {
+        args.extend(live_args(live));
+    }
+
     if opts.start_time.is_some() || opts.end_time.is_some() {
         let start = opts.start_time.clone().unwrap_or_else(|| "0".to_string());
         let end = opts.end_time.clone().unwrap_or_else(|| "inf".to_string());
@@ -296,6 +380,7 @@ pub struct DownloadEngine {
     client: Client,
     config: DownloadConfig,
     ytdlp_options: YtDlpOptions,
+    live_options: LiveOptions,
 }
 
 /// Upper bound on establishing a connection (TCP + TLS handshake) for the
@@ -322,19 +407,26 @@ fn simple_temp_path(output_path: &Path) -> PathBuf {
     }
 }
 
+/// The client for the engine's own requests, sending `jar`'s cookies when
+/// one is given.
+fn http_client(jar: Option<Arc<CookieJar>>) -> Client {
+    let mut builder = Client::builder()
+        .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/91.0.4472.124 Safari/537.36")
+        .connect_timeout(CONNECT_TIMEOUT);
+    if let Some(jar) = jar {
+        builder = builder.cookie_provider(jar);
+    }
+    builder.build().expect("Failed to create HTTP client")
+}
+
 impl DownloadEngine {
     /// Create new download engine with configuration
     pub fn new(config: DownloadConfig) -> Self {
-        let client = Client::builder()
-            .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/91.0.4472.124 Safari/537.36")
-            .connect_timeout(CONNECT_TIMEOUT)
-            .build()
-            .expect("Failed to create HTTP client");
-
         Self {
-            client,
+            client: http_client(None),
             config,
             ytdlp_options: YtDlpOptions::default(),
+            live_options: LiveOptions::default(),
         }
     }
 
@@ -345,6 +437,68 @@ impl DownloadEngine {
         self
     }
 
+    /// Send `jar`'s cookies with the engine's own requests — the probe,
+    /// segments and simple downloads — and keep the ones responses set
+    /// (builder-style). yt-dlp downloads get their cookies from the
+    /// [`YtDlpOptions::cookies`] arguments instead.
+    pub fn with_cookie_jar(mut self, jar: Arc<CookieJar>) -> Self {
+        self.client = http_client(Some(jar));
+        self
+    }
+
+    /// Configure how [`record_live`](Self::record_live) records streams
+    /// (builder-style).
+    pub fn with_live_options(mut self, options: LiveOptions) -> Self {
+        self.live_options = options;
+        self
+    }
+
+    pub fn live_options(&self) -> &LiveOptions {
+        &self.live_options
+    }
+
+    /// Record the live or upcoming stream `info` through yt-dlp, waiting for
+    /// it to start if it is scheduled, until it ends, a limit of the
+    /// configured [`LiveOptions`] is reached or `stop` is cancelled. The
+    /// result is remuxed into the preferred container (MKV by default, which
+    /// takes any codec) and its path returned.
+    pub async fn record_live(
+        &self,
+        info: &VideoInfo,
+        output_path: &Path,
+        format_id: Option<&str>,
+        stop: CancellationToken,
+        progress: impl ProgressSink + 'static,
+    ) -> Result<PathBuf> {
+        let options = YtDlpOptions {
+            format_id: format_id
+                .map(str::to_string)
+                .or_else(|| self.ytdlp_options.format_id.clone()),
+            ..self.ytdlp_options.clone()
+        };
+        let ext = if options.audio_only {
+            options
+                .audio_format
+                .clone()
+                .unwrap_or_else(|| "m4a".to_string())
+        } else {
+            options
+                .effective_preferences()
+                .container
+                .map_or_else(|| "mkv".to_string(), |c| c.to_string())
+        };
+        live::record(
+            &options,
+            &self.live_options,
+            info,
+            output_path,
+            &ext,
+            stop,
+            Arc::new(progress),
+        )
+        .await
+    }
+
     /// Download file with progress tracking.
     ///
     /// `output_path`'s extension is treated as **provisional** (callers derive
@@ -357,12 +511,31 @@ impl DownloadEngine {
     /// to the caller's `output_path`, so cancel/remove cleanup and the
     /// cross-session resume identity guard are unaffected; only the final
     /// rename adopts the corrected name.
+    ///
+    /// Progress is reported to `progress` without ever waiting on its
+    /// consumer (see [`ProgressSink`]); an `mpsc::Sender<DownloadProgress>`
+    /// still works and drops updates when its buffer is full.
     pub async fn download(
         &self,
         url: &str,
         output_path: &Path,
-        progress_tx: mpsc::Sender<DownloadProgress>,
+        progress: impl ProgressSink + 'static,
     ) -> Result<PathBuf> {
+        self.download_format(url, output_path, None, progress).await
+    }
+
+    /// [`download`](Self::download) with a per-download yt-dlp format
+    /// selection (`-f`, e.g. `137+140`) that overrides the configured
+    /// [`YtDlpOptions::format_id`]/`quality`. Only the yt-dlp path uses it: a
+    /// direct media URL already *is* the chosen format.
+    pub async fn download_format(
+        &self,
+        url: &str,
+        output_path: &Path,
+        format_id: Option<&str>,
+        progress: impl ProgressSink + 'static,
+    ) -> Result<PathBuf> {
+        let progress_tx: SharedProgressSink = Arc::new(progress);
         debug!("🚀🚀🚀 [ENGINE-ENTRY] download() ENTERED - First line executed!");
         debug!("    URL: {}", url);
         debug!("    Output: {:?}", output_path);
@@ -371,12 +544,7 @@ impl DownloadEngine {
         debug!("📤 [ENGINE] Sending initial progress (Initializing)...");
         let mut initial = DownloadProgress::new(0, 1);
         initial.status = DownloadStatus::Initializing;
-        if let Err(e) = progress_tx.send(initial.clone()).await {
-            error!("⚠️ [ENGINE] Failed to send initial progress: {}", e);
-            warn!("Failed to send initial progress: {}", e);
-        } else {
-            debug!("✅ [ENGINE] Initial progress sent");
-        }
+        progress_tx.report(&initial);
 
         // Route on WHAT THE URL ACTUALLY IS, not its site name. Probe the URL
         // with a SINGLE ranged GET (`Range: bytes=0-0`) and let the response's
@@ -407,7 +575,9 @@ impl DownloadEngine {
             Err(e) => {
                 info!("🔀 [ENGINE] Taking path: yt-dlp fallback (probe failed)");
                 warn!("⚠️ [ENGINE] Probe failed, falling back to yt-dlp: {}", e);
-                return self.download_via_ytdlp(url, output_path, progress_tx).await;
+                return self
+                    .download_via_ytdlp(url, output_path, format_id, progress_tx)
+                    .await;
             }
         };
 
@@ -418,7 +588,9 @@ impl DownloadEngine {
                 "🔀 [ENGINE] Taking path: yt-dlp (not a direct media URL; content_type={:?})",
                 probe.content_type
             );
-            return self.download_via_ytdlp(url, output_path, progress_tx).await;
+            return self
+                .download_via_ytdlp(url, output_path, format_id, progress_tx)
+                .await;
         }
 
         info!(
@@ -446,13 +618,7 @@ impl DownloadEngine {
 
         // Send initial progress
         debug!("📤 [ENGINE] Sending initial progress (based on probed file size)...");
-        if let Err(e) = progress_tx.send(progress.clone()).await {
-            warn!(
-                "⚠️ [ENGINE] Failed to send initial progress (probed): {}",
-                e
-            );
-            warn!("Failed to send initial progress: {}", e);
-        }
+        progress_tx.report(&progress);
 
         // Determine download strategy
         if !supports_ranges || file_size < 1024 * 1024 {
@@ -594,13 +760,7 @@ impl DownloadEngine {
                     progress.update(total_downloaded, speed);
                     progress.status = DownloadStatus::Downloading;
 
-                    if let Err(e) = progress_tx_clone.send(progress).await {
-                        warn!("⚠️ [ENGINE] Failed to send aggregated progress: {}", e);
-                        warn!("Failed to send progress update: {}", e);
-                        break;
-                    } else {
-                        debug!("✅ [ENGINE] Sent aggregated progress to progress_tx_clone");
-                    }
+                    progress_tx_clone.report(&progress);
 
                     last_update_time = now;
                     last_downloaded = total_downloaded;
@@ -612,7 +772,7 @@ impl DownloadEngine {
         // `DownloadStatus::Stalled` event if no forward progress is observed
         // within the stall threshold (`STALL_DETECTION_SECONDS`). This is the
         // automatic stall detection the engine previously lacked; it flows
-        // through the same `progress_tx` the GUI and CLI already consume.
+        // through the same progress sink the GUI and CLI already observe.
         let stall_progress = Arc::clone(&segment_progress_clone);
         let stall_tx = progress_tx.clone();
         let stall_watchdog = tokio::spawn(async move {
@@ -634,9 +794,7 @@ impl DownloadEngine {
                     let mut progress = DownloadProgress::new(file_size, segments_count);
                     progress.downloaded_bytes = downloaded;
                     progress.stalled();
-                    if stall_tx.send(progress).await.is_err() {
-                        break;
-                    }
+                    stall_tx.report(&progress);
                 }
             }
         });
@@ -667,9 +825,7 @@ impl DownloadEngine {
             let mut failed_progress = progress.clone();
             failed_progress.failed(error.to_string());
 
-            if let Err(e) = progress_tx.send(failed_progress).await {
-                warn!("Failed to send failed progress: {}", e);
-            }
+            progress_tx.report(&failed_progress);
 
             return Err(error);
         }
@@ -677,9 +833,7 @@ impl DownloadEngine {
         // Update progress to merging state
         progress.update_segment(completed_segments);
         progress.status = DownloadStatus::Merging;
-        if let Err(e) = progress_tx.send(progress.clone()).await {
-            warn!("Failed to send merging progress: {}", e);
-        }
+        progress_tx.report(&progress);
 
         // Create channel for merge progress
         let (merge_progress_tx, mut merge_progress_rx) = mpsc::channel::<MergeProgress>(10);
@@ -703,10 +857,7 @@ impl DownloadEngine {
             progress.downloaded_bytes = merge_progress.total_bytes;
             progress.segments_completed = merge_progress.segment_index + 1;
 
-            if let Err(e) = progress_tx.send(progress.clone()).await {
-                warn!("Failed to send merge progress: {}", e);
-                break;
-            }
+            progress_tx.report(&progress);
         }
 
         // Wait for merge to complete
@@ -716,9 +867,7 @@ impl DownloadEngine {
             let mut failed_progress = progress.clone();
             failed_progress.failed(e.to_string());
 
-            if let Err(e) = progress_tx.send(failed_progress).await {
-                warn!("Failed to send merge failed progress: {}", e);
-            }
+            progress_tx.report(&failed_progress);
 
             return Err(e);
         }
@@ -750,9 +899,7 @@ impl DownloadEngine {
 
         // Mark as completed
         progress.complete();
-        if let Err(e) = progress_tx.send(progress).await {
-            warn!("Failed to send completed progress: {}", e);
-        }
+        progress_tx.report(&progress);
 
         Ok(final_path)
     }
@@ -767,21 +914,20 @@ impl DownloadEngine {
         &self,
         url: &str,
         output_path: &Path,
-        progress_tx: mpsc::Sender<DownloadProgress>,
+        format_id: Option<&str>,
+        progress_tx: SharedProgressSink,
     ) -> Result<PathBuf> {
         debug!("download_via_ytdlp called for URL: {}", url);
 
-        // Clone the sender before spawning any background tasks and
-        // use a best-effort initial send so a dropped receiver doesn't fail us.
+        // Clone the sink before spawning any background tasks.
         let progress_tx_for_spawn = progress_tx.clone();
 
-        debug!("📤 [YT-DLP] Sending initial 0% progress (best-effort)");
+        debug!("📤 [YT-DLP] Sending initial 0% progress");
         let mut initial = DownloadProgress::new(0, 1);
         initial.status = DownloadStatus::Downloading;
         initial.downloaded_bytes = 0;
         initial.speed = 0.0;
-        // Best-effort: don't propagate an error if receiver was dropped.
-        let _ = progress_tx_for_spawn.send(initial.clone()).await;
+        progress_tx_for_spawn.report(&initial);
 
         // Prepare command: yt-dlp with explicit progress flags. Arguments are
         // built from the configured options; with default options this is the
@@ -793,9 +939,19 @@ impl DownloadEngine {
         // like any other external tool).
         let aria2c_available = self.ytdlp_options.use_aria2c && find_aria2c().is_some();
         debug!("🔧 [YT-DLP] aria2c_available={}", aria2c_available);
-        let args = build_ytdlp_args(&self.ytdlp_options, url, &out, aria2c_available);
+        let args = match format_id {
+            Some(id) => {
+                let options = YtDlpOptions {
+                    format_id: Some(id.to_string()),
+                    ..self.ytdlp_options.clone()
+                };
+                build_ytdlp_args(&options, url, &out, aria2c_available)
+            }
+            None => build_ytdlp_args(&self.ytdlp_options, url, &out, aria2c_available),
+        };
         debug!("🔧 [YT-DLP] Args: {:?}", args);
-        let mut cmd = AsyncCommand::new("yt-dlp");
+        let ytdlp = find_ytdlp().ok_or(RustloaderError::YtDlpNotFound)?;
+        let mut cmd = AsyncCommand::new(ytdlp);
         cmd.args(&args);
         // Combine stderr and stdout to capture all output
         cmd.stderr(Stdio::piped());
@@ -830,7 +986,7 @@ impl DownloadEngine {
                         error!("❌ [YT-DLP] Error detected: {}", line);
                         let mut p = DownloadProgress::new(100, 1);
                         p.status = DownloadStatus::Failed(line.clone());
-                        let _ = progress_for_reader.send(p).await;
+                        progress_for_reader.report(&p);
                         break;
                     }
 
@@ -853,11 +1009,7 @@ impl DownloadEngine {
                         p.speed = speed_bps;
                         p.segments_completed = 0;
 
-                        // Best-effort send: don't treat a closed receiver as fatal here
-                        if let Err(e) = progress_for_reader.send(p).await {
-                            warn!("⚠️ [YT-DLP] Failed to send parsed progress: {}", e);
-                            break;
-                        }
+                        progress_for_reader.report(&p);
                     }
                 }
 
@@ -897,7 +1049,7 @@ impl DownloadEngine {
                         p.speed = speed_bps;
                         p.segments_completed = 0;
 
-                        let _ = progress_for_stdout.send(p).await;
+                        progress_for_stdout.report(&p);
                     }
                 }
             });
@@ -932,7 +1084,7 @@ impl DownloadEngine {
             done.downloaded_bytes = 0;
             done.speed = 0.0;
             done.complete();
-            let _ = progress_tx.send(done).await;
+            progress_tx.report(&done);
             // Adopt the file yt-dlp actually wrote (its real container may
             // differ from the caller's provisional extension). Best-effort:
             // fall back to the caller's path if discovery finds nothing
@@ -945,7 +1097,7 @@ impl DownloadEngine {
             error!("❌ [YT-DLP] Download failed");
             let mut failed = DownloadProgress::new(0, 1);
             failed.failed("yt-dlp failed".to_string());
-            let _ = progress_tx.send(failed).await;
+            progress_tx.report(&failed);
             Err(anyhow::anyhow!("yt-dlp download failed"))
         }
     }
@@ -959,7 +1111,7 @@ impl DownloadEngine {
         url: &str,
         output_path: &Path,
         final_path: &Path,
-        progress_tx: mpsc::Sender<DownloadProgress>,
+        progress_tx: SharedProgressSink,
     ) -> Result<PathBuf> {
         debug!("Using simple download for URL: {}", url);
 
@@ -1004,9 +1156,7 @@ impl DownloadEngine {
         progress.status = DownloadStatus::Downloading;
 
         // Send initial progress
-        if let Err(e) = progress_tx.send(progress.clone()).await {
-            warn!("Failed to send initial progress: {}", e);
-        }
+        progress_tx.report(&progress);
 
         // Stream into a temp part file next to the output and rename into
         // place only on success, so a failed simple download never leaves a
@@ -1064,10 +1214,7 @@ impl DownloadEngine {
                     progress.downloaded_bytes = downloaded;
                     progress.speed = speed;
 
-                    if let Err(e) = progress_tx.send(progress.clone()).await {
-                        warn!("Failed to send progress update: {}", e);
-                        break;
-                    }
+                    progress_tx.report(&progress);
 
                     last_update_time = now;
                     last_downloaded = downloaded;
@@ -1109,9 +1256,7 @@ impl DownloadEngine {
         progress.speed = speed;
         progress.complete();
 
-        if let Err(e) = progress_tx.send(progress).await {
-            warn!("Failed to send final progress: {}", e);
-        }
+        progress_tx.report(&progress);
 
         Ok(final_path.to_path_buf())
     }
@@ -1130,7 +1275,7 @@ impl DownloadEngine {
     /// usable length, which makes the caller fall back to the simple
     /// (non-segmented) download path; `content_type` drives media-vs-yt-dlp
     /// routing (see [`is_direct_media`]).
-    async fn probe(&self, url: &str) -> Result<ProbeResult> {
+    pub async fn probe(&self, url: &str) -> Result<ProbeResult> {
         let response = tokio::time::timeout(
             std::time::Duration::from_secs(10),
             self.client.get(url).header("Range", "bytes=0-0").send(),
@@ -1196,13 +1341,13 @@ impl DownloadEngine {
 /// supports byte ranges, the total size (`0` if unknown), and the response
 /// `Content-Type` (used by [`is_direct_media`] to route media vs yt-dlp).
 #[derive(Debug, Clone)]
-struct ProbeResult {
-    supports_ranges: bool,
-    size: u64,
-    content_type: Option<String>,
+pub struct ProbeResult {
+    pub supports_ranges: bool,
+    pub size: u64,
+    pub content_type: Option<String>,
     /// The URL the response actually came from (after redirects); its path
     /// extension is the fallback for `application/octet-stream` responses.
-    final_url: Option<String>,
+    pub final_url: Option<String>,
 }
 
 /// Decide whether a `Content-Type` denotes a directly-downloadable media stream
@@ -1215,7 +1360,7 @@ struct ProbeResult {
 /// missing header — is treated as "not a direct media file" and routed to yt-dlp,
 /// which resolves the real streams. This is what makes engine coverage equal
 /// yt-dlp's without any per-site logic.
-fn is_direct_media(content_type: Option<&str>) -> bool {
+pub(crate) fn is_direct_media(content_type: Option<&str>) -> bool {
     match content_type {
         Some(ct) => {
             // Strip any `; charset=...`/parameters and normalise case.
@@ -1246,7 +1391,7 @@ fn is_direct_media(content_type: Option<&str>) -> bool {
 /// `application/octet-stream` — a generic binary says nothing about the
 /// container, so the caller falls back to the URL path's extension — and for
 /// any type not in the map.
-fn extension_for_content_type(content_type: &str) -> Option<&'static str> {
+pub(crate) fn extension_for_content_type(content_type: &str) -> Option<&'static str> {
     let main = content_type
         .split(';')
         .next()
@@ -1280,7 +1425,7 @@ fn extension_for_content_type(content_type: &str) -> Option<&'static str> {
 /// 1–5 ASCII alphanumeric characters after the last dot of the last path
 /// segment. Query string and fragment never leak in because the URL is
 /// actually parsed, not string-split.
-fn extension_from_url(url: &str) -> Option<String> {
+pub(crate) fn extension_from_url(url: &str) -> Option<String> {
     let parsed = reqwest::Url::parse(url).ok()?;
     // A trailing-slash URL yields an empty last segment, which has no dot and
     // so correctly produces no extension below.
@@ -1387,6 +1532,7 @@ impl Default for DownloadEngine {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::extractor::{Container, DynamicRange, SubtitleFormat};
 
     // ============================================================
     // CONFIGURATION TESTS
@@ -1641,6 +1787,90 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_build_ytdlp_args_format_id_overrides_quality() {
+        let opts = YtDlpOptions {
+            quality: Some(720),
+            format_id: Some("137+140".to_string()),
+            ..Default::default()
+        };
+        let args = build_ytdlp_args(&opts, "URL", "/out.mp4", false);
+        assert_eq!(&args[..2], ["-f", "137+140"]);
+        assert!(!args.iter().any(|a| a.contains("height<=")));
+
+        // Audio extraction keeps `-x` and extracts from the picked stream.
+        let opts = YtDlpOptions {
+            audio_only: true,
+            format_id: Some("140".to_string()),
+            ..Default::default()
+        };
+        let args = build_ytdlp_args(&opts, "URL", "/out.mp3", false);
+        assert!(args.iter().any(|a| a == "-x"));
+        assert!(args.windows(2).any(|w| w == ["-f", "140"]));
+    }
+
+    #[test]
+    fn test_build_ytdlp_args_preferences() {
+        let opts = YtDlpOptions {
+            quality: Some(2160),
+            format_sort: Some("+size".parse().unwrap()),
+            preferences: FormatPreferences::default()
+                .with_max_height(1080)
+                .with_container(Container::Mkv)
+                .with_dynamic_range(DynamicRange::Sdr)
+                .with_audio_languages(vec!["es".to_string()]),
+            ..Default::default()
+        };
+        let args = build_ytdlp_args(&opts, "URL", "/out.mp4", false);
+        assert_eq!(
+            &args[..8],
+            [
+                "-f",
+                "bv*+ba[language^=es]/bv*+ba/b",
+                "--merge-output-format",
+                "mkv",
+                "--remux-video",
+                "mkv",
+                "-S",
+                "+size,res:2160,+hdr"
+            ]
+        );
+
+        // Audio extraction takes the wanted language.
+        let opts = YtDlpOptions {
+            audio_only: true,
+            ..opts
+        };
+        let args = build_ytdlp_args(&opts, "URL", "/out.mp3", false);
+        assert!(args
+            .windows(2)
+            .any(|w| w == ["-f", "ba[language^=es]/ba/b"]));
+        assert!(!args.iter().any(|a| a == "--remux-video"));
+    }
+
+    #[test]
+    fn test_build_ytdlp_args_selector_expression_and_sort() {
+        let opts = YtDlpOptions {
+            quality: Some(720),
+            format_selector: Some("bv*[height<=1440]+ba/b".parse().unwrap()),
+            format_sort: Some("res,+size".parse().unwrap()),
+            ..Default::default()
+        };
+        let args = build_ytdlp_args(&opts, "URL", "/out.mp4", false);
+        assert_eq!(
+            &args[..4],
+            ["-f", "bv*[height<=1440]+ba/b", "-S", "res,+size"]
+        );
+
+        // An exact pick still wins over the expression.
+        let opts = YtDlpOptions {
+            format_id: Some("22".to_string()),
+            ..opts
+        };
+        let args = build_ytdlp_args(&opts, "URL", "/out.mp4", false);
+        assert_eq!(&args[..2], ["-f", "22"]);
+    }
+
     #[test]
     fn test_build_ytdlp_args_audio_only() {
         let opts = YtDlpOptions {
@@ -1668,6 +1898,44 @@ mod tests {
         assert!(args.iter().any(|a| a == "--write-subs"));
         assert!(args.windows(2).any(|w| w == ["--sub-langs", "all"]));
         assert!(args.iter().any(|a| a == "--yes-playlist"));
+        assert!(!args
+            .iter()
+            .any(|a| a == "--write-auto-subs" || a == "--embed-subs"));
+    }
+
+    #[test]
+    fn test_build_ytdlp_args_subtitle_selection() {
+        let opts = YtDlpOptions {
+            subtitles: true,
+            subtitle_options: SubtitleOptions::default()
+                .with_languages(vec!["en".to_string(), "es.*".to_string()])
+                .with_auto_generated(true)
+                .with_format(SubtitleFormat::Srt)
+                .with_embed(true),
+            ..Default::default()
+        };
+        let args = build_ytdlp_args(&opts, "URL", "/out.mp4", false);
+        let start = args.iter().position(|a| a == "--write-subs").unwrap();
+        assert_eq!(
+            &args[start..start + 7],
+            [
+                "--write-subs",
+                "--write-auto-subs",
+                "--sub-langs",
+                "en,es.*",
+                "--convert-subs",
+                "srt",
+                "--embed-subs"
+            ]
+        );
+
+        // Audio extractions keep sidecars.
+        let opts = YtDlpOptions {
+            audio_only: true,
+            ..opts
+        };
+        let args = build_ytdlp_args(&opts, "URL", "/out.mp3", false);
+        assert!(!args.iter().any(|a| a == "--embed-subs"));
     }
 
     #[test]
@@ -2630,4 +2898,58 @@ mod tests {
             "resume sidecar (keyed to the provisional name) must be cleaned up"
         );
     }
+
+    /// Answers with one byte of media when the request carries
+    /// `Cookie: auth=ok`, and `403 Forbidden` otherwise.
+    async fn spawn_cookie_gated_server() -> (String, tokio::task::JoinHandle<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("local_addr");
+
+        let handle = tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 8192];
+                    let mut req = Vec::new();
+                    loop {
+                        let n = match socket.read(&mut buf).await {
+                            Ok(0) | Err(_) => return,
+                            Ok(n) => n,
+                        };
+                        req.extend_from_slice(&buf[..n]);
+                        if req.windows(4).any(|w| w == b"\r\n\r\n") {
+                            break;
+                        }
+                    }
+                    let authorized = String::from_utf8_lossy(&req)
+                        .lines()
+                        .any(|l| l.eq_ignore_ascii_case("cookie: auth=ok"));
+                    let response: &[u8] = if authorized {
+                        b"HTTP/1.1 206 Partial Content\r\nContent-Range: bytes 0-0/10\r\nContent-Length: 1\r\nContent-Type: video/mp4\r\nConnection: close\r\n\r\nx"
+                    } else {
+                        b"HTTP/1.1 403 Forbidden\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                    };
+                    let _ = socket.write_all(response).await;
+                    let _ = socket.flush().await;
+                });
+            }
+        });
+
+        (format!("http://{}", addr), handle)
+    }
+
+    #[tokio::test]
+    async fn test_probe_sends_cookies_from_the_jar() {
+        let (base_url, _server) = spawn_cookie_gated_server().await;
+        let url = format!("{base_url}/private/clip.mp4");
+
+        assert!(DownloadEngine::default().probe(&url).await.is_err());
+
+        let jar = CookieJar::with_cookies(crate::utils::cookie_jar::parse_netscape(
+            "127.0.0.1\tFALSE\t/private\tFALSE\t0\tauth\tok\n",
+        ));
+        let engine = DownloadEngine::default().with_cookie_jar(Arc::new(jar));
+        let probe = engine.probe(&url).await.expect("cookie opens the gate");
+        assert!(probe.supports_ranges);
+        assert_eq!(probe.size, 10);
+    }
 }
@@ -0,0 +1,28 @@
+//! Running ffmpeg for the post-processing Rustloader does itself (cover art,
+//! chapters), so files from the native engine and from yt-dlp end up alike.
+
+use crate::extractor::ytdlp::find_ffmpeg;
+use anyhow::{Context, Result};
+use std::process::Stdio;
+use tokio::process::Command;
+use tracing::debug;
+
+/// Run ffmpeg with `args`. `Ok(false)` when ffmpeg is not installed; an
+/// error carrying ffmpeg's own message when it fails.
+pub async fn run(args: &[String]) -> Result<bool> {
+    let Some(ffmpeg) = find_ffmpeg() else {
+        debug!("ffmpeg not found; skipping post-processing");
+        return Ok(false);
+    };
+    let result = Command::new(ffmpeg)
+        .args(args)
+        .stdin(Stdio::null())
+        .kill_on_drop(true)
+        .output()
+        .await
+        .context("Failed to run ffmpeg")?;
+    if !result.status.success() {
+        anyhow::bail!("{}", String::from_utf8_lossy(&result.stderr).trim());
+    }
+    Ok(true)
+}
@@ -0,0 +1,617 @@
+//! Live streams: recording a broadcast as it happens, waiting for scheduled
+//! streams and premieres, and turning what was recorded into a regular file.
+//!
+//! A recording has no end to measure progress against, so instead of a
+//! percentage it reports how long it has recorded and how much. It runs
+//! until the stream ends, a time or size limit is reached, or it is stopped;
+//! yt-dlp writes MPEG-TS without `.part` files, so whatever was written up
+//! to then is playable and ffmpeg remuxes it into the final container.
+
+use super::engine::{build_ytdlp_args, ytdlp_output_template, YtDlpOptions};
+use super::ffmpeg;
+use super::progress::{DownloadProgress, DownloadStatus};
+use super::sink::SharedProgressSink;
+use crate::extractor::ytdlp::find_ytdlp;
+use crate::extractor::VideoInfo;
+use crate::utils::RustloaderError;
+use anyhow::Result;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::fs;
+use tokio::process::{Child, Command};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+/// How often a recording reports progress and checks its limits.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long a stopped recording gets to finish its files and exit before it
+/// is killed.
+const STOP_GRACE: Duration = Duration::from_secs(10);
+
+/// How long yt-dlp waits between checks whether a scheduled stream started.
+const WAIT_RETRY_SECS: &str = "15";
+
+/// How live streams are recorded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LiveOptions {
+    /// Record from the beginning of the broadcast when the site keeps it
+    /// (DVR), instead of from now.
+    pub from_start: bool,
+    /// Wait for scheduled streams and premieres to start instead of failing.
+    pub wait_for_start: bool,
+    /// Stop after recording this many seconds.
+    pub max_duration: Option<u64>,
+    /// Stop once the recording reaches this many bytes.
+    pub max_size: Option<u64>,
+}
+
+impl Default for LiveOptions {
+    fn default() -> Self {
+        Self {
+            from_start: false,
+            wait_for_start: true,
+            max_duration: None,
+            max_size: None,
+        }
+    }
+}
+
+impl LiveOptions {
+    pub fn with_from_start(mut self, from_start: bool) -> Self {
+        self.from_start = from_start;
+        self
+    }
+
+    pub fn with_wait_for_start(mut self, wait_for_start: bool) -> Self {
+        self.wait_for_start = wait_for_start;
+        self
+    }
+
+    pub fn with_max_duration(mut self, seconds: u64) -> Self {
+        self.max_duration = Some(seconds);
+        self
+    }
+
+    pub fn with_max_size(mut self, bytes: u64) -> Self {
+        self.max_size = Some(bytes);
+        self
+    }
+
+    /// The limit a recording of `elapsed` and `bytes` has reached, if any.
+    pub fn limit_reached(&self, elapsed: Duration, bytes: u64) -> Option<StopReason> {
+        if self
+            .max_duration
+            .is_some_and(|max| elapsed.as_secs() >= max)
+        {
+            Some(StopReason::TimeLimit)
+        } else if self.max_size.is_some_and(|max| bytes >= max) {
+            Some(StopReason::SizeLimit)
+        } else {
+            None
+        }
+    }
+}
+
+/// Why a recording ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    /// The broadcast ended (or yt-dlp gave up on it).
+    Ended,
+    TimeLimit,
+    SizeLimit,
+    /// Stopped by the user.
+    Stopped,
+}
+
+/// The yt-dlp arguments that make a download a recording: MPEG-TS written
+/// straight to the output (so it stays playable when cut off), retries until
+/// a scheduled stream starts when waiting is wanted, and optionally the
+/// broadcast from its start.
+pub fn live_args(live: &LiveOptions) -> Vec<String> {
+    let mut args: Vec<String> = ["--hls-use-mpegts", "--no-part"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    if live.wait_for_start {
+        args.extend(["--wait-for-video".to_string(), WAIT_RETRY_SECS.to_string()]);
+    }
+    if live.from_start {
+        args.push("--live-from-start".to_string());
+    }
+    args
+}
+
+/// Parse a size such as `500M`, `4G`, `1.5GiB` or plain bytes. Suffixes are
+/// binary (`K` = 1024), as yt-dlp's are.
+pub fn parse_size(s: &str) -> Result<u64, String> {
+    let s = s.trim();
+    let invalid = || format!("'{s}' is not a size (expected e.g. 500M or 4G)");
+    let lower = s.to_ascii_lowercase();
+    let unit_start = lower
+        .find(|c: char| c.is_ascii_alphabetic())
+        .unwrap_or(lower.len());
+    let (number, unit) = lower.split_at(unit_start);
+    let number: f64 = number.trim().parse().map_err(|_| invalid())?;
+    let exponent = match unit.trim_end_matches("ib").trim_end_matches('b') {
+        "" => 0,
+        "k" => 1,
+        "m" => 2,
+        "g" => 3,
+        "t" => 4,
+        _ => return Err(invalid()),
+    };
+    if number < 0.0 {
+        return Err(invalid());
+    }
+    Ok((number * 1024f64.powi(exponent)) as u64)
+}
+
+/// `bytes` in the largest whole unit [`parse_size`] reads back, e.g. `4G`.
+pub fn format_size(bytes: u64) -> String {
+    ["T", "G", "M", "K"]
+        .iter()
+        .zip((1..=4).rev())
+        .map(|(unit, exponent)| (unit, 1024u64.pow(exponent)))
+        .find(|(_, size)| bytes >= *size && bytes.is_multiple_of(*size))
+        .map_or_else(
+            || bytes.to_string(),
+            |(unit, size)| format!("{}{unit}", bytes / size),
+        )
+}
+
+/// Where the recording of `output_path` is written before it is finalized: a
+/// hidden directory next to it, unique to this recording, so nothing else in
+/// the output folder is ever taken for part of it.
+pub fn recording_dir(output_path: &Path) -> PathBuf {
+    let stem = output_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let parent = match output_path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    parent.join(format!(".{stem}.{}.recording", Uuid::new_v4().simple()))
+}
+
+/// The files yt-dlp wrote into the recording directory `dir`: one, or a
+/// video and an audio stream when recording from the start. Thumbnails,
+/// subtitles and yt-dlp's bookkeeping files are not part of it.
+pub async fn recording_pieces(dir: &Path) -> Vec<PathBuf> {
+    let Ok(mut entries) = fs::read_dir(dir).await else {
+        return Vec::new();
+    };
+    let mut pieces = Vec::new();
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_ascii_lowercase();
+        let sidecar = matches!(
+            ext.as_str(),
+            "jpg" | "jpeg" | "png" | "webp" | "vtt" | "srt" | "ass" | "json" | "ytdl" | "txt"
+        );
+        if !sidecar && entry.file_type().await.is_ok_and(|t| t.is_file()) {
+            pieces.push(path);
+        }
+    }
+    pieces.sort();
+    pieces
+}
+
+async fn recorded_bytes(dir: &Path) -> u64 {
+    let mut total = 0;
+    for piece in recording_pieces(dir).await {
+        total += fs::metadata(&piece).await.map(|m| m.len()).unwrap_or(0);
+    }
+    total
+}
+
+/// The ffmpeg arguments that copy every stream of `pieces` into `output`.
+pub fn finalize_args(pieces: &[PathBuf], output: &Path) -> Vec<String> {
+    let mut args: Vec<String> = vec!["-y".into(), "-loglevel".into(), "error".into()];
+    for piece in pieces {
+        args.push("-i".into());
+        args.push(piece.to_string_lossy().to_string());
+    }
+    for index in 0..pieces.len() {
+        args.push("-map".into());
+        args.push(index.to_string());
+    }
+    args.extend(["-c".into(), "copy".into()]);
+    args.push(output.to_string_lossy().to_string());
+    args
+}
+
+/// Remux the recorded `pieces` into `<stem>.<ext>` next to `output_path`.
+/// Without ffmpeg (or when it cannot read them) the largest piece is moved
+/// there as it is, so a recording is never lost.
+pub async fn finalize_recording(
+    pieces: &[PathBuf],
+    output_path: &Path,
+    ext: &str,
+) -> Result<PathBuf> {
+    let Some(largest) = largest_piece(pieces).await else {
+        return Err(RustloaderError::DownloadError("nothing was recorded".to_string()).into());
+    };
+    let target = output_path.with_extension(ext);
+    let temp = output_path.with_extension(format!("recording.{ext}"));
+    match ffmpeg::run(&finalize_args(pieces, &temp)).await {
+        Ok(true) => {
+            for piece in pieces {
+                let _ = fs::remove_file(piece).await;
+            }
+            fs::rename(&temp, &target).await?;
+            return Ok(target);
+        }
+        Ok(false) => {}
+        Err(e) => {
+            let _ = fs::remove_file(&temp).await;
+            warn!("Could not remux the recording: {e}; keeping it as recorded");
+        }
+    }
+    let kept = match largest.extension() {
+        Some(ext) => output_path.with_extension(ext),
+        None => output_path.to_path_buf(),
+    };
+    fs::rename(&largest, &kept).await?;
+    Ok(kept)
+}
+
+async fn largest_piece(pieces: &[PathBuf]) -> Option<PathBuf> {
+    let mut largest: Option<(u64, &PathBuf)> = None;
+    for piece in pieces {
+        let size = fs::metadata(piece).await.map(|m| m.len()).unwrap_or(0);
+        if largest.is_none_or(|(max, _)| size > max) {
+            largest = Some((size, piece));
+        }
+    }
+    largest.map(|(_, piece)| piece.clone())
+}
+
+/// Removes a recording directory once the recording is finished with it,
+/// including when it is cancelled mid-way (its future dropped).
+struct RemoveOnDrop(PathBuf);
+
+impl Drop for RemoveOnDrop {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_dir_all(&self.0) {
+            debug!("Could not remove {}: {}", self.0.display(), e);
+        }
+    }
+}
+
+/// Kills the process group of a recording's yt-dlp, and with it the ffmpeg
+/// yt-dlp hands HLS transfers to, if the recording is dropped mid-way.
+/// `kill_on_drop` alone would leave that ffmpeg recording on its own.
+struct KillGroupOnDrop(Option<u32>);
+
+impl KillGroupOnDrop {
+    fn disarm(&mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for KillGroupOnDrop {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Some(pid) = self.0 {
+            signal_group(pid, libc::SIGKILL);
+        }
+    }
+}
+
+/// Send `signal` to every process in the group led by `pid`. Whether any
+/// process was left to receive it.
+#[cfg(unix)]
+fn signal_group(pid: u32, signal: libc::c_int) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // SAFETY: kill(2) has no memory-safety preconditions.
+    unsafe { libc::kill(-pid, signal) == 0 }
+}
+
+/// Stop a recording the way Ctrl-C would: SIGINT to yt-dlp's process group,
+/// so yt-dlp and its ffmpeg both finish the file they are writing and exit.
+/// Whatever is still running after `grace` is killed. Elsewhere only yt-dlp
+/// itself can be stopped.
+#[cfg_attr(not(unix), allow(unused_variables))]
+async fn stop_recording(child: &mut Child, grace: Duration) {
+    #[cfg(unix)]
+    if let Some(pid) = child.id() {
+        let deadline = tokio::time::Instant::now() + grace;
+        signal_group(pid, libc::SIGINT);
+        let _ = tokio::time::timeout_at(deadline, child.wait()).await;
+        // yt-dlp may exit before its ffmpeg has finished writing.
+        while signal_group(pid, 0) && tokio::time::Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        signal_group(pid, libc::SIGKILL);
+    }
+    let _ = child.start_kill();
+    let _ = child.wait().await;
+}
+
+/// Record the live (or upcoming) stream `info` into `output_path`,
+/// whose extension becomes `ext`. Returns the finished file.
+pub async fn record(
+    options: &YtDlpOptions,
+    live: &LiveOptions,
+    info: &VideoInfo,
+    output_path: &Path,
+    ext: &str,
+    stop: CancellationToken,
+    progress: SharedProgressSink,
+) -> Result<PathBuf> {
+    if let Some(starts_at) = info.scheduled_start() {
+        if !live.wait_for_start {
+            return Err(RustloaderError::DownloadError(format!(
+                "\"{}\" has not started yet (scheduled for {})",
+                info.title,
+                starts_at.to_rfc3339()
+            ))
+            .into());
+        }
+        let mut waiting = DownloadProgress::new(0, 1);
+        waiting.status = DownloadStatus::Waiting;
+        waiting.starts_at = Some(starts_at);
+        progress.report(&waiting);
+        info!("Waiting for \"{}\" to start at {}", info.title, starts_at);
+        let wait = (starts_at - Utc::now()).to_std().unwrap_or_default();
+        tokio::select! {
+            _ = tokio::time::sleep(wait) => {}
+            _ = stop.cancelled() => {
+                return Err(RustloaderError::DownloadError(
+                    "stopped before the stream started".to_string(),
+                )
+                .into());
+            }
+        }
+    }
+
+    let options = YtDlpOptions {
+        live: Some(live.clone()),
+        ..options.clone()
+    };
+    let url = &info.url;
+    let work_dir = recording_dir(output_path);
+    fs::create_dir_all(&work_dir).await?;
+    let _remove_work_dir = RemoveOnDrop(work_dir.clone());
+    let work_path = work_dir.join(output_path.file_name().unwrap_or_default());
+    let args = build_ytdlp_args(&options, url, &ytdlp_output_template(&work_path), false);
+    debug!("Recording {} with yt-dlp {:?}", url, args);
+    let ytdlp = find_ytdlp().ok_or(RustloaderError::YtDlpNotFound)?;
+    let mut command = Command::new(ytdlp);
+    command
+        .args(&args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    // Its own process group, so a stop reaches the ffmpeg it starts too.
+    #[cfg(unix)]
+    command.process_group(0);
+    let mut child = command
+        .spawn()
+        .map_err(|_| RustloaderError::YtDlpNotFound)?;
+    let mut kill_group = KillGroupOnDrop(child.id());
+
+    let last_error = Arc::new(Mutex::new(None::<String>));
+    if let Some(stderr) = child.stderr.take() {
+        let last_error = Arc::clone(&last_error);
+        tokio::spawn(async move {
+            use tokio::io::AsyncBufReadExt;
+            let mut lines = tokio::io::BufReader::new(stderr).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                debug!("[yt-dlp live] {}", line);
+                if line.contains("ERROR:") {
+                    *last_error.lock().unwrap_or_else(|p| p.into_inner()) = Some(line);
+                }
+            }
+        });
+    }
+
+    let mut ticker = tokio::time::interval(POLL_INTERVAL);
+    let mut recording_since: Option<Instant> = None;
+    let mut last_bytes = 0;
+    let mut report = DownloadProgress::new(0, 1);
+    report.status = DownloadStatus::Waiting;
+    report.starts_at = info.scheduled_start();
+    let (reason, exit) = loop {
+        tokio::select! {
+            exit = child.wait() => break (StopReason::Ended, Some(exit?)),
+            _ = stop.cancelled() => break (StopReason::Stopped, None),
+            _ = ticker.tick() => {
+                let bytes = recorded_bytes(&work_dir).await;
+                if bytes > 0 && recording_since.is_none() {
+                    recording_since = Some(Instant::now());
+                    report.status = DownloadStatus::Recording;
+                    report.starts_at = None;
+                }
+                let elapsed = recording_since.map(|since| since.elapsed());
+                report.elapsed = elapsed;
+                report.speed = bytes.saturating_sub(last_bytes) as f64 / POLL_INTERVAL.as_secs_f64();
+                report.downloaded_bytes = bytes;
+                last_bytes = bytes;
+                progress.report(&report);
+                if let Some(reason) = elapsed.and_then(|e| live.limit_reached(e, bytes)) {
+                    break (reason, None);
+                }
+            }
+        }
+    };
+    if exit.is_none() {
+        // The recording so far is complete MPEG-TS; stopping yt-dlp loses at
+        // most the segment in flight. Only once it and its ffmpeg are gone
+        // are the pieces finalized and the work directory removed.
+        stop_recording(&mut child, STOP_GRACE).await;
+    }
+    kill_group.disarm();
+    info!("Recording of {} ended: {:?}", url, reason);
+
+    let pieces = recording_pieces(&work_dir).await;
+    let failed = exit.is_some_and(|status| !status.success());
+    if pieces.is_empty() {
+        let message = match (
+            reason,
+            last_error.lock().unwrap_or_else(|p| p.into_inner()).take(),
+        ) {
+            (StopReason::Stopped, _) => "stopped before anything was recorded".to_string(),
+            (_, Some(error)) => error,
+            _ => "yt-dlp recorded nothing".to_string(),
+        };
+        let mut failed_progress = DownloadProgress::new(0, 1);
+        failed_progress.failed(message.clone());
+        progress.report(&failed_progress);
+        return Err(RustloaderError::DownloadError(message).into());
+    }
+    if failed {
+        warn!("yt-dlp exited with an error; keeping what was recorded");
+    }
+
+    let path = finalize_recording(&pieces, output_path, ext).await?;
+    report.status = DownloadStatus::Completed;
+    report.downloaded_bytes = fs::metadata(&path)
+        .await
+        .map(|m| m.len())
+        .unwrap_or(last_bytes);
+    report.speed = 0.0;
+    progress.report(&report);
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limits_stop_a_recording() {
+        let live = LiveOptions::default()
+            .with_max_duration(3600)
+            .with_max_size(parse_size("2G").unwrap());
+        assert_eq!(live.limit_reached(Duration::from_secs(60), 1024), None);
+        assert_eq!(
+            live.limit_reached(Duration::from_secs(3600), 0),
+            Some(StopReason::TimeLimit)
+        );
+        assert_eq!(
+            live.limit_reached(Duration::from_secs(1), 2 * 1024 * 1024 * 1024),
+            Some(StopReason::SizeLimit)
+        );
+        assert_eq!(
+            LiveOptions::default().limit_reached(Duration::MAX, u64::MAX),
+            None
+        );
+    }
+
+    #[test]
+    fn sizes_parse_with_binary_suffixes() {
+        assert_eq!(parse_size("1024"), Ok(1024));
+        assert_eq!(parse_size("500M"), Ok(500 * 1024 * 1024));
+        assert_eq!(parse_size("1.5GiB"), Ok(3 * 512 * 1024 * 1024));
+        assert_eq!(parse_size(" 2 kb "), Ok(2048));
+        assert!(parse_size("lots").is_err());
+        assert!(parse_size("5X").is_err());
+        assert_eq!(format_size(4 * 1024 * 1024 * 1024), "4G");
+        assert_eq!(format_size(1536 * 1024 * 1024), "1536M");
+        assert_eq!(format_size(1000), "1000");
+    }
+
+    #[test]
+    fn live_args_write_playable_output() {
+        let args = live_args(&LiveOptions::default());
+        assert!(args.iter().any(|a| a == "--hls-use-mpegts"));
+        assert!(args.iter().any(|a| a == "--no-part"));
+        assert!(args.windows(2).any(|w| w == ["--wait-for-video", "15"]));
+        assert!(!args.iter().any(|a| a == "--live-from-start"));
+        let args = live_args(&LiveOptions::default().with_from_start(true));
+        assert!(args.iter().any(|a| a == "--live-from-start"));
+        // Without waiting, a stream with no known start time fails in yt-dlp
+        // instead of holding a download slot until it begins.
+        let args = live_args(&LiveOptions::default().with_wait_for_start(false));
+        assert!(!args.iter().any(|a| a == "--wait-for-video"));
+
+        let pieces = [PathBuf::from("a.f1.mp4"), PathBuf::from("a.f2.m4a")];
+        let args = finalize_args(&pieces, Path::new("a.recording.mkv"));
+        assert!(args.windows(2).any(|w| w == ["-map", "1"]));
+        assert_eq!(args.last().map(String::as_str), Some("a.recording.mkv"));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn stopping_a_recording_stops_what_yt_dlp_started() {
+        use tokio::io::AsyncBufReadExt;
+
+        // A stand-in for yt-dlp that leaves a child (ffmpeg) running, one
+        // that ignores SIGINT so only the kill after the grace period ends it.
+        let mut command = Command::new("sh");
+        command
+            .args(["-c", "sleep 30 & echo $!; wait"])
+            .stdout(Stdio::piped())
+            .kill_on_drop(true)
+            .process_group(0);
+        let mut child = command.spawn().unwrap();
+        let stdout = child.stdout.take().unwrap();
+        let mut lines = tokio::io::BufReader::new(stdout).lines();
+        let grandchild: u32 = lines.next_line().await.unwrap().unwrap().parse().unwrap();
+
+        let started = Instant::now();
+        stop_recording(&mut child, Duration::from_millis(300)).await;
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(child.try_wait().unwrap().is_some());
+        // Gone, or at most a zombie waiting to be reaped.
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            let stat = std::fs::read_to_string(format!("/proc/{grandchild}/stat"));
+            let running = stat.is_ok_and(|stat| {
+                !stat
+                    .rsplit_once(')')
+                    .is_some_and(|(_, rest)| rest.trim_start().starts_with('Z'))
+            });
+            if !running {
+                break;
+            }
+            assert!(Instant::now() < deadline, "the child of yt-dlp survived");
+            std::thread::sleep(Duration::from_millis(50));
+        }
+    }
+
+    #[tokio::test]
+    async fn pieces_skip_sidecars_and_finalize_keeps_the_recording() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("Stream.mp4");
+        let work_dir = recording_dir(&output);
+        assert_eq!(work_dir.parent(), Some(dir.path()));
+        assert_ne!(work_dir, recording_dir(&output), "unique per recording");
+        std::fs::create_dir(&work_dir).unwrap();
+        std::fs::write(work_dir.join("Stream.mp4"), b"ts data").unwrap();
+        std::fs::write(work_dir.join("Stream.jpg"), b"cover").unwrap();
+        // Unrelated files next to the output are never part of it.
+        std::fs::write(dir.path().join("Stream.f1.mp4"), b"other").unwrap();
+        let pieces = recording_pieces(&work_dir).await;
+        assert_eq!(pieces, vec![work_dir.join("Stream.mp4")]);
+        assert_eq!(recorded_bytes(&work_dir).await, 7);
+
+        // Whether or not ffmpeg can remux it, the recording survives next
+        // to the output.
+        let finished = finalize_recording(&pieces, &output, "mkv").await;
+        let finished = finished.unwrap();
+        assert!(finished.exists());
+        assert_eq!(finished.parent(), Some(dir.path()));
+        assert_eq!(
+            std::fs::read(dir.path().join("Stream.f1.mp4")).unwrap(),
+            b"other"
+        );
+        assert!(finalize_recording(&[], &output, "mkv").await.is_err());
+    }
+}
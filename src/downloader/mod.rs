@@ -1,15 +1,31 @@
 //! Download engine module
 
+pub mod chapters;
 pub mod engine;
+pub mod ffmpeg;
+pub mod live;
 pub mod merger;
 pub mod progress;
 pub mod resume_guard;
 pub mod segment;
+pub mod sink;
+pub mod thumbnail;
 
 // Re-export for convenience
 #[allow(unused_imports)] // Exposed for external callers; may be unused internally
+pub use chapters::ChapterMode;
+#[allow(unused_imports)] // Exposed for external callers; may be unused internally
 pub use engine::{
     build_ytdlp_args, ytdlp_output_template, DownloadConfig, DownloadEngine, YtDlpOptions,
 };
 #[allow(unused_imports)] // Exposed for external callers; may be unused internally
+pub use live::LiveOptions;
+#[allow(unused_imports)] // Exposed for external callers; may be unused internally
 pub use progress::{DownloadProgress, DownloadStatus};
+#[allow(unused_imports)] // Exposed for external callers; may be unused internally
+pub use sink::{
+    BroadcastSink, CallbackSink, FanoutSink, JsonLinesSink, ProgressSink, SharedProgressSink,
+    WatchSink,
+};
+#[allow(unused_imports)] // Exposed for external callers; may be unused internally
+pub use thumbnail::ThumbnailCache;
@@ -30,6 +30,11 @@ pub struct DownloadProgress {
     pub status: DownloadStatus,
     pub segments_completed: usize,
     pub total_segments: usize,
+    /// How long a live stream has been recorded. A recording has no total,
+    /// so this and `downloaded_bytes` stand in for a percentage.
+    pub elapsed: Option<Duration>,
+    /// When the upcoming stream a recording waits for is scheduled to start.
+    pub starts_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 impl DownloadProgress {
@@ -43,6 +48,8 @@ impl DownloadProgress {
             status: DownloadStatus::Initializing,
             segments_completed: 0,
             total_segments,
+            elapsed: None,
+            starts_at: None,
         }
     }
 
@@ -125,6 +132,17 @@ pub enum DownloadStatus {
     Paused,
     /// No forward progress was observed within the stall threshold.
     Stalled,
+    /// Waiting for a scheduled live stream or premiere to start.
+    Waiting,
+    /// Recording a live stream.
+    Recording,
+}
+
+impl DownloadStatus {
+    /// Whether this is the last status a download reports.
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, DownloadStatus::Completed | DownloadStatus::Failed(_))
+    }
 }
 
 /// Detects download stalls: no forward progress within a time threshold.
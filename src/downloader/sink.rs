@@ -0,0 +1,337 @@
+//! Progress observers.
+//!
+//! The engine reports progress through a [`ProgressSink`] instead of awaiting
+//! on an `mpsc::Sender`. `report` is synchronous and must never block, so a
+//! slow (or vanished) consumer can no longer throttle the transfer — each
+//! sink decides for itself what to do when its consumer falls behind: keep
+//! only the latest value ([`WatchSink`]), let lagging subscribers skip ahead
+//! ([`BroadcastSink`]), or drop the update (`mpsc::Sender`).
+
+use crate::downloader::progress::{DownloadProgress, DownloadStatus};
+use chrono::Utc;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{broadcast, mpsc, watch};
+use tracing::warn;
+
+/// Receives progress updates for one download.
+///
+/// Implementations are called from the transfer's hot path: they must return
+/// promptly and never wait on a consumer.
+pub trait ProgressSink: Send + Sync {
+    /// Observe a progress update.
+    fn report(&self, progress: &DownloadProgress);
+}
+
+/// A type-erased, shareable sink.
+pub type SharedProgressSink = Arc<dyn ProgressSink>;
+
+impl<S: ProgressSink + ?Sized> ProgressSink for Arc<S> {
+    fn report(&self, progress: &DownloadProgress) {
+        (**self).report(progress)
+    }
+}
+
+/// Compatibility sink for existing channel consumers. Uses `try_send`, so a
+/// full channel drops an intermediate update rather than stalling the
+/// download, and a closed one is silently ignored. The final `Completed` or
+/// `Failed` update is never dropped: if the channel is full it is sent in
+/// the background as soon as the consumer makes room.
+impl ProgressSink for mpsc::Sender<DownloadProgress> {
+    fn report(&self, progress: &DownloadProgress) {
+        let Err(mpsc::error::TrySendError::Full(progress)) = self.try_send(progress.clone()) else {
+            return;
+        };
+        if !progress.status.is_terminal() {
+            return;
+        }
+        let tx = self.clone();
+        match tokio::runtime::Handle::try_current() {
+            Ok(runtime) => {
+                runtime.spawn(async move {
+                    let _ = tx.send(progress).await;
+                });
+            }
+            Err(_) => {
+                std::thread::spawn(move || {
+                    let _ = tx.blocking_send(progress);
+                });
+            }
+        }
+    }
+}
+
+/// Latest-value sink: readers see the most recent progress and never lag.
+pub struct WatchSink {
+    tx: watch::Sender<Option<DownloadProgress>>,
+}
+
+impl WatchSink {
+    /// Create a sink and the receiver observing it. The receiver holds `None`
+    /// until the first report.
+    pub fn channel() -> (Self, watch::Receiver<Option<DownloadProgress>>) {
+        let (tx, rx) = watch::channel(None);
+        (Self { tx }, rx)
+    }
+
+    /// Another receiver of the same progress.
+    pub fn subscribe(&self) -> watch::Receiver<Option<DownloadProgress>> {
+        self.tx.subscribe()
+    }
+}
+
+impl ProgressSink for WatchSink {
+    fn report(&self, progress: &DownloadProgress) {
+        self.tx.send_replace(Some(progress.clone()));
+    }
+}
+
+/// Fan-out sink: every subscriber sees every update, and a subscriber that
+/// falls more than `capacity` updates behind skips ahead (`Lagged`).
+pub struct BroadcastSink {
+    tx: broadcast::Sender<DownloadProgress>,
+}
+
+impl BroadcastSink {
+    pub fn new(capacity: usize) -> Self {
+        let (tx, _) = broadcast::channel(capacity);
+        Self { tx }
+    }
+
+    /// A new subscriber, receiving updates reported from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<DownloadProgress> {
+        self.tx.subscribe()
+    }
+}
+
+impl ProgressSink for BroadcastSink {
+    fn report(&self, progress: &DownloadProgress) {
+        // No subscribers is not an error: nobody is watching right now.
+        let _ = self.tx.send(progress.clone());
+    }
+}
+
+/// Sink that calls a closure for every update.
+pub struct CallbackSink<F>(F);
+
+impl<F> CallbackSink<F>
+where
+    F: Fn(&DownloadProgress) + Send + Sync,
+{
+    pub fn new(callback: F) -> Self {
+        Self(callback)
+    }
+}
+
+impl<F> ProgressSink for CallbackSink<F>
+where
+    F: Fn(&DownloadProgress) + Send + Sync,
+{
+    fn report(&self, progress: &DownloadProgress) {
+        (self.0)(progress)
+    }
+}
+
+/// Sink that appends one JSON object per update to a writer (a log file,
+/// stdout, a pipe to another process).
+pub struct JsonLinesSink<W> {
+    writer: Mutex<W>,
+}
+
+impl<W: Write + Send> JsonLinesSink<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer: Mutex::new(writer),
+        }
+    }
+
+    /// Recover the writer (e.g. to inspect a buffer in tests).
+    pub fn into_inner(self) -> W {
+        self.writer
+            .into_inner()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl<W: Write + Send> ProgressSink for JsonLinesSink<W> {
+    fn report(&self, progress: &DownloadProgress) {
+        let line = progress_json(progress);
+        let mut writer = self
+            .writer
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Err(e) = writeln!(writer, "{line}").and_then(|_| writer.flush()) {
+            warn!("Failed to write progress line: {}", e);
+        }
+    }
+}
+
+/// Forward every update to several sinks, in order.
+#[derive(Default)]
+pub struct FanoutSink {
+    sinks: Vec<SharedProgressSink>,
+}
+
+impl FanoutSink {
+    pub fn new(sinks: Vec<SharedProgressSink>) -> Self {
+        Self { sinks }
+    }
+
+    pub fn push(&mut self, sink: SharedProgressSink) {
+        self.sinks.push(sink);
+    }
+}
+
+impl ProgressSink for FanoutSink {
+    fn report(&self, progress: &DownloadProgress) {
+        for sink in &self.sinks {
+            sink.report(progress);
+        }
+    }
+}
+
+fn status_label(status: &DownloadStatus) -> &'static str {
+    match status {
+        DownloadStatus::Initializing => "initializing",
+        DownloadStatus::Downloading => "downloading",
+        DownloadStatus::Merging => "merging",
+        DownloadStatus::Completed => "completed",
+        DownloadStatus::Failed(_) => "failed",
+        DownloadStatus::Paused => "paused",
+        DownloadStatus::Stalled => "stalled",
+        DownloadStatus::Waiting => "waiting",
+        DownloadStatus::Recording => "recording",
+    }
+}
+
+fn progress_json(progress: &DownloadProgress) -> serde_json::Value {
+    let error = match &progress.status {
+        DownloadStatus::Failed(e) => Some(e.as_str()),
+        _ => None,
+    };
+    serde_json::json!({
+        "timestamp": Utc::now().to_rfc3339(),
+        "status": status_label(&progress.status),
+        "error": error,
+        "downloaded_bytes": progress.downloaded_bytes,
+        "total_bytes": progress.total_bytes,
+        "speed": progress.speed,
+        "eta_secs": progress.eta.map(|d| d.as_secs()),
+        "segments_completed": progress.segments_completed,
+        "total_segments": progress.total_segments,
+        "elapsed_secs": progress.elapsed.map(|d| d.as_secs()),
+        "starts_at": progress.starts_at.map(|t| t.to_rfc3339()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn at(downloaded: u64) -> DownloadProgress {
+        let mut p = DownloadProgress::new(100, 1);
+        p.update(downloaded, 10.0);
+        p
+    }
+
+    #[test]
+    fn watch_sink_keeps_only_the_latest_value() {
+        let (sink, rx) = WatchSink::channel();
+        assert!(rx.borrow().is_none());
+        for n in 1..=50 {
+            sink.report(&at(n));
+        }
+        assert_eq!(rx.borrow().as_ref().unwrap().downloaded_bytes, 50);
+    }
+
+    #[test]
+    fn broadcast_sink_lagging_subscriber_skips_ahead() {
+        let sink = BroadcastSink::new(4);
+        let mut fast = sink.subscribe();
+        let mut slow = sink.subscribe();
+
+        sink.report(&at(1));
+        assert_eq!(fast.try_recv().unwrap().downloaded_bytes, 1);
+
+        for n in 2..=10 {
+            sink.report(&at(n));
+        }
+        assert!(matches!(
+            slow.try_recv(),
+            Err(broadcast::error::TryRecvError::Lagged(_))
+        ));
+        assert_eq!(slow.try_recv().unwrap().downloaded_bytes, 7);
+    }
+
+    #[test]
+    fn broadcast_sink_without_subscribers_does_not_fail() {
+        BroadcastSink::new(4).report(&at(1));
+    }
+
+    #[test]
+    fn callback_sink_is_invoked_per_update() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let sink = CallbackSink::new(move |_: &DownloadProgress| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+        sink.report(&at(1));
+        sink.report(&at(2));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn json_lines_sink_writes_one_object_per_update() {
+        let sink = JsonLinesSink::new(Vec::new());
+        sink.report(&at(40));
+        let mut failed = at(41);
+        failed.failed("boom".to_string());
+        sink.report(&failed);
+
+        let out = String::from_utf8(sink.into_inner()).unwrap();
+        let lines: Vec<serde_json::Value> = out
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["downloaded_bytes"], 40);
+        assert_eq!(lines[0]["total_bytes"], 100);
+        assert_eq!(lines[1]["status"], "failed");
+        assert_eq!(lines[1]["error"], "boom");
+    }
+
+    #[test]
+    fn full_or_closed_mpsc_sender_never_blocks() {
+        let (tx, rx) = mpsc::channel(1);
+        tx.report(&at(1));
+        tx.report(&at(2)); // full: dropped
+        drop(rx);
+        tx.report(&at(3)); // closed: ignored
+    }
+
+    #[tokio::test]
+    async fn full_mpsc_sender_still_delivers_the_final_update() {
+        let (tx, mut rx) = mpsc::channel(1);
+        tx.report(&at(1));
+        tx.report(&at(2)); // full: dropped
+        let mut done = at(100);
+        done.complete();
+        tx.report(&done); // full, but terminal: delivered once there is room
+
+        assert_eq!(rx.recv().await.unwrap().downloaded_bytes, 1);
+        let last = rx.recv().await.unwrap();
+        assert_eq!(last.status, DownloadStatus::Completed);
+        assert_eq!(last.downloaded_bytes, 100);
+    }
+
+    #[test]
+    fn fanout_reaches_every_sink() {
+        let (a, rx_a) = WatchSink::channel();
+        let (b, rx_b) = WatchSink::channel();
+        let fanout = FanoutSink::new(vec![Arc::new(a), Arc::new(b)]);
+        fanout.report(&at(9));
+        assert_eq!(rx_a.borrow().as_ref().unwrap().downloaded_bytes, 9);
+        assert_eq!(rx_b.borrow().as_ref().unwrap().downloaded_bytes, 9);
+    }
+}
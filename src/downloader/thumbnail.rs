@@ -0,0 +1,314 @@
+//! Thumbnails: fetched once per download into a local cache (which the GUI
+//! queue and history show), then written next to the finished file and
+//! embedded into it as cover art.
+//!
+//! The cache holds one JPEG per task, named by task id, so a history record
+//! finds its picture long after the task left the queue. Whatever the site
+//! serves (WebP, PNG, ...) is converted to JPEG, the format every container
+//! and media library accepts for cover art.
+
+use super::ffmpeg;
+use crate::extractor::VideoInfo;
+use crate::utils::get_app_support_dir;
+use anyhow::{Context, Result};
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::fs;
+use tracing::debug;
+
+/// Thumbnails are small; a slow image host must not hold a download up.
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The best thumbnail of `info`: the extractor's pick (yt-dlp's `thumbnail`
+/// and the native extractors' are already the largest), or YouTube's
+/// standard still for entries listed without one (flat playlists).
+pub fn best_thumbnail_url(info: &VideoInfo) -> Option<String> {
+    if let Some(url) = info.thumbnail.as_deref().filter(|u| u.starts_with("http")) {
+        return Some(url.to_string());
+    }
+    let youtube = info
+        .extractor
+        .as_deref()
+        .is_some_and(|e| e.to_ascii_lowercase().starts_with("youtube"));
+    (youtube && !info.id.is_empty())
+        .then(|| format!("https://i.ytimg.com/vi/{}/hqdefault.jpg", info.id))
+}
+
+/// Re-encode an image in any format the `image` crate reads as JPEG.
+pub fn to_jpeg(bytes: &[u8]) -> Result<Vec<u8>> {
+    let image = image::load_from_memory(bytes).context("Thumbnail is not a readable image")?;
+    let mut jpeg = Cursor::new(Vec::new());
+    image::DynamicImage::ImageRgb8(image.to_rgb8())
+        .write_to(&mut jpeg, image::ImageOutputFormat::Jpeg(90))
+        .context("Failed to encode thumbnail as JPEG")?;
+    Ok(jpeg.into_inner())
+}
+
+/// Per-task thumbnail cache.
+#[derive(Debug, Clone)]
+pub struct ThumbnailCache {
+    dir: PathBuf,
+    client: reqwest::Client,
+}
+
+impl Default for ThumbnailCache {
+    /// The cache the backend uses unless told otherwise:
+    /// `<app support dir>/thumbnails`.
+    fn default() -> Self {
+        Self::new(get_app_support_dir().join("thumbnails"))
+    }
+}
+
+impl ThumbnailCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(FETCH_TIMEOUT)
+            .build()
+            .expect("Failed to create HTTP client");
+        Self {
+            dir: dir.into(),
+            client,
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Where the thumbnail of `task_id` is (or would be) cached.
+    pub fn path(&self, task_id: &str) -> PathBuf {
+        self.dir.join(format!("{task_id}.jpg"))
+    }
+
+    /// The cached thumbnail of `task_id`, if it was fetched.
+    pub fn cached(&self, task_id: &str) -> Option<PathBuf> {
+        let path = self.path(task_id);
+        path.is_file().then_some(path)
+    }
+
+    /// The thumbnail of `task_id`, downloading the best one of `info` unless
+    /// it is already cached.
+    pub async fn fetch(&self, task_id: &str, info: &VideoInfo) -> Result<PathBuf> {
+        if let Some(path) = self.cached(task_id) {
+            return Ok(path);
+        }
+        let url = best_thumbnail_url(info)
+            .with_context(|| format!("'{}' has no thumbnail", info.title))?;
+        debug!("Fetching thumbnail {} for task {}", url, task_id);
+        let bytes = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .with_context(|| format!("Failed to fetch thumbnail {url}"))?
+            .bytes()
+            .await?;
+        let jpeg = tokio::task::spawn_blocking(move || to_jpeg(&bytes)).await??;
+
+        // Write then rename, so a concurrent fetch never sees half a file.
+        fs::create_dir_all(&self.dir).await?;
+        let path = self.path(task_id);
+        let partial = path.with_extension("jpg.part");
+        fs::write(&partial, jpeg).await?;
+        fs::rename(&partial, &path).await?;
+        Ok(path)
+    }
+
+    /// Drop the cached thumbnail of `task_id` (its history record is gone).
+    pub async fn remove(&self, task_id: &str) {
+        let _ = fs::remove_file(self.path(task_id)).await;
+    }
+}
+
+/// The ffmpeg arguments that write `media` with `cover` embedded as cover
+/// art to `output`, or `None` for a container Rustloader does not embed
+/// into. Streams are copied, never re-encoded.
+pub fn cover_art_args(media: &Path, cover: &Path, output: &Path) -> Option<Vec<String>> {
+    let ext = media.extension()?.to_str()?.to_ascii_lowercase();
+    let media = media.to_string_lossy().to_string();
+    let cover = cover.to_string_lossy().to_string();
+    let mut args: Vec<String> = ["-y", "-loglevel", "error", "-i", &media]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    let rest: Vec<&str> = match ext.as_str() {
+        // Matroska keeps cover art as an attachment named `cover.jpg`.
+        "mkv" => vec![
+            "-map",
+            "0",
+            "-c",
+            "copy",
+            "-attach",
+            &cover,
+            "-metadata:s:t",
+            "mimetype=image/jpeg",
+            "-metadata:s:t",
+            "filename=cover.jpg",
+        ],
+        "mp3" => vec![
+            "-i",
+            &cover,
+            "-map",
+            "0:a",
+            "-map",
+            "1",
+            "-c",
+            "copy",
+            "-id3v2_version",
+            "3",
+            "-metadata:s:v",
+            "title=Album cover",
+            "-metadata:s:v",
+            "comment=Cover (front)",
+            "-disposition:v",
+            "attached_pic",
+        ],
+        "m4a" => vec![
+            "-i",
+            &cover,
+            "-map",
+            "0:a",
+            "-map",
+            "1",
+            "-c",
+            "copy",
+            "-disposition:v",
+            "attached_pic",
+        ],
+        // The video is stream v:0, so the cover is v:1.
+        "mp4" => vec![
+            "-i",
+            &cover,
+            "-map",
+            "0",
+            "-map",
+            "1",
+            "-c",
+            "copy",
+            "-disposition:v:1",
+            "attached_pic",
+        ],
+        _ => return None,
+    };
+    args.extend(rest.into_iter().map(str::to_string));
+    args.push(output.to_string_lossy().to_string());
+    Some(args)
+}
+
+/// Embed `cover` into `media` in place. `Ok(false)` when the container has
+/// no cover art or ffmpeg is not installed; the file is untouched then, and
+/// also when ffmpeg fails.
+pub async fn embed_cover_art(media: &Path, cover: &Path) -> Result<bool> {
+    let Some(ext) = media.extension().and_then(|e| e.to_str()) else {
+        return Ok(false);
+    };
+    let output = media.with_extension(format!("cover.{ext}"));
+    let Some(args) = cover_art_args(media, cover, &output) else {
+        return Ok(false);
+    };
+    match ffmpeg::run(&args).await {
+        Ok(true) => {}
+        Ok(false) => return Ok(false),
+        Err(e) => {
+            let _ = fs::remove_file(&output).await;
+            anyhow::bail!("ffmpeg could not embed cover art: {e}");
+        }
+    }
+    fs::rename(&output, media).await?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn png() -> Vec<u8> {
+        let mut png = Cursor::new(Vec::new());
+        image::DynamicImage::new_rgb8(4, 3)
+            .write_to(&mut png, image::ImageOutputFormat::Png)
+            .unwrap();
+        png.into_inner()
+    }
+
+    #[test]
+    fn best_thumbnail_falls_back_to_youtube_still() {
+        let mut info = VideoInfo {
+            id: "dQw4w9WgXcQ".to_string(),
+            extractor: Some("youtube".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            best_thumbnail_url(&info).as_deref(),
+            Some("https://i.ytimg.com/vi/dQw4w9WgXcQ/hqdefault.jpg")
+        );
+        info.thumbnail = Some("https://example.com/big.webp".to_string());
+        assert_eq!(
+            best_thumbnail_url(&info).as_deref(),
+            Some("https://example.com/big.webp")
+        );
+        assert_eq!(best_thumbnail_url(&VideoInfo::default()), None);
+    }
+
+    #[test]
+    fn cover_art_args_per_container() {
+        let cover = Path::new("/tmp/cover.jpg");
+        let mkv = cover_art_args(Path::new("a.mkv"), cover, Path::new("a.cover.mkv")).unwrap();
+        assert!(mkv.windows(2).any(|w| w == ["-attach", "/tmp/cover.jpg"]));
+        assert!(mkv.iter().any(|a| a == "filename=cover.jpg"));
+
+        let mp3 = cover_art_args(Path::new("a.MP3"), cover, Path::new("o.mp3")).unwrap();
+        assert!(mp3.windows(2).any(|w| w == ["-map", "0:a"]));
+        assert!(mp3.windows(2).any(|w| w == ["-id3v2_version", "3"]));
+        assert_eq!(mp3.last().map(String::as_str), Some("o.mp3"));
+
+        let mp4 = cover_art_args(Path::new("a.mp4"), cover, Path::new("o.mp4")).unwrap();
+        assert!(mp4
+            .windows(2)
+            .any(|w| w == ["-disposition:v:1", "attached_pic"]));
+
+        assert!(cover_art_args(Path::new("a.webm"), cover, Path::new("o.webm")).is_none());
+    }
+
+    #[tokio::test]
+    async fn fetch_converts_to_jpeg_and_caches_per_task() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        // Serves one request only: a second fetch must come from the cache.
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 4096];
+            let _ = socket.read(&mut buf).await;
+            let body = png();
+            let head = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: image/png\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            );
+            socket.write_all(head.as_bytes()).await.unwrap();
+            socket.write_all(&body).await.unwrap();
+        });
+
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ThumbnailCache::new(dir.path().join("thumbnails"));
+        let info = VideoInfo {
+            thumbnail: Some(format!("http://{addr}/thumb.png")),
+            ..Default::default()
+        };
+        assert!(cache.cached("task-1").is_none());
+
+        let path = cache.fetch("task-1", &info).await.unwrap();
+        assert_eq!(path, cache.path("task-1"));
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(
+            image::guess_format(&bytes).unwrap(),
+            image::ImageFormat::Jpeg
+        );
+        assert_eq!(cache.fetch("task-1", &info).await.unwrap(), path);
+
+        cache.remove("task-1").await;
+        assert!(cache.cached("task-1").is_none());
+    }
+}
@@ -0,0 +1,110 @@
+//! Batch extraction: many URLs in one go.
+//!
+//! [`Extractor::extract_batch`](crate::extractor::Extractor::extract_batch)
+//! sends one [`BatchItem`] per URL as soon as it is ready, in whatever order
+//! the extractor finishes them. Callers that queue the results (playlists,
+//! pasted URL lists) want them in their own order, without waiting for the
+//! whole batch: [`InOrder`] hands each result on once every earlier one is in.
+
+use crate::extractor::models::VideoInfo;
+use anyhow::Result;
+use std::collections::HashMap;
+
+/// One URL's extraction result, keyed by the URL as it was given.
+pub type BatchItem = (String, Result<VideoInfo>);
+
+/// Releases batch results in the order of a list of URLs.
+#[derive(Debug)]
+pub struct InOrder {
+    urls: Vec<String>,
+    next: usize,
+    ready: HashMap<String, Result<VideoInfo, String>>,
+}
+
+impl InOrder {
+    /// Expect results for `urls`, in this order. A URL may appear more than
+    /// once; one result serves every occurrence.
+    pub fn new(urls: Vec<String>) -> Self {
+        Self {
+            urls,
+            next: 0,
+            ready: HashMap::new(),
+        }
+    }
+
+    /// Record `url`'s result and return every result now due, as
+    /// `(position, result)` with positions into the original list.
+    pub fn push(
+        &mut self,
+        url: String,
+        result: Result<VideoInfo>,
+    ) -> Vec<(usize, Result<VideoInfo, String>)> {
+        self.ready.insert(url, result.map_err(|e| e.to_string()));
+        let mut due = Vec::new();
+        while let Some(result) = self.urls.get(self.next).and_then(|url| self.ready.get(url)) {
+            due.push((self.next, result.clone()));
+            self.next += 1;
+        }
+        due
+    }
+
+    /// Every result still held back once the batch has ended. A URL the
+    /// extractor never answered fails.
+    pub fn finish(self) -> Vec<(usize, Result<VideoInfo, String>)> {
+        self.urls
+            .iter()
+            .enumerate()
+            .skip(self.next)
+            .map(|(i, url)| {
+                let result = self
+                    .ready
+                    .get(url)
+                    .cloned()
+                    .unwrap_or_else(|| Err(format!("No extraction result for {url}")));
+                (i, result)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(url: &str) -> Result<VideoInfo> {
+        Ok(VideoInfo {
+            url: url.to_string(),
+            ..Default::default()
+        })
+    }
+
+    fn positions(due: &[(usize, Result<VideoInfo, String>)]) -> Vec<usize> {
+        due.iter().map(|(i, _)| *i).collect()
+    }
+
+    #[test]
+    fn holds_results_until_earlier_ones_arrive() {
+        let mut order = InOrder::new(vec!["a".into(), "b".into(), "c".into()]);
+        assert!(order.push("b".into(), info("b")).is_empty());
+        assert!(order.push("c".into(), info("c")).is_empty());
+        let due = order.push("a".into(), info("a"));
+        assert_eq!(positions(&due), [0, 1, 2]);
+        assert_eq!(due[1].1.as_ref().unwrap().url, "b");
+        assert!(order.finish().is_empty());
+    }
+
+    #[test]
+    fn failures_keep_their_place_and_missing_urls_fail_at_the_end() {
+        let mut order = InOrder::new(vec!["a".into(), "b".into(), "a".into(), "c".into()]);
+        let due = order.push("a".into(), Err(anyhow::anyhow!("gone")));
+        assert_eq!(positions(&due), [0]);
+        assert_eq!(due[0].1.as_ref().unwrap_err(), "gone");
+        let due = order.push("b".into(), info("b"));
+        // The repeated "a" is served by the same result.
+        assert_eq!(positions(&due), [1, 2]);
+
+        let rest = order.finish();
+        assert_eq!(positions(&rest), [3]);
+        assert!(rest[0].1.as_ref().unwrap_err().contains("c"));
+    }
+}
@@ -0,0 +1,421 @@
+//! Extraction result cache.
+//!
+//! `ExtractInfo`, retries and task resets used to re-run the extractor (for
+//! yt-dlp, a full `--dump-json` round-trip) every time. Results are now kept
+//! in the `extraction_cache` table, keyed by a normalized URL, and reused for
+//! a per-extractor TTL.
+//!
+//! Signed format URLs outlive neither the TTL nor, sometimes, the afternoon:
+//! a cached entry whose format URLs carry an expiry that has passed is
+//! reported as [`CacheLookup::FormatsExpired`], so the caller re-resolves
+//! while the metadata itself stays usable.
+
+use crate::database::{CachedExtractionRecord, DatabaseManager};
+use crate::extractor::models::VideoInfo;
+use crate::extractor::native::youtube::video_id_from_url;
+use anyhow::Result;
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use reqwest::Url;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::debug;
+
+/// Format URLs expiring within this window are treated as already expired:
+/// the download would not get far before the signature lapses.
+const EXPIRY_MARGIN_SECS: i64 = 60;
+
+/// Query parameters that only track where a link was shared from and never
+/// change what it points to.
+const TRACKING_PARAMS: &[&str] = &["fbclid", "gclid", "si", "feature", "igshid", "ref_src"];
+
+/// How long cached results stay fresh, per extractor.
+#[derive(Debug, Clone)]
+pub struct CachePolicy {
+    default_ttl: Duration,
+    per_extractor: HashMap<String, Duration>,
+}
+
+impl Default for CachePolicy {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(6 * 3600))
+            // Stream URLs are signed for ~6h; keep well inside that.
+            .with_ttl("youtube", Duration::from_secs(2 * 3600))
+            // A direct file's metadata is just its name and size.
+            .with_ttl("direct", Duration::from_secs(24 * 3600))
+    }
+}
+
+impl CachePolicy {
+    /// A policy with `default_ttl` for every extractor.
+    pub fn new(default_ttl: Duration) -> Self {
+        Self {
+            default_ttl,
+            per_extractor: HashMap::new(),
+        }
+    }
+
+    /// Override the TTL for results produced by `extractor` (the
+    /// `VideoInfo::extractor` name, e.g. `youtube` or a site rule's id).
+    pub fn with_ttl(mut self, extractor: impl Into<String>, ttl: Duration) -> Self {
+        self.per_extractor
+            .insert(extractor.into().to_lowercase(), ttl);
+        self
+    }
+
+    /// The TTL for results from `extractor`.
+    pub fn ttl_for(&self, extractor: Option<&str>) -> Duration {
+        extractor
+            .and_then(|name| self.per_extractor.get(&name.to_lowercase()))
+            .copied()
+            .unwrap_or(self.default_ttl)
+    }
+
+    /// The longest TTL any extractor gets; entries older than this are dead.
+    fn max_ttl(&self) -> Duration {
+        self.per_extractor
+            .values()
+            .copied()
+            .fold(self.default_ttl, Duration::max)
+    }
+}
+
+/// Outcome of a cache lookup.
+#[derive(Debug, Clone)]
+pub enum CacheLookup {
+    /// Within its TTL and every format URL still valid.
+    Fresh(VideoInfo),
+    /// Within its TTL, but at least one signed format URL has expired: the
+    /// metadata is good, the formats need re-resolving.
+    FormatsExpired(VideoInfo),
+    /// Not cached, past its TTL, or unreadable.
+    Miss,
+}
+
+/// SQLite-backed cache of extraction results.
+#[derive(Clone)]
+pub struct ExtractionCache {
+    db: Arc<DatabaseManager>,
+    policy: CachePolicy,
+}
+
+impl ExtractionCache {
+    pub fn new(db: Arc<DatabaseManager>, policy: CachePolicy) -> Self {
+        Self { db, policy }
+    }
+
+    pub fn policy(&self) -> &CachePolicy {
+        &self.policy
+    }
+
+    /// Look `url` up.
+    pub async fn lookup(&self, url: &str) -> Result<CacheLookup> {
+        self.lookup_at(url, Utc::now()).await
+    }
+
+    async fn lookup_at(&self, url: &str, now: DateTime<Utc>) -> Result<CacheLookup> {
+        let key = cache_key(url);
+        let Some(record) = self.db.get_cached_extraction(&key).await? else {
+            return Ok(CacheLookup::Miss);
+        };
+
+        let ttl = self.policy.ttl_for(record.extractor.as_deref());
+        let age = now.signed_duration_since(record.fetched_at);
+        if age.to_std().is_ok_and(|age| age > ttl) {
+            debug!(
+                "Cached extraction for {} is stale ({}s old)",
+                key,
+                age.num_seconds()
+            );
+            return Ok(CacheLookup::Miss);
+        }
+
+        let info: VideoInfo = match serde_json::from_str(&record.info_json) {
+            Ok(info) => info,
+            Err(e) => {
+                debug!("Discarding unreadable cache entry {}: {}", key, e);
+                return Ok(CacheLookup::Miss);
+            }
+        };
+
+        if formats_expired(&info, now) {
+            debug!("Cached formats for {} have expired", key);
+            Ok(CacheLookup::FormatsExpired(info))
+        } else {
+            debug!("Extraction cache hit for {}", key);
+            Ok(CacheLookup::Fresh(info))
+        }
+    }
+
+    /// Store (or replace) the result for `url`.
+    pub async fn store(&self, url: &str, info: &VideoInfo) -> Result<()> {
+        let record = CachedExtractionRecord {
+            cache_key: cache_key(url),
+            url: url.to_string(),
+            extractor: info.extractor.clone(),
+            info_json: serde_json::to_string(info)?,
+            fetched_at: Utc::now(),
+        };
+        self.db.save_cached_extraction(&record).await
+    }
+
+    /// Forget the result for `url`.
+    pub async fn invalidate(&self, url: &str) -> Result<()> {
+        self.db.delete_cached_extraction(&cache_key(url)).await
+    }
+
+    /// Drop every entry older than the longest TTL; returns how many went.
+    pub async fn prune(&self) -> Result<u64> {
+        let max_ttl = chrono::Duration::from_std(self.policy.max_ttl())
+            .unwrap_or_else(|_| chrono::Duration::days(365));
+        self.db.prune_cached_extractions(Utc::now() - max_ttl).await
+    }
+}
+
+/// The key a URL is cached under. YouTube links of every shape collapse to
+/// `youtube:<id>`; anything else is normalized with [`normalize_url`].
+pub fn cache_key(url: &str) -> String {
+    match video_id_from_url(url) {
+        Some(id) => format!("youtube:{id}"),
+        None => normalize_url(url),
+    }
+}
+
+/// Normalize a URL so trivially different links to the same page share a
+/// cache entry: lower-case host, no fragment, no tracking parameters, sorted
+/// query and no trailing slash. Unparseable input is returned trimmed.
+pub fn normalize_url(url: &str) -> String {
+    let Ok(mut parsed) = Url::parse(url.trim()) else {
+        return url.trim().to_string();
+    };
+    parsed.set_fragment(None);
+
+    let mut pairs: Vec<(String, String)> = parsed
+        .query_pairs()
+        .filter(|(k, _)| !k.starts_with("utm_") && !TRACKING_PARAMS.contains(&k.as_ref()))
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    pairs.sort();
+    if pairs.is_empty() {
+        parsed.set_query(None);
+    } else {
+        parsed.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+
+    if parsed.path().len() > 1 && parsed.path().ends_with('/') {
+        let path = parsed.path().trim_end_matches('/').to_string();
+        parsed.set_path(&path);
+    }
+
+    let normalized = parsed.to_string();
+    // `Url` already lower-cases the host; drop the bare root slash it adds.
+    match normalized.strip_suffix('/') {
+        Some(stripped) if parsed.path() == "/" && parsed.query().is_none() => stripped.to_string(),
+        _ => normalized,
+    }
+}
+
+/// Whether any of `info`'s format URLs (or its resolved direct URL) carries
+/// a signature expiring before `now` plus the safety margin.
+fn formats_expired(info: &VideoInfo, now: DateTime<Utc>) -> bool {
+    let deadline = now + chrono::Duration::seconds(EXPIRY_MARGIN_SECS);
+    info.formats
+        .iter()
+        .map(|f| f.url.as_str())
+        .chain(std::iter::once(info.direct_url.as_str()))
+        .filter(|url| !url.is_empty())
+        .filter_map(format_url_expiry)
+        .any(|expiry| expiry <= deadline)
+}
+
+/// When a signed media URL stops working, for the signing schemes seen in
+/// practice: YouTube's `expire`, CloudFront's `Expires`, S3's `X-Amz-Date` +
+/// `X-Amz-Expires`, and Akamai's `exp` / `hdnts=exp=...` tokens. `None` for
+/// unsigned URLs.
+pub fn format_url_expiry(url: &str) -> Option<DateTime<Utc>> {
+    let parsed = Url::parse(url).ok()?;
+    let params: HashMap<String, String> = parsed
+        .query_pairs()
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    let unix = |value: &str| {
+        value
+            .parse::<i64>()
+            .ok()
+            .and_then(|secs| Utc.timestamp_opt(secs, 0).single())
+    };
+
+    for key in ["expire", "Expires", "exp"] {
+        if let Some(expiry) = params.get(key).and_then(|v| unix(v)) {
+            return Some(expiry);
+        }
+    }
+
+    if let (Some(date), Some(secs)) = (params.get("X-Amz-Date"), params.get("X-Amz-Expires")) {
+        let signed = NaiveDateTime::parse_from_str(date, "%Y%m%dT%H%M%SZ").ok()?;
+        let secs = secs.parse::<i64>().ok()?;
+        return Some(Utc.from_utc_datetime(&signed) + chrono::Duration::seconds(secs));
+    }
+
+    ["hdnts", "hdnea", "__token__"]
+        .iter()
+        .filter_map(|key| params.get(*key))
+        .flat_map(|token| token.split('~'))
+        .find_map(|field| field.strip_prefix("exp=").and_then(unix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::initialize_database;
+    use crate::extractor::models::Format;
+
+    async fn fresh_cache(name: &str, policy: CachePolicy) -> ExtractionCache {
+        let dir = std::env::temp_dir().join(format!(
+            "rl-extraction-cache-{}-{}",
+            name,
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).expect("create temp dir");
+        let db_path = dir.join("cache.db");
+        let _ = std::fs::remove_file(&db_path);
+        let pool = initialize_database(&format!("sqlite://{}?mode=rwc", db_path.display()))
+            .await
+            .expect("init db");
+        ExtractionCache::new(Arc::new(DatabaseManager::new(pool)), policy)
+    }
+
+    fn sample_info(format_url: &str) -> VideoInfo {
+        VideoInfo {
+            id: "abc".to_string(),
+            title: "Sample".to_string(),
+            url: "https://example.com/watch/abc".to_string(),
+            extractor: Some("generic".to_string()),
+            formats: vec![Format {
+                format_id: "0".to_string(),
+                url: format_url.to_string(),
+                ext: "mp4".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn normalizes_equivalent_urls_to_one_key() {
+        assert_eq!(
+            normalize_url("https://Example.COM/video/?b=2&utm_source=x&a=1#t=30"),
+            "https://example.com/video?a=1&b=2"
+        );
+        assert_eq!(
+            normalize_url("https://example.com/?fbclid=abc"),
+            "https://example.com"
+        );
+        assert_eq!(normalize_url(" not a url "), "not a url");
+    }
+
+    #[test]
+    fn youtube_links_share_a_key() {
+        let key = cache_key("https://www.youtube.com/watch?v=dQw4w9WgXcQ&si=xyz");
+        assert_eq!(key, "youtube:dQw4w9WgXcQ");
+        assert_eq!(cache_key("https://youtu.be/dQw4w9WgXcQ?t=42"), key);
+        assert_eq!(cache_key("https://youtube.com/shorts/dQw4w9WgXcQ"), key);
+    }
+
+    #[test]
+    fn look_alike_links_on_other_hosts_keep_their_own_key() {
+        let key = cache_key("https://other.site/watch?v=dQw4w9WgXcQ");
+        assert_eq!(key, "https://other.site/watch?v=dQw4w9WgXcQ");
+        assert_ne!(
+            cache_key("https://evil.example/embed/dQw4w9WgXcQ"),
+            "youtube:dQw4w9WgXcQ"
+        );
+    }
+
+    #[test]
+    fn parses_signed_url_expiries() {
+        let at = |secs| Utc.timestamp_opt(secs, 0).single();
+        assert_eq!(
+            format_url_expiry(
+                "https://rr1.googlevideo.com/videoplayback?expire=1700000000&itag=18"
+            ),
+            at(1_700_000_000)
+        );
+        assert_eq!(
+            format_url_expiry("https://d1.cloudfront.net/v.mp4?Expires=1700000100&Signature=x"),
+            at(1_700_000_100)
+        );
+        assert_eq!(
+            format_url_expiry(
+                "https://b.s3.amazonaws.com/v.mp4?X-Amz-Date=20231114T221320Z&X-Amz-Expires=3600"
+            ),
+            at(1_700_000_000 + 3600)
+        );
+        assert_eq!(
+            format_url_expiry("https://cdn.example.com/v.m3u8?hdnts=st=1~exp=1700000200~acl=*"),
+            at(1_700_000_200)
+        );
+        assert_eq!(format_url_expiry("https://example.com/v.mp4"), None);
+    }
+
+    #[test]
+    fn per_extractor_ttls_override_the_default() {
+        let policy =
+            CachePolicy::new(Duration::from_secs(60)).with_ttl("YouTube", Duration::from_secs(10));
+        assert_eq!(policy.ttl_for(Some("youtube")), Duration::from_secs(10));
+        assert_eq!(policy.ttl_for(Some("generic")), Duration::from_secs(60));
+        assert_eq!(policy.ttl_for(None), Duration::from_secs(60));
+    }
+
+    #[tokio::test]
+    async fn fresh_entries_hit_and_stale_entries_miss() {
+        let cache = fresh_cache(
+            "ttl",
+            CachePolicy::new(Duration::from_secs(3600))
+                .with_ttl("generic", Duration::from_secs(60)),
+        )
+        .await;
+        let url = "https://example.com/watch/abc";
+        cache
+            .store(url, &sample_info("https://cdn.example.com/a.mp4"))
+            .await
+            .unwrap();
+
+        let hit = cache
+            .lookup("https://example.com/watch/abc/?utm_medium=share")
+            .await
+            .unwrap();
+        assert!(matches!(hit, CacheLookup::Fresh(info) if info.title == "Sample"));
+
+        let later = Utc::now() + chrono::Duration::seconds(120);
+        assert!(matches!(
+            cache.lookup_at(url, later).await.unwrap(),
+            CacheLookup::Miss
+        ));
+
+        cache.invalidate(url).await.unwrap();
+        assert!(matches!(
+            cache.lookup(url).await.unwrap(),
+            CacheLookup::Miss
+        ));
+    }
+
+    #[tokio::test]
+    async fn expired_format_urls_keep_metadata_but_ask_for_a_re_resolve() {
+        let cache = fresh_cache("expired-formats", CachePolicy::default()).await;
+        let url = "https://example.com/watch/abc";
+        let expired = (Utc::now() - chrono::Duration::minutes(5)).timestamp();
+        cache
+            .store(
+                url,
+                &sample_info(&format!("https://cdn.example.com/a.mp4?expire={expired}")),
+            )
+            .await
+            .unwrap();
+
+        match cache.lookup(url).await.unwrap() {
+            CacheLookup::FormatsExpired(info) => assert_eq!(info.title, "Sample"),
+            other => panic!("expected FormatsExpired, got {other:?}"),
+        }
+    }
+}
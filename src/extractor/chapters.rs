@@ -0,0 +1,143 @@
+//! Chapters: the ones extractors report and, for videos without them, the
+//! `0:00 Intro` style timestamps uploaders put in descriptions.
+
+use super::models::{Chapter, VideoInfo};
+use regex::Regex;
+use std::sync::OnceLock;
+
+fn timestamp_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\b(?:(\d{1,2}):)?(\d{1,2}):(\d{2})\b").unwrap())
+}
+
+/// Characters separating a timestamp from its title (`0:00 - Intro`,
+/// `[1:02] Theme`, `1. 0:00 | Opening`).
+fn is_separator(c: char) -> bool {
+    c.is_whitespace() || "-–—:|.)([]•".contains(c)
+}
+
+/// Chapters from one timestamp per description line. Like YouTube, the
+/// first must start at 0:00, there must be at least two and they must
+/// ascend; anything else yields none. The last chapter ends at `duration`.
+pub fn parse_description_chapters(description: &str, duration: Option<u64>) -> Vec<Chapter> {
+    let mut chapters: Vec<Chapter> = Vec::new();
+    for line in description.lines() {
+        let Some(found) = timestamp_regex().captures(line) else {
+            continue;
+        };
+        let number = |i: usize| {
+            found
+                .get(i)
+                .map_or(0, |m| m.as_str().parse::<u64>().unwrap_or(0))
+        };
+        let (hours, minutes, seconds) = (number(1), number(2), number(3));
+        if seconds >= 60 || (found.get(1).is_some() && minutes >= 60) {
+            continue;
+        }
+        let start = (hours * 3600 + minutes * 60 + seconds) as f64;
+
+        let whole = found.get(0).unwrap();
+        let before = line[..whole.start()].trim_matches(is_separator);
+        let after = line[whole.end()..].trim_matches(is_separator);
+        // A leading `1.` is a list number, not part of the title.
+        let before = if before.chars().all(|c| c.is_ascii_digit()) {
+            ""
+        } else {
+            before
+        };
+        let title = match (before.is_empty(), after.is_empty()) {
+            (true, _) => after.to_string(),
+            (false, true) => before.to_string(),
+            (false, false) => format!("{before} {after}"),
+        };
+
+        if let Some(previous) = chapters.last_mut() {
+            if start <= previous.start_time {
+                return Vec::new();
+            }
+            previous.end_time = Some(start);
+        } else if start != 0.0 {
+            return Vec::new();
+        }
+        chapters.push(Chapter {
+            start_time: start,
+            end_time: None,
+            title,
+        });
+    }
+    if chapters.len() < 2 {
+        return Vec::new();
+    }
+    if let Some(last) = chapters.last_mut() {
+        last.end_time = duration.map(|d| d as f64);
+    }
+    chapters
+}
+
+/// Fill `info.chapters` from the description when the extractor found none.
+pub fn fill_from_description(info: &mut VideoInfo) {
+    if info.chapters.is_empty() {
+        if let Some(description) = &info.description {
+            info.chapters = parse_description_chapters(description, info.duration);
+        }
+    }
+}
+
+/// `1:02:03` / `4:05`, for listings.
+pub fn format_timestamp(seconds: f64) -> String {
+    let total = seconds.max(0.0) as u64;
+    let (hours, minutes, seconds) = (total / 3600, total / 60 % 60, total % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_description_timestamps() {
+        let description = "Live set from the festival.\n\
+            Tracklist:\n\
+            0:00 Intro\n\
+            1. [04:30] - Opening Theme\n\
+            Deep Cut | 12:05\n\
+            1:02:03 – Encore\n\
+            Thanks for watching!";
+        let chapters = parse_description_chapters(description, Some(4000));
+        let titles: Vec<_> = chapters.iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(titles, ["Intro", "Opening Theme", "Deep Cut", "Encore"]);
+        assert_eq!(chapters[1].start_time, 270.0);
+        assert_eq!(chapters[1].end_time, Some(725.0));
+        assert_eq!(chapters[3].start_time, 3723.0);
+        assert_eq!(chapters[3].end_time, Some(4000.0));
+    }
+
+    #[test]
+    fn rejects_timestamps_that_are_not_chapters() {
+        // Not starting at 0:00, a single stamp, and a backwards jump.
+        assert!(parse_description_chapters("1:00 a\n2:00 b", None).is_empty());
+        assert!(parse_description_chapters("0:00 only", None).is_empty());
+        assert!(parse_description_chapters("0:00 a\n5:00 b\n3:00 c", None).is_empty());
+    }
+
+    #[test]
+    fn extractor_chapters_win_over_the_description() {
+        let mut info = VideoInfo {
+            description: Some("0:00 A\n1:00 B".to_string()),
+            ..Default::default()
+        };
+        fill_from_description(&mut info);
+        assert_eq!(info.chapters.len(), 2);
+
+        info.chapters.truncate(1);
+        info.chapters[0].title = "From yt-dlp".to_string();
+        fill_from_description(&mut info);
+        assert_eq!(info.chapters.len(), 1);
+        assert_eq!(format_timestamp(3723.9), "1:02:03");
+        assert_eq!(format_timestamp(65.0), "1:05");
+    }
+}
@@ -0,0 +1,250 @@
+//! Listing and picking formats: the rows shown by `--list-formats` and the
+//! GUI format picker, and `video+audio` pairs that yt-dlp downloads and
+//! merges.
+
+use super::models::{Format, VideoInfo};
+
+/// What a format carries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamKind {
+    /// Video and audio together — or no codec info at all (direct files).
+    Muxed,
+    VideoOnly,
+    AudioOnly,
+}
+
+impl std::fmt::Display for StreamKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            StreamKind::Muxed => "video+audio",
+            StreamKind::VideoOnly => "video only",
+            StreamKind::AudioOnly => "audio only",
+        })
+    }
+}
+
+fn has_codec(codec: &Option<String>) -> bool {
+    codec.as_deref().is_some_and(|c| c != "none")
+}
+
+impl Format {
+    pub fn kind(&self) -> StreamKind {
+        match (has_codec(&self.vcodec), has_codec(&self.acodec)) {
+            (true, false) => StreamKind::VideoOnly,
+            (false, true) => StreamKind::AudioOnly,
+            _ => StreamKind::Muxed,
+        }
+    }
+
+    /// YouTube's storyboard "formats" are thumbnail sheets, not media.
+    pub fn is_storyboard(&self) -> bool {
+        self.format_id.starts_with("sb") || self.format_note.as_deref() == Some("storyboard")
+    }
+}
+
+/// The formats worth offering, in the extractor's order (yt-dlp lists them
+/// worst to best).
+pub fn pickable(info: &VideoInfo) -> Vec<&Format> {
+    info.formats.iter().filter(|f| !f.is_storyboard()).collect()
+}
+
+/// One-line description: resolution, codecs, fps, bitrate and size. Parts
+/// the extractor did not report are left out.
+pub fn describe(format: &Format) -> String {
+    let mut parts = Vec::new();
+    match (format.width, format.height) {
+        (Some(w), Some(h)) if w > 0 && h > 0 => parts.push(format!("{w}x{h}")),
+        _ => match &format.resolution {
+            Some(resolution) => parts.push(resolution.clone()),
+            None if format.kind() == StreamKind::AudioOnly => parts.push("audio".to_string()),
+            None => {}
+        },
+    }
+    let codecs: Vec<&str> = [&format.vcodec, &format.acodec]
+        .into_iter()
+        .filter(|c| has_codec(c))
+        .filter_map(|c| c.as_deref())
+        .collect();
+    if !codecs.is_empty() {
+        parts.push(codecs.join(" + "));
+    }
+    if let Some(fps) = format.fps.filter(|fps| *fps > 0.0) {
+        parts.push(format!("{fps:.0} fps"));
+    }
+    if let Some(kbps) = format.tbr.or(format.abr).or(format.vbr) {
+        parts.push(format!("{kbps:.0} kbps"));
+    }
+    if let Some(bytes) = format.filesize {
+        parts.push(format_size(bytes));
+    }
+    if let Some(note) = format.format_note.as_deref().filter(|n| !n.is_empty()) {
+        parts.push(note.to_string());
+    }
+    parts.join(" · ")
+}
+
+/// `12.3 MB`-style size.
+pub fn format_size(bytes: u64) -> String {
+    let mb = bytes as f64 / (1024.0 * 1024.0);
+    if mb >= 1024.0 {
+        format!("{:.2} GB", mb / 1024.0)
+    } else {
+        format!("{mb:.1} MB")
+    }
+}
+
+/// The `--list-formats` table.
+pub fn format_table(info: &VideoInfo) -> String {
+    let mut table = format!("{:<14} {:<5} {:<12} DETAILS\n", "ID", "EXT", "KIND");
+    for format in pickable(info) {
+        table.push_str(&format!(
+            "{:<14} {:<5} {:<12} {}\n",
+            format.format_id,
+            format.ext,
+            format.kind().to_string(),
+            describe(format)
+        ));
+    }
+    table
+}
+
+/// The selection for a picked video and/or audio stream: `video+audio` when
+/// both are set (yt-dlp merges them), either one alone otherwise.
+pub fn selection_id(video: Option<&str>, audio: Option<&str>) -> Option<String> {
+    match (video, audio) {
+        (Some(video), Some(audio)) => Some(format!("{video}+{audio}")),
+        (Some(id), None) | (None, Some(id)) => Some(id.to_string()),
+        (None, None) => None,
+    }
+}
+
+/// True for a `video+audio` selection, which only yt-dlp can download.
+pub fn is_merge(format_id: &str) -> bool {
+    format_id.contains('+')
+}
+
+/// The format `format_id` names in `info`; a `video+audio` pair resolves
+/// to their [`merged`] format.
+pub fn resolve(info: &VideoInfo, format_id: &str) -> Result<Format, String> {
+    let parts = format_id
+        .split('+')
+        .map(|id| {
+            info.formats
+                .iter()
+                .find(|f| f.format_id == id)
+                .ok_or_else(|| format!("Format not found: {id}"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(merged(&parts))
+}
+
+/// One format standing for `parts` downloaded together: the first video
+/// part carrying the `+`-joined ids, the audio codec of the audio part and
+/// the combined size and bitrate. A single part is returned as it is.
+pub fn merged(parts: &[&Format]) -> Format {
+    let video = match parts {
+        [] => return Format::default(),
+        [single] => return (*single).clone(),
+        _ => parts
+            .iter()
+            .find(|f| f.kind() != StreamKind::AudioOnly)
+            .unwrap_or(&parts[0]),
+    };
+    let audio = parts.iter().find(|f| f.kind() == StreamKind::AudioOnly);
+    let ids: Vec<&str> = parts.iter().map(|f| f.format_id.as_str()).collect();
+    Format {
+        format_id: ids.join("+"),
+        acodec: audio.map_or(video.acodec.clone(), |a| a.acodec.clone()),
+        abr: audio.map_or(video.abr, |a| a.abr),
+        filesize: parts.iter().map(|f| f.filesize).sum(),
+        tbr: parts.iter().map(|f| f.tbr).sum(),
+        ..(*video).clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fmt(id: &str, vcodec: &str, acodec: &str) -> Format {
+        Format {
+            format_id: id.to_string(),
+            ext: "mp4".to_string(),
+            vcodec: Some(vcodec.to_string()),
+            acodec: Some(acodec.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn info() -> VideoInfo {
+        VideoInfo {
+            formats: vec![
+                Format {
+                    format_note: Some("storyboard".to_string()),
+                    ..fmt("sb0", "none", "none")
+                },
+                Format {
+                    abr: Some(129.5),
+                    tbr: Some(129.5),
+                    filesize: Some(3 * 1024 * 1024),
+                    ..fmt("140", "none", "mp4a.40.2")
+                },
+                Format {
+                    width: Some(1920),
+                    height: Some(1080),
+                    fps: Some(30.0),
+                    tbr: Some(4500.0),
+                    filesize: Some(100 * 1024 * 1024),
+                    ..fmt("137", "avc1.640028", "none")
+                },
+                fmt("18", "avc1.42001E", "mp4a.40.2"),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn lists_and_describes_formats_without_storyboards() {
+        let info = info();
+        let ids: Vec<_> = pickable(&info).iter().map(|f| &f.format_id).collect();
+        assert_eq!(ids, ["140", "137", "18"]);
+
+        assert_eq!(info.formats[1].kind(), StreamKind::AudioOnly);
+        assert_eq!(info.formats[2].kind(), StreamKind::VideoOnly);
+        assert_eq!(info.formats[3].kind(), StreamKind::Muxed);
+        assert_eq!(Format::default().kind(), StreamKind::Muxed);
+
+        assert_eq!(
+            describe(&info.formats[2]),
+            "1920x1080 · avc1.640028 · 30 fps · 4500 kbps · 100.0 MB"
+        );
+        assert_eq!(
+            describe(&info.formats[1]),
+            "audio · mp4a.40.2 · 130 kbps · 3.0 MB"
+        );
+
+        let table = format_table(&info);
+        assert_eq!(table.lines().count(), 4);
+        assert!(table.lines().nth(2).unwrap().starts_with("137 "));
+        assert!(!table.contains("sb0"));
+    }
+
+    #[test]
+    fn video_and_audio_picks_resolve_to_a_merged_format() {
+        assert_eq!(selection_id(Some("137"), Some("140")).unwrap(), "137+140");
+        assert_eq!(selection_id(None, Some("140")).unwrap(), "140");
+        assert_eq!(selection_id(None, None), None);
+
+        let merged = resolve(&info(), "137+140").unwrap();
+        assert!(is_merge(&merged.format_id));
+        assert_eq!(merged.format_id, "137+140");
+        assert_eq!(merged.vcodec.as_deref(), Some("avc1.640028"));
+        assert_eq!(merged.acodec.as_deref(), Some("mp4a.40.2"));
+        assert_eq!(merged.filesize, Some(103 * 1024 * 1024));
+        assert_eq!(merged.height, Some(1080));
+
+        assert_eq!(resolve(&info(), "18").unwrap().format_id, "18");
+        let err = resolve(&info(), "137+999").unwrap_err();
+        assert_eq!(err, "Format not found: 999");
+    }
+}
@@ -1,16 +1,164 @@
+use crate::extractor::batch::BatchItem;
+use crate::extractor::cache::{CacheLookup, ExtractionCache};
+use crate::extractor::chapters;
 use crate::extractor::models::{Format, VideoInfo};
+use crate::extractor::search::{SearchPage, SearchQuery};
 use crate::extractor::traits::Extractor;
+use crate::utils::RustloaderError;
 use anyhow::Result;
-use std::sync::Arc;
-use tracing::{debug, info};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+
+/// Error text of failures that say nothing about the URL itself: yt-dlp's
+/// own reports of network trouble, throttling and server errors, and our
+/// subprocess timeouts.
+const INFRASTRUCTURE_MARKERS: &[&str] = &[
+    "timed out",
+    "stalled",
+    "http error 5",
+    "http error 429",
+    "too many requests",
+    "connection",
+    "name resolution",
+    "network is unreachable",
+];
+
+/// Whether `error` means the extractor itself is in trouble — it could not
+/// run, timed out, or the site failed or throttled the request — rather than
+/// that this one URL has no video. Only these count towards opening the
+/// circuit, so a list of dead links cannot take a working extractor out of
+/// rotation.
+fn is_infrastructure_failure(error: &anyhow::Error) -> bool {
+    for cause in error.chain() {
+        if let Some(e) = cause.downcast_ref::<reqwest::Error>() {
+            return e.status().is_none_or(|status| {
+                status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+            });
+        }
+        if cause.is::<std::io::Error>()
+            || cause.is::<tokio::time::error::Elapsed>()
+            || matches!(
+                cause.downcast_ref::<RustloaderError>(),
+                Some(RustloaderError::YtDlpNotFound)
+            )
+        {
+            return true;
+        }
+    }
+    let message = format!("{error:#}").to_ascii_lowercase();
+    INFRASTRUCTURE_MARKERS
+        .iter()
+        .any(|marker| message.contains(marker))
+}
+
+/// When an extractor is taken out of rotation.
+#[derive(Debug, Clone, Copy)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive failures that open the circuit.
+    pub failure_threshold: u32,
+    /// How long an open circuit bypasses the extractor before it gets
+    /// another (single) chance.
+    pub cooldown: Duration,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            cooldown: Duration::from_secs(120),
+        }
+    }
+}
+
+/// Per-extractor counters, for diagnostics.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExtractorStats {
+    pub id: String,
+    pub successes: u64,
+    /// Every failed call, whether the URL or the extractor was at fault.
+    pub failures: u64,
+    /// Failed calls that say the extractor itself is broken; only these
+    /// count towards the circuit breaker.
+    pub infrastructure_failures: u64,
+    pub consecutive_failures: u32,
+    /// Mean duration of completed (successful or failed) calls.
+    pub average_latency: Option<Duration>,
+    pub last_error: Option<String>,
+    /// Currently bypassed by the circuit breaker.
+    pub circuit_open: bool,
+}
+
+/// How far an extraction has got, for progress display.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExtractionStage {
+    /// Waiting for a free extraction slot.
+    Queued,
+    /// Looking the URL up in the cache and picking extractors for it.
+    Resolving,
+    /// The named extractor is fetching the video's formats.
+    FetchingFormats { extractor: String },
+}
+
+impl std::fmt::Display for ExtractionStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Queued => write!(f, "Waiting"),
+            Self::Resolving => write!(f, "Resolving"),
+            Self::FetchingFormats { extractor } => {
+                write!(f, "Fetching formats ({extractor})")
+            }
+        }
+    }
+}
+
+/// Observer for [`ExtractionStage`] changes.
+pub type StageReporter<'a> = &'a (dyn Fn(ExtractionStage) + Send + Sync);
+
+/// Every extractor in the chain failed; lists each one's error in order.
+#[derive(Debug, thiserror::Error)]
+#[error("All extractors failed for {url}: {}", format_failures(.failures))]
+pub struct ChainError {
+    pub url: String,
+    /// `(extractor id, error)` in the order they were tried.
+    pub failures: Vec<(String, String)>,
+}
+
+fn format_failures(failures: &[(String, String)]) -> String {
+    failures
+        .iter()
+        .map(|(id, error)| format!("[{id}] {error}"))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+#[derive(Default)]
+struct Health {
+    successes: u64,
+    failures: u64,
+    infrastructure_failures: u64,
+    consecutive_failures: u32,
+    total_latency: Duration,
+    last_error: Option<String>,
+    open_until: Option<Instant>,
+}
 
 /// The Hybrid Extractor Registry
 ///
-/// This struct holds a list of available extractors and routes requests
-/// to the most appropriate one based on `supports(url)`.
+/// This struct holds a list of available extractors and, for every
+/// operation, tries the ones whose `supports(url)` matches in registration
+/// order, then the fallback, then the last resort. Extractors that keep
+/// failing are bypassed for a while (see [`CircuitBreakerConfig`]).
 pub struct HybridExtractor {
     extractors: Vec<Arc<dyn Extractor>>,
     fallback: Arc<dyn Extractor>,
+    last_resort: Option<Arc<dyn Extractor>>,
+    cache: Option<ExtractionCache>,
+    breaker: CircuitBreakerConfig,
+    health: Mutex<HashMap<String, Health>>,
 }
 
 impl HybridExtractor {
@@ -19,54 +167,820 @@ impl HybridExtractor {
         Self {
             extractors,
             fallback,
+            last_resort: None,
+            cache: None,
+            breaker: CircuitBreakerConfig::default(),
+            health: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Serve `extract_info` from (and record results in) an extraction
+    /// cache.
+    pub fn with_cache(mut self, cache: ExtractionCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// The extraction cache, if one is attached.
+    pub fn cache(&self) -> Option<&ExtractionCache> {
+        self.cache.as_ref()
+    }
+
+    /// Add an extractor tried only after the fallback has failed too (the
+    /// generic page scanner, for sites yt-dlp does not know).
+    pub fn with_last_resort(mut self, extractor: Arc<dyn Extractor>) -> Self {
+        self.last_resort = Some(extractor);
+        self
+    }
+
+    /// Replace the default circuit breaker thresholds.
+    pub fn with_circuit_breaker(mut self, config: CircuitBreakerConfig) -> Self {
+        self.breaker = config;
+        self
+    }
+
+    /// The extractors tried for `url`, in order: every registered extractor
+    /// that supports it, the fallback, then the last resort if it supports
+    /// it. Each extractor appears once.
+    pub fn chain_for(&self, url: &str) -> Vec<Arc<dyn Extractor>> {
+        let mut chain: Vec<Arc<dyn Extractor>> = Vec::new();
+        let candidates = self
+            .extractors
+            .iter()
+            .filter(|e| e.supports(url))
+            .chain(std::iter::once(&self.fallback))
+            .chain(self.last_resort.iter().filter(|e| e.supports(url)));
+        for extractor in candidates {
+            if !chain.iter().any(|e| e.id() == extractor.id()) {
+                chain.push(Arc::clone(extractor));
+            }
         }
+        chain
     }
 
-    /// Find the best extractor for a given URL
-    fn find_extractor(&self, url: &str) -> &Arc<dyn Extractor> {
-        for extractor in &self.extractors {
-            if extractor.supports(url) {
-                debug!("Routing to extractor: {}", extractor.id());
-                return extractor;
+    /// Success/failure counts, latency and circuit state for every extractor
+    /// in the registry, in chain order.
+    pub fn stats(&self) -> Vec<ExtractorStats> {
+        let health = self.health.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        let mut stats: Vec<ExtractorStats> = Vec::new();
+        let all = self
+            .extractors
+            .iter()
+            .chain(std::iter::once(&self.fallback))
+            .chain(self.last_resort.iter());
+        for extractor in all {
+            let id = extractor.id();
+            if stats.iter().any(|s| s.id == id) {
+                continue;
+            }
+            let mut entry = ExtractorStats {
+                id: id.to_string(),
+                ..Default::default()
+            };
+            if let Some(h) = health.get(id) {
+                let calls = h.successes + h.failures;
+                entry.successes = h.successes;
+                entry.failures = h.failures;
+                entry.infrastructure_failures = h.infrastructure_failures;
+                entry.consecutive_failures = h.consecutive_failures;
+                entry.average_latency =
+                    (calls > 0).then(|| h.total_latency / u32::try_from(calls).unwrap_or(u32::MAX));
+                entry.last_error = h.last_error.clone();
+                entry.circuit_open = h.open_until.is_some_and(|until| until > now);
             }
+            stats.push(entry);
         }
-        debug!("Routing to fallback extractor: {}", self.fallback.id());
-        &self.fallback
+        stats
+    }
+
+    fn circuit_open(&self, id: &str) -> bool {
+        let health = self.health.lock().unwrap_or_else(|e| e.into_inner());
+        health
+            .get(id)
+            .and_then(|h| h.open_until)
+            .is_some_and(|until| until > Instant::now())
     }
 
-    /// Extract video info using the best matching strategy
+    fn record(&self, id: &str, latency: Duration, outcome: Result<(), &anyhow::Error>) {
+        let mut health = self.health.lock().unwrap_or_else(|e| e.into_inner());
+        let h = health.entry(id.to_string()).or_default();
+        h.total_latency += latency;
+        if let Err(e) = outcome {
+            h.failures += 1;
+            h.last_error = Some(e.to_string());
+        }
+        match outcome {
+            // The extractor ran and answered, if only that this URL has no
+            // video (unavailable, private, age-gated): it is healthy.
+            Ok(()) => {
+                h.successes += 1;
+                h.consecutive_failures = 0;
+                h.open_until = None;
+            }
+            Err(e) if !is_infrastructure_failure(e) => {
+                h.consecutive_failures = 0;
+                h.open_until = None;
+            }
+            Err(_) => {
+                h.infrastructure_failures += 1;
+                h.consecutive_failures += 1;
+                // Reaching the threshold opens the circuit; a failed retry
+                // after the cooldown (half-open) re-opens it straight away.
+                if h.consecutive_failures >= self.breaker.failure_threshold {
+                    if h.open_until.is_none_or(|until| until <= Instant::now()) {
+                        warn!(
+                            "Extractor {} failed {} times in a row; bypassing it for {:?}",
+                            id, h.consecutive_failures, self.breaker.cooldown
+                        );
+                    }
+                    h.open_until = Some(Instant::now() + self.breaker.cooldown);
+                }
+            }
+        }
+    }
+
+    /// Run `call` against the chain for `url` until one extractor succeeds.
+    /// Extractors with an open circuit are skipped — unless every extractor
+    /// in the chain is open, in which case they are all tried anyway rather
+    /// than failing without an attempt. A single failure is returned as-is;
+    /// several are combined into a [`ChainError`].
+    async fn run_chain<T, F, Fut>(&self, url: &str, operation: &str, mut call: F) -> Result<T>
+    where
+        F: FnMut(Arc<dyn Extractor>) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let chain = self.chain_for(url);
+        let all_open = chain.iter().all(|e| self.circuit_open(e.id()));
+        let mut errors: Vec<(String, anyhow::Error)> = Vec::new();
+
+        for extractor in chain {
+            let id = extractor.id().to_string();
+            if !all_open && self.circuit_open(&id) {
+                debug!("Skipping extractor {} for {}: circuit open", id, operation);
+                continue;
+            }
+            debug!("Routing {} to extractor: {}", operation, id);
+
+            let started = Instant::now();
+            match call(extractor).await {
+                Ok(value) => {
+                    self.record(&id, started.elapsed(), Ok(()));
+                    return Ok(value);
+                }
+                Err(e) => {
+                    let unsupported = matches!(
+                        e.downcast_ref::<RustloaderError>(),
+                        Some(RustloaderError::NotSupported(_))
+                    );
+                    if !unsupported {
+                        self.record(&id, started.elapsed(), Err(&e));
+                    }
+                    info!(
+                        "Extractor {} failed {}: {}. Trying next...",
+                        id, operation, e
+                    );
+                    errors.push((id, e));
+                }
+            }
+        }
+
+        if errors.len() == 1 {
+            return Err(errors.remove(0).1);
+        }
+        Err(ChainError {
+            url: url.to_string(),
+            failures: errors
+                .into_iter()
+                .map(|(id, e)| (id, e.to_string()))
+                .collect(),
+        }
+        .into())
+    }
+
+    /// Extract video info, served from the cache when one is attached and
+    /// holds a fresh result. A cached entry whose signed format URLs have
+    /// expired is re-extracted; if that fails the cached metadata is still
+    /// returned, since downloads re-resolve their direct URL anyway.
     pub async fn extract_info(&self, url: &str) -> Result<VideoInfo> {
-        let extractor = self.find_extractor(url);
-        match extractor.extract_info(url).await {
-            Ok(info) => Ok(info),
+        self.extract_info_reporting(url, &|_| {}).await
+    }
+
+    /// [`Self::extract_info`], telling `report` about each stage it reaches.
+    pub async fn extract_info_reporting(
+        &self,
+        url: &str,
+        report: StageReporter<'_>,
+    ) -> Result<VideoInfo> {
+        report(ExtractionStage::Resolving);
+        let Some(cache) = &self.cache else {
+            return self.extract_live(url, report).await;
+        };
+        let cached = match cache.lookup(url).await {
+            Ok(CacheLookup::Fresh(info)) => return Ok(info),
+            Ok(CacheLookup::FormatsExpired(info)) => Some(info),
+            Ok(CacheLookup::Miss) => None,
             Err(e) => {
-                // If a specialized extractor fails, try the fallback!
-                info!(
-                    "Primary extractor {} failed: {}. Retrying with fallback...",
-                    extractor.id(),
-                    e
-                );
-                if extractor.id() != self.fallback.id() {
-                    self.fallback.extract_info(url).await
-                } else {
-                    Err(e)
+                warn!("Extraction cache lookup failed for {}: {}", url, e);
+                None
+            }
+        };
+        self.extract_or_cached(url, cached, report).await
+    }
+
+    /// Re-extract `url`, falling back to `cached` metadata if that fails.
+    async fn extract_or_cached(
+        &self,
+        url: &str,
+        cached: Option<VideoInfo>,
+        report: StageReporter<'_>,
+    ) -> Result<VideoInfo> {
+        match (self.extract_and_store(url, report).await, cached) {
+            (Err(e), Some(info)) => {
+                warn!("Re-extracting {} failed: {}. Using cached metadata", url, e);
+                Ok(info)
+            }
+            (result, _) => result,
+        }
+    }
+
+    /// Extract video info, bypassing any cached result, and update the cache
+    /// with the new one.
+    pub async fn refresh_info(&self, url: &str) -> Result<VideoInfo> {
+        self.refresh_info_reporting(url, &|_| {}).await
+    }
+
+    /// [`Self::refresh_info`], telling `report` about each stage it reaches.
+    pub async fn refresh_info_reporting(
+        &self,
+        url: &str,
+        report: StageReporter<'_>,
+    ) -> Result<VideoInfo> {
+        report(ExtractionStage::Resolving);
+        self.extract_and_store(url, report).await
+    }
+
+    async fn extract_and_store(&self, url: &str, report: StageReporter<'_>) -> Result<VideoInfo> {
+        let info = self.extract_live(url, report).await?;
+        self.store(url, &info).await;
+        Ok(info)
+    }
+
+    async fn store(&self, url: &str, info: &VideoInfo) {
+        // A live or upcoming stream changes state; it is not worth caching.
+        if let (Some(cache), false) = (&self.cache, info.live().needs_recording()) {
+            if let Err(e) = cache.store(url, info).await {
+                warn!("Failed to cache extraction for {}: {}", url, e);
+            }
+        }
+    }
+
+    /// Extract many URLs, sending each `(url, result)` on `results` as soon
+    /// as it is ready. Fresh cached results go out first. URLs whose first
+    /// choice is the fallback are handed to it as one batch (yt-dlp then
+    /// needs a few processes instead of one per URL); a URL the batch fails
+    /// still gets the rest of its chain. Other URLs are extracted one by one
+    /// as with [`Self::extract_info`], alongside the batch.
+    pub async fn extract_batch(&self, urls: &[String], results: mpsc::Sender<BatchItem>) {
+        let mut batched = Vec::new();
+        let mut single = Vec::new();
+        let mut stale = HashMap::new();
+        for url in urls {
+            if let Some(cache) = &self.cache {
+                match cache.lookup(url).await {
+                    Ok(CacheLookup::Fresh(info)) => {
+                        if results.send((url.clone(), Ok(info))).await.is_err() {
+                            return;
+                        }
+                        continue;
+                    }
+                    Ok(CacheLookup::FormatsExpired(info)) => {
+                        stale.insert(url.clone(), info);
+                    }
+                    Ok(CacheLookup::Miss) => {}
+                    Err(e) => warn!("Extraction cache lookup failed for {}: {}", url, e),
+                }
+            }
+            if self.batches_through_fallback(url) {
+                batched.push(url.clone());
+            } else {
+                single.push(url.clone());
+            }
+        }
+        debug!(
+            "Batch of {}: {} through {}, {} one by one",
+            urls.len(),
+            batched.len(),
+            self.fallback.id(),
+            single.len()
+        );
+
+        let stale = &stale;
+        let (batch_tx, mut batch_rx) = mpsc::channel(16);
+        let batch = self.fallback.extract_batch(&batched, batch_tx);
+        let forward = async {
+            let id = self.fallback.id();
+            let mut last = Instant::now();
+            while let Some((url, result)) = batch_rx.recv().await {
+                // Results arrive one after another; charge each the wait for it.
+                self.record(id, last.elapsed(), result.as_ref().map(|_| ()));
+                last = Instant::now();
+                let result = match result {
+                    Ok(mut info) => {
+                        chapters::fill_from_description(&mut info);
+                        self.store(&url, &info).await;
+                        Ok(info)
+                    }
+                    Err(e) => {
+                        self.recover_batch_failure(&url, e, stale.get(&url).cloned())
+                            .await
+                    }
+                };
+                if results.send((url, result)).await.is_err() {
+                    return;
+                }
+            }
+        };
+        let singles = async {
+            for url in &single {
+                let result = self
+                    .extract_or_cached(url, stale.get(url).cloned(), &|_| {})
+                    .await;
+                if results.send((url.clone(), result)).await.is_err() {
+                    return;
+                }
+            }
+        };
+        tokio::join!(batch, forward, singles);
+    }
+
+    /// Whether `url` would go to the fallback first: no registered extractor
+    /// supports it and the fallback's circuit is closed.
+    fn batches_through_fallback(&self, url: &str) -> bool {
+        let fallback = self.fallback.id();
+        !self.circuit_open(fallback)
+            && self
+                .chain_for(url)
+                .first()
+                .is_some_and(|e| e.id() == fallback)
+    }
+
+    /// The fallback's batch failed `url`: try what follows it in the chain
+    /// (the last resort), then settle for cached metadata.
+    async fn recover_batch_failure(
+        &self,
+        url: &str,
+        error: anyhow::Error,
+        cached: Option<VideoInfo>,
+    ) -> Result<VideoInfo> {
+        let fallback = self.fallback.id();
+        let mut errors = vec![(fallback.to_string(), error)];
+        let rest = self
+            .chain_for(url)
+            .into_iter()
+            .skip_while(|e| e.id() != fallback)
+            .skip(1);
+        for extractor in rest {
+            let id = extractor.id().to_string();
+            if self.circuit_open(&id) {
+                continue;
+            }
+            let started = Instant::now();
+            match extractor.extract_info(url).await {
+                Ok(mut info) => {
+                    self.record(&id, started.elapsed(), Ok(()));
+                    chapters::fill_from_description(&mut info);
+                    self.store(url, &info).await;
+                    return Ok(info);
+                }
+                Err(e) => {
+                    self.record(&id, started.elapsed(), Err(&e));
+                    errors.push((id, e));
                 }
             }
         }
+        if let Some(info) = cached {
+            warn!(
+                "Re-extracting {} failed: {}. Using cached metadata",
+                url, errors[0].1
+            );
+            return Ok(info);
+        }
+        if errors.len() == 1 {
+            return Err(errors.remove(0).1);
+        }
+        Err(ChainError {
+            url: url.to_string(),
+            failures: errors
+                .into_iter()
+                .map(|(id, e)| (id, e.to_string()))
+                .collect(),
+        }
+        .into())
+    }
+
+    /// Extract video info by walking the extractor chain. Videos without
+    /// chapters get the ones their description lists.
+    async fn extract_live(&self, url: &str, report: StageReporter<'_>) -> Result<VideoInfo> {
+        let mut info = self
+            .run_chain(url, "extract_info", |e| async move {
+                report(ExtractionStage::FetchingFormats {
+                    extractor: e.id().to_string(),
+                });
+                e.extract_info(url).await
+            })
+            .await?;
+        chapters::fill_from_description(&mut info);
+        Ok(info)
     }
 
     pub async fn extract_playlist(&self, url: &str) -> Result<Vec<VideoInfo>> {
-        let extractor = self.find_extractor(url);
-        extractor.extract_playlist(url).await
+        self.run_chain(url, "extract_playlist", |e| async move {
+            e.extract_playlist(url).await
+        })
+        .await
     }
 
     pub async fn get_formats(&self, url: &str) -> Result<Vec<Format>> {
-        let extractor = self.find_extractor(url);
-        extractor.get_formats(url).await
+        self.run_chain(
+            url,
+            "get_formats",
+            |e| async move { e.get_formats(url).await },
+        )
+        .await
     }
 
+    /// Run a site search. Searches are not URLs, so the chain is the one for
+    /// yt-dlp's search pseudo-URL (`ytsearch10:terms`): in practice the
+    /// fallback, plus any extractor that claims such targets.
+    pub async fn search(&self, query: &SearchQuery) -> Result<SearchPage> {
+        self.run_chain(&query.target(), "search", |e| async move {
+            e.search(query).await
+        })
+        .await
+    }
+
+    /// Resolve a format's direct URL. Walks the same chain as
+    /// `extract_info`: the format may have come from any extractor in it.
     pub async fn get_direct_url(&self, url: &str, format_id: &str) -> Result<String> {
-        let extractor = self.find_extractor(url);
-        extractor.get_direct_url(url, format_id).await
+        self.run_chain(url, "get_direct_url", |e| async move {
+            e.get_direct_url(url, format_id).await
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Extractor for URLs containing `pattern`; fails with its id (as an IO
+    /// error) while `fail` is set, reports `/gone/` URLs as unavailable, and
+    /// counts every call.
+    struct Scripted {
+        id: &'static str,
+        pattern: &'static str,
+        fail: bool,
+        calls: AtomicUsize,
+        batches: AtomicUsize,
+    }
+
+    impl Scripted {
+        fn new(id: &'static str, pattern: &'static str, fail: bool) -> Arc<Self> {
+            Arc::new(Self {
+                id,
+                pattern,
+                fail,
+                calls: AtomicUsize::new(0),
+                batches: AtomicUsize::new(0),
+            })
+        }
+
+        fn calls(&self) -> usize {
+            self.calls.load(Ordering::SeqCst)
+        }
+
+        fn broke(&self) -> anyhow::Error {
+            std::io::Error::other(format!("{} broke", self.id)).into()
+        }
+    }
+
+    #[async_trait]
+    impl Extractor for Scripted {
+        fn id(&self) -> &str {
+            self.id
+        }
+
+        fn supports(&self, url: &str) -> bool {
+            url.contains(self.pattern)
+        }
+
+        async fn extract_info(&self, url: &str) -> Result<VideoInfo> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.fail {
+                return Err(self.broke());
+            }
+            if url.contains("/gone/") {
+                return Err(RustloaderError::ExtractionError(format!(
+                    "ERROR: [{}] {url}: Video unavailable",
+                    self.id
+                ))
+                .into());
+            }
+            Ok(VideoInfo {
+                title: self.id.to_string(),
+                url: url.to_string(),
+                ..Default::default()
+            })
+        }
+
+        async fn extract_batch(&self, urls: &[String], results: mpsc::Sender<BatchItem>) {
+            self.batches.fetch_add(1, Ordering::SeqCst);
+            for url in urls {
+                let _ = results
+                    .send((url.clone(), self.extract_info(url).await))
+                    .await;
+            }
+        }
+
+        async fn get_direct_url(&self, _url: &str, _format_id: &str) -> Result<String> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.fail {
+                return Err(self.broke());
+            }
+            Ok(format!("https://cdn.test/{}", self.id))
+        }
+    }
+
+    fn as_dyn(e: &Arc<Scripted>) -> Arc<dyn Extractor> {
+        Arc::clone(e) as Arc<dyn Extractor>
+    }
+
+    #[tokio::test]
+    async fn walks_every_matching_extractor_before_the_fallback() {
+        let first = Scripted::new("first", "site.test", true);
+        let second = Scripted::new("second", "site.test", false);
+        let other = Scripted::new("other", "elsewhere.test", false);
+        let fallback = Scripted::new("fallback", "", false);
+        let hybrid = HybridExtractor::new(
+            vec![as_dyn(&first), as_dyn(&other), as_dyn(&second)],
+            as_dyn(&fallback),
+        );
+
+        let ids: Vec<_> = hybrid
+            .chain_for("https://site.test/v/1")
+            .iter()
+            .map(|e| e.id().to_string())
+            .collect();
+        assert_eq!(ids, ["first", "second", "fallback"]);
+
+        let info = hybrid.extract_info("https://site.test/v/1").await.unwrap();
+        assert_eq!(info.title, "second");
+        assert_eq!(fallback.calls(), 0);
+        assert_eq!(other.calls(), 0);
+
+        let direct = hybrid
+            .get_direct_url("https://site.test/v/1", "0")
+            .await
+            .unwrap();
+        assert_eq!(direct, "https://cdn.test/second");
+    }
+
+    async fn batch(hybrid: &HybridExtractor, urls: &[&str]) -> HashMap<String, Result<VideoInfo>> {
+        let urls: Vec<String> = urls.iter().map(|u| u.to_string()).collect();
+        let (tx, mut rx) = mpsc::channel(4);
+        let (_, received) = tokio::join!(hybrid.extract_batch(&urls, tx), async {
+            let mut received = HashMap::new();
+            while let Some((url, result)) = rx.recv().await {
+                received.insert(url, result);
+            }
+            received
+        });
+        received
+    }
+
+    #[tokio::test]
+    async fn batches_only_the_urls_meant_for_the_fallback() {
+        let native = Scripted::new("native", "site.test", false);
+        let fallback = Scripted::new("fallback", "", false);
+        let hybrid = HybridExtractor::new(vec![as_dyn(&native)], as_dyn(&fallback));
+
+        let received = batch(
+            &hybrid,
+            &[
+                "https://site.test/v/1",
+                "https://x.test/2",
+                "https://x.test/3",
+            ],
+        )
+        .await;
+        assert_eq!(received.len(), 3);
+        assert_eq!(
+            received["https://site.test/v/1"].as_ref().unwrap().title,
+            "native"
+        );
+        assert_eq!(
+            received["https://x.test/3"].as_ref().unwrap().title,
+            "fallback"
+        );
+        assert_eq!(native.calls(), 1);
+        assert_eq!(fallback.calls(), 2);
+        assert_eq!(fallback.batches.load(Ordering::SeqCst), 1);
+        assert_eq!(hybrid.stats()[1].successes, 2);
+    }
+
+    #[tokio::test]
+    async fn urls_the_batch_fails_still_reach_the_last_resort() {
+        let fallback = Scripted::new("fallback", "", true);
+        let last = Scripted::new("last", "rescue", false);
+        let hybrid =
+            HybridExtractor::new(Vec::new(), as_dyn(&fallback)).with_last_resort(as_dyn(&last));
+
+        let received = batch(&hybrid, &["https://rescue.test/1", "https://x.test/2"]).await;
+        assert_eq!(
+            received["https://rescue.test/1"].as_ref().unwrap().title,
+            "last"
+        );
+        let err = received["https://x.test/2"].as_ref().unwrap_err();
+        assert_eq!(err.to_string(), "fallback broke");
+        assert_eq!(fallback.batches.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn combined_error_lists_every_failure_in_order() {
+        let native = Scripted::new("native", "site.test", true);
+        let fallback = Scripted::new("fallback", "", true);
+        let last = Scripted::new("last", "", true);
+        let hybrid = HybridExtractor::new(vec![as_dyn(&native)], as_dyn(&fallback))
+            .with_last_resort(as_dyn(&last));
+
+        let err = hybrid
+            .extract_info("https://site.test/v/1")
+            .await
+            .unwrap_err();
+        let chain = err.downcast_ref::<ChainError>().expect("a ChainError");
+        let ids: Vec<_> = chain.failures.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, ["native", "fallback", "last"]);
+        let message = err.to_string();
+        assert!(message.contains("[native] native broke"), "{message}");
+        assert!(message.contains("[last] last broke"), "{message}");
+
+        // A lone failure is passed through untouched.
+        let hybrid = HybridExtractor::new(Vec::new(), as_dyn(&fallback));
+        let err = hybrid.extract_info("https://x.test").await.unwrap_err();
+        assert!(err.downcast_ref::<ChainError>().is_none());
+        assert_eq!(err.to_string(), "fallback broke");
+    }
+
+    #[tokio::test]
+    async fn stages_name_each_extractor_tried() {
+        let native = Scripted::new("native", "site.test", true);
+        let fallback = Scripted::new("fallback", "", false);
+        let hybrid = HybridExtractor::new(vec![as_dyn(&native)], as_dyn(&fallback));
+
+        let stages = Mutex::new(Vec::new());
+        hybrid
+            .extract_info_reporting("https://site.test/v/1", &|stage| {
+                stages.lock().unwrap().push(stage)
+            })
+            .await
+            .unwrap();
+        let fetching = |id: &str| ExtractionStage::FetchingFormats {
+            extractor: id.to_string(),
+        };
+        assert_eq!(
+            stages.into_inner().unwrap(),
+            [
+                ExtractionStage::Resolving,
+                fetching("native"),
+                fetching("fallback")
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn playlists_fall_back_past_extractors_without_playlist_support() {
+        let native = Scripted::new("native", "site.test", false);
+        let fallback = Scripted::new("fallback", "", false);
+        let hybrid = HybridExtractor::new(vec![as_dyn(&native)], as_dyn(&fallback));
+
+        let err = hybrid
+            .extract_playlist("https://site.test/list")
+            .await
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Playlist extraction not supported by native"));
+        // "Not supported" is not a failure: no stats, no circuit pressure.
+        let stats = hybrid.stats();
+        assert_eq!(stats[0].failures, 0);
+        assert_eq!(stats[1].failures, 0);
+    }
+
+    #[tokio::test]
+    async fn circuit_opens_after_repeated_failures_and_closes_after_cooldown() {
+        let flaky = Scripted::new("flaky", "site.test", true);
+        let fallback = Scripted::new("fallback", "", false);
+        let hybrid = HybridExtractor::new(vec![as_dyn(&flaky)], as_dyn(&fallback))
+            .with_circuit_breaker(CircuitBreakerConfig {
+                failure_threshold: 2,
+                cooldown: Duration::from_millis(200),
+            });
+        let url = "https://site.test/v/1";
+
+        for _ in 0..2 {
+            hybrid.extract_info(url).await.unwrap();
+        }
+        assert_eq!(flaky.calls(), 2);
+        let stats = hybrid.stats();
+        assert!(stats[0].circuit_open);
+        assert_eq!(stats[0].last_error.as_deref(), Some("flaky broke"));
+
+        // Open: bypassed entirely.
+        hybrid.extract_info(url).await.unwrap();
+        assert_eq!(flaky.calls(), 2);
+
+        // Half-open after the cooldown: one more try, which fails and
+        // re-opens the circuit.
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        hybrid.extract_info(url).await.unwrap();
+        assert_eq!(flaky.calls(), 3);
+        hybrid.extract_info(url).await.unwrap();
+        assert_eq!(flaky.calls(), 3);
+
+        let stats = hybrid.stats();
+        assert_eq!(stats[0].failures, 3);
+        assert_eq!(stats[0].infrastructure_failures, 3);
+        assert_eq!(stats[1].id, "fallback");
+        assert_eq!(stats[1].successes, 5);
+        assert!(stats[1].average_latency.is_some());
+    }
+
+    #[tokio::test]
+    async fn an_all_open_chain_is_still_attempted() {
+        let fallback = Scripted::new("fallback", "", true);
+        let hybrid = HybridExtractor::new(Vec::new(), as_dyn(&fallback)).with_circuit_breaker(
+            CircuitBreakerConfig {
+                failure_threshold: 1,
+                cooldown: Duration::from_secs(60),
+            },
+        );
+
+        assert!(hybrid.extract_info("https://x.test").await.is_err());
+        assert!(hybrid.stats()[0].circuit_open);
+        assert!(hybrid.extract_info("https://x.test").await.is_err());
+        assert_eq!(fallback.calls(), 2);
+    }
+
+    #[tokio::test]
+    async fn content_errors_leave_the_circuit_closed() {
+        let fallback = Scripted::new("fallback", "", false);
+        let hybrid = HybridExtractor::new(Vec::new(), as_dyn(&fallback));
+
+        let dead: Vec<String> = (0..5).map(|i| format!("https://x.test/gone/{i}")).collect();
+        let dead: Vec<&str> = dead.iter().map(String::as_str).collect();
+        let received = batch(&hybrid, &dead).await;
+        assert!(received.values().all(|r| r.is_err()));
+        for url in &dead {
+            assert!(hybrid.extract_info(url).await.is_err());
+        }
+
+        let stats = hybrid.stats();
+        assert!(!stats[0].circuit_open);
+        assert_eq!(stats[0].infrastructure_failures, 0);
+        // They are still failures, for the diagnostics view.
+        let failed = stats[0].failures;
+        assert_eq!(failed, dead.len() as u64 * 2);
+        assert!(stats[0].last_error.is_some());
+        hybrid.extract_info("https://x.test/ok").await.unwrap();
+        // Every call counts towards the mean latency.
+        let stats = hybrid.stats();
+        assert_eq!(stats[0].successes + stats[0].failures, failed + 1);
+    }
+
+    #[test]
+    fn only_infrastructure_errors_count_as_failures() {
+        let content = |m: &str| anyhow::Error::from(RustloaderError::ExtractionError(m.into()));
+        assert!(!is_infrastructure_failure(&content(
+            "ERROR: [youtube] abc: Private video"
+        )));
+        assert!(!is_infrastructure_failure(&content(
+            "ERROR: [youtube] abc: Sign in to confirm your age"
+        )));
+        assert!(is_infrastructure_failure(&content(
+            "ERROR: Unable to download webpage: HTTP Error 503: Service Unavailable"
+        )));
+        assert!(is_infrastructure_failure(&content(
+            "ERROR: HTTP Error 429: Too Many Requests"
+        )));
+        assert!(is_infrastructure_failure(&anyhow!(
+            "yt-dlp extraction timed out after 60s (subprocess killed)"
+        )));
+        assert!(is_infrastructure_failure(&anyhow::Error::from(
+            std::io::Error::other("spawn failed")
+        )));
     }
 }
@@ -1,10 +1,31 @@
+pub mod batch;
+pub mod cache;
+pub mod chapters;
+pub mod formats;
 pub mod hybrid;
 pub mod models;
 pub mod native;
+pub mod playlist;
+pub mod plugins;
+pub mod preferences;
+pub mod search;
+pub mod selector;
+pub mod subtitles;
 pub mod traits;
 pub mod ytdlp;
 
-pub use hybrid::HybridExtractor;
-pub use models::{Format, VideoInfo};
+pub use batch::{BatchItem, InOrder};
+pub use cache::{CacheLookup, CachePolicy, ExtractionCache};
+pub use formats::StreamKind;
+pub use hybrid::{
+    ChainError, CircuitBreakerConfig, ExtractionStage, ExtractorStats, HybridExtractor,
+    StageReporter,
+};
+pub use models::{Chapter, Format, LiveStatus, SubtitleFile, VideoInfo};
+pub use playlist::{Playlist, PlaylistSelection};
+pub use preferences::{Container, DynamicRange, FormatPreferences};
+pub use search::{SearchPage, SearchProvider, SearchQuery};
+pub use selector::{FormatSelector, FormatSort};
+pub use subtitles::{SubtitleFormat, SubtitleOptions, SubtitleTrack};
 pub use traits::Extractor;
 pub use ytdlp::YtDlpExtractor;
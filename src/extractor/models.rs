@@ -1,6 +1,7 @@
 //! Data structures for video information
 
 use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
 
 /// Deserialize an optional unsigned integer that the source may emit as a JSON
 /// float (yt-dlp does this for some sites' `duration`, e.g. SoundCloud's
@@ -14,6 +15,26 @@ where
     Ok(Option::<f64>::deserialize(deserializer)?.map(|f| f as u64))
 }
 
+/// Deserialize `null` as the type's default. yt-dlp writes `"chapters": null`
+/// for videos without chapters, which `#[serde(default)]` alone rejects.
+fn deserialize_null_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+/// Read yt-dlp's `live_status`; values this version does not know are
+/// ignored rather than failing the parse.
+fn deserialize_live_status<'de, D>(deserializer: D) -> Result<Option<LiveStatus>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Option::<String>::deserialize(deserializer)?
+        .and_then(|s| serde_json::from_value(serde_json::Value::String(s)).ok()))
+}
+
 /// Video information structure
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct VideoInfo {
@@ -51,6 +72,83 @@ pub struct VideoInfo {
     pub view_count: Option<u64>,
     pub like_count: Option<u64>,
     pub extractor: Option<String>,
+    /// Title of the playlist this entry came from (flat playlist output).
+    #[serde(default)]
+    pub playlist_title: Option<String>,
+    /// Subtitle tracks by language, as yt-dlp lists them.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub subtitles: BTreeMap<String, Vec<SubtitleFile>>,
+    /// Auto-generated captions by language.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub automatic_captions: BTreeMap<String, Vec<SubtitleFile>>,
+    /// Chapters in playback order, as yt-dlp lists them (or as parsed from
+    /// the description's timestamps).
+    #[serde(
+        default,
+        deserialize_with = "deserialize_null_default",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub chapters: Vec<Chapter>,
+    /// yt-dlp's `live_status`; see [`VideoInfo::live`].
+    #[serde(
+        default,
+        deserialize_with = "deserialize_live_status",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub live_status: Option<LiveStatus>,
+    /// Older yt-dlp versions (and some extractors) only set these.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_live: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub was_live: Option<bool>,
+    /// When an upcoming stream or premiere is scheduled to start (Unix time).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub release_timestamp: Option<i64>,
+}
+
+/// Whether a video is, was or will be a live stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LiveStatus {
+    NotLive,
+    /// Streaming right now.
+    IsLive,
+    /// A scheduled stream or premiere that has not started.
+    IsUpcoming,
+    /// A finished stream, available as a regular video.
+    WasLive,
+    /// A finished stream still being processed; its recording may only go
+    /// back a few hours.
+    PostLive,
+}
+
+impl LiveStatus {
+    /// Live or upcoming: recorded rather than downloaded.
+    pub fn needs_recording(self) -> bool {
+        matches!(self, LiveStatus::IsLive | LiveStatus::IsUpcoming)
+    }
+}
+
+/// A chapter of a video. Times are in seconds.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct Chapter {
+    pub start_time: f64,
+    /// `None` runs to the end of the video.
+    #[serde(default)]
+    pub end_time: Option<f64>,
+    #[serde(default)]
+    pub title: String,
+}
+
+/// One downloadable file of a subtitle track.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct SubtitleFile {
+    pub ext: String,
+    #[serde(default)]
+    pub url: String,
+    /// The track's display name (`English (auto-generated)`).
+    #[serde(default)]
+    pub name: Option<String>,
 }
 
 /// Video format information
@@ -72,6 +170,12 @@ pub struct Format {
     pub tbr: Option<f32>, // Total bitrate
     pub vbr: Option<f32>, // Video bitrate
     pub abr: Option<f32>, // Audio bitrate
+    /// Audio language (`en`, `es-419`, ...), when the site labels tracks.
+    #[serde(default)]
+    pub language: Option<String>,
+    /// `SDR`, `HDR10`, `HLG`, `DV`, ... as yt-dlp reports it.
+    #[serde(default)]
+    pub dynamic_range: Option<String>,
 }
 
 impl VideoInfo {
@@ -89,6 +193,25 @@ impl VideoInfo {
             }
         }
     }
+
+    /// The live status, from `live_status` or else the `is_live`/`was_live`
+    /// flags.
+    pub fn live(&self) -> LiveStatus {
+        match (self.live_status, self.is_live, self.was_live) {
+            (Some(status), _, _) => status,
+            (None, Some(true), _) => LiveStatus::IsLive,
+            (None, _, Some(true)) => LiveStatus::WasLive,
+            _ => LiveStatus::NotLive,
+        }
+    }
+
+    /// When an upcoming stream starts, if it is upcoming and the site said.
+    pub fn scheduled_start(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        if self.live() != LiveStatus::IsUpcoming {
+            return None;
+        }
+        chrono::DateTime::from_timestamp(self.release_timestamp?, 0)
+    }
 }
 
 #[cfg(test)]
@@ -130,6 +253,39 @@ mod tests {
         assert_eq!(v.url, "https://h/page", "should prefer webpage_url");
     }
 
+    #[test]
+    fn parses_ytdlp_chapters() {
+        let json = r#"{"id":"c","title":"Set","chapters":[
+            {"start_time":0.0,"end_time":95.5,"title":"Opener"},
+            {"start_time":95.5,"end_time":300.0,"title":"Peak"}]}"#;
+        let v: VideoInfo = serde_json::from_str(json).expect("chapters should parse");
+        assert_eq!(v.chapters.len(), 2);
+        assert_eq!(v.chapters[1].title, "Peak");
+        assert_eq!(v.chapters[1].end_time, Some(300.0));
+
+        let json = r#"{"id":"c","title":"Set","chapters":null}"#;
+        let v: VideoInfo = serde_json::from_str(json).expect("null chapters should parse");
+        assert!(v.chapters.is_empty());
+    }
+
+    #[test]
+    fn parses_live_status() {
+        let json = r#"{"id":"l","title":"Soon","live_status":"is_upcoming",
+            "release_timestamp":1900000000}"#;
+        let v: VideoInfo = serde_json::from_str(json).expect("live fields should parse");
+        assert_eq!(v.live(), LiveStatus::IsUpcoming);
+        assert!(v.live().needs_recording());
+        assert_eq!(v.scheduled_start().unwrap().timestamp(), 1_900_000_000);
+
+        let json = r#"{"id":"l","title":"Now","live_status":"something_new","is_live":true}"#;
+        let v: VideoInfo = serde_json::from_str(json).expect("unknown status should parse");
+        assert_eq!(v.live(), LiveStatus::IsLive);
+        assert_eq!(v.scheduled_start(), None);
+
+        let v: VideoInfo = serde_json::from_str(r#"{"id":"v","title":"t"}"#).unwrap();
+        assert_eq!(v.live(), LiveStatus::NotLive);
+    }
+
     #[test]
     fn parses_youtube_style_webpage_url_only() {
         let json = r#"{"id":"y","title":"Vid","webpage_url":"https://yt/watch?v=y"}"#;
@@ -0,0 +1,292 @@
+//! Direct media link extractor.
+//!
+//! A URL that points straight at a media file (`.../clip.mp4`) needs no
+//! extraction at all: one ranged probe — the same one
+//! [`DownloadEngine::probe`] does before downloading — yields its size and
+//! type. Answering that natively makes direct links extract instantly and
+//! work without yt-dlp installed.
+
+use crate::downloader::engine::{extension_for_content_type, extension_from_url, is_direct_media};
+use crate::downloader::DownloadEngine;
+use crate::extractor::models::{Format, VideoInfo};
+use crate::extractor::traits::Extractor;
+use crate::utils::CookieJar;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use std::sync::Arc;
+use tracing::{debug, info};
+
+/// File extensions that mark a URL as a direct media link. Manifests
+/// (`.m3u8`, `.mpd`) are deliberately absent: they need yt-dlp.
+const MEDIA_EXTENSIONS: &[&str] = &[
+    "mp4", "m4v", "webm", "mkv", "mov", "avi", "flv", "ogv", "mp3", "m4a", "aac", "ogg", "oga",
+    "opus", "flac", "wav",
+];
+
+/// The single format a direct link offers.
+const FORMAT_ID: &str = "0";
+
+pub struct DirectMediaExtractor {
+    engine: DownloadEngine,
+}
+
+impl Default for DirectMediaExtractor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DirectMediaExtractor {
+    pub fn new() -> Self {
+        Self {
+            engine: DownloadEngine::default(),
+        }
+    }
+
+    /// Probe with `jar`'s cookies, for direct links on sites that need a
+    /// login.
+    pub fn with_cookie_jar(mut self, jar: Arc<CookieJar>) -> Self {
+        self.engine = self.engine.with_cookie_jar(jar);
+        self
+    }
+}
+
+#[async_trait]
+impl Extractor for DirectMediaExtractor {
+    fn id(&self) -> &str {
+        "native-direct"
+    }
+
+    fn supports(&self, url: &str) -> bool {
+        (url.starts_with("http://") || url.starts_with("https://"))
+            && extension_from_url(url).is_some_and(|ext| MEDIA_EXTENSIONS.contains(&ext.as_str()))
+    }
+
+    async fn extract_info(&self, url: &str) -> Result<VideoInfo> {
+        info!("DirectMediaExtractor probing: {}", url);
+        let probe = self.engine.probe(url).await?;
+        let content_type = probe.content_type.as_deref();
+        if !is_direct_media(content_type) {
+            return Err(anyhow!(
+                "Not a direct media response (content_type={:?})",
+                content_type
+            ));
+        }
+        debug!(
+            "Direct: size={} ranges={} content_type={:?}",
+            probe.size, probe.supports_ranges, content_type
+        );
+
+        // A generic binary says nothing about the format; the (redirected)
+        // URL's extension does.
+        let final_url = probe.final_url.as_deref().unwrap_or(url);
+        let ext = content_type
+            .and_then(extension_for_content_type)
+            .map(str::to_string)
+            .or_else(|| extension_from_url(final_url))
+            .or_else(|| extension_from_url(url))
+            .unwrap_or_else(|| "bin".to_string());
+        let mime = content_type.map(|ct| {
+            ct.split(';')
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_ascii_lowercase()
+        });
+        let audio_only = mime.as_deref().is_some_and(|m| m.starts_with("audio/"));
+        let filesize = (probe.size > 0).then_some(probe.size);
+        let stem = file_stem(final_url).or_else(|| file_stem(url));
+
+        let format = Format {
+            format_id: FORMAT_ID.to_string(),
+            ext,
+            resolution: audio_only.then(|| "audio only".to_string()),
+            filesize,
+            url: url.to_string(),
+            vcodec: audio_only.then(|| "none".to_string()),
+            format_note: mime,
+            ..Default::default()
+        };
+
+        Ok(VideoInfo {
+            id: stem.clone().unwrap_or_else(|| "media".to_string()),
+            title: stem.unwrap_or_else(|| url.to_string()),
+            url: url.to_string(),
+            direct_url: url.to_string(),
+            filesize,
+            formats: vec![format],
+            extractor: Some("direct".to_string()),
+            ..Default::default()
+        })
+    }
+
+    async fn get_direct_url(&self, url: &str, format_id: &str) -> Result<String> {
+        if format_id == FORMAT_ID {
+            Ok(url.to_string())
+        } else {
+            Err(anyhow!("Format {} not found on {}", format_id, url))
+        }
+    }
+}
+
+/// The percent-decoded file name of `url` without its extension.
+fn file_stem(url: &str) -> Option<String> {
+    let parsed = reqwest::Url::parse(url).ok()?;
+    let file = parsed.path_segments()?.next_back()?;
+    let stem = file.rsplit_once('.').map_or(file, |(stem, _)| stem);
+    let decoded = percent_decode(stem);
+    let trimmed = decoded.trim();
+    (!trimmed.is_empty()).then(|| trimmed.to_string())
+}
+
+/// Decode `%XX` escapes, leaving malformed ones untouched. Invalid UTF-8 is
+/// replaced rather than rejected: this only feeds a display title.
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = (
+                (bytes[i + 1] as char).to_digit(16),
+                (bytes[i + 2] as char).to_digit(16),
+            );
+            if let (Some(hi), Some(lo)) = hex {
+                out.push((hi * 16 + lo) as u8);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Serve a ranged mp4, an unranged mp3, a generic binary and an HTML
+    /// page, all on media-looking paths.
+    async fn spawn_media_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("local_addr");
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 4096];
+                    let mut req = Vec::new();
+                    loop {
+                        let n = match socket.read(&mut buf).await {
+                            Ok(0) | Err(_) => return,
+                            Ok(n) => n,
+                        };
+                        req.extend_from_slice(&buf[..n]);
+                        if req.windows(4).any(|w| w == b"\r\n\r\n") {
+                            break;
+                        }
+                    }
+                    let req = String::from_utf8_lossy(&req);
+                    let path = req.split_whitespace().nth(1).unwrap_or("/");
+                    let response = match path {
+                        "/media/My%20Holiday%20Clip.mp4" => "HTTP/1.1 206 Partial Content\r\nContent-Type: video/mp4\r\nContent-Range: bytes 0-0/123456\r\nContent-Length: 1\r\nConnection: close\r\n\r\nx".to_string(),
+                        "/song.mp3" => "HTTP/1.1 200 OK\r\nContent-Type: audio/mpeg\r\nContent-Length: 4\r\nConnection: close\r\n\r\nID3x".to_string(),
+                        "/blob.webm" => "HTTP/1.1 200 OK\r\nContent-Type: application/octet-stream\r\nContent-Length: 4\r\nConnection: close\r\n\r\nabcd".to_string(),
+                        "/page.mp4" => {
+                            let body = "<html><body>Sign in</body></html>";
+                            format!("HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}", body.len())
+                        }
+                        _ => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string(),
+                    };
+                    let _ = socket.write_all(response.as_bytes()).await;
+                    let _ = socket.shutdown().await;
+                });
+            }
+        });
+        format!("http://{addr}")
+    }
+
+    #[test]
+    fn supports_only_media_extensions() {
+        let extractor = DirectMediaExtractor::new();
+        assert!(extractor.supports("https://cdn.example.com/a/b/clip.MP4?token=1"));
+        assert!(extractor.supports("http://example.com/track.flac"));
+        assert!(!extractor.supports("https://www.youtube.com/watch?v=dQw4w9WgXcQ"));
+        assert!(!extractor.supports("https://example.com/live/index.m3u8"));
+        assert!(!extractor.supports("https://example.com/video/"));
+        assert!(!extractor.supports("ftp://example.com/clip.mp4"));
+    }
+
+    #[tokio::test]
+    async fn ranged_video_becomes_a_single_format() {
+        let base = spawn_media_server().await;
+        let url = format!("{base}/media/My%20Holiday%20Clip.mp4");
+        let info = DirectMediaExtractor::new()
+            .extract_info(&url)
+            .await
+            .unwrap();
+
+        assert_eq!(info.title, "My Holiday Clip");
+        assert_eq!(info.extractor.as_deref(), Some("direct"));
+        assert_eq!(info.filesize, Some(123456));
+        assert_eq!(info.formats.len(), 1);
+        let format = &info.formats[0];
+        assert_eq!(format.format_id, "0");
+        assert_eq!(format.ext, "mp4");
+        assert_eq!(format.filesize, Some(123456));
+        assert_eq!(format.format_note.as_deref(), Some("video/mp4"));
+        assert_eq!(format.url, url);
+        assert!(format.vcodec.is_none());
+    }
+
+    #[tokio::test]
+    async fn audio_is_marked_audio_only() {
+        let base = spawn_media_server().await;
+        let info = DirectMediaExtractor::new()
+            .extract_info(&format!("{base}/song.mp3"))
+            .await
+            .unwrap();
+        let format = &info.formats[0];
+        assert_eq!(format.ext, "mp3");
+        assert_eq!(format.vcodec.as_deref(), Some("none"));
+        assert_eq!(format.filesize, Some(4));
+        assert_eq!(info.title, "song");
+    }
+
+    #[tokio::test]
+    async fn octet_stream_takes_the_url_extension() {
+        let base = spawn_media_server().await;
+        let info = DirectMediaExtractor::new()
+            .extract_info(&format!("{base}/blob.webm"))
+            .await
+            .unwrap();
+        assert_eq!(info.formats[0].ext, "webm");
+    }
+
+    #[tokio::test]
+    async fn html_response_is_rejected_for_fallback() {
+        let base = spawn_media_server().await;
+        let err = DirectMediaExtractor::new()
+            .extract_info(&format!("{base}/page.mp4"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Not a direct media response"));
+    }
+
+    #[tokio::test]
+    async fn direct_url_is_the_link_itself() {
+        let extractor = DirectMediaExtractor::new();
+        let url = "https://example.com/clip.mp4";
+        assert_eq!(extractor.get_direct_url(url, "0").await.unwrap(), url);
+        assert!(extractor.get_direct_url(url, "22").await.is_err());
+    }
+
+    #[test]
+    fn percent_decoding_tolerates_malformed_escapes() {
+        assert_eq!(percent_decode("a%20b%2"), "a b%2");
+        assert_eq!(percent_decode("%E2%9C%93done%zz"), "\u{2713}done%zz");
+    }
+}
@@ -0,0 +1,824 @@
+//! Generic HTML page extractor.
+//!
+//! Fetches a web page and collects the media it references: `<video>` and
+//! `<source>` tags, `og:video*` / `twitter:player:stream` meta tags, JSON-LD
+//! `VideoObject`s and `.m3u8`/`.mp4` URLs inlined in scripts. Each distinct
+//! URL becomes a [`Format`]; the page's metadata fills the title, thumbnail
+//! and description.
+//!
+//! This is a last resort for simple sites yt-dlp has no extractor for, so
+//! `HybridExtractor` only consults it after the yt-dlp fallback has failed.
+
+use crate::extractor::models::{Format, VideoInfo};
+use crate::extractor::traits::Extractor;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use regex::Regex;
+use reqwest::Url;
+use scraper::{Html, Selector};
+use serde_json::Value;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tracing::{debug, info};
+
+const USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Only this much of a page is scanned, whether or not it says how long it
+/// is; the media of the simple pages this extractor is for comes early.
+const MAX_PAGE_BYTES: usize = 5 * 1024 * 1024;
+
+/// File extensions recognised as media when deciding whether a URL found in
+/// a page is worth offering.
+const MEDIA_EXTENSIONS: &[&str] = &[
+    "mp4", "m4v", "webm", "mov", "mkv", "ogv", "m3u8", "mp3", "m4a", "ogg", "opus",
+];
+
+pub struct GenericHtmlExtractor {
+    client: reqwest::Client,
+}
+
+impl Default for GenericHtmlExtractor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GenericHtmlExtractor {
+    pub fn new() -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(USER_AGENT)
+            .build()
+            .expect("Failed to create HTTP client");
+        Self { client }
+    }
+
+    /// Fetch `url` and return the final (post-redirect) URL and the HTML.
+    async fn fetch_page(&self, url: &str) -> Result<(Url, String)> {
+        let response = self.client.get(url).send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("HTTP error: {}", response.status()));
+        }
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        if !content_type.is_empty() && !content_type.contains("html") {
+            return Err(anyhow!(
+                "Not an HTML page (content_type={}); nothing to scan",
+                content_type
+            ));
+        }
+        let final_url = response.url().clone();
+        // Stop reading at the cap instead of buffering whatever the server
+        // sends, with or without a Content-Length.
+        let mut response = response;
+        let mut bytes = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            let room = MAX_PAGE_BYTES - bytes.len();
+            bytes.extend_from_slice(&chunk[..chunk.len().min(room)]);
+            if bytes.len() >= MAX_PAGE_BYTES {
+                debug!(
+                    "Generic: only scanning the first {} bytes of {}",
+                    MAX_PAGE_BYTES, url
+                );
+                break;
+            }
+        }
+        Ok((final_url, String::from_utf8_lossy(&bytes).into_owned()))
+    }
+}
+
+#[async_trait]
+impl Extractor for GenericHtmlExtractor {
+    fn id(&self) -> &str {
+        "native-generic"
+    }
+
+    fn supports(&self, url: &str) -> bool {
+        url.starts_with("http://") || url.starts_with("https://")
+    }
+
+    async fn extract_info(&self, url: &str) -> Result<VideoInfo> {
+        info!("GenericHtmlExtractor scanning: {}", url);
+        let (page_url, html) = self.fetch_page(url).await?;
+        let info = scan_page(&html, &page_url, url);
+        if info.formats.is_empty() {
+            return Err(anyhow!("No media found on {}", url));
+        }
+        debug!("Generic: {} candidate(s) on {}", info.formats.len(), url);
+        Ok(info)
+    }
+
+    async fn get_direct_url(&self, url: &str, format_id: &str) -> Result<String> {
+        let info = self.extract_info(url).await?;
+        info.formats
+            .into_iter()
+            .find(|f| f.format_id == format_id)
+            .map(|f| f.url)
+            .ok_or_else(|| anyhow!("Format {} not found on {}", format_id, url))
+    }
+}
+
+/// Where a candidate was found. Declared from least to most reliable: the
+/// formats list is ordered the same way, worst first, like yt-dlp's.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Origin {
+    Inline,
+    Twitter,
+    OpenGraph,
+    JsonLd,
+    VideoTag,
+}
+
+impl Origin {
+    fn label(self) -> &'static str {
+        match self {
+            Origin::Inline => "inline",
+            Origin::Twitter => "twitter",
+            Origin::OpenGraph => "og",
+            Origin::JsonLd => "jsonld",
+            Origin::VideoTag => "html5",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Candidate {
+    origin: Origin,
+    url: Url,
+    mime: Option<String>,
+    width: Option<u32>,
+    height: Option<u32>,
+}
+
+/// Page-level metadata, each field from the most specific source present.
+#[derive(Debug, Default)]
+struct PageMeta {
+    title: Option<String>,
+    thumbnail: Option<String>,
+    description: Option<String>,
+    uploader: Option<String>,
+    upload_date: Option<String>,
+    duration: Option<u64>,
+}
+
+/// Scan `html` (served from `page_url`) into a [`VideoInfo`] for
+/// `requested_url`. `formats` is empty when the page has no media.
+fn scan_page(html: &str, page_url: &Url, requested_url: &str) -> VideoInfo {
+    let document = Html::parse_document(html);
+    let tags = parse_tags(&document);
+    let mut candidates = Vec::new();
+    let mut meta = PageMeta::default();
+
+    collect_video_tags(&tags, page_url, &mut candidates, &mut meta);
+    collect_meta_tags(&tags, page_url, &mut candidates, &mut meta);
+    collect_json_ld(&document, page_url, &mut candidates, &mut meta);
+    collect_inline_urls(html, page_url, &mut candidates);
+
+    if meta.title.is_none() {
+        meta.title = title_tag(&document);
+    }
+
+    // One format per URL, keeping its most reliable origin; then worst
+    // first, document order within an origin (both sorts are stable).
+    candidates.sort_by_key(|c| std::cmp::Reverse(c.origin));
+    let mut unique: Vec<Candidate> = Vec::new();
+    for candidate in candidates {
+        if !unique.iter().any(|c| c.url == candidate.url) {
+            unique.push(candidate);
+        }
+    }
+    unique.sort_by_key(|c| c.origin);
+
+    let formats = unique
+        .iter()
+        .enumerate()
+        .map(|(i, c)| to_format(i, c))
+        .collect();
+
+    VideoInfo {
+        id: page_id(page_url),
+        title: meta
+            .title
+            .unwrap_or_else(|| page_url.host_str().unwrap_or("video").to_string()),
+        url: requested_url.to_string(),
+        duration: meta.duration,
+        thumbnail: meta.thumbnail,
+        uploader: meta.uploader,
+        upload_date: meta.upload_date,
+        formats,
+        description: meta.description,
+        extractor: Some("generic".to_string()),
+        ..Default::default()
+    }
+}
+
+fn to_format(index: usize, candidate: &Candidate) -> Format {
+    let ext = candidate
+        .mime
+        .as_deref()
+        .and_then(ext_for_mime)
+        .or_else(|| url_extension(&candidate.url))
+        .unwrap_or("mp4")
+        .to_string();
+    let is_hls = ext == "m3u8";
+    let resolution = match (candidate.width, candidate.height) {
+        (Some(w), Some(h)) => Some(format!("{w}x{h}")),
+        _ => None,
+    };
+    Format {
+        format_id: format!("{}-{}", candidate.origin.label(), index),
+        ext: if is_hls { "mp4".to_string() } else { ext },
+        resolution,
+        url: candidate.url.to_string(),
+        quality: candidate.height.map(|h| h as f32),
+        format_note: Some(if is_hls {
+            format!("{} HLS", candidate.origin.label())
+        } else {
+            candidate.origin.label().to_string()
+        }),
+        width: candidate.width,
+        height: candidate.height,
+        ..Default::default()
+    }
+}
+
+/// A start tag: lowercase name plus attributes (lowercase names, entity-
+/// decoded values), as the HTML parser read them.
+#[derive(Debug)]
+struct Tag {
+    name: String,
+    attrs: Vec<(String, String)>,
+}
+
+impl Tag {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn dimension(&self, name: &str) -> Option<u32> {
+        self.attr(name).and_then(|v| v.trim().parse().ok())
+    }
+}
+
+/// A compiled CSS selector for one of the fixed queries below.
+fn selector(cell: &'static OnceLock<Selector>, css: &str) -> &'static Selector {
+    cell.get_or_init(|| Selector::parse(css).expect("valid selector"))
+}
+
+/// The media-related start tags of `document`, in document order.
+fn parse_tags(document: &Html) -> Vec<Tag> {
+    static TAGS: OnceLock<Selector> = OnceLock::new();
+    document
+        .select(selector(&TAGS, "video, audio, picture, source, meta"))
+        .map(|element| Tag {
+            name: element.value().name().to_string(),
+            attrs: element
+                .value()
+                .attrs()
+                .map(|(name, value)| (name.to_ascii_lowercase(), value.to_string()))
+                .collect(),
+        })
+        .collect()
+}
+
+fn collect_video_tags(tags: &[Tag], page_url: &Url, out: &mut Vec<Candidate>, meta: &mut PageMeta) {
+    // A <source> inherits the dimensions of the <video> it sits in.
+    let mut current: Option<(Option<u32>, Option<u32>)> = None;
+    for tag in tags {
+        match tag.name.as_str() {
+            "video" => {
+                let dims = (tag.dimension("width"), tag.dimension("height"));
+                current = Some(dims);
+                if meta.thumbnail.is_none() {
+                    meta.thumbnail = tag
+                        .attr("poster")
+                        .and_then(|p| resolve(page_url, p))
+                        .map(|u| u.to_string());
+                }
+                if let Some(url) = tag.attr("src").and_then(|s| resolve(page_url, s)) {
+                    out.push(Candidate {
+                        origin: Origin::VideoTag,
+                        url,
+                        mime: tag.attr("type").map(str::to_string),
+                        width: dims.0,
+                        height: dims.1,
+                    });
+                }
+            }
+            "audio" | "picture" => current = None,
+            "source" => {
+                let Some((width, height)) = current else {
+                    continue; // <source> of an <audio>/<picture>, not a video
+                };
+                if let Some(url) = tag.attr("src").and_then(|s| resolve(page_url, s)) {
+                    out.push(Candidate {
+                        origin: Origin::VideoTag,
+                        url,
+                        mime: tag.attr("type").map(str::to_string),
+                        width,
+                        height,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn collect_meta_tags(tags: &[Tag], page_url: &Url, out: &mut Vec<Candidate>, meta: &mut PageMeta) {
+    let content = |key: &str| {
+        tags.iter()
+            .filter(|t| t.name == "meta")
+            .find(|t| t.attr("property").or_else(|| t.attr("name")) == Some(key))
+            .and_then(|t| t.attr("content"))
+            .map(str::to_string)
+    };
+
+    let og_type = content("og:video:type");
+    let og_width = content("og:video:width").and_then(|w| w.parse().ok());
+    let og_height = content("og:video:height").and_then(|h| h.parse().ok());
+    for key in ["og:video", "og:video:url", "og:video:secure_url"] {
+        if let Some(url) = content(key).and_then(|u| resolve(page_url, &u)) {
+            if is_media_url(&url, og_type.as_deref()) {
+                out.push(Candidate {
+                    origin: Origin::OpenGraph,
+                    url,
+                    mime: og_type.clone().filter(|t| t.starts_with("video/")),
+                    width: og_width,
+                    height: og_height,
+                });
+            }
+        }
+    }
+
+    let tw_type = content("twitter:player:stream:content_type");
+    if let Some(url) = content("twitter:player:stream").and_then(|u| resolve(page_url, &u)) {
+        if is_media_url(&url, tw_type.as_deref()) {
+            out.push(Candidate {
+                origin: Origin::Twitter,
+                url,
+                mime: tw_type,
+                width: content("twitter:player:width").and_then(|w| w.parse().ok()),
+                height: content("twitter:player:height").and_then(|h| h.parse().ok()),
+            });
+        }
+    }
+
+    meta.title = meta
+        .title
+        .take()
+        .or_else(|| content("og:title"))
+        .or_else(|| content("twitter:title"));
+    meta.thumbnail = content("og:image")
+        .or_else(|| content("twitter:image"))
+        .and_then(|u| resolve(page_url, &u))
+        .map(|u| u.to_string())
+        .or(meta.thumbnail.take());
+    meta.description = meta
+        .description
+        .take()
+        .or_else(|| content("og:description"))
+        .or_else(|| content("description"));
+}
+
+fn collect_json_ld(document: &Html, page_url: &Url, out: &mut Vec<Candidate>, meta: &mut PageMeta) {
+    static SCRIPTS: OnceLock<Selector> = OnceLock::new();
+    let blocks = document
+        .select(selector(&SCRIPTS, "script[type]"))
+        .filter(|script| {
+            script
+                .value()
+                .attr("type")
+                .is_some_and(|t| t.trim().eq_ignore_ascii_case("application/ld+json"))
+        })
+        .map(|script| script.text().collect::<String>());
+
+    let mut objects = Vec::new();
+    for block in blocks {
+        match serde_json::from_str::<Value>(block.trim()) {
+            Ok(value) => find_video_objects(&value, &mut objects),
+            Err(e) => debug!("Skipping malformed JSON-LD block: {}", e),
+        }
+    }
+
+    for object in objects {
+        let width = json_u32(&object["width"]);
+        let height = json_u32(&object["height"]);
+        if let Some(url) = object["contentUrl"]
+            .as_str()
+            .and_then(|u| resolve(page_url, u))
+        {
+            out.push(Candidate {
+                origin: Origin::JsonLd,
+                url,
+                mime: object["encodingFormat"].as_str().map(str::to_string),
+                width,
+                height,
+            });
+        }
+
+        // JSON-LD is structured, so it beats the loosely scraped <title>
+        // but not the explicit social-card metadata handled above.
+        meta.title = meta
+            .title
+            .take()
+            .or_else(|| object["name"].as_str().map(str::to_string));
+        meta.description = meta
+            .description
+            .take()
+            .or_else(|| object["description"].as_str().map(str::to_string));
+        if meta.thumbnail.is_none() {
+            let thumb = match &object["thumbnailUrl"] {
+                Value::String(s) => Some(s.as_str()),
+                Value::Array(list) => list.iter().rev().find_map(|v| v.as_str()),
+                _ => None,
+            };
+            meta.thumbnail = thumb
+                .and_then(|t| resolve(page_url, t))
+                .map(|u| u.to_string());
+        }
+        meta.duration = meta
+            .duration
+            .or_else(|| object["duration"].as_str().and_then(parse_iso8601_duration));
+        meta.upload_date = meta.upload_date.take().or_else(|| {
+            object["uploadDate"]
+                .as_str()
+                .and_then(|d| d.get(..10))
+                .map(|d| d.replace('-', ""))
+        });
+        meta.uploader = meta.uploader.take().or_else(|| match &object["author"] {
+            Value::String(s) => Some(s.clone()),
+            Value::Object(_) => object["author"]["name"].as_str().map(str::to_string),
+            _ => None,
+        });
+    }
+}
+
+/// Collect every `VideoObject` in a JSON-LD document (top level, arrays and
+/// `@graph`).
+fn find_video_objects(value: &Value, out: &mut Vec<Value>) {
+    match value {
+        Value::Array(items) => items.iter().for_each(|v| find_video_objects(v, out)),
+        Value::Object(map) => {
+            let is_video = match map.get("@type") {
+                Some(Value::String(t)) => t == "VideoObject",
+                Some(Value::Array(types)) => types.iter().any(|t| t == "VideoObject"),
+                _ => false,
+            };
+            if is_video {
+                out.push(value.clone());
+            }
+            if let Some(graph) = map.get("@graph") {
+                find_video_objects(graph, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn collect_inline_urls(html: &str, page_url: &Url, out: &mut Vec<Candidate>) {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| {
+        Regex::new(r#"(?i)https?://[^\s"'<>()\\]+?\.(?:m3u8|mp4)(?:\?[^\s"'<>()\\]*)?"#).unwrap()
+    });
+    // Script JSON often escapes slashes (`https:\/\/...`).
+    let unescaped = html.replace("\\/", "/");
+    for m in re.find_iter(&unescaped) {
+        if let Some(url) = resolve(page_url, &decode_entities(m.as_str())) {
+            out.push(Candidate {
+                origin: Origin::Inline,
+                url,
+                mime: None,
+                width: None,
+                height: None,
+            });
+        }
+    }
+}
+
+fn title_tag(document: &Html) -> Option<String> {
+    static TITLE: OnceLock<Selector> = OnceLock::new();
+    document
+        .select(selector(&TITLE, "title"))
+        .next()
+        .map(|title| title.text().collect::<String>().trim().to_string())
+        .filter(|t| !t.is_empty())
+}
+
+fn resolve(base: &Url, reference: &str) -> Option<Url> {
+    let url = base.join(reference.trim()).ok()?;
+    matches!(url.scheme(), "http" | "https").then_some(url)
+}
+
+fn url_extension(url: &Url) -> Option<&'static str> {
+    let ext = url.path().rsplit_once('.')?.1.to_ascii_lowercase();
+    MEDIA_EXTENSIONS.iter().copied().find(|e| *e == ext)
+}
+
+/// Whether a meta-tag URL points at media rather than an embeddable player
+/// page: a media file extension, or a declared `video/*`/`audio/*` type.
+fn is_media_url(url: &Url, declared_type: Option<&str>) -> bool {
+    url_extension(url).is_some()
+        || declared_type.is_some_and(|t| t.starts_with("video/") || t.starts_with("audio/"))
+}
+
+fn ext_for_mime(mime: &str) -> Option<&'static str> {
+    let media_type = mime.split(';').next()?.trim().to_ascii_lowercase();
+    Some(match media_type.as_str() {
+        "video/mp4" => "mp4",
+        "video/webm" | "audio/webm" => "webm",
+        "video/quicktime" => "mov",
+        "video/ogg" => "ogv",
+        "audio/mpeg" => "mp3",
+        "audio/mp4" => "m4a",
+        "application/x-mpegurl" | "application/vnd.apple.mpegurl" => "m3u8",
+        _ => return None,
+    })
+}
+
+fn json_u32(value: &Value) -> Option<u32> {
+    value
+        .as_u64()
+        .map(|v| v as u32)
+        .or_else(|| value.as_str().and_then(|s| s.trim().parse().ok()))
+}
+
+/// Parse an ISO 8601 duration (`PT1H2M3S`, `P1DT2H`) into whole seconds.
+fn parse_iso8601_duration(text: &str) -> Option<u64> {
+    let rest = text.strip_prefix('P')?;
+    let mut seconds = 0f64;
+    let mut number = String::new();
+    let mut in_time = false;
+    for c in rest.chars() {
+        match c {
+            'T' => in_time = true,
+            '0'..='9' | '.' => number.push(c),
+            unit => {
+                let value: f64 = number.parse().ok()?;
+                number.clear();
+                seconds += value
+                    * match (unit, in_time) {
+                        ('D', false) => 86_400.0,
+                        ('W', false) => 604_800.0,
+                        ('H', true) => 3_600.0,
+                        ('M', true) => 60.0,
+                        ('S', true) => 1.0,
+                        _ => return None,
+                    };
+            }
+        }
+    }
+    number.is_empty().then_some(seconds as u64)
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+/// A stable ID for a page: its last non-empty path segment, else its host.
+fn page_id(url: &Url) -> String {
+    url.path_segments()
+        .and_then(|mut segments| segments.rfind(|s| !s.is_empty()))
+        .map(|s| s.rsplit_once('.').map_or(s, |(stem, _)| stem).to_string())
+        .or_else(|| url.host_str().map(str::to_string))
+        .unwrap_or_else(|| "video".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    const VIDEO_TAG: &str = include_str!("../../../tests/fixtures/html/video_tag.html");
+    const META_TAGS: &str = include_str!("../../../tests/fixtures/html/meta_tags.html");
+    const JSON_LD: &str = include_str!("../../../tests/fixtures/html/json_ld.html");
+    const INLINE_SCRIPT: &str = include_str!("../../../tests/fixtures/html/inline_script.html");
+    const NO_MEDIA: &str = include_str!("../../../tests/fixtures/html/no_media.html");
+
+    /// Serve the HTML fixtures by path (`/video_tag.html`, ...) and a
+    /// non-HTML body at `/clip.mp4`.
+    async fn spawn_fixture_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("local_addr");
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 4096];
+                    let mut req = Vec::new();
+                    loop {
+                        let n = match socket.read(&mut buf).await {
+                            Ok(0) | Err(_) => return,
+                            Ok(n) => n,
+                        };
+                        req.extend_from_slice(&buf[..n]);
+                        if req.windows(4).any(|w| w == b"\r\n\r\n") {
+                            break;
+                        }
+                    }
+                    let req = String::from_utf8_lossy(&req);
+                    let path = req.split_whitespace().nth(1).unwrap_or("/");
+                    if path == "/endless.html" || path == "/long.html" {
+                        // No length (or one past the cap), and a body that
+                        // never ends: only a capped read gets past this.
+                        let length = if path == "/long.html" {
+                            format!("Content-Length: {}\r\n", MAX_PAGE_BYTES * 2)
+                        } else {
+                            String::new()
+                        };
+                        let head = format!(
+                            "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\n{length}Connection: close\r\n\r\n\
+                            <html><head><title>Endless</title></head><body>\
+                            <video src=\"/endless.mp4\"></video>"
+                        );
+                        if socket.write_all(head.as_bytes()).await.is_err() {
+                            return;
+                        }
+                        let padding = "<p>filler</p>".repeat(4096);
+                        while socket.write_all(padding.as_bytes()).await.is_ok() {}
+                        return;
+                    }
+                    let (status, content_type, body) = match path {
+                        "/watch/video_tag.html" => ("200 OK", "text/html", VIDEO_TAG),
+                        "/meta_tags.html" => ("200 OK", "text/html", META_TAGS),
+                        "/json_ld.html" => ("200 OK", "text/html; charset=utf-8", JSON_LD),
+                        "/inline_script.html" => ("200 OK", "text/html", INLINE_SCRIPT),
+                        "/no_media.html" => ("200 OK", "text/html", NO_MEDIA),
+                        "/clip.mp4" => ("200 OK", "video/mp4", "not really a video"),
+                        _ => ("404 Not Found", "text/plain", "not found"),
+                    };
+                    let response = format!(
+                        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                        body.len()
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                    let _ = socket.shutdown().await;
+                });
+            }
+        });
+        format!("http://{addr}")
+    }
+
+    async fn extract(path: &str) -> (String, Result<VideoInfo>) {
+        let base = spawn_fixture_server().await;
+        let url = format!("{base}{path}");
+        let result = GenericHtmlExtractor::new().extract_info(&url).await;
+        (base, result)
+    }
+
+    fn urls(info: &VideoInfo) -> Vec<&str> {
+        info.formats.iter().map(|f| f.url.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn video_and_source_tags_become_formats() {
+        let (base, info) = extract("/watch/video_tag.html").await;
+        let info = info.unwrap();
+
+        assert_eq!(
+            urls(&info),
+            [
+                format!("{base}/media/harbour-720.webm"),
+                format!("{base}/watch/media/harbour-720.mp4"),
+                "https://cdn.example.net/clips/harbour-480.mp4".to_string(),
+            ]
+        );
+        assert_eq!(info.formats[0].ext, "webm");
+        assert_eq!(info.formats[1].ext, "mp4");
+        assert_eq!(info.formats[1].resolution.as_deref(), Some("1280x720"));
+        assert_eq!(info.formats[2].height, Some(480));
+        assert!(info
+            .formats
+            .iter()
+            .all(|f| f.format_id.starts_with("html5-")));
+
+        // og:title wins over <title>; entities are decoded.
+        assert_eq!(info.title, "Harbour Timelapse & Sunset");
+        // og:image wins over the <video> poster and is made absolute.
+        assert_eq!(
+            info.thumbnail.as_deref(),
+            Some(format!("{base}/media/harbour-poster.jpg").as_str())
+        );
+        assert_eq!(
+            info.description.as_deref(),
+            Some("Six hours of harbour traffic in ninety seconds.")
+        );
+        assert_eq!(info.id, "video_tag");
+        assert_eq!(info.extractor.as_deref(), Some("generic"));
+    }
+
+    #[tokio::test]
+    async fn og_and_twitter_streams_skip_player_pages() {
+        let (_, info) = extract("/meta_tags.html").await;
+        let info = info.unwrap();
+
+        // Worst first: twitter, then og. The og:video embed page is dropped.
+        assert_eq!(
+            urls(&info),
+            [
+                "https://cdn.example.org/tw/abc.mp4?sig=1&exp=2",
+                "https://cdn.example.org/og/abc.mp4",
+            ]
+        );
+        assert_eq!(info.formats[1].resolution.as_deref(), Some("1920x1080"));
+        assert_eq!(info.title, "Open Graph Clip");
+        assert_eq!(
+            info.thumbnail.as_deref(),
+            Some("https://img.example.org/og.jpg")
+        );
+    }
+
+    #[tokio::test]
+    async fn json_ld_video_object_fills_metadata() {
+        let (_, info) = extract("/json_ld.html").await;
+        let info = info.unwrap();
+
+        assert_eq!(urls(&info), ["https://media.example.edu/lectures/l1.mp4"]);
+        assert_eq!(info.formats[0].format_id, "jsonld-0");
+        assert_eq!(info.formats[0].height, Some(720));
+        assert_eq!(info.title, "Intro to Ownership");
+        assert_eq!(
+            info.thumbnail.as_deref(),
+            Some("https://static.example.edu/thumbs/l1.jpg")
+        );
+        assert_eq!(info.duration, Some(3723));
+        assert_eq!(info.upload_date.as_deref(), Some("20240305"));
+        assert_eq!(info.uploader.as_deref(), Some("Prof. Example"));
+    }
+
+    #[tokio::test]
+    async fn inline_script_urls_are_unescaped_and_deduplicated() {
+        let (_, info) = extract("/inline_script.html").await;
+        let info = info.unwrap();
+
+        assert_eq!(
+            urls(&info),
+            [
+                "https://stream.example.com/live/master.m3u8?token=xyz",
+                "https://stream.example.com/vod/clip_720p.mp4",
+            ]
+        );
+        assert_eq!(info.formats[0].format_note.as_deref(), Some("inline HLS"));
+        assert_eq!(info.title, "Inline player");
+    }
+
+    #[tokio::test]
+    async fn endless_pages_are_scanned_up_to_the_cap() {
+        let (base, info) = extract("/endless.html").await;
+        let info = info.unwrap();
+        assert_eq!(urls(&info), [format!("{base}/endless.mp4")]);
+        assert_eq!(info.title, "Endless");
+
+        // The same page announced as too long is scanned the same way.
+        let (base, info) = extract("/long.html").await;
+        assert_eq!(urls(&info.unwrap()), [format!("{base}/endless.mp4")]);
+    }
+
+    #[tokio::test]
+    async fn page_without_media_is_an_error() {
+        let (_, info) = extract("/no_media.html").await;
+        assert!(info.unwrap_err().to_string().contains("No media found"));
+    }
+
+    #[tokio::test]
+    async fn non_html_responses_are_not_scanned() {
+        let (_, info) = extract("/clip.mp4").await;
+        assert!(info.unwrap_err().to_string().contains("Not an HTML page"));
+    }
+
+    #[tokio::test]
+    async fn direct_url_resolves_a_scanned_format() {
+        let base = spawn_fixture_server().await;
+        let url = format!("{base}/json_ld.html");
+        let extractor = GenericHtmlExtractor::new();
+        assert_eq!(
+            extractor.get_direct_url(&url, "jsonld-0").await.unwrap(),
+            "https://media.example.edu/lectures/l1.mp4"
+        );
+        assert!(extractor.get_direct_url(&url, "html5-0").await.is_err());
+    }
+
+    #[test]
+    fn iso8601_durations() {
+        assert_eq!(parse_iso8601_duration("PT1H2M3S"), Some(3723));
+        assert_eq!(parse_iso8601_duration("PT90S"), Some(90));
+        assert_eq!(parse_iso8601_duration("P1DT1M"), Some(86_460));
+        assert_eq!(parse_iso8601_duration("PT1.5S"), Some(1));
+        assert_eq!(parse_iso8601_duration("1:30"), None);
+        assert_eq!(parse_iso8601_duration("PT5"), None);
+    }
+}
@@ -1 +1,3 @@
+pub mod direct;
+pub mod generic;
 pub mod youtube;
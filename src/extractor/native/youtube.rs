@@ -1,10 +1,46 @@
-use crate::extractor::models::VideoInfo;
+//! Native YouTube extractor.
+//!
+//! Asks InnerTube (YouTube's internal API) for the player response of a
+//! video and maps it into [`VideoInfo`], without spawning yt-dlp. When the
+//! API call fails, the `ytInitialPlayerResponse` embedded in the watch page
+//! is used instead.
+//!
+//! Only formats that come with a plain `url` are returned: formats behind a
+//! `signatureCipher` would need YouTube's player JavaScript to decipher, which
+//! is yt-dlp's job. A video with no plain formats is therefore an error, and
+//! `HybridExtractor` retries it with the yt-dlp fallback.
+
+use crate::extractor::models::{Format, LiveStatus, SubtitleFile, VideoInfo};
 use crate::extractor::traits::Extractor;
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
-use tracing::{info, warn};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+const DEFAULT_BASE_URL: &str = "https://www.youtube.com";
+
+/// Client the player request identifies as. The Android client is served
+/// plain stream URLs for most videos.
+const CLIENT_NAME: &str = "ANDROID";
+const CLIENT_NAME_ID: &str = "3";
+const CLIENT_VERSION: &str = "19.09.37";
+const ANDROID_SDK_VERSION: u32 = 30;
+const CLIENT_USER_AGENT: &str = "com.google.android.youtube/19.09.37 (Linux; U; Android 11) gzip";
+const BROWSER_USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
 
-pub struct NativeYoutubeExtractor;
+/// Per-request timeout. Well under yt-dlp's `EXTRACTION_TIMEOUT`, so a slow
+/// native attempt still leaves the fallback room to run.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Marker preceding the player response JSON in the watch page.
+const PLAYER_RESPONSE_MARKER: &str = "ytInitialPlayerResponse = ";
+
+pub struct NativeYoutubeExtractor {
+    client: reqwest::Client,
+    base_url: String,
+}
 
 impl Default for NativeYoutubeExtractor {
     fn default() -> Self {
@@ -14,30 +50,712 @@ impl Default for NativeYoutubeExtractor {
 
 impl NativeYoutubeExtractor {
     pub fn new() -> Self {
-        Self
+        Self::with_base_url(DEFAULT_BASE_URL)
+    }
+
+    /// Point the extractor at another host serving the InnerTube API and
+    /// watch pages (a mirror, or a local server in tests).
+    pub fn with_base_url(base_url: impl Into<String>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .expect("Failed to create HTTP client");
+        Self {
+            client,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Fetch the player response for `video_id`: InnerTube first, the watch
+    /// page second.
+    async fn player_response(&self, video_id: &str) -> Result<Value> {
+        match self.innertube_player(video_id).await {
+            Ok(response) => return Ok(response),
+            Err(e) => warn!(
+                "InnerTube player request failed for {}: {}; trying the watch page",
+                video_id, e
+            ),
+        }
+        self.watch_page_player(video_id).await
+    }
+
+    async fn innertube_player(&self, video_id: &str) -> Result<Value> {
+        let body = json!({
+            "context": {
+                "client": {
+                    "clientName": CLIENT_NAME,
+                    "clientVersion": CLIENT_VERSION,
+                    "androidSdkVersion": ANDROID_SDK_VERSION,
+                    "hl": "en",
+                    "gl": "US",
+                }
+            },
+            "videoId": video_id,
+            "contentCheckOk": true,
+            "racyCheckOk": true,
+        });
+        let response = self
+            .client
+            .post(format!("{}/youtubei/v1/player", self.base_url))
+            .header(reqwest::header::USER_AGENT, CLIENT_USER_AGENT)
+            .header("X-YouTube-Client-Name", CLIENT_NAME_ID)
+            .header("X-YouTube-Client-Version", CLIENT_VERSION)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.to_string())
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("HTTP error: {}", response.status()));
+        }
+        Ok(serde_json::from_str(&response.text().await?)?)
+    }
+
+    async fn watch_page_player(&self, video_id: &str) -> Result<Value> {
+        let response = self
+            .client
+            .get(format!("{}/watch?v={}&hl=en", self.base_url, video_id))
+            .header(reqwest::header::USER_AGENT, BROWSER_USER_AGENT)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("HTTP error: {}", response.status()));
+        }
+        let html = response.text().await?;
+        player_response_from_html(&html)
     }
 }
 
 #[async_trait]
 impl Extractor for NativeYoutubeExtractor {
-    fn id(&self) -> &'static str {
+    fn id(&self) -> &str {
         "native-youtube"
     }
 
     fn supports(&self, url: &str) -> bool {
-        url.contains("youtube.com") || url.contains("youtu.be")
+        reqwest::Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(is_youtube_host))
+            .unwrap_or(false)
     }
 
     async fn extract_info(&self, url: &str) -> Result<VideoInfo> {
         info!("NativeYoutubeExtractor invoked for: {}", url);
-        // Stub: for now, we just return an error to force fallback or fail safely
-        // In reality, this would use untrusted-native-extraction logic
+        let video_id =
+            video_id_from_url(url).ok_or_else(|| anyhow!("No YouTube video ID in {}", url))?;
+        let response = self.player_response(&video_id).await?;
+        parse_player_response(&response, url)
+    }
+
+    async fn get_direct_url(&self, url: &str, format_id: &str) -> Result<String> {
+        let info = self.extract_info(url).await?;
+        info.formats
+            .into_iter()
+            .find(|f| f.format_id == format_id)
+            .map(|f| f.url)
+            .ok_or_else(|| anyhow!("Format {} not available natively", format_id))
+    }
+}
+
+/// True for `youtube.com`, its subdomains (`www.`, `m.`, `music.`) and
+/// `youtu.be`.
+pub fn is_youtube_host(host: &str) -> bool {
+    let host = host.to_ascii_lowercase();
+    let host = host.trim_end_matches('.');
+    host == "youtube.com" || host.ends_with(".youtube.com") || host == "youtu.be"
+}
+
+/// Extract the 11-character video ID from the URL shapes YouTube uses:
+/// `watch?v=`, `youtu.be/`, `/shorts/`, `/embed/`, `/live/` and `/v/`.
+/// URLs on other hosts have no YouTube ID, whatever their path looks like.
+pub fn video_id_from_url(url: &str) -> Option<String> {
+    let parsed = reqwest::Url::parse(url).ok()?;
+    let host = parsed.host_str()?;
+    if !is_youtube_host(host) {
+        return None;
+    }
+    let host = host.trim_start_matches("www.");
+
+    let candidate = if host == "youtu.be" {
+        parsed.path_segments()?.next().map(str::to_string)
+    } else if let Some((_, v)) = parsed.query_pairs().find(|(k, _)| k == "v") {
+        Some(v.into_owned())
+    } else {
+        let mut segments = parsed.path_segments()?;
+        match segments.next() {
+            Some("shorts" | "embed" | "live" | "v") => segments.next().map(str::to_string),
+            _ => None,
+        }
+    }?;
+
+    let is_id = candidate.len() == 11
+        && candidate
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    is_id.then_some(candidate)
+}
+
+/// Pull the `ytInitialPlayerResponse` object out of a watch page.
+pub(crate) fn player_response_from_html(html: &str) -> Result<Value> {
+    let start = html
+        .find(PLAYER_RESPONSE_MARKER)
+        .map(|i| i + PLAYER_RESPONSE_MARKER.len())
+        .context("watch page has no ytInitialPlayerResponse")?;
+    let object = balanced_json_object(&html[start..])
+        .context("ytInitialPlayerResponse is not a complete JSON object")?;
+    Ok(serde_json::from_str(object)?)
+}
+
+/// The leading `{...}` of `text`, matched by brace depth while skipping
+/// braces inside string literals.
+fn balanced_json_object(text: &str) -> Option<&str> {
+    if !text.starts_with('{') {
+        return None;
+    }
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in text.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&text[..=i]);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Map a player response into a [`VideoInfo`].
+pub(crate) fn parse_player_response(response: &Value, url: &str) -> Result<VideoInfo> {
+    let playability = &response["playabilityStatus"];
+    let status = playability["status"].as_str().unwrap_or("UNKNOWN");
+    if status == "LIVE_STREAM_OFFLINE" && response["videoDetails"]["isUpcoming"] == true {
+        return Ok(upcoming_stream(response, url));
+    }
+    if status != "OK" {
+        let reason = playability["reason"].as_str().unwrap_or("no reason given");
+        return Err(anyhow!("Video is not playable ({}): {}", status, reason));
+    }
+
+    let details = &response["videoDetails"];
+    let video_id = details["videoId"]
+        .as_str()
+        .context("player response has no videoDetails.videoId")?;
+
+    let streaming = &response["streamingData"];
+    let mut formats: Vec<Format> = streaming["formats"]
+        .as_array()
+        .into_iter()
+        .chain(streaming["adaptiveFormats"].as_array())
+        .flatten()
+        .filter_map(map_format)
+        .collect();
+
+    if let Some(manifest) = streaming["hlsManifestUrl"].as_str() {
+        formats.push(Format {
+            format_id: "hls".to_string(),
+            ext: "mp4".to_string(),
+            url: manifest.to_string(),
+            format_note: Some("HLS".to_string()),
+            ..Default::default()
+        });
+    }
+
+    if formats.is_empty() {
+        return Err(anyhow!(
+            "No natively downloadable formats for {} (all formats need signature deciphering)",
+            video_id
+        ));
+    }
+    debug!(
+        "Native YouTube: {} format(s) for {}",
+        formats.len(),
+        video_id
+    );
+
+    let microformat = &response["microformat"]["playerMicroformatRenderer"];
+    let upload_date = microformat["uploadDate"]
+        .as_str()
+        .or_else(|| microformat["publishDate"].as_str())
+        .and_then(|d| d.get(..10))
+        .map(|d| d.replace('-', ""));
+
+    // Thumbnails are listed smallest first.
+    let thumbnail = details["thumbnail"]["thumbnails"]
+        .as_array()
+        .and_then(|t| t.last())
+        .and_then(|t| t["url"].as_str())
+        .map(str::to_string);
+
+    let (subtitles, automatic_captions) = caption_tracks(response);
+
+    let live_status = match (details["isLive"] == true, details["isLiveContent"] == true) {
+        (true, _) => LiveStatus::IsLive,
+        (false, true) => LiveStatus::WasLive,
+        (false, false) => LiveStatus::NotLive,
+    };
+
+    Ok(VideoInfo {
+        id: video_id.to_string(),
+        title: details["title"].as_str().unwrap_or(video_id).to_string(),
+        url: url.to_string(),
+        // A stream in progress reports a length of 0.
+        duration: string_u64(&details["lengthSeconds"]).filter(|&d| d > 0),
+        thumbnail,
+        uploader: details["author"].as_str().map(str::to_string),
+        upload_date,
+        formats,
+        description: details["shortDescription"].as_str().map(str::to_string),
+        view_count: string_u64(&details["viewCount"]),
+        extractor: Some("youtube".to_string()),
+        subtitles,
+        automatic_captions,
+        live_status: Some(live_status),
+        ..Default::default()
+    })
+}
+
+/// A scheduled stream or premiere: no formats yet, but enough to wait for
+/// it and record it.
+fn upcoming_stream(response: &Value, url: &str) -> VideoInfo {
+    let details = &response["videoDetails"];
+    let id = details["videoId"].as_str().unwrap_or_default();
+    let slate = &response["playabilityStatus"]["liveStreamability"]["liveStreamabilityRenderer"]
+        ["offlineSlate"]["liveStreamOfflineSlateRenderer"];
+    let release_timestamp = string_u64(&slate["scheduledStartTime"])
+        .map(|t| t as i64)
+        .or_else(|| {
+            response["microformat"]["playerMicroformatRenderer"]["liveBroadcastDetails"]
+                ["startTimestamp"]
+                .as_str()
+                .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+                .map(|t| t.timestamp())
+        });
+    VideoInfo {
+        id: id.to_string(),
+        title: details["title"].as_str().unwrap_or(id).to_string(),
+        url: url.to_string(),
+        thumbnail: details["thumbnail"]["thumbnails"]
+            .as_array()
+            .and_then(|t| t.last())
+            .and_then(|t| t["url"].as_str())
+            .map(str::to_string),
+        uploader: details["author"].as_str().map(str::to_string),
+        description: details["shortDescription"].as_str().map(str::to_string),
+        extractor: Some("youtube".to_string()),
+        live_status: Some(LiveStatus::IsUpcoming),
+        release_timestamp,
+        ..Default::default()
+    }
+}
+
+type SubtitleMap = BTreeMap<String, Vec<SubtitleFile>>;
+
+/// Uploaded subtitles and auto-generated (`kind: asr`) captions, served as
+/// WebVTT.
+fn caption_tracks(response: &Value) -> (SubtitleMap, SubtitleMap) {
+    let mut subtitles = SubtitleMap::new();
+    let mut automatic = SubtitleMap::new();
+    let tracks = response["captions"]["playerCaptionsTracklistRenderer"]["captionTracks"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    for track in tracks {
+        let (Some(url), Some(language)) =
+            (track["baseUrl"].as_str(), track["languageCode"].as_str())
+        else {
+            continue;
+        };
+        let name = track["name"]["simpleText"]
+            .as_str()
+            .or_else(|| track["name"]["runs"][0]["text"].as_str())
+            .map(str::to_string);
+        let target = if track["kind"].as_str() == Some("asr") {
+            &mut automatic
+        } else {
+            &mut subtitles
+        };
+        target
+            .entry(language.to_string())
+            .or_default()
+            .push(SubtitleFile {
+                ext: "vtt".to_string(),
+                url: format!("{url}&fmt=vtt"),
+                name,
+            });
+    }
+    (subtitles, automatic)
+}
+
+/// Map one `formats`/`adaptiveFormats` entry. `None` for entries without a
+/// plain URL (`signatureCipher`).
+fn map_format(entry: &Value) -> Option<Format> {
+    let url = entry["url"].as_str()?;
+    let itag = entry["itag"].as_u64()?;
+    let mime = entry["mimeType"].as_str().unwrap_or_default();
+    let (media_type, codecs) = parse_mime(mime);
+    let is_audio = media_type.starts_with("audio/");
+
+    let (vcodec, acodec) = match (is_audio, codecs.as_slice()) {
+        (true, [a, ..]) => ("none".to_string(), a.clone()),
+        (false, [v, a, ..]) => (v.clone(), a.clone()),
+        (false, [v]) => (v.clone(), "none".to_string()),
+        _ => ("none".to_string(), "none".to_string()),
+    };
+
+    let ext = match media_type {
+        "video/mp4" => "mp4",
+        "audio/mp4" => "m4a",
+        "video/webm" | "audio/webm" => "webm",
+        "video/3gpp" => "3gp",
+        _ => "mp4",
+    };
+
+    let width = entry["width"].as_u64().map(|w| w as u32);
+    let height = entry["height"].as_u64().map(|h| h as u32);
+    let resolution = match (width, height) {
+        (Some(w), Some(h)) => Some(format!("{w}x{h}")),
+        _ if is_audio => Some("audio only".to_string()),
+        _ => None,
+    };
+    let kbps = entry["averageBitrate"]
+        .as_f64()
+        .or_else(|| entry["bitrate"].as_f64())
+        .map(|b| (b / 1000.0) as f32);
+    let format_note = entry["qualityLabel"]
+        .as_str()
+        .or_else(|| entry["audioQuality"].as_str())
+        .map(|q| {
+            q.trim_start_matches("AUDIO_QUALITY_")
+                .to_ascii_lowercase()
+                .to_string()
+        });
+
+    // Multi-language videos label each audio track `es.4`, `en-US.3`, ...
+    let language = entry["audioTrack"]["id"]
+        .as_str()
+        .and_then(|id| id.split('.').next())
+        .map(str::to_string);
+    let dynamic_range = (!is_audio).then(|| {
+        match entry["colorInfo"]["transferCharacteristics"].as_str() {
+            Some(t) if t.ends_with("SMPTEST2084") => "HDR10",
+            Some(t) if t.ends_with("ARIB_STD_B67") => "HLG",
+            _ => "SDR",
+        }
+        .to_string()
+    });
+
+    Some(Format {
+        format_id: itag.to_string(),
+        ext: ext.to_string(),
+        resolution,
+        filesize: string_u64(&entry["contentLength"]),
+        url: url.to_string(),
+        quality: height.map(|h| h as f32),
+        fps: entry["fps"].as_f64().map(|f| f as f32),
+        vcodec: Some(vcodec.clone()),
+        acodec: Some(acodec.clone()),
+        format_note,
+        width,
+        height,
+        tbr: kbps,
+        vbr: if vcodec != "none" && acodec == "none" {
+            kbps
+        } else {
+            None
+        },
+        abr: if vcodec == "none" && acodec != "none" {
+            kbps
+        } else {
+            None
+        },
+        language,
+        dynamic_range,
+    })
+}
+
+/// Split `video/mp4; codecs="avc1.42001E, mp4a.40.2"` into the media type
+/// and its codec list.
+fn parse_mime(mime: &str) -> (&str, Vec<String>) {
+    let mut parts = mime.splitn(2, ';');
+    let media_type = parts.next().unwrap_or_default().trim();
+    let codecs = parts
+        .next()
+        .and_then(|params| params.trim().strip_prefix("codecs="))
+        .map(|list| {
+            list.trim_matches('"')
+                .split(',')
+                .map(|c| c.trim().to_string())
+                .filter(|c| !c.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+    (media_type, codecs)
+}
+
+/// InnerTube encodes 64-bit numbers as strings; accept either form.
+fn string_u64(value: &Value) -> Option<u64> {
+    value
+        .as_str()
+        .and_then(|s| s.parse().ok())
+        .or_else(|| value.as_u64())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    const PLAYER_RESPONSE: &str =
+        include_str!("../../../tests/fixtures/youtube/player_response.json");
+    const WATCH_PAGE: &str = include_str!("../../../tests/fixtures/youtube/watch.html");
+    const UNPLAYABLE: &str = include_str!("../../../tests/fixtures/youtube/unplayable.json");
+    const CIPHERED_ONLY: &str = include_str!("../../../tests/fixtures/youtube/ciphered_only.json");
+    const UPCOMING: &str = include_str!("../../../tests/fixtures/youtube/upcoming.json");
+
+    const WATCH_URL: &str = "https://www.youtube.com/watch?v=dQw4w9WgXcQ";
+
+    fn fixture(json: &str) -> Value {
+        serde_json::from_str(json).expect("fixture is valid JSON")
+    }
+
+    #[test]
+    fn video_ids_are_found_in_every_url_shape() {
+        for url in [
+            "https://www.youtube.com/watch?v=dQw4w9WgXcQ",
+            "https://youtube.com/watch?feature=share&v=dQw4w9WgXcQ&t=42",
+            "https://m.youtube.com/watch?v=dQw4w9WgXcQ",
+            "https://youtu.be/dQw4w9WgXcQ?si=abc",
+            "https://www.youtube.com/shorts/dQw4w9WgXcQ",
+            "https://www.youtube.com/embed/dQw4w9WgXcQ",
+            "https://www.youtube.com/live/dQw4w9WgXcQ",
+        ] {
+            assert_eq!(
+                video_id_from_url(url).as_deref(),
+                Some("dQw4w9WgXcQ"),
+                "{url}"
+            );
+        }
+        assert_eq!(
+            video_id_from_url("https://www.youtube.com/@channel/videos"),
+            None
+        );
+        assert_eq!(video_id_from_url("https://youtu.be/short"), None);
+        for url in [
+            "https://other.site/watch?v=dQw4w9WgXcQ",
+            "https://notyoutube.com/embed/dQw4w9WgXcQ",
+            "https://other.site/page?next=youtube.com/watch",
+        ] {
+            assert_eq!(video_id_from_url(url), None, "{url}");
+            assert!(!NativeYoutubeExtractor::new().supports(url), "{url}");
+        }
+        assert!(NativeYoutubeExtractor::new().supports("https://music.youtube.com/watch?v=x"));
+    }
+
+    #[test]
+    fn player_response_maps_video_details() {
+        let info = parse_player_response(&fixture(PLAYER_RESPONSE), WATCH_URL).unwrap();
+        assert_eq!(info.id, "dQw4w9WgXcQ");
+        assert_eq!(info.title, "Fixture Video Title");
+        assert_eq!(info.url, WATCH_URL);
+        assert_eq!(info.duration, Some(190));
+        assert_eq!(info.uploader.as_deref(), Some("Fixture Channel"));
+        assert_eq!(info.upload_date.as_deref(), Some("20091024"));
+        assert_eq!(info.view_count, Some(1_534_871_201));
+        assert_eq!(
+            info.thumbnail.as_deref(),
+            Some("https://i.ytimg.com/vi/dQw4w9WgXcQ/maxresdefault.jpg")
+        );
+        assert_eq!(info.extractor.as_deref(), Some("youtube"));
+
+        let english = &info.subtitles["en"][0];
+        assert_eq!(english.ext, "vtt");
+        assert!(english.url.ends_with("&lang=en&fmt=vtt"));
+        assert_eq!(english.name.as_deref(), Some("English"));
+        let auto = &info.automatic_captions["en"][0];
+        assert_eq!(auto.name.as_deref(), Some("English (auto-generated)"));
+    }
+
+    #[test]
+    fn progressive_and_adaptive_formats_are_mapped() {
+        let info = parse_player_response(&fixture(PLAYER_RESPONSE), WATCH_URL).unwrap();
+        let ids: Vec<_> = info.formats.iter().map(|f| f.format_id.as_str()).collect();
+        // 399 is signatureCipher-only and must be skipped.
+        assert_eq!(ids, ["18", "137", "248", "140", "251"]);
+
+        let progressive = &info.formats[0];
+        assert_eq!(progressive.ext, "mp4");
+        assert_eq!(progressive.vcodec.as_deref(), Some("avc1.42001E"));
+        assert_eq!(progressive.acodec.as_deref(), Some("mp4a.40.2"));
+        assert_eq!(progressive.resolution.as_deref(), Some("640x360"));
+        assert_eq!(progressive.filesize, Some(10_213_312));
+        assert_eq!(progressive.format_note.as_deref(), Some("360p"));
+
+        let video_only = &info.formats[1];
+        assert_eq!(video_only.acodec.as_deref(), Some("none"));
+        assert_eq!(video_only.height, Some(1080));
+        assert!(video_only.vbr.is_some() && video_only.abr.is_none());
+
+        let webm = &info.formats[2];
+        assert_eq!(webm.ext, "webm");
+        assert_eq!(webm.vcodec.as_deref(), Some("vp9"));
+
+        let audio = &info.formats[3];
+        assert_eq!(audio.ext, "m4a");
+        assert_eq!(audio.vcodec.as_deref(), Some("none"));
+        assert_eq!(audio.acodec.as_deref(), Some("mp4a.40.2"));
+        assert_eq!(audio.resolution.as_deref(), Some("audio only"));
+        assert_eq!(audio.format_note.as_deref(), Some("medium"));
+        assert!(audio.abr.is_some() && audio.vbr.is_none());
+
+        assert_eq!(webm.dynamic_range.as_deref(), Some("SDR"));
+        assert_eq!(audio.dynamic_range, None);
+        assert_eq!(info.formats[4].language.as_deref(), Some("en"));
+        assert_eq!(audio.language, None);
+    }
+
+    #[test]
+    fn unplayable_video_reports_the_reason() {
+        let err = parse_player_response(&fixture(UNPLAYABLE), WATCH_URL).unwrap_err();
+        let msg = err.to_string();
+        assert!(msg.contains("LOGIN_REQUIRED"), "{msg}");
+        assert!(msg.contains("Sign in to confirm your age"), "{msg}");
+    }
+
+    #[test]
+    fn upcoming_stream_is_listed_with_its_start_time() {
+        let info = parse_player_response(&fixture(UPCOMING), WATCH_URL).unwrap();
+        assert_eq!(info.title, "Fixture Premiere");
+        assert_eq!(info.live(), LiveStatus::IsUpcoming);
+        assert_eq!(info.release_timestamp, Some(1_900_000_000));
+        assert!(info.formats.is_empty());
+
+        let mut live = fixture(PLAYER_RESPONSE);
+        live["videoDetails"]["isLive"] = Value::Bool(true);
+        live["videoDetails"]["lengthSeconds"] = Value::from("0");
+        let info = parse_player_response(&live, WATCH_URL).unwrap();
+        assert_eq!(info.live(), LiveStatus::IsLive);
+        assert_eq!(info.duration, None);
+        let info = parse_player_response(&fixture(PLAYER_RESPONSE), WATCH_URL).unwrap();
+        assert_eq!(info.live(), LiveStatus::NotLive);
+    }
+
+    #[test]
+    fn ciphered_only_video_is_left_to_the_fallback() {
+        let err = parse_player_response(&fixture(CIPHERED_ONLY), WATCH_URL).unwrap_err();
+        assert!(err.to_string().contains("signature deciphering"));
+    }
+
+    #[test]
+    fn watch_page_player_response_survives_braces_in_strings() {
+        let response = player_response_from_html(WATCH_PAGE).unwrap();
+        let info = parse_player_response(&response, WATCH_URL).unwrap();
+        assert_eq!(info.title, "Fixture Video Title");
+        assert_eq!(
+            info.description.as_deref(),
+            Some("Braces {inside} a \"quoted\"; string};var x = 1;")
+        );
+        assert_eq!(info.formats.len(), 5);
+    }
+
+    #[test]
+    fn watch_page_without_player_response_is_an_error() {
+        assert!(player_response_from_html("<html><body>consent</body></html>").is_err());
+    }
+
+    /// Serve `player` for InnerTube POSTs (or a 500 when `None`) and the
+    /// fixture watch page for GETs.
+    async fn spawn_youtube_server(player: Option<&'static str>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("local_addr");
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 8192];
+                    let mut req = Vec::new();
+                    let header_end = loop {
+                        let n = match socket.read(&mut buf).await {
+                            Ok(0) | Err(_) => return,
+                            Ok(n) => n,
+                        };
+                        req.extend_from_slice(&buf[..n]);
+                        if let Some(i) = req.windows(4).position(|w| w == b"\r\n\r\n") {
+                            break i + 4;
+                        }
+                    };
+                    let head = String::from_utf8_lossy(&req[..header_end]).to_string();
+                    let content_length = head
+                        .lines()
+                        .find_map(|l| {
+                            l.to_ascii_lowercase()
+                                .strip_prefix("content-length:")
+                                .map(|v| v.trim().parse::<usize>().unwrap_or(0))
+                        })
+                        .unwrap_or(0);
+                    while req.len() < header_end + content_length {
+                        match socket.read(&mut buf).await {
+                            Ok(0) | Err(_) => return,
+                            Ok(n) => req.extend_from_slice(&buf[..n]),
+                        }
+                    }
+
+                    let (status, content_type, body) =
+                        if head.starts_with("POST /youtubei/v1/player") {
+                            match player {
+                                Some(body) => ("200 OK", "application/json", body),
+                                None => ("500 Internal Server Error", "text/plain", "boom"),
+                            }
+                        } else if head.starts_with("GET /watch?v=dQw4w9WgXcQ") {
+                            ("200 OK", "text/html; charset=utf-8", WATCH_PAGE)
+                        } else {
+                            ("404 Not Found", "text/plain", "not found")
+                        };
+                    let response = format!(
+                        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                        body.len()
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                    let _ = socket.shutdown().await;
+                });
+            }
+        });
+        format!("http://{addr}")
+    }
+
+    #[tokio::test]
+    async fn extracts_through_innertube() {
+        let base = spawn_youtube_server(Some(PLAYER_RESPONSE)).await;
+        let extractor = NativeYoutubeExtractor::with_base_url(base);
+        let info = extractor.extract_info(WATCH_URL).await.unwrap();
+        assert_eq!(info.title, "Fixture Video Title");
+        assert_eq!(info.formats.len(), 5);
 
-        warn!("Native extraction not yet implemented, returning stub error");
-        Err(anyhow!("Native extraction not implemented yet"))
+        let direct = extractor.get_direct_url(WATCH_URL, "140").await.unwrap();
+        assert!(direct.contains("itag=140"));
+        assert!(extractor.get_direct_url(WATCH_URL, "399").await.is_err());
     }
 
-    async fn get_direct_url(&self, _url: &str, _format_id: &str) -> Result<String> {
-        Err(anyhow!("Native extraction not implemented yet"))
+    #[tokio::test]
+    async fn falls_back_to_the_watch_page_when_innertube_fails() {
+        let base = spawn_youtube_server(None).await;
+        let extractor = NativeYoutubeExtractor::with_base_url(base);
+        let info = extractor.extract_info(WATCH_URL).await.unwrap();
+        assert_eq!(info.id, "dQw4w9WgXcQ");
+        assert_eq!(info.formats.len(), 5);
     }
 }
@@ -0,0 +1,382 @@
+//! Playlist expansion: which entries of a playlist to download.
+//!
+//! A playlist is extracted flat (one cheap line per entry, usually without
+//! formats), then narrowed down by a [`PlaylistSelection`]: 1-based index
+//! ranges like `1-10,15` plus optional duration, upload-date and title
+//! filters. Each kept entry becomes its own queue task.
+
+use crate::extractor::models::VideoInfo;
+use chrono::NaiveDate;
+use regex::Regex;
+use reqwest::Url;
+use std::fmt;
+
+/// An expanded playlist.
+#[derive(Debug, Clone, Default)]
+pub struct Playlist {
+    pub url: String,
+    pub title: String,
+    pub entries: Vec<VideoInfo>,
+    /// 1-based indices of entries already in the download archive, as marked
+    /// by the backend.
+    pub archived: Vec<usize>,
+}
+
+impl Playlist {
+    /// Wrap extracted entries; the title comes from the entries'
+    /// `playlist_title`, falling back to the URL.
+    pub fn new(url: impl Into<String>, entries: Vec<VideoInfo>) -> Self {
+        let url = url.into();
+        let title = entries
+            .iter()
+            .find_map(|e| e.playlist_title.clone())
+            .filter(|t| !t.trim().is_empty())
+            .unwrap_or_else(|| url.clone());
+        Self {
+            url,
+            title,
+            entries,
+            archived: Vec::new(),
+        }
+    }
+
+    /// The entry at a 1-based index.
+    pub fn entry(&self, index: usize) -> Option<&VideoInfo> {
+        index.checked_sub(1).and_then(|i| self.entries.get(i))
+    }
+}
+
+/// Whether a URL names a playlist rather than a single video. A YouTube
+/// `watch?v=...&list=...` link plays one video, so only a bare `list=` (or a
+/// `/playlist` page) counts.
+pub fn is_playlist_url(url: &str) -> bool {
+    let Ok(parsed) = Url::parse(url) else {
+        return false;
+    };
+    let host = parsed.host_str().unwrap_or_default();
+    let path = parsed.path();
+    let has_query = |key: &str| parsed.query_pairs().any(|(k, _)| k == key);
+
+    if host.ends_with("youtube.com") || host.ends_with("youtu.be") {
+        return path == "/playlist" || (has_query("list") && !has_query("v"));
+    }
+    (host.ends_with("soundcloud.com") && path.contains("/sets/"))
+        || (host.ends_with("bandcamp.com") && path.starts_with("/album/"))
+        || (host.ends_with("vimeo.com") && path.starts_with("/showcase/"))
+}
+
+/// The web URLs in pasted text, one per whitespace-separated word, in order.
+/// Words that are not `http(s)` URLs are ignored.
+pub fn split_urls(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .filter(|word| Url::parse(word).is_ok_and(|url| matches!(url.scheme(), "http" | "https")))
+        .map(str::to_string)
+        .collect()
+}
+
+/// A 1-based, inclusive index range; `end: None` runs to the last entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndexRange {
+    pub start: usize,
+    pub end: Option<usize>,
+}
+
+impl IndexRange {
+    pub fn contains(&self, index: usize) -> bool {
+        index >= self.start && self.end.is_none_or(|end| index <= end)
+    }
+}
+
+impl fmt::Display for IndexRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.end {
+            Some(end) if end == self.start => write!(f, "{}", self.start),
+            Some(end) => write!(f, "{}-{}", self.start, end),
+            None => write!(f, "{}-", self.start),
+        }
+    }
+}
+
+/// Parse an item spec like `1-10,15,20-`. Indices are 1-based; blanks between
+/// commas are ignored.
+pub fn parse_index_ranges(spec: &str) -> Result<Vec<IndexRange>, String> {
+    let parse_index = |s: &str| -> Result<usize, String> {
+        match s.trim().parse::<usize>() {
+            Ok(0) => Err("playlist indices start at 1".to_string()),
+            Ok(n) => Ok(n),
+            Err(_) => Err(format!("'{}' is not a playlist index", s.trim())),
+        }
+    };
+
+    let mut ranges = Vec::new();
+    for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let range = match part.split_once('-') {
+            Some((start, end)) => {
+                let start = parse_index(start)?;
+                let end = if end.trim().is_empty() {
+                    None
+                } else {
+                    Some(parse_index(end)?)
+                };
+                if end.is_some_and(|end| end < start) {
+                    return Err(format!("range '{part}' runs backwards"));
+                }
+                IndexRange { start, end }
+            }
+            None => {
+                let index = parse_index(part)?;
+                IndexRange {
+                    start: index,
+                    end: Some(index),
+                }
+            }
+        };
+        ranges.push(range);
+    }
+    if ranges.is_empty() {
+        return Err("no playlist items given".to_string());
+    }
+    Ok(ranges)
+}
+
+/// Parse `YYYYMMDD` (yt-dlp's `upload_date`) or `YYYY-MM-DD`.
+pub fn parse_date(s: &str) -> Result<NaiveDate, String> {
+    let s = s.trim();
+    NaiveDate::parse_from_str(s, "%Y%m%d")
+        .or_else(|_| NaiveDate::parse_from_str(s, "%Y-%m-%d"))
+        .map_err(|_| format!("'{s}' is not a date (expected YYYYMMDD or YYYY-MM-DD)"))
+}
+
+/// Parse a duration as plain seconds (`90`) or a clock (`1:30`, `1:02:03`).
+pub fn parse_duration(s: &str) -> Result<u64, String> {
+    let s = s.trim();
+    let invalid = || format!("'{s}' is not a duration (expected seconds or m:ss)");
+    let parts: Vec<&str> = s.split(':').collect();
+    if parts.len() > 3 {
+        return Err(invalid());
+    }
+    parts.iter().try_fold(0u64, |total, part| {
+        let value: u64 = part.trim().parse().map_err(|_| invalid())?;
+        Ok(total * 60 + value)
+    })
+}
+
+/// Which entries of a playlist to keep. Every filter is optional; an entry
+/// whose duration or upload date is unknown (common in flat playlists) is
+/// kept rather than silently dropped.
+#[derive(Debug, Clone, Default)]
+pub struct PlaylistSelection {
+    pub items: Option<Vec<IndexRange>>,
+    /// Seconds, inclusive.
+    pub min_duration: Option<u64>,
+    /// Seconds, inclusive.
+    pub max_duration: Option<u64>,
+    /// Inclusive.
+    pub date_after: Option<NaiveDate>,
+    /// Inclusive.
+    pub date_before: Option<NaiveDate>,
+    pub title_pattern: Option<Regex>,
+}
+
+impl PlaylistSelection {
+    /// Every entry.
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    pub fn with_items(mut self, items: Vec<IndexRange>) -> Self {
+        self.items = Some(items);
+        self
+    }
+
+    pub fn with_min_duration(mut self, seconds: u64) -> Self {
+        self.min_duration = Some(seconds);
+        self
+    }
+
+    pub fn with_max_duration(mut self, seconds: u64) -> Self {
+        self.max_duration = Some(seconds);
+        self
+    }
+
+    pub fn with_date_after(mut self, date: NaiveDate) -> Self {
+        self.date_after = Some(date);
+        self
+    }
+
+    pub fn with_date_before(mut self, date: NaiveDate) -> Self {
+        self.date_before = Some(date);
+        self
+    }
+
+    pub fn with_title_pattern(mut self, pattern: Regex) -> Self {
+        self.title_pattern = Some(pattern);
+        self
+    }
+
+    /// Whether the entry at a 1-based `index` passes every filter.
+    pub fn matches(&self, index: usize, entry: &VideoInfo) -> bool {
+        if let Some(items) = &self.items {
+            if !items.iter().any(|r| r.contains(index)) {
+                return false;
+            }
+        }
+        if let Some(duration) = entry.duration {
+            if self.min_duration.is_some_and(|min| duration < min)
+                || self.max_duration.is_some_and(|max| duration > max)
+            {
+                return false;
+            }
+        }
+        if let Some(date) = entry
+            .upload_date
+            .as_deref()
+            .and_then(|d| parse_date(d).ok())
+        {
+            if self.date_after.is_some_and(|after| date < after)
+                || self.date_before.is_some_and(|before| date > before)
+            {
+                return false;
+            }
+        }
+        self.title_pattern
+            .as_ref()
+            .is_none_or(|re| re.is_match(&entry.title))
+    }
+
+    /// 1-based indices of the entries to keep, in playlist order.
+    pub fn apply(&self, entries: &[VideoInfo]) -> Vec<usize> {
+        entries
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| (i + 1, entry))
+            .filter(|(index, entry)| self.matches(*index, entry))
+            .map(|(index, _)| index)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extractor::search::parse_search_output;
+
+    fn fixture() -> Playlist {
+        Playlist::new(
+            "https://www.youtube.com/playlist?list=PLlofi",
+            parse_search_output(include_str!(
+                "../../tests/fixtures/playlist/flat_playlist.jsonl"
+            )),
+        )
+    }
+
+    #[test]
+    fn parses_index_ranges() {
+        let ranges = parse_index_ranges("1-3, 7,10-").unwrap();
+        assert_eq!(
+            ranges,
+            [
+                IndexRange {
+                    start: 1,
+                    end: Some(3)
+                },
+                IndexRange {
+                    start: 7,
+                    end: Some(7)
+                },
+                IndexRange {
+                    start: 10,
+                    end: None
+                },
+            ]
+        );
+        let shown: Vec<_> = ranges.iter().map(ToString::to_string).collect();
+        assert_eq!(shown, ["1-3", "7", "10-"]);
+
+        assert!(parse_index_ranges("0").is_err());
+        assert!(parse_index_ranges("5-2").unwrap_err().contains("backwards"));
+        assert!(parse_index_ranges("a-3").is_err());
+        assert!(parse_index_ranges(" , ").is_err());
+    }
+
+    #[test]
+    fn parses_durations_and_dates() {
+        assert_eq!(parse_duration("90"), Ok(90));
+        assert_eq!(parse_duration("5:00"), Ok(300));
+        assert_eq!(parse_duration("1:02:03"), Ok(3723));
+        assert!(parse_duration("5m").is_err());
+        assert!(parse_duration("1:2:3:4").is_err());
+
+        assert_eq!(parse_date("20240301"), parse_date("2024-03-01"));
+        assert!(parse_date("March 1").is_err());
+    }
+
+    #[test]
+    fn expands_flat_output_with_the_playlist_title() {
+        let playlist = fixture();
+        assert_eq!(playlist.title, "Lofi Mix");
+        assert_eq!(playlist.entries.len(), 5);
+        assert_eq!(playlist.entry(3).unwrap().duration, None);
+        assert!(playlist.entry(0).is_none());
+
+        let untitled = Playlist::new("https://example.com/list", Vec::new());
+        assert_eq!(untitled.title, "https://example.com/list");
+    }
+
+    #[test]
+    fn filters_combine_and_keep_unknown_values() {
+        let playlist = fixture();
+        assert_eq!(
+            PlaylistSelection::all().apply(&playlist.entries),
+            [1, 2, 3, 4, 5]
+        );
+
+        let ranged = PlaylistSelection::all().with_items(parse_index_ranges("2-3,5").unwrap());
+        assert_eq!(ranged.apply(&playlist.entries), [2, 3, 5]);
+
+        // Part 3 has no duration and no date, so it survives both filters.
+        let long = PlaylistSelection::all()
+            .with_min_duration(600)
+            .with_date_after(parse_date("2023-06-01").unwrap());
+        assert_eq!(long.apply(&playlist.entries), [2, 3, 4]);
+
+        let before = PlaylistSelection::all().with_date_before(parse_date("20231231").unwrap());
+        assert_eq!(before.apply(&playlist.entries), [1, 2, 3]);
+
+        let titled = PlaylistSelection::all()
+            .with_title_pattern(Regex::new(r"(?i)part \d$").unwrap())
+            .with_max_duration(2000);
+        assert_eq!(titled.apply(&playlist.entries), [2]);
+    }
+
+    #[test]
+    fn recognizes_playlist_urls() {
+        assert!(is_playlist_url(
+            "https://www.youtube.com/playlist?list=PL590L5WQmH8fJ54F369BLDSqIwcs-TCfs"
+        ));
+        assert!(is_playlist_url(
+            "https://music.youtube.com/watch?list=OLAK5uy"
+        ));
+        assert!(!is_playlist_url(
+            "https://www.youtube.com/watch?v=dQw4w9WgXcQ&list=PL590L5WQmH8f"
+        ));
+        assert!(is_playlist_url("https://soundcloud.com/artist/sets/album"));
+        assert!(!is_playlist_url("https://soundcloud.com/artist/track"));
+        assert!(!is_playlist_url("not a url"));
+    }
+
+    #[test]
+    fn splits_pasted_url_lists() {
+        let text =
+            "https://youtu.be/a\n  https://vimeo.com/1 notes ftp://x.test/f\thttp://b.test/2";
+        assert_eq!(
+            split_urls(text),
+            [
+                "https://youtu.be/a",
+                "https://vimeo.com/1",
+                "http://b.test/2"
+            ]
+        );
+        assert!(split_urls("   ").is_empty());
+    }
+}
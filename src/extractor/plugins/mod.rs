@@ -0,0 +1,117 @@
+//! User-supplied extractor plugins, loaded from the config directory at
+//! startup and registered ahead of the built-in extractors: declarative
+//! site rules (`.toml`/`.json`) and, with the `wasm-plugins` feature,
+//! sandboxed WebAssembly modules (`.wasm`/`.wat`).
+
+pub mod site_rules;
+#[cfg(feature = "wasm-plugins")]
+pub mod wasm;
+
+pub use site_rules::{SiteRule, SiteRuleExtractor};
+#[cfg(feature = "wasm-plugins")]
+pub use wasm::{PluginManifest, WasmLimits, WasmPluginExtractor};
+
+use crate::extractor::traits::Extractor;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// Where plugins live by default: `<config dir>/extractors`.
+pub fn default_plugin_dir() -> PathBuf {
+    crate::utils::platform::config_dir().join("extractors")
+}
+
+/// Load every plugin in `dir`, in file-name order. A broken plugin is logged
+/// and skipped — it must not keep the application from starting. A missing
+/// directory simply means no plugins.
+pub fn load_plugins(dir: &Path) -> Vec<Arc<dyn Extractor>> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file())
+        .collect();
+    paths.sort();
+
+    let mut plugins: Vec<Arc<dyn Extractor>> = Vec::new();
+    for path in paths {
+        // Read along with the plugin it describes.
+        if path.to_string_lossy().ends_with(".manifest.toml") {
+            continue;
+        }
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("toml" | "json") => match SiteRuleExtractor::from_file(&path) {
+                Ok(rule) => {
+                    info!("Loaded site rule {} from {}", rule.id(), path.display());
+                    plugins.push(Arc::new(rule));
+                }
+                Err(e) => warn!("Skipping site rule {}: {:#}", path.display(), e),
+            },
+            #[cfg(feature = "wasm-plugins")]
+            Some("wasm" | "wat") => match WasmPluginExtractor::from_file(&path) {
+                Ok(plugin) => {
+                    info!("Loaded wasm plugin {} from {}", plugin.id(), path.display());
+                    plugins.push(Arc::new(plugin));
+                }
+                Err(e) => warn!("Skipping wasm plugin {}: {:#}", path.display(), e),
+            },
+            _ => continue,
+        }
+    }
+    plugins
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_valid_rules_and_skips_broken_ones() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("a.toml"),
+            include_str!("../../../tests/fixtures/rules/api_rule.toml"),
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("b.json"),
+            include_str!("../../../tests/fixtures/rules/page_rule.json"),
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("c.toml"), "id = \"broken\"").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "ignored").unwrap();
+
+        let plugins = load_plugins(dir.path());
+        let ids: Vec<_> = plugins.iter().map(|p| p.id().to_string()).collect();
+        assert_eq!(ids, ["portal-api", "portal-page"]);
+    }
+
+    #[cfg(feature = "wasm-plugins")]
+    #[test]
+    fn loads_wasm_plugins() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("sample.wat"),
+            include_str!("../../../examples/plugins/sample_plugin.wat"),
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("sample.manifest.toml"),
+            include_str!("../../../examples/plugins/sample_plugin.manifest.toml"),
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("garbage.wasm"), b"not wasm").unwrap();
+
+        let plugins = load_plugins(dir.path());
+        let ids: Vec<_> = plugins.iter().map(|p| p.id().to_string()).collect();
+        assert_eq!(ids, ["wasm-sample"]);
+        assert!(plugins[0].supports("https://example.com/sample/clip1"));
+    }
+
+    #[test]
+    fn missing_directory_means_no_plugins() {
+        assert!(load_plugins(Path::new("/nonexistent/rustloader/extractors")).is_empty());
+    }
+}
@@ -0,0 +1,937 @@
+//! Declarative site-rule extractors.
+//!
+//! A site rule is a TOML or JSON file describing how to extract media from
+//! one site: which URLs it handles, which requests to make, and where in the
+//! responses the title, thumbnail and formats are. Rules are compiled into
+//! [`SiteRuleExtractor`]s at startup, so internal or niche sites can be
+//! supported without recompiling.
+//!
+//! ```toml
+//! id = "corp-video"
+//! url_patterns = ['^https://video\.corp\.example/watch/(?P<id>\d+)']
+//! headers = { Authorization = "Bearer {env.RUSTLOADER_RULE_CORP_TOKEN}" }
+//!
+//! [[steps]]
+//! name = "api"
+//! url = "https://video.corp.example/api/videos/{id}"
+//!
+//! [fields]
+//! title = { json = "$.title" }
+//! thumbnail = { json = "$.poster" }
+//!
+//! [[formats]]
+//! json = "$.sources[*]"
+//! url_path = "src"
+//! id_path = "label"
+//! height_path = "height"
+//! ```
+//!
+//! Step URLs are resolved against the input URL, so `/api/...` stays on
+//! the same host. Templates (`url`, `headers`, `body`) substitute `{url}`,
+//! the URL pattern's named captures, variables extracted by earlier steps
+//! and `{env.NAME}`, where `NAME` must start with `RUSTLOADER_RULE_` so a
+//! rule cannot read (and send off) any other environment variable.
+//!
+//! A selector is a small pipeline over one step's response: an optional
+//! `css` selection (element text, or `attr`), then an optional `regex`
+//! (capture `group`, default 1 when the pattern has groups), then an
+//! optional `json` path parsed from what is left. Without any steps the rule
+//! fetches `{url}` once.
+
+use crate::downloader::engine::extension_from_url;
+use crate::extractor::models::{Format, VideoInfo};
+use crate::extractor::traits::Extractor;
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use regex::Regex;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::Url;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tracing::{debug, info};
+
+const USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// A rule file as written on disk.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SiteRule {
+    /// Extractor id, shown in logs and routing decisions.
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Regexes matched against the input URL; the first match routes the URL
+    /// to this rule and its named captures become template variables.
+    pub url_patterns: Vec<String>,
+    /// Headers the site requires on every request (API keys, `Referer`).
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    #[serde(default)]
+    pub steps: Vec<RequestStep>,
+    #[serde(default)]
+    pub fields: FieldRules,
+    pub formats: Vec<FormatRule>,
+}
+
+/// One HTTP request. Its response is what selectors read.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RequestStep {
+    pub name: String,
+    /// URL template, resolved against the input URL when relative.
+    pub url: String,
+    /// `GET` (default) or `POST`.
+    #[serde(default)]
+    pub method: Option<String>,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    #[serde(default)]
+    pub body: Option<String>,
+    /// Variables extracted from this response for later steps' templates.
+    #[serde(default)]
+    pub vars: BTreeMap<String, Selector>,
+}
+
+/// Where to find a value. `step` defaults to the last step (or, for a
+/// step's `vars`, the step itself).
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Selector {
+    #[serde(default)]
+    pub step: Option<String>,
+    #[serde(default)]
+    pub css: Option<String>,
+    #[serde(default)]
+    pub attr: Option<String>,
+    #[serde(default)]
+    pub regex: Option<String>,
+    #[serde(default)]
+    pub group: Option<usize>,
+    #[serde(default)]
+    pub json: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FieldRules {
+    #[serde(default)]
+    pub id: Option<Selector>,
+    #[serde(default)]
+    pub title: Option<Selector>,
+    #[serde(default)]
+    pub description: Option<Selector>,
+    #[serde(default)]
+    pub thumbnail: Option<Selector>,
+    #[serde(default)]
+    pub uploader: Option<Selector>,
+    #[serde(default)]
+    pub upload_date: Option<Selector>,
+    /// Seconds, as a number or numeric string.
+    #[serde(default)]
+    pub duration: Option<Selector>,
+}
+
+/// Produces one format per selected value. When the selection yields JSON
+/// objects, the `*_path` keys read each object's fields; otherwise every
+/// value is a media URL.
+#[derive(Debug, Clone, Deserialize)]
+pub struct FormatRule {
+    #[serde(flatten)]
+    pub select: Selector,
+    #[serde(default)]
+    pub url_path: Option<String>,
+    #[serde(default)]
+    pub id_path: Option<String>,
+    #[serde(default)]
+    pub width_path: Option<String>,
+    #[serde(default)]
+    pub height_path: Option<String>,
+    #[serde(default)]
+    pub note_path: Option<String>,
+    /// Fixed extension; derived from the URL when unset.
+    #[serde(default)]
+    pub ext: Option<String>,
+    /// Fixed format note (e.g. "hls").
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+/// A compiled [`SiteRule`].
+pub struct SiteRuleExtractor {
+    rule: SiteRule,
+    patterns: Vec<Regex>,
+    steps: Vec<CompiledStep>,
+    fields: CompiledFields,
+    formats: Vec<CompiledFormat>,
+    client: reqwest::Client,
+}
+
+struct CompiledStep {
+    name: String,
+    url: String,
+    post: bool,
+    headers: Vec<(HeaderName, String)>,
+    body: Option<String>,
+    vars: Vec<(String, CompiledSelector)>,
+}
+
+struct CompiledFields {
+    id: Option<CompiledSelector>,
+    title: Option<CompiledSelector>,
+    description: Option<CompiledSelector>,
+    thumbnail: Option<CompiledSelector>,
+    uploader: Option<CompiledSelector>,
+    upload_date: Option<CompiledSelector>,
+    duration: Option<CompiledSelector>,
+}
+
+struct CompiledFormat {
+    select: CompiledSelector,
+    url_path: Option<JsonPath>,
+    id_path: Option<JsonPath>,
+    width_path: Option<JsonPath>,
+    height_path: Option<JsonPath>,
+    note_path: Option<JsonPath>,
+    ext: Option<String>,
+    note: Option<String>,
+}
+
+struct CompiledSelector {
+    step: usize,
+    css: Option<(scraper::Selector, Option<String>)>,
+    regex: Option<(Regex, usize)>,
+    json: Option<JsonPath>,
+}
+
+/// A fetched step response.
+struct StepResponse {
+    url: Url,
+    body: String,
+}
+
+impl SiteRuleExtractor {
+    /// Compile a rule, validating every pattern, selector and step reference.
+    pub fn compile(rule: SiteRule) -> Result<Self> {
+        if rule.id.trim().is_empty() {
+            bail!("rule id is empty");
+        }
+        if rule.url_patterns.is_empty() {
+            bail!("rule {} has no url_patterns", rule.id);
+        }
+        if rule.formats.is_empty() {
+            bail!("rule {} has no formats", rule.id);
+        }
+        let patterns = rule
+            .url_patterns
+            .iter()
+            .map(|p| Regex::new(p).with_context(|| format!("invalid url pattern {p:?}")))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut raw_steps = rule.steps.clone();
+        if raw_steps.is_empty() {
+            raw_steps.push(RequestStep {
+                name: "page".to_string(),
+                url: "{url}".to_string(),
+                method: None,
+                headers: BTreeMap::new(),
+                body: None,
+                vars: BTreeMap::new(),
+            });
+        }
+        let names: Vec<String> = raw_steps.iter().map(|s| s.name.clone()).collect();
+        let last = names.len() - 1;
+
+        let mut steps = Vec::with_capacity(raw_steps.len());
+        for (index, step) in raw_steps.into_iter().enumerate() {
+            let post = match step.method.as_deref().map(str::to_ascii_uppercase) {
+                None => false,
+                Some(m) if m == "GET" => false,
+                Some(m) if m == "POST" => true,
+                Some(m) => bail!("step {}: unsupported method {}", step.name, m),
+            };
+            let headers = rule
+                .headers
+                .iter()
+                .chain(&step.headers)
+                .map(|(name, value)| {
+                    let name = HeaderName::from_bytes(name.as_bytes())
+                        .with_context(|| format!("invalid header name {name:?}"))?;
+                    Ok((name, value.clone()))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let vars = step
+                .vars
+                .iter()
+                .map(|(name, selector)| {
+                    let compiled = compile_selector(selector, &names, index)
+                        .with_context(|| format!("step {}: var {}", step.name, name))?;
+                    if compiled.step > index {
+                        bail!("step {}: var {} reads a later step", step.name, name);
+                    }
+                    Ok((name.clone(), compiled))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            steps.push(CompiledStep {
+                name: step.name,
+                url: step.url,
+                post,
+                headers,
+                body: step.body,
+                vars,
+            });
+        }
+
+        let field = |selector: &Option<Selector>, name: &str| {
+            selector
+                .as_ref()
+                .map(|s| compile_selector(s, &names, last).with_context(|| format!("field {name}")))
+                .transpose()
+        };
+        let fields = CompiledFields {
+            id: field(&rule.fields.id, "id")?,
+            title: field(&rule.fields.title, "title")?,
+            description: field(&rule.fields.description, "description")?,
+            thumbnail: field(&rule.fields.thumbnail, "thumbnail")?,
+            uploader: field(&rule.fields.uploader, "uploader")?,
+            upload_date: field(&rule.fields.upload_date, "upload_date")?,
+            duration: field(&rule.fields.duration, "duration")?,
+        };
+
+        let path = |p: &Option<String>| p.as_deref().map(JsonPath::parse).transpose();
+        let formats = rule
+            .formats
+            .iter()
+            .enumerate()
+            .map(|(i, f)| {
+                Ok(CompiledFormat {
+                    select: compile_selector(&f.select, &names, last)
+                        .with_context(|| format!("format {i}"))?,
+                    url_path: path(&f.url_path)?,
+                    id_path: path(&f.id_path)?,
+                    width_path: path(&f.width_path)?,
+                    height_path: path(&f.height_path)?,
+                    note_path: path(&f.note_path)?,
+                    ext: f.ext.clone(),
+                    note: f.note.clone(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(USER_AGENT)
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Ok(Self {
+            rule,
+            patterns,
+            steps,
+            fields,
+            formats,
+            client,
+        })
+    }
+
+    pub fn from_toml_str(source: &str) -> Result<Self> {
+        Self::compile(toml::from_str(source)?)
+    }
+
+    pub fn from_json_str(source: &str) -> Result<Self> {
+        Self::compile(serde_json::from_str(source)?)
+    }
+
+    /// Load and compile a `.toml` or `.json` rule file.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let source = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => Self::from_toml_str(&source),
+            Some("json") => Self::from_json_str(&source),
+            _ => bail!("not a .toml or .json rule file"),
+        }
+    }
+
+    /// The rule this extractor was compiled from.
+    pub fn rule(&self) -> &SiteRule {
+        &self.rule
+    }
+
+    /// Template variables from the URL: `url` plus the first matching
+    /// pattern's named captures.
+    fn url_vars(&self, url: &str) -> Option<HashMap<String, String>> {
+        self.patterns.iter().find_map(|pattern| {
+            let caps = pattern.captures(url)?;
+            let mut vars = HashMap::from([("url".to_string(), url.to_string())]);
+            for name in pattern.capture_names().flatten() {
+                if let Some(m) = caps.name(name) {
+                    vars.insert(name.to_string(), m.as_str().to_string());
+                }
+            }
+            Some(vars)
+        })
+    }
+
+    async fn run_steps(&self, url: &str) -> Result<Vec<StepResponse>> {
+        let mut vars = self
+            .url_vars(url)
+            .ok_or_else(|| anyhow!("{} does not match rule {}", url, self.rule.id))?;
+        let base = Url::parse(url)?;
+        let mut responses: Vec<StepResponse> = Vec::with_capacity(self.steps.len());
+
+        for step in &self.steps {
+            let target = base
+                .join(&render(&step.url, &vars)?)
+                .with_context(|| format!("step {}: invalid URL", step.name))?;
+            let mut headers = HeaderMap::new();
+            for (name, value) in &step.headers {
+                headers.insert(name.clone(), HeaderValue::from_str(&render(value, &vars)?)?);
+            }
+            debug!("Site rule {} step {}: {}", self.rule.id, step.name, target);
+            let request = if step.post {
+                self.client.post(target)
+            } else {
+                self.client.get(target)
+            };
+            let request = match &step.body {
+                Some(body) => request.body(render(body, &vars)?),
+                None => request,
+            };
+            let response = request.headers(headers).send().await?;
+            if !response.status().is_success() {
+                bail!("step {}: HTTP error: {}", step.name, response.status());
+            }
+            let final_url = response.url().clone();
+            let body = response.text().await?;
+            responses.push(StepResponse {
+                url: final_url,
+                body,
+            });
+
+            for (name, selector) in &step.vars {
+                let value = select(selector, &responses)?
+                    .iter()
+                    .find_map(value_string)
+                    .ok_or_else(|| anyhow!("step {}: var {} not found", step.name, name))?;
+                vars.insert(name.clone(), value);
+            }
+        }
+        Ok(responses)
+    }
+
+    fn build_info(&self, url: &str, responses: &[StepResponse]) -> Result<VideoInfo> {
+        let first = |selector: &Option<CompiledSelector>| -> Result<Option<String>> {
+            match selector {
+                Some(s) => Ok(select(s, responses)?.iter().find_map(value_string)),
+                None => Ok(None),
+            }
+        };
+
+        let mut formats = Vec::new();
+        for (rule_index, rule) in self.formats.iter().enumerate() {
+            let base = &responses[rule.select.step].url;
+            for (i, value) in select(&rule.select, responses)?.iter().enumerate() {
+                let read = |path: &Option<JsonPath>| {
+                    path.as_ref()
+                        .and_then(|p| p.eval(value).first().and_then(|v| value_string(v)))
+                };
+                let raw_url = match &rule.url_path {
+                    Some(path) => path.eval(value).first().and_then(|v| value_string(v)),
+                    None => value_string(value),
+                };
+                let Some(media_url) = raw_url.and_then(|u| base.join(&u).ok()) else {
+                    continue;
+                };
+                let media_url = media_url.to_string();
+                let width = read(&rule.width_path).and_then(|w| w.parse::<u32>().ok());
+                let height = read(&rule.height_path).and_then(|h| h.parse::<u32>().ok());
+                let resolution = match (width, height) {
+                    (Some(w), Some(h)) => Some(format!("{w}x{h}")),
+                    (None, Some(h)) => Some(format!("{h}p")),
+                    _ => None,
+                };
+                formats.push(Format {
+                    format_id: read(&rule.id_path).unwrap_or_else(|| format!("{rule_index}-{i}")),
+                    ext: rule
+                        .ext
+                        .clone()
+                        .or_else(|| extension_from_url(&media_url))
+                        .unwrap_or_else(|| "mp4".to_string()),
+                    resolution,
+                    url: media_url,
+                    format_note: read(&rule.note_path).or_else(|| rule.note.clone()),
+                    width,
+                    height,
+                    ..Default::default()
+                });
+            }
+        }
+        if formats.is_empty() {
+            bail!("Site rule {} found no formats on {}", self.rule.id, url);
+        }
+
+        let page = &responses[responses.len() - 1].url;
+        let id = first(&self.fields.id)?
+            .or_else(|| self.url_vars(url).and_then(|v| v.get("id").cloned()))
+            .or_else(|| {
+                Url::parse(url)
+                    .ok()?
+                    .path_segments()?
+                    .rfind(|s| !s.is_empty())
+                    .map(str::to_string)
+            })
+            .unwrap_or_else(|| self.rule.id.clone());
+        let title = first(&self.fields.title)?.unwrap_or_else(|| id.clone());
+        let thumbnail = first(&self.fields.thumbnail)?
+            .and_then(|t| page.join(&t).ok())
+            .map(|t| t.to_string());
+        let duration = first(&self.fields.duration)?
+            .and_then(|d| d.parse::<f64>().ok())
+            .map(|d| d as u64);
+
+        Ok(VideoInfo {
+            id,
+            title,
+            url: url.to_string(),
+            duration,
+            thumbnail,
+            uploader: first(&self.fields.uploader)?,
+            upload_date: first(&self.fields.upload_date)?,
+            description: first(&self.fields.description)?,
+            formats,
+            extractor: Some(self.rule.id.clone()),
+            ..Default::default()
+        })
+    }
+}
+
+#[async_trait]
+impl Extractor for SiteRuleExtractor {
+    fn id(&self) -> &str {
+        &self.rule.id
+    }
+
+    fn supports(&self, url: &str) -> bool {
+        self.patterns.iter().any(|p| p.is_match(url))
+    }
+
+    async fn extract_info(&self, url: &str) -> Result<VideoInfo> {
+        info!("Site rule {} extracting: {}", self.rule.id, url);
+        let responses = self.run_steps(url).await?;
+        self.build_info(url, &responses)
+    }
+
+    async fn get_direct_url(&self, url: &str, format_id: &str) -> Result<String> {
+        let info = self.extract_info(url).await?;
+        info.formats
+            .into_iter()
+            .find(|f| f.format_id == format_id)
+            .map(|f| f.url)
+            .ok_or_else(|| anyhow!("Format {} not found on {}", format_id, url))
+    }
+}
+
+fn compile_selector(
+    selector: &Selector,
+    steps: &[String],
+    default_step: usize,
+) -> Result<CompiledSelector> {
+    let step = match &selector.step {
+        Some(name) => steps
+            .iter()
+            .position(|s| s == name)
+            .ok_or_else(|| anyhow!("unknown step {name:?}"))?,
+        None => default_step,
+    };
+    let css = selector
+        .css
+        .as_deref()
+        .map(|css| {
+            scraper::Selector::parse(css)
+                .map(|s| (s, selector.attr.clone()))
+                .map_err(|e| anyhow!("invalid css selector {css:?}: {e}"))
+        })
+        .transpose()?;
+    let regex = selector
+        .regex
+        .as_deref()
+        .map(|re| {
+            let re = Regex::new(re).with_context(|| format!("invalid regex {re:?}"))?;
+            let group = selector
+                .group
+                .unwrap_or(if re.captures_len() > 1 { 1 } else { 0 });
+            if group >= re.captures_len() {
+                bail!("regex {:?} has no group {}", re.as_str(), group);
+            }
+            Ok((re, group))
+        })
+        .transpose()?;
+    let json = selector.json.as_deref().map(JsonPath::parse).transpose()?;
+    if css.is_none() && regex.is_none() && json.is_none() {
+        bail!("selector needs css, regex or json");
+    }
+    Ok(CompiledSelector {
+        step,
+        css,
+        regex,
+        json,
+    })
+}
+
+/// Run a selector pipeline over its step's response.
+fn select(selector: &CompiledSelector, responses: &[StepResponse]) -> Result<Vec<Value>> {
+    let body = &responses[selector.step].body;
+    let mut texts = match &selector.css {
+        Some((css, attr)) => {
+            let document = scraper::Html::parse_document(body);
+            document
+                .select(css)
+                .filter_map(|element| match attr {
+                    Some(attr) => element.value().attr(attr).map(str::to_string),
+                    None => Some(element.text().collect::<String>().trim().to_string()),
+                })
+                .collect()
+        }
+        None => vec![body.clone()],
+    };
+    if let Some((re, group)) = &selector.regex {
+        texts = texts
+            .iter()
+            .flat_map(|text| {
+                re.captures_iter(text)
+                    .filter_map(|caps| caps.get(*group).map(|m| m.as_str().to_string()))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+    }
+    match &selector.json {
+        Some(path) => {
+            let mut values = Vec::new();
+            for text in texts {
+                let doc: Value =
+                    serde_json::from_str(&text).context("selected text is not JSON")?;
+                values.extend(path.eval(&doc).into_iter().cloned());
+            }
+            Ok(values)
+        }
+        None => Ok(texts.into_iter().map(Value::String).collect()),
+    }
+}
+
+/// A scalar as a string; `None` for null, arrays and objects.
+fn value_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) if !s.trim().is_empty() => Some(s.trim().to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// The prefix of every environment variable a rule may read.
+const RULE_ENV_PREFIX: &str = "RUSTLOADER_RULE_";
+
+/// Substitute `{name}` and `{env.NAME}` in `template`.
+fn render(template: &str, vars: &HashMap<String, String>) -> Result<String> {
+    static PLACEHOLDER: OnceLock<Regex> = OnceLock::new();
+    let placeholder =
+        PLACEHOLDER.get_or_init(|| Regex::new(r"\{([A-Za-z_][A-Za-z0-9_.]*)\}").unwrap());
+    let mut out = String::with_capacity(template.len());
+    let mut last = 0;
+    for caps in placeholder.captures_iter(template) {
+        let whole = caps.get(0).expect("group 0");
+        let name = &caps[1];
+        let value = match name.strip_prefix("env.") {
+            Some(var) if !var.starts_with(RULE_ENV_PREFIX) => {
+                bail!(
+                    "rules can only read environment variables named {RULE_ENV_PREFIX}*, not {var}"
+                )
+            }
+            Some(var) => std::env::var(var)
+                .with_context(|| format!("environment variable {var} is not set"))?,
+            None => vars
+                .get(name)
+                .cloned()
+                .ok_or_else(|| anyhow!("unknown template variable {{{name}}}"))?,
+        };
+        out.push_str(&template[last..whole.start()]);
+        out.push_str(&value);
+        last = whole.end();
+    }
+    out.push_str(&template[last..]);
+    Ok(out)
+}
+
+/// The JSONPath subset rules need: `$`, `.key`, `['key']`, `[n]` and the
+/// `[*]`/`.*` wildcard. A leading `$` is optional.
+#[derive(Debug, Clone)]
+struct JsonPath(Vec<PathSegment>);
+
+#[derive(Debug, Clone)]
+enum PathSegment {
+    Key(String),
+    Index(usize),
+    Wildcard,
+}
+
+impl JsonPath {
+    fn parse(path: &str) -> Result<Self> {
+        let invalid = || anyhow!("invalid json path {path:?}");
+        let mut rest = path.trim();
+        rest = rest.strip_prefix('$').unwrap_or(rest);
+        let mut segments = Vec::new();
+        while !rest.is_empty() {
+            if let Some(after) = rest.strip_prefix('[') {
+                let end = after.find(']').ok_or_else(invalid)?;
+                let inner = after[..end].trim();
+                let segment = if inner == "*" {
+                    PathSegment::Wildcard
+                } else if let Ok(index) = inner.parse::<usize>() {
+                    PathSegment::Index(index)
+                } else {
+                    let key = inner
+                        .strip_prefix('\'')
+                        .and_then(|k| k.strip_suffix('\''))
+                        .or_else(|| inner.strip_prefix('"').and_then(|k| k.strip_suffix('"')))
+                        .ok_or_else(invalid)?;
+                    PathSegment::Key(key.to_string())
+                };
+                segments.push(segment);
+                rest = &after[end + 1..];
+            } else {
+                let after = rest.strip_prefix('.').unwrap_or(rest);
+                let end = after.find(['.', '[']).unwrap_or(after.len());
+                let key = &after[..end];
+                if key.is_empty() {
+                    return Err(invalid());
+                }
+                segments.push(if key == "*" {
+                    PathSegment::Wildcard
+                } else {
+                    PathSegment::Key(key.to_string())
+                });
+                rest = &after[end..];
+            }
+        }
+        Ok(Self(segments))
+    }
+
+    fn eval<'a>(&self, root: &'a Value) -> Vec<&'a Value> {
+        let mut current = vec![root];
+        for segment in &self.0 {
+            current = current
+                .into_iter()
+                .flat_map(|value| -> Vec<&'a Value> {
+                    match (segment, value) {
+                        (PathSegment::Key(key), Value::Object(map)) => {
+                            map.get(key).into_iter().collect()
+                        }
+                        (PathSegment::Index(i), Value::Array(items)) => {
+                            items.get(*i).into_iter().collect()
+                        }
+                        (PathSegment::Wildcard, Value::Array(items)) => items.iter().collect(),
+                        (PathSegment::Wildcard, Value::Object(map)) => map.values().collect(),
+                        _ => Vec::new(),
+                    }
+                })
+                .collect();
+        }
+        current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    const API_RULE: &str = include_str!("../../../tests/fixtures/rules/api_rule.toml");
+    const PAGE_RULE: &str = include_str!("../../../tests/fixtures/rules/page_rule.json");
+    const WATCH_PAGE: &str = include_str!("../../../tests/fixtures/rules/watch_page.html");
+
+    /// A fake intranet portal: `/watch/<id>` pages embedding a token, and a
+    /// JSON API that insists on that token and an `X-Api-Key` header.
+    async fn spawn_portal() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("local_addr");
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 4096];
+                    let mut req = Vec::new();
+                    loop {
+                        let n = match socket.read(&mut buf).await {
+                            Ok(0) | Err(_) => return,
+                            Ok(n) => n,
+                        };
+                        req.extend_from_slice(&buf[..n]);
+                        if req.windows(4).any(|w| w == b"\r\n\r\n") {
+                            break;
+                        }
+                    }
+                    let req = String::from_utf8_lossy(&req).to_ascii_lowercase();
+                    let path = req.split_whitespace().nth(1).unwrap_or("/").to_string();
+                    let authorized = req.contains("x-api-key: s3cret");
+                    let (status, content_type, body) = match path.as_str() {
+                        "/watch/42" => ("200 OK", "text/html", WATCH_PAGE.to_string()),
+                        "/api/videos/42?token=tok-abc" if authorized => (
+                            "200 OK",
+                            "application/json",
+                            serde_json::json!({
+                                "title": "Quarterly all-hands",
+                                "poster": "/posters/42.jpg",
+                                "length": 3605.4,
+                                "sources": [
+                                    {"src": "/media/42-720.mp4", "label": "720p", "height": 720},
+                                    {"src": "https://cdn.example/42-1080.mp4", "label": "1080p", "height": 1080}
+                                ]
+                            })
+                            .to_string(),
+                        ),
+                        _ if path.starts_with("/api/") => {
+                            ("403 Forbidden", "text/plain", "forbidden".to_string())
+                        }
+                        _ => ("404 Not Found", "text/plain", "not found".to_string()),
+                    };
+                    let response = format!(
+                        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                        body.len()
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                    let _ = socket.shutdown().await;
+                });
+            }
+        });
+        format!("http://{addr}")
+    }
+
+    #[test]
+    fn json_path_subset() {
+        let doc = serde_json::json!({"a": {"b": [{"c": 1}, {"c": 2}]}, "k e y": "v"});
+        let eval = |p: &str| -> Vec<Value> {
+            JsonPath::parse(p)
+                .unwrap()
+                .eval(&doc)
+                .into_iter()
+                .cloned()
+                .collect()
+        };
+        assert_eq!(eval("$.a.b[1].c"), vec![serde_json::json!(2)]);
+        assert_eq!(eval("a.b[*].c").len(), 2);
+        assert_eq!(eval("$['k e y']"), vec![serde_json::json!("v")]);
+        assert!(eval("$.missing.path").is_empty());
+        assert!(JsonPath::parse("$.a[").is_err());
+    }
+
+    #[test]
+    fn templates_substitute_vars_and_env() {
+        std::env::set_var("RUSTLOADER_RULE_TEST_TOKEN", "t0k");
+        let vars = HashMap::from([("id".to_string(), "42".to_string())]);
+        assert_eq!(
+            render("/v/{id}?t={env.RUSTLOADER_RULE_TEST_TOKEN}", &vars).unwrap(),
+            "/v/42?t=t0k"
+        );
+        assert!(render("{nope}", &vars).is_err());
+        std::env::set_var("RUSTLOADER_TEST_SECRET", "s3cret");
+        let err = render("{env.RUSTLOADER_TEST_SECRET}", &vars).unwrap_err();
+        assert!(err.to_string().contains("RUSTLOADER_RULE_"), "{err}");
+        assert!(render("{env.HOME}", &vars).is_err());
+    }
+
+    #[test]
+    fn invalid_rules_are_rejected_at_compile_time() {
+        let bad_step = r#"
+            id = "x"
+            url_patterns = ["^https://x/"]
+            [[formats]]
+            step = "missing"
+            css = "video"
+        "#;
+        let err = SiteRuleExtractor::from_toml_str(bad_step).err().unwrap();
+        assert!(format!("{err:#}").contains("unknown step"));
+
+        let bad_css = r#"
+            id = "x"
+            url_patterns = ["^https://x/"]
+            [[formats]]
+            css = "video[["
+        "#;
+        assert!(SiteRuleExtractor::from_toml_str(bad_css).is_err());
+
+        let no_selector = r#"
+            id = "x"
+            url_patterns = ["^https://x/"]
+            [[formats]]
+            attr = "src"
+        "#;
+        assert!(SiteRuleExtractor::from_toml_str(no_selector).is_err());
+    }
+
+    #[test]
+    fn supports_follows_url_patterns() {
+        let rule = SiteRuleExtractor::from_toml_str(API_RULE).unwrap();
+        assert_eq!(rule.id(), "portal-api");
+        assert!(rule.supports("http://portal.test/watch/42"));
+        assert!(!rule.supports("http://portal.test/about"));
+    }
+
+    #[tokio::test]
+    async fn multi_step_rule_with_headers_and_json_formats() {
+        std::env::set_var("RUSTLOADER_RULE_TEST_API_KEY", "s3cret");
+        let base = spawn_portal().await;
+        let rule = SiteRuleExtractor::from_toml_str(API_RULE).unwrap();
+        let info = rule
+            .extract_info(&format!("{base}/watch/42"))
+            .await
+            .unwrap();
+
+        assert_eq!(info.id, "42");
+        assert_eq!(info.title, "Quarterly all-hands");
+        assert_eq!(info.duration, Some(3605));
+        assert_eq!(
+            info.thumbnail.as_deref(),
+            Some(format!("{base}/posters/42.jpg").as_str())
+        );
+        assert_eq!(info.extractor.as_deref(), Some("portal-api"));
+        let ids: Vec<_> = info.formats.iter().map(|f| f.format_id.as_str()).collect();
+        assert_eq!(ids, ["720p", "1080p"]);
+        assert_eq!(info.formats[0].url, format!("{base}/media/42-720.mp4"));
+        assert_eq!(info.formats[0].height, Some(720));
+        assert_eq!(info.formats[0].resolution.as_deref(), Some("720p"));
+        assert_eq!(info.formats[1].url, "https://cdn.example/42-1080.mp4");
+
+        let direct = rule
+            .get_direct_url(&format!("{base}/watch/42"), "1080p")
+            .await
+            .unwrap();
+        assert_eq!(direct, "https://cdn.example/42-1080.mp4");
+    }
+
+    #[tokio::test]
+    async fn single_page_rule_with_css_and_regex() {
+        let base = spawn_portal().await;
+        let rule = SiteRuleExtractor::from_json_str(PAGE_RULE).unwrap();
+        let info = rule
+            .extract_info(&format!("{base}/watch/42"))
+            .await
+            .unwrap();
+
+        assert_eq!(info.title, "All-hands (page title)");
+        assert_eq!(info.uploader.as_deref(), Some("Internal Comms"));
+        let urls: Vec<_> = info.formats.iter().map(|f| f.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                format!("{base}/media/42-480.mp4").as_str(),
+                "https://cdn.example/42/master.m3u8"
+            ]
+        );
+        assert_eq!(info.formats[0].format_id, "0-0");
+        assert_eq!(info.formats[1].ext, "m3u8");
+        assert_eq!(info.formats[1].format_note.as_deref(), Some("hls"));
+    }
+}
@@ -0,0 +1,1022 @@
+//! Sandboxed WebAssembly extractor plugins.
+//!
+//! For sites that need real logic (signature deciphering, multi-step APIs)
+//! a plugin can be any wasm32 core module implementing this ABI:
+//!
+//! | export | signature | |
+//! |---|---|---|
+//! | `memory` | memory | |
+//! | `alloc` | `(len: i32) -> i32` | buffer for host-written data |
+//! | `id` | `() -> i64` | packed UTF-8 string |
+//! | `supports` | `(url_ptr, url_len) -> i32` | non-zero = handled |
+//! | `extract_info` | `(url_ptr, url_len) -> i64` | packed `{"ok": VideoInfo}` / `{"err": "..."}` |
+//! | `get_direct_url` | `(url_ptr, url_len, fmt_ptr, fmt_len) -> i64` | optional, packed `{"ok": "url"}` / `{"err": "..."}` |
+//!
+//! A packed value is `(ptr << 32) | len`. The host provides, in module
+//! `rustloader`:
+//!
+//! - `http_fetch(req_ptr, req_len) -> i64`: the request is a URL or a JSON
+//!   object `{"url", "method", "headers", "body"}`; returns the packed
+//!   response body (written through `alloc`), or `-1` when the request failed.
+//! - `http_status() -> i32`: status of the last fetch (`0` after a failure).
+//! - `log(ptr, len)`: debug logging.
+//!
+//! Every call runs in a fresh instance with a fuel (CPU) budget, a memory
+//! cap, a wall-clock deadline and a cap on fetches; `http_fetch` is only
+//! available while extracting. See `examples/plugins/sample_plugin.wat`.
+//!
+//! An optional [`PluginManifest`] next to the module
+//! (`<name>.manifest.toml`) declares the plugin's `url_patterns`, which
+//! answer `supports` without running the guest (otherwise its answer is
+//! cached per URL), and the `allowed_hosts` it may fetch from. Without an
+//! allow-list a plugin may fetch from any public host, but never from
+//! loopback, link-local or private addresses — the user's network and
+//! cloud metadata endpoints stay out of reach.
+
+use crate::extractor::models::VideoInfo;
+use crate::extractor::traits::Extractor;
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use regex::Regex;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::Url;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::runtime::Handle;
+use tracing::{debug, info, warn};
+use wasmtime::{
+    Caller, Config, Engine, Instance, Linker, Module, Store, StoreLimits, StoreLimitsBuilder, Trap,
+};
+
+/// Resolution of the wall-clock deadline.
+const EPOCH_TICK: Duration = Duration::from_millis(10);
+
+/// Budget for the cheap calls (`id`, `supports`), made outside extraction.
+const QUERY_FUEL: u64 = 10_000_000;
+const QUERY_TIMEOUT: Duration = Duration::from_secs(1);
+/// How many `supports` answers a plugin remembers before starting over.
+const SUPPORTS_CACHE_LIMIT: usize = 256;
+
+/// Largest request or result a plugin may hand the host.
+const MAX_GUEST_STRING: usize = 16 * 1024 * 1024;
+
+const USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
+
+/// Resource limits for one extraction call.
+#[derive(Debug, Clone)]
+pub struct WasmLimits {
+    /// Fuel (roughly, wasm instructions) the call may consume.
+    pub fuel: u64,
+    /// Maximum linear memory, in bytes.
+    pub memory_bytes: usize,
+    /// Wall-clock deadline, including time spent in `http_fetch`.
+    pub timeout: Duration,
+    /// Maximum `http_fetch` calls.
+    pub max_fetches: u32,
+    /// Maximum size of one fetched body.
+    pub max_response_bytes: usize,
+}
+
+impl Default for WasmLimits {
+    fn default() -> Self {
+        Self {
+            fuel: 2_000_000_000,
+            memory_bytes: 64 * 1024 * 1024,
+            timeout: Duration::from_secs(30),
+            max_fetches: 16,
+            max_response_bytes: 16 * 1024 * 1024,
+        }
+    }
+}
+
+/// What a plugin declares about itself, read from `<name>.manifest.toml`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PluginManifest {
+    /// Regexes matched against input URLs. When given, they decide which
+    /// URLs the plugin handles and its `supports` export is never called.
+    #[serde(default)]
+    pub url_patterns: Vec<String>,
+    /// Hosts the plugin may fetch from. When given, no other host may be
+    /// fetched, and these may be on the local network.
+    #[serde(default)]
+    pub allowed_hosts: Vec<String>,
+}
+
+impl PluginManifest {
+    /// Where the manifest of the plugin at `plugin` lives.
+    pub fn path_for(plugin: &Path) -> PathBuf {
+        plugin.with_extension("manifest.toml")
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        toml::from_str(&text).with_context(|| format!("invalid manifest {}", path.display()))
+    }
+}
+
+/// Which hosts a plugin's fetches (and the redirects they follow) may reach.
+#[derive(Debug, Default)]
+struct HostPolicy {
+    /// Lower-cased; empty means any public host.
+    allowed: Vec<String>,
+}
+
+impl HostPolicy {
+    fn check(&self, url: &Url) -> Result<()> {
+        if !matches!(url.scheme(), "http" | "https") {
+            bail!("only http(s) URLs may be fetched");
+        }
+        let host = url
+            .host_str()
+            .ok_or_else(|| anyhow!("URL has no host"))?
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_ascii_lowercase();
+        if !self.allowed.is_empty() {
+            if !self.allowed.contains(&host) {
+                bail!("{} is not in the plugin's allowed hosts", host);
+            }
+            return Ok(());
+        }
+        match host.parse::<IpAddr>() {
+            Ok(ip) if !is_public(ip) => bail!("{} is not a public address", ip),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Whether `ip` is reachable on the public internet: not loopback,
+/// unspecified, private (RFC 1918, unique-local), shared (CGNAT),
+/// link-local, documentation, benchmarking, reserved or multicast.
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, c, _] = v4.octets();
+            !(v4.is_loopback()
+                || v4.is_private()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                || v4.is_documentation()
+                || v4.is_multicast()
+                || a == 0
+                || a >= 240
+                || (a == 100 && (64..128).contains(&b))
+                || (a == 192 && b == 0 && c == 0)
+                || (a == 198 && (18..20).contains(&b)))
+        }
+        IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                return is_public(IpAddr::V4(v4));
+            }
+            let first = v6.segments()[0];
+            !(v6.is_loopback()
+                || v6.is_unspecified()
+                || v6.is_multicast()
+                || (first & 0xfe00) == 0xfc00
+                || (first & 0xffc0) == 0xfe80
+                || (first == 0x2001 && v6.segments()[1] == 0x0db8))
+        }
+    }
+}
+
+/// Resolves like the system does, but refuses names that point at a
+/// non-public address, so a public-looking host cannot be used to reach
+/// the local network.
+struct PublicOnlyResolver;
+
+impl Resolve for PublicOnlyResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> =
+                tokio::net::lookup_host((host.as_str(), 0)).await?.collect();
+            if let Some(addr) = addrs.iter().find(|addr| !is_public(addr.ip())) {
+                return Err(
+                    format!("{} resolves to non-public address {}", host, addr.ip()).into(),
+                );
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// The HTTP client a plugin fetches through, enforcing `policy` on every
+/// request and redirect.
+fn plugin_client(policy: &Arc<HostPolicy>) -> reqwest::Client {
+    let redirects = Arc::clone(policy);
+    let mut builder = reqwest::Client::builder()
+        .user_agent(USER_AGENT)
+        .no_proxy()
+        .redirect(reqwest::redirect::Policy::custom(move |attempt| {
+            if attempt.previous().len() >= 10 {
+                return attempt.error("too many redirects");
+            }
+            match redirects.check(attempt.url()) {
+                Ok(()) => attempt.follow(),
+                Err(e) => attempt.error(e.to_string()),
+            }
+        }));
+    if policy.allowed.is_empty() {
+        builder = builder.dns_resolver(Arc::new(PublicOnlyResolver));
+    }
+    builder.build().expect("Failed to create HTTP client")
+}
+
+/// A compiled plugin, wrapped as an [`Extractor`].
+pub struct WasmPluginExtractor {
+    inner: Arc<Plugin>,
+}
+
+struct Plugin {
+    id: String,
+    module: Module,
+    linker: Linker<HostState>,
+    limits: WasmLimits,
+    client: reqwest::Client,
+    policy: Arc<HostPolicy>,
+    /// From the manifest; when empty, `supports` asks the guest.
+    url_patterns: Vec<Regex>,
+    /// The guest's `supports` answers, by URL: plugins may decide by path.
+    supported_urls: Mutex<HashMap<String, bool>>,
+    has_direct_url: bool,
+}
+
+/// Per-call store data.
+struct HostState {
+    limits: StoreLimits,
+    fetcher: Option<Fetcher>,
+    fetches: u32,
+    last_status: i32,
+    plugin_id: String,
+}
+
+/// Host-side HTTP access for one extraction call.
+struct Fetcher {
+    client: reqwest::Client,
+    policy: Arc<HostPolicy>,
+    handle: Handle,
+    deadline: Instant,
+    max_fetches: u32,
+    max_response_bytes: usize,
+}
+
+#[derive(Deserialize)]
+struct FetchRequest {
+    url: String,
+    #[serde(default)]
+    method: Option<String>,
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+    #[serde(default)]
+    body: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Envelope<T> {
+    Ok(T),
+    Err(String),
+}
+
+/// The engine shared by every plugin. Fuel meters CPU; epoch interruption,
+/// driven by a ticker thread, enforces wall-clock deadlines.
+fn engine() -> &'static Engine {
+    static ENGINE: OnceLock<Engine> = OnceLock::new();
+    ENGINE.get_or_init(|| {
+        let mut config = Config::new();
+        config.consume_fuel(true).epoch_interruption(true);
+        let engine = Engine::new(&config).expect("Failed to create wasm engine");
+        let ticker = engine.clone();
+        std::thread::Builder::new()
+            .name("wasm-epoch".to_string())
+            .spawn(move || loop {
+                std::thread::sleep(EPOCH_TICK);
+                ticker.increment_epoch();
+            })
+            .expect("Failed to start wasm epoch ticker");
+        engine
+    })
+}
+
+impl WasmPluginExtractor {
+    /// Load a `.wasm` (or `.wat`) plugin, with its manifest if it has one.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let module = Module::from_file(engine(), path)?;
+        let plugin = Self::from_module(module)?;
+        let manifest = PluginManifest::path_for(path);
+        if manifest.exists() {
+            return plugin.with_manifest(PluginManifest::from_file(&manifest)?);
+        }
+        Ok(plugin)
+    }
+
+    /// Load a plugin from wasm binary or text.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let module = Module::new(engine(), bytes)?;
+        Self::from_module(module)
+    }
+
+    fn from_module(module: Module) -> Result<Self> {
+        for export in ["memory", "alloc", "id", "supports", "extract_info"] {
+            if module.get_export(export).is_none() {
+                bail!("plugin does not export `{}`", export);
+            }
+        }
+        let mut linker = Linker::new(engine());
+        add_host_functions(&mut linker)?;
+        let policy = Arc::new(HostPolicy::default());
+        let mut plugin = Plugin {
+            id: String::new(),
+            has_direct_url: module.get_export("get_direct_url").is_some(),
+            module,
+            linker,
+            limits: WasmLimits::default(),
+            client: plugin_client(&policy),
+            policy,
+            url_patterns: Vec::new(),
+            supported_urls: Mutex::new(HashMap::new()),
+        };
+        let id = plugin.query(|store, instance| {
+            let packed = instance
+                .get_typed_func::<(), i64>(&mut *store, "id")?
+                .call(&mut *store, ())?;
+            read_packed(store, instance, packed)
+        })?;
+        let id = String::from_utf8(id).context("plugin id is not UTF-8")?;
+        if id.trim().is_empty() {
+            bail!("plugin id is empty");
+        }
+        plugin.id = id;
+        Ok(Self {
+            inner: Arc::new(plugin),
+        })
+    }
+
+    /// Replace the default resource limits for extraction calls.
+    pub fn with_limits(mut self, limits: WasmLimits) -> Self {
+        match Arc::get_mut(&mut self.inner) {
+            Some(plugin) => plugin.limits = limits,
+            None => unreachable!("limits are set before the plugin is shared"),
+        }
+        self
+    }
+
+    /// Apply `manifest`: its URL patterns and allowed hosts.
+    pub fn with_manifest(mut self, manifest: PluginManifest) -> Result<Self> {
+        let url_patterns = manifest
+            .url_patterns
+            .iter()
+            .map(|pattern| {
+                Regex::new(pattern).with_context(|| format!("invalid url pattern {pattern:?}"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let policy = Arc::new(HostPolicy {
+            allowed: manifest
+                .allowed_hosts
+                .iter()
+                .map(|host| host.trim().to_ascii_lowercase())
+                .collect(),
+        });
+        match Arc::get_mut(&mut self.inner) {
+            Some(plugin) => {
+                plugin.client = plugin_client(&policy);
+                plugin.policy = policy;
+                plugin.url_patterns = url_patterns;
+            }
+            None => unreachable!("the manifest is applied before the plugin is shared"),
+        }
+        Ok(self)
+    }
+
+    /// Run `call` on a blocking thread: the guest (and `http_fetch`, which
+    /// blocks on the runtime) must not stall an async worker.
+    async fn run_blocking<T, F>(&self, call: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Plugin, Handle) -> Result<T> + Send + 'static,
+    {
+        let plugin = Arc::clone(&self.inner);
+        let handle = Handle::current();
+        tokio::task::spawn_blocking(move || call(&plugin, handle))
+            .await
+            .context("plugin task failed")?
+    }
+}
+
+impl Plugin {
+    /// Instantiate in a fresh store with the given budget and run `body`.
+    fn call<T>(
+        &self,
+        fuel: u64,
+        timeout: Duration,
+        fetcher: Option<Fetcher>,
+        body: impl FnOnce(&mut Store<HostState>, &Instance) -> Result<T>,
+    ) -> Result<T> {
+        let state = HostState {
+            limits: StoreLimitsBuilder::new()
+                .memory_size(self.limits.memory_bytes)
+                .instances(1)
+                .build(),
+            fetcher,
+            fetches: 0,
+            last_status: 0,
+            plugin_id: self.id.clone(),
+        };
+        let mut store = Store::new(engine(), state);
+        store.limiter(|state| &mut state.limits);
+        store.set_fuel(fuel)?;
+        store.set_epoch_deadline((timeout.as_millis() / EPOCH_TICK.as_millis()).max(1) as u64);
+        store.epoch_deadline_trap();
+
+        let instance = self
+            .linker
+            .instantiate(&mut store, &self.module)
+            .map_err(|e| self.describe(e))
+            .context("failed to instantiate plugin")?;
+        body(&mut store, &instance).map_err(|e| self.describe(e))
+    }
+
+    /// A cheap call outside extraction: no network, small budget.
+    fn query<T>(
+        &self,
+        body: impl FnOnce(&mut Store<HostState>, &Instance) -> Result<T>,
+    ) -> Result<T> {
+        self.call(QUERY_FUEL, QUERY_TIMEOUT, None, body)
+    }
+
+    fn fetcher(&self, handle: Handle) -> Fetcher {
+        Fetcher {
+            client: self.client.clone(),
+            policy: Arc::clone(&self.policy),
+            handle,
+            deadline: Instant::now() + self.limits.timeout,
+            max_fetches: self.limits.max_fetches,
+            max_response_bytes: self.limits.max_response_bytes,
+        }
+    }
+
+    fn extract(&self, url: &str, handle: Handle) -> Result<VideoInfo> {
+        let fetcher = self.fetcher(handle);
+        let result = self.call(
+            self.limits.fuel,
+            self.limits.timeout,
+            Some(fetcher),
+            |store, instance| {
+                let (ptr, len) = write_guest(store, instance, url.as_bytes())?;
+                let packed = instance
+                    .get_typed_func::<(i32, i32), i64>(&mut *store, "extract_info")?
+                    .call(&mut *store, (ptr, len))?;
+                read_packed(store, instance, packed)
+            },
+        )?;
+        let mut info: VideoInfo = match serde_json::from_slice(&result)
+            .with_context(|| format!("plugin {} returned malformed video info", self.id))?
+        {
+            Envelope::Ok(info) => info,
+            Envelope::Err(message) => bail!("plugin {}: {}", self.id, message),
+        };
+        info.normalize_url();
+        if info.url.is_empty() {
+            info.url = url.to_string();
+        }
+        if info.extractor.is_none() {
+            info.extractor = Some(self.id.clone());
+        }
+        Ok(info)
+    }
+
+    fn direct_url(&self, url: &str, format_id: &str, handle: Handle) -> Result<String> {
+        let fetcher = self.fetcher(handle);
+        let result = self.call(
+            self.limits.fuel,
+            self.limits.timeout,
+            Some(fetcher),
+            |store, instance| {
+                let (url_ptr, url_len) = write_guest(store, instance, url.as_bytes())?;
+                let (fmt_ptr, fmt_len) = write_guest(store, instance, format_id.as_bytes())?;
+                let packed = instance
+                    .get_typed_func::<(i32, i32, i32, i32), i64>(&mut *store, "get_direct_url")?
+                    .call(&mut *store, (url_ptr, url_len, fmt_ptr, fmt_len))?;
+                read_packed(store, instance, packed)
+            },
+        )?;
+        match serde_json::from_slice(&result)
+            .with_context(|| format!("plugin {} returned a malformed direct URL", self.id))?
+        {
+            Envelope::Ok(direct) => Ok(direct),
+            Envelope::Err(message) => bail!("plugin {}: {}", self.id, message),
+        }
+    }
+
+    /// Turn limit traps into messages that say which limit was hit.
+    fn describe(&self, error: anyhow::Error) -> anyhow::Error {
+        match error.downcast_ref::<Trap>() {
+            Some(Trap::OutOfFuel) => anyhow!("plugin {} exceeded its CPU budget", self.id),
+            Some(Trap::Interrupt) => anyhow!("plugin {} timed out", self.id),
+            _ => error,
+        }
+    }
+}
+
+#[async_trait]
+impl Extractor for WasmPluginExtractor {
+    fn id(&self) -> &str {
+        &self.inner.id
+    }
+
+    fn supports(&self, url: &str) -> bool {
+        let plugin = &self.inner;
+        if !plugin.url_patterns.is_empty() {
+            return plugin.url_patterns.iter().any(|p| p.is_match(url));
+        }
+        if let Some(&known) = plugin.supported_urls.lock().unwrap().get(url) {
+            return known;
+        }
+        let result = plugin.query(|store, instance| {
+            let (ptr, len) = write_guest(store, instance, url.as_bytes())?;
+            Ok(instance
+                .get_typed_func::<(i32, i32), i32>(&mut *store, "supports")?
+                .call(&mut *store, (ptr, len))?
+                != 0)
+        });
+        match result {
+            Ok(supported) => {
+                let mut cache = plugin.supported_urls.lock().unwrap();
+                if cache.len() >= SUPPORTS_CACHE_LIMIT {
+                    cache.clear();
+                }
+                cache.insert(url.to_string(), supported);
+                supported
+            }
+            Err(e) => {
+                warn!("Plugin {} failed in supports(): {:#}", plugin.id, e);
+                false
+            }
+        }
+    }
+
+    async fn extract_info(&self, url: &str) -> Result<VideoInfo> {
+        info!("Wasm plugin {} extracting: {}", self.inner.id, url);
+        let url = url.to_string();
+        self.run_blocking(move |plugin, handle| plugin.extract(&url, handle))
+            .await
+    }
+
+    async fn get_direct_url(&self, url: &str, format_id: &str) -> Result<String> {
+        if self.inner.has_direct_url {
+            let (url, format_id) = (url.to_string(), format_id.to_string());
+            return self
+                .run_blocking(move |plugin, handle| plugin.direct_url(&url, &format_id, handle))
+                .await;
+        }
+        let info = self.extract_info(url).await?;
+        info.formats
+            .into_iter()
+            .find(|f| f.format_id == format_id)
+            .map(|f| f.url)
+            .ok_or_else(|| anyhow!("Format {} not found on {}", format_id, url))
+    }
+}
+
+fn add_host_functions(linker: &mut Linker<HostState>) -> Result<()> {
+    linker.func_wrap(
+        "rustloader",
+        "http_fetch",
+        |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| -> Result<i64> {
+            let request = read_caller(&mut caller, ptr, len)?;
+            let state = caller.data_mut();
+            let fetcher = state
+                .fetcher
+                .as_ref()
+                .ok_or_else(|| anyhow!("http_fetch is only available during extraction"))?;
+            if state.fetches >= fetcher.max_fetches {
+                bail!(
+                    "plugin exceeded its limit of {} fetches",
+                    fetcher.max_fetches
+                );
+            }
+            state.fetches += 1;
+            match fetcher.fetch(&request) {
+                Ok((status, body)) => {
+                    caller.data_mut().last_status = i32::from(status);
+                    write_caller(&mut caller, &body)
+                }
+                Err(e) => {
+                    let state = caller.data_mut();
+                    debug!("Plugin {} fetch failed: {:#}", state.plugin_id, e);
+                    state.last_status = 0;
+                    Ok(-1)
+                }
+            }
+        },
+    )?;
+    linker.func_wrap(
+        "rustloader",
+        "http_status",
+        |caller: Caller<'_, HostState>| -> i32 { caller.data().last_status },
+    )?;
+    linker.func_wrap(
+        "rustloader",
+        "log",
+        |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| -> Result<()> {
+            let message = read_caller(&mut caller, ptr, len)?;
+            debug!(
+                "[plugin {}] {}",
+                caller.data().plugin_id,
+                String::from_utf8_lossy(&message)
+            );
+            Ok(())
+        },
+    )?;
+    Ok(())
+}
+
+impl Fetcher {
+    /// Perform one request, blocking this (non-async) thread until the
+    /// response body is in or the call's deadline passes.
+    fn fetch(&self, raw: &[u8]) -> Result<(u16, Vec<u8>)> {
+        let text = std::str::from_utf8(raw).context("request is not UTF-8")?;
+        let request = if text.trim_start().starts_with('{') {
+            serde_json::from_str::<FetchRequest>(text).context("malformed fetch request")?
+        } else {
+            FetchRequest {
+                url: text.trim().to_string(),
+                method: None,
+                headers: BTreeMap::new(),
+                body: None,
+            }
+        };
+        let url = Url::parse(&request.url)?;
+        self.policy.check(&url)?;
+        let method = match request.method.as_deref() {
+            Some(method) => reqwest::Method::from_bytes(method.to_ascii_uppercase().as_bytes())?,
+            None => reqwest::Method::GET,
+        };
+        let mut builder = self.client.request(method, url);
+        for (name, value) in &request.headers {
+            builder = builder.header(name, value);
+        }
+        if let Some(body) = request.body {
+            builder = builder.body(body);
+        }
+
+        let remaining = self.deadline.saturating_duration_since(Instant::now());
+        let max = self.max_response_bytes;
+        self.handle.block_on(async move {
+            tokio::time::timeout(remaining, async move {
+                let mut response = builder.send().await?;
+                let status = response.status().as_u16();
+                let mut body = Vec::new();
+                while let Some(chunk) = response.chunk().await? {
+                    if body.len() + chunk.len() > max {
+                        bail!("response exceeds {} bytes", max);
+                    }
+                    body.extend_from_slice(&chunk);
+                }
+                Ok((status, body))
+            })
+            .await
+            .map_err(|_| anyhow!("fetch timed out"))?
+        })
+    }
+}
+
+fn pack(ptr: i32, len: i32) -> i64 {
+    ((ptr as u32 as i64) << 32) | (len as u32 as i64)
+}
+
+fn unpack(packed: i64) -> (usize, usize) {
+    (
+        (packed as u64 >> 32) as usize,
+        (packed as u64 & 0xffff_ffff) as usize,
+    )
+}
+
+fn read_caller(caller: &mut Caller<'_, HostState>, ptr: i32, len: i32) -> Result<Vec<u8>> {
+    let memory = caller
+        .get_export("memory")
+        .and_then(|e| e.into_memory())
+        .ok_or_else(|| anyhow!("plugin has no memory"))?;
+    let len = usize::try_from(len).context("negative length")?;
+    if len > MAX_GUEST_STRING {
+        bail!("plugin string exceeds {} bytes", MAX_GUEST_STRING);
+    }
+    let mut buf = vec![0; len];
+    memory.read(&*caller, ptr as u32 as usize, &mut buf)?;
+    Ok(buf)
+}
+
+fn write_caller(caller: &mut Caller<'_, HostState>, bytes: &[u8]) -> Result<i64> {
+    let len = i32::try_from(bytes.len()).context("data too large for the plugin")?;
+    let alloc = caller
+        .get_export("alloc")
+        .and_then(|e| e.into_func())
+        .ok_or_else(|| anyhow!("plugin has no alloc"))?
+        .typed::<i32, i32>(&*caller)?;
+    let ptr = alloc.call(&mut *caller, len)?;
+    let memory = caller
+        .get_export("memory")
+        .and_then(|e| e.into_memory())
+        .ok_or_else(|| anyhow!("plugin has no memory"))?;
+    memory.write(&mut *caller, ptr as u32 as usize, bytes)?;
+    Ok(pack(ptr, len))
+}
+
+fn write_guest(
+    store: &mut Store<HostState>,
+    instance: &Instance,
+    bytes: &[u8],
+) -> Result<(i32, i32)> {
+    let len = i32::try_from(bytes.len()).context("data too large for the plugin")?;
+    let ptr = instance
+        .get_typed_func::<i32, i32>(&mut *store, "alloc")?
+        .call(&mut *store, len)?;
+    let memory = instance
+        .get_memory(&mut *store, "memory")
+        .ok_or_else(|| anyhow!("plugin has no memory"))?;
+    memory.write(&mut *store, ptr as u32 as usize, bytes)?;
+    Ok((ptr, len))
+}
+
+fn read_packed(store: &mut Store<HostState>, instance: &Instance, packed: i64) -> Result<Vec<u8>> {
+    let (ptr, len) = unpack(packed);
+    if len > MAX_GUEST_STRING {
+        bail!("plugin result exceeds {} bytes", MAX_GUEST_STRING);
+    }
+    let memory = instance
+        .get_memory(&mut *store, "memory")
+        .ok_or_else(|| anyhow!("plugin has no memory"))?;
+    let mut buf = vec![0; len];
+    memory.read(&*store, ptr, &mut buf)?;
+    Ok(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    const SAMPLE: &str = include_str!("../../../examples/plugins/sample_plugin.wat");
+    const SAMPLE_MANIFEST: &str =
+        include_str!("../../../examples/plugins/sample_plugin.manifest.toml");
+
+    /// The sample plugin, allowed to fetch from the local test site.
+    fn sample_on_localhost() -> WasmPluginExtractor {
+        let mut manifest: PluginManifest = toml::from_str(SAMPLE_MANIFEST).unwrap();
+        manifest.allowed_hosts = vec!["127.0.0.1".to_string()];
+        WasmPluginExtractor::from_bytes(SAMPLE.as_bytes())
+            .unwrap()
+            .with_manifest(manifest)
+            .unwrap()
+    }
+
+    /// Minimal plugin whose `extract_info` body is `body`.
+    fn plugin_with(memory_pages: u32, body: &str) -> String {
+        format!(
+            r#"(module
+                (memory (export "memory") {memory_pages})
+                (data (i32.const 0) "looper")
+                (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+                (func (export "id") (result i64) (i64.const 6))
+                (func (export "supports") (param i32 i32) (result i32) (i32.const 1))
+                (func (export "extract_info") (param i32 i32) (result i64) {body}))"#
+        )
+    }
+
+    /// Stand-in for a site: `/sample/<id>?format=json` returns video info,
+    /// anything else a 404.
+    async fn spawn_site() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("local_addr");
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 4096];
+                    let mut req = Vec::new();
+                    loop {
+                        let n = match socket.read(&mut buf).await {
+                            Ok(0) | Err(_) => return,
+                            Ok(n) => n,
+                        };
+                        req.extend_from_slice(&buf[..n]);
+                        if req.windows(4).any(|w| w == b"\r\n\r\n") {
+                            break;
+                        }
+                    }
+                    let req = String::from_utf8_lossy(&req);
+                    let path = req.split_whitespace().nth(1).unwrap_or("/");
+                    let (status, body) = match path {
+                        "/sample/clip1?format=json" => (
+                            "200 OK",
+                            serde_json::json!({
+                                "id": "clip1",
+                                "title": "Sample clip",
+                                "duration": 12,
+                                "formats": [
+                                    {"format_id": "hd", "ext": "mp4", "url": "https://cdn.example/clip1-hd.mp4", "height": 720}
+                                ]
+                            })
+                            .to_string(),
+                        ),
+                        _ => ("404 Not Found", "not found".to_string()),
+                    };
+                    let response = format!(
+                        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                        body.len()
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                    let _ = socket.shutdown().await;
+                });
+            }
+        });
+        format!("http://{addr}")
+    }
+
+    #[test]
+    fn sample_plugin_loads_and_routes() {
+        let plugin = WasmPluginExtractor::from_bytes(SAMPLE.as_bytes()).unwrap();
+        assert_eq!(plugin.id(), "wasm-sample");
+        assert!(plugin.supports("https://example.com/sample/clip1"));
+        assert!(!plugin.supports("https://example.com/watch/clip1"));
+        assert!(!plugin.supports("https://other.example/watch/clip1"));
+
+        let plugin = plugin
+            .with_manifest(toml::from_str(SAMPLE_MANIFEST).unwrap())
+            .unwrap();
+        assert!(plugin.supports("https://example.com/sample/clip1"));
+        assert!(!plugin.supports("https://example.com/watch/clip1"));
+    }
+
+    #[test]
+    fn supports_answers_are_remembered_per_url_not_per_host() {
+        let plugin = WasmPluginExtractor::from_bytes(SAMPLE.as_bytes()).unwrap();
+        // A first "no" for one page of a host says nothing about another.
+        assert!(!plugin.supports("https://a.com/about"));
+        assert!(plugin.supports("https://a.com/sample/1"));
+        assert!(!plugin.supports("https://a.com/about"));
+        for i in 0..SUPPORTS_CACHE_LIMIT * 2 {
+            plugin.supports(&format!("https://b.com/page/{i}"));
+        }
+        assert!(plugin.inner.supported_urls.lock().unwrap().len() <= SUPPORTS_CACHE_LIMIT);
+        assert!(plugin.supports("https://a.com/sample/1"));
+    }
+
+    #[tokio::test]
+    async fn plugins_cannot_reach_private_addresses_unless_allowed() {
+        let base = spawn_site().await;
+        let plugin = WasmPluginExtractor::from_bytes(SAMPLE.as_bytes()).unwrap();
+        let err = plugin
+            .extract_info(&format!("{base}/sample/clip1"))
+            .await
+            .unwrap_err();
+        // The host refused the fetch; the guest saw `-1`.
+        assert_eq!(err.to_string(), "plugin wasm-sample: fetch failed");
+
+        let public = HostPolicy::default();
+        for url in [
+            "http://127.0.0.1/",
+            "http://169.254.169.254/latest/meta-data",
+            "http://10.0.0.1/",
+            "http://192.168.1.1/",
+            "http://[::1]/",
+            "http://[fd00::1]/",
+            "http://[::ffff:127.0.0.1]/",
+            "file:///etc/passwd",
+        ] {
+            assert!(public.check(&Url::parse(url).unwrap()).is_err(), "{url}");
+        }
+        assert!(public
+            .check(&Url::parse("https://93.184.216.34/").unwrap())
+            .is_ok());
+        assert!(public
+            .check(&Url::parse("https://example.com/").unwrap())
+            .is_ok());
+
+        let listed = HostPolicy {
+            allowed: vec!["192.168.1.5".to_string(), "intranet.local".to_string()],
+        };
+        assert!(listed
+            .check(&Url::parse("http://192.168.1.5/").unwrap())
+            .is_ok());
+        assert!(listed
+            .check(&Url::parse("http://intranet.local/").unwrap())
+            .is_ok());
+        assert!(listed
+            .check(&Url::parse("https://example.com/").unwrap())
+            .is_err());
+    }
+
+    #[test]
+    fn modules_missing_the_abi_are_rejected() {
+        let err = WasmPluginExtractor::from_bytes(b"(module (memory (export \"memory\") 1))")
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("does not export `alloc`"));
+    }
+
+    #[tokio::test]
+    async fn sample_plugin_extracts_through_host_fetch() {
+        let base = spawn_site().await;
+        let plugin = sample_on_localhost();
+        let url = format!("{base}/sample/clip1");
+
+        let info = plugin.extract_info(&url).await.unwrap();
+        assert_eq!(info.id, "clip1");
+        assert_eq!(info.title, "Sample clip");
+        assert_eq!(info.url, url);
+        assert_eq!(info.extractor.as_deref(), Some("wasm-sample"));
+        assert_eq!(info.formats[0].height, Some(720));
+
+        let direct = plugin.get_direct_url(&url, "hd").await.unwrap();
+        assert_eq!(direct, "https://cdn.example/clip1-hd.mp4");
+    }
+
+    #[tokio::test]
+    async fn plugin_errors_are_reported() {
+        let base = spawn_site().await;
+        let plugin = sample_on_localhost();
+        let err = plugin
+            .extract_info(&format!("{base}/sample/missing"))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "plugin wasm-sample: sample endpoint returned an error"
+        );
+    }
+
+    #[tokio::test]
+    async fn runaway_plugin_exhausts_its_cpu_budget() {
+        let source = plugin_with(1, "(loop (br 0)) (i64.const 0)");
+        let plugin = WasmPluginExtractor::from_bytes(source.as_bytes())
+            .unwrap()
+            .with_limits(WasmLimits {
+                fuel: 1_000_000,
+                ..WasmLimits::default()
+            });
+        assert_eq!(plugin.id(), "looper");
+        let err = plugin
+            .extract_info("https://example.com/")
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "plugin looper exceeded its CPU budget");
+    }
+
+    #[tokio::test]
+    async fn slow_plugin_hits_the_deadline() {
+        let source = plugin_with(1, "(loop (br 0)) (i64.const 0)");
+        let plugin = WasmPluginExtractor::from_bytes(source.as_bytes())
+            .unwrap()
+            .with_limits(WasmLimits {
+                fuel: u64::MAX,
+                timeout: Duration::from_millis(100),
+                ..WasmLimits::default()
+            });
+        let started = Instant::now();
+        let err = plugin
+            .extract_info("https://example.com/")
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "plugin looper timed out");
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn memory_is_capped() {
+        // Growing past the cap fails inside the guest (memory.grow = -1).
+        let source = plugin_with(
+            1,
+            r#"(if (i32.eq (memory.grow (i32.const 64)) (i32.const -1))
+                 (then (return (i64.const 0))))
+               unreachable"#,
+        );
+        let plugin = WasmPluginExtractor::from_bytes(source.as_bytes())
+            .unwrap()
+            .with_limits(WasmLimits {
+                memory_bytes: 1024 * 1024,
+                ..WasmLimits::default()
+            });
+        // The (0, 0) result is an empty, hence malformed, envelope: the
+        // guest saw the failed grow rather than trapping.
+        let err = plugin
+            .extract_info("https://example.com/")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("malformed video info"), "{err}");
+
+        // A module whose initial memory is already over the cap never runs.
+        let big = plugin_with(2048, "(i64.const 0)");
+        let err = WasmPluginExtractor::from_bytes(big.as_bytes())
+            .err()
+            .unwrap();
+        assert!(format!("{err:#}").contains("instantiate"), "{err:#}");
+    }
+}
@@ -0,0 +1,397 @@
+//! Structured format preferences: maximum height, a codec ranking, the
+//! container, SDR/HDR and audio languages. They become a yt-dlp `-S` sort
+//! and `-f` selector, which the native format choice evaluates the same way.
+
+use super::selector::{codec_family, FormatSelector, FormatSort};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Output container for merged and remuxed downloads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Container {
+    Mp4,
+    Mkv,
+    Webm,
+}
+
+impl Container {
+    pub const ALL: [Container; 3] = [Container::Mp4, Container::Mkv, Container::Webm];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Container::Mp4 => "mp4",
+            Container::Mkv => "mkv",
+            Container::Webm => "webm",
+        }
+    }
+
+    /// The `ext:VIDEO:AUDIO` preference that avoids re-encoding; MKV holds
+    /// anything.
+    fn ext_preference(self) -> Option<&'static str> {
+        match self {
+            Container::Mp4 => Some("mp4:m4a"),
+            Container::Mkv => None,
+            Container::Webm => Some("webm:webm"),
+        }
+    }
+}
+
+impl fmt::Display for Container {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Container {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Container::ALL
+            .into_iter()
+            .find(|c| c.as_str().eq_ignore_ascii_case(value.trim()))
+            .ok_or_else(|| format!("unknown container '{value}' (one of mp4, mkv, webm)"))
+    }
+}
+
+/// SDR or HDR video.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DynamicRange {
+    Sdr,
+    Hdr,
+}
+
+impl DynamicRange {
+    pub const ALL: [DynamicRange; 2] = [DynamicRange::Sdr, DynamicRange::Hdr];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DynamicRange::Sdr => "sdr",
+            DynamicRange::Hdr => "hdr",
+        }
+    }
+}
+
+impl fmt::Display for DynamicRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DynamicRange {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        DynamicRange::ALL
+            .into_iter()
+            .find(|r| r.as_str().eq_ignore_ascii_case(value.trim()))
+            .ok_or_else(|| format!("unknown dynamic range '{value}' (sdr or hdr)"))
+    }
+}
+
+/// What to prefer when several formats fit. Empty preferences change
+/// nothing.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FormatPreferences {
+    /// Largest height wanted (e.g. 1440, 2160). Taller formats are only
+    /// picked when nothing fits.
+    pub max_height: Option<u32>,
+    /// Video codec families, most wanted first (`h264`, `vp9`, `av1`, ...).
+    /// A ranked codec wins over resolution.
+    pub video_codecs: Vec<String>,
+    /// Video codec families never to download.
+    pub avoid_codecs: Vec<String>,
+    pub container: Option<Container>,
+    pub dynamic_range: Option<DynamicRange>,
+    /// Audio languages, most wanted first (`es`, `en`). Tracks are matched
+    /// by prefix, so `es` also takes `es-419`.
+    pub audio_languages: Vec<String>,
+}
+
+/// Video codec families a preference can name.
+pub const VIDEO_CODECS: [&str; 5] = ["h264", "h265", "vp9", "av1", "vp8"];
+
+/// A video codec family from a family or codec name: `avc1` → `h264`.
+pub fn parse_video_codec(value: &str) -> Result<String, String> {
+    let family = codec_family(value);
+    VIDEO_CODECS
+        .iter()
+        .find(|known| known.eq_ignore_ascii_case(family))
+        .map(|known| known.to_string())
+        .ok_or_else(|| {
+            format!(
+                "unknown video codec '{value}' (one of {})",
+                VIDEO_CODECS.join(", ")
+            )
+        })
+}
+
+/// Regex matching the codec strings of a family (`avc1.640028` for h264).
+fn codec_pattern(family: &str) -> String {
+    match codec_family(family) {
+        "h264" => "^(avc|h264)".to_string(),
+        "h265" => "^(hev|hvc|h265)".to_string(),
+        "vp9" => "^vp0?9".to_string(),
+        "av1" => "^av0?1".to_string(),
+        "vp8" => "^vp0?8".to_string(),
+        other => format!("^{}", regex::escape(other)),
+    }
+}
+
+impl FormatPreferences {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn with_max_height(mut self, height: u32) -> Self {
+        self.max_height = Some(height);
+        self
+    }
+
+    pub fn with_video_codecs(mut self, codecs: Vec<String>) -> Self {
+        self.video_codecs = codecs;
+        self
+    }
+
+    pub fn with_avoid_codecs(mut self, codecs: Vec<String>) -> Self {
+        self.avoid_codecs = codecs;
+        self
+    }
+
+    pub fn with_container(mut self, container: Container) -> Self {
+        self.container = Some(container);
+        self
+    }
+
+    pub fn with_dynamic_range(mut self, range: DynamicRange) -> Self {
+        self.dynamic_range = Some(range);
+        self
+    }
+
+    pub fn with_audio_languages(mut self, languages: Vec<String>) -> Self {
+        self.audio_languages = languages;
+        self
+    }
+
+    /// The `-S` fields: `res:HEIGHT`, the dynamic range (`+hdr` puts SDR
+    /// first) and the container's extensions.
+    pub fn format_sort(&self) -> FormatSort {
+        let mut fields = Vec::new();
+        if let Some(height) = self.max_height {
+            fields.push(format!("res:{height}"));
+        }
+        match self.dynamic_range {
+            Some(DynamicRange::Sdr) => fields.push("+hdr".to_string()),
+            Some(DynamicRange::Hdr) => fields.push("hdr".to_string()),
+            None => {}
+        }
+        if let Some(ext) = self.container.and_then(Container::ext_preference) {
+            fields.push(format!("ext:{ext}"));
+        }
+        // Built from known keys and numbers, so it always parses.
+        fields.join(",").parse().unwrap_or_default()
+    }
+
+    /// The `-f` expression for codecs and languages: each ranked codec in
+    /// turn (then any other), each wanted language in turn (then any other)
+    /// and the best single file last. `None` when neither is set.
+    pub fn selector(&self) -> Option<FormatSelector> {
+        if self.video_codecs.is_empty()
+            && self.avoid_codecs.is_empty()
+            && self.audio_languages.is_empty()
+        {
+            return None;
+        }
+        let avoid = self.avoid_filters();
+        let mut videos: Vec<String> = self
+            .video_codecs
+            .iter()
+            .map(|codec| format!("[vcodec~='{}']{avoid}", codec_pattern(codec)))
+            .collect();
+        videos.push(avoid);
+        let mut audios: Vec<String> = self
+            .audio_languages
+            .iter()
+            .map(|lang| format!("ba[language^={lang}]"))
+            .collect();
+        audios.push("ba".to_string());
+
+        let mut alternatives = Vec::new();
+        for audio in &audios {
+            for video in &videos {
+                alternatives.push(format!("bv*{video}+{audio}"));
+            }
+        }
+        alternatives.extend(videos.iter().map(|video| format!("b{video}")));
+        alternatives.join("/").parse().ok()
+    }
+
+    /// What the native path falls back to when [`selector`](Self::selector)
+    /// picks nothing: [`FormatSelector::native_default`], still skipping the
+    /// avoided codecs.
+    pub fn native_fallback(&self) -> FormatSelector {
+        if self.avoid_codecs.is_empty() {
+            return FormatSelector::native_default();
+        }
+        let avoid = self.avoid_filters();
+        format!("b{avoid}/bv*{avoid}/ba")
+            .parse()
+            .expect("the fallback selector parses")
+    }
+
+    /// `[vcodec!~=?'...']` for each avoided codec.
+    fn avoid_filters(&self) -> String {
+        self.avoid_codecs
+            .iter()
+            .map(|codec| format!("[vcodec!~=?'{}']", codec_pattern(codec)))
+            .collect()
+    }
+
+    /// [`selector`](Self::selector) for audio-only downloads.
+    pub fn audio_selector(&self) -> Option<FormatSelector> {
+        if self.audio_languages.is_empty() {
+            return None;
+        }
+        let mut alternatives: Vec<String> = self
+            .audio_languages
+            .iter()
+            .map(|lang| format!("ba[language^={lang}]"))
+            .collect();
+        alternatives.push("ba/b".to_string());
+        alternatives.join("/").parse().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extractor::Format;
+
+    fn format(id: &str, vcodec: &str, acodec: &str, height: Option<u32>) -> Format {
+        Format {
+            format_id: id.to_string(),
+            ext: if vcodec == "none" { "m4a" } else { "mp4" }.to_string(),
+            vcodec: Some(vcodec.to_string()),
+            acodec: Some(acodec.to_string()),
+            height,
+            ..Default::default()
+        }
+    }
+
+    /// Worst to best, with an English and a Spanish audio track.
+    fn formats() -> Vec<Format> {
+        vec![
+            format("18", "avc1.42001E", "mp4a.40.2", Some(360)),
+            Format {
+                language: Some("en".to_string()),
+                tbr: Some(128.0),
+                ..format("140-en", "none", "mp4a.40.2", None)
+            },
+            Format {
+                language: Some("es-419".to_string()),
+                tbr: Some(130.0),
+                ..format("140-es", "none", "mp4a.40.2", None)
+            },
+            format("137", "avc1.640028", "none", Some(1080)),
+            Format {
+                tbr: Some(2200.0),
+                ..format("248", "vp9", "none", Some(1080))
+            },
+            Format {
+                dynamic_range: Some("HDR10".to_string()),
+                ..format("337", "vp09.02.51.10", "none", Some(2160))
+            },
+            Format {
+                dynamic_range: Some("SDR".to_string()),
+                ..format("313", "vp9", "none", Some(2160))
+            },
+            format("401", "av01.0.12M.08", "none", Some(2160)),
+        ]
+    }
+
+    fn pick(prefs: &FormatPreferences) -> String {
+        let selector = prefs
+            .selector()
+            .unwrap_or_else(|| "bv*+ba/b".parse().unwrap());
+        selector
+            .pick(&formats(), &prefs.format_sort())
+            .unwrap()
+            .format_id
+    }
+
+    #[test]
+    fn empty_preferences_add_nothing() {
+        let prefs = FormatPreferences::default();
+        assert!(prefs.is_empty());
+        assert!(prefs.format_sort().is_empty());
+        assert!(prefs.selector().is_none());
+        assert!(prefs.audio_selector().is_none());
+    }
+
+    #[test]
+    fn preferences_become_a_sort_and_a_selector() {
+        let prefs = FormatPreferences::default()
+            .with_max_height(1440)
+            .with_dynamic_range(DynamicRange::Sdr)
+            .with_container(Container::Mp4)
+            .with_video_codecs(vec!["h264".to_string()])
+            .with_avoid_codecs(vec!["av1".to_string()])
+            .with_audio_languages(vec!["es".to_string()]);
+        assert_eq!(prefs.format_sort().to_string(), "res:1440,+hdr,ext:mp4:m4a");
+        assert_eq!(
+            prefs.selector().unwrap().to_string(),
+            "bv*[vcodec~='^(avc|h264)'][vcodec!~=?'^av0?1']+ba[language^=es]\
+             /bv*[vcodec!~=?'^av0?1']+ba[language^=es]\
+             /bv*[vcodec~='^(avc|h264)'][vcodec!~=?'^av0?1']+ba\
+             /bv*[vcodec!~=?'^av0?1']+ba\
+             /b[vcodec~='^(avc|h264)'][vcodec!~=?'^av0?1']\
+             /b[vcodec!~=?'^av0?1']"
+        );
+        assert_eq!(
+            prefs.audio_selector().unwrap().to_string(),
+            "ba[language^=es]/ba/b"
+        );
+    }
+
+    #[test]
+    fn native_choice_follows_the_preferences() {
+        let prefs = FormatPreferences::default();
+        assert_eq!(pick(&prefs.clone().with_max_height(1080)), "248+140-es");
+        assert_eq!(
+            pick(&prefs.clone().with_audio_languages(vec!["en".to_string()])),
+            "401+140-en"
+        );
+        // A ranked codec beats resolution; an avoided one is never picked.
+        assert_eq!(
+            pick(&prefs.clone().with_video_codecs(vec!["h264".to_string()])),
+            "137+140-es"
+        );
+        assert_eq!(
+            pick(&prefs.clone().with_avoid_codecs(vec!["av1".to_string()])),
+            "313+140-es"
+        );
+        assert_eq!(
+            pick(
+                &prefs
+                    .clone()
+                    .with_avoid_codecs(vec!["av1".to_string()])
+                    .with_dynamic_range(DynamicRange::Hdr)
+            ),
+            "337+140-es"
+        );
+    }
+
+    #[test]
+    fn containers_ranges_and_codecs_parse() {
+        assert_eq!(parse_video_codec("avc1").unwrap(), "h264");
+        assert_eq!(parse_video_codec("AV1").unwrap(), "av1");
+        assert!(parse_video_codec("divx").is_err());
+        assert_eq!("MKV".parse::<Container>().unwrap(), Container::Mkv);
+        assert!("avi".parse::<Container>().is_err());
+        assert_eq!("hdr".parse::<DynamicRange>().unwrap(), DynamicRange::Hdr);
+        assert!("dolby".parse::<DynamicRange>().is_err());
+    }
+}
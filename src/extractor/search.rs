@@ -0,0 +1,337 @@
+//! Site search through yt-dlp's search prefixes (`ytsearch`, `scsearch`, ...).
+//!
+//! yt-dlp treats `<prefix><N>:<terms>` as a playlist of the first N hits and
+//! prints one JSON object per line with `--dump-json`. A page is a slice of
+//! that playlist (`--playlist-start`/`--playlist-end`); flat mode adds
+//! `--flat-playlist`, which lists the hits without resolving each video and is
+//! much faster, at the cost of formats and some metadata.
+
+use crate::extractor::models::VideoInfo;
+use serde_json::Value;
+use std::fmt;
+use std::str::FromStr;
+use tracing::debug;
+
+/// A site yt-dlp can search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SearchProvider {
+    #[default]
+    YouTube,
+    /// YouTube, newest uploads first.
+    YouTubeByDate,
+    SoundCloud,
+    Bilibili,
+    Niconico,
+}
+
+impl SearchProvider {
+    pub const ALL: [SearchProvider; 5] = [
+        SearchProvider::YouTube,
+        SearchProvider::YouTubeByDate,
+        SearchProvider::SoundCloud,
+        SearchProvider::Bilibili,
+        SearchProvider::Niconico,
+    ];
+
+    /// The yt-dlp search prefix (`ytsearch`, `scsearch`, ...).
+    pub fn prefix(self) -> &'static str {
+        match self {
+            SearchProvider::YouTube => "ytsearch",
+            SearchProvider::YouTubeByDate => "ytsearchdate",
+            SearchProvider::SoundCloud => "scsearch",
+            SearchProvider::Bilibili => "bilisearch",
+            SearchProvider::Niconico => "nicosearch",
+        }
+    }
+
+    /// The short name accepted by [`FromStr`] (and the CLI).
+    pub fn name(self) -> &'static str {
+        match self {
+            SearchProvider::YouTube => "youtube",
+            SearchProvider::YouTubeByDate => "youtube-date",
+            SearchProvider::SoundCloud => "soundcloud",
+            SearchProvider::Bilibili => "bilibili",
+            SearchProvider::Niconico => "niconico",
+        }
+    }
+}
+
+impl fmt::Display for SearchProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SearchProvider::YouTube => "YouTube",
+            SearchProvider::YouTubeByDate => "YouTube (newest)",
+            SearchProvider::SoundCloud => "SoundCloud",
+            SearchProvider::Bilibili => "Bilibili",
+            SearchProvider::Niconico => "Niconico",
+        })
+    }
+}
+
+impl FromStr for SearchProvider {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.to_lowercase();
+        SearchProvider::ALL
+            .into_iter()
+            .find(|p| p.name() == lower || p.prefix() == lower)
+            .ok_or_else(|| {
+                let names: Vec<_> = SearchProvider::ALL.iter().map(|p| p.name()).collect();
+                format!(
+                    "unknown search provider '{s}' (expected one of: {})",
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// One page of a search.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchQuery {
+    pub terms: String,
+    pub provider: SearchProvider,
+    /// 1-based page number.
+    pub page: usize,
+    pub page_size: usize,
+    /// List hits without resolving each video (fast; no formats).
+    pub flat: bool,
+}
+
+impl SearchQuery {
+    pub const DEFAULT_PAGE_SIZE: usize = 10;
+
+    /// First page of a flat YouTube search.
+    pub fn new(terms: impl Into<String>) -> Self {
+        Self {
+            terms: terms.into(),
+            provider: SearchProvider::default(),
+            page: 1,
+            page_size: Self::DEFAULT_PAGE_SIZE,
+            flat: true,
+        }
+    }
+
+    pub fn with_provider(mut self, provider: SearchProvider) -> Self {
+        self.provider = provider;
+        self
+    }
+
+    pub fn with_page(mut self, page: usize) -> Self {
+        self.page = page.max(1);
+        self
+    }
+
+    pub fn with_page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size.max(1);
+        self
+    }
+
+    pub fn with_flat(mut self, flat: bool) -> Self {
+        self.flat = flat;
+        self
+    }
+
+    /// The same search, one page further.
+    pub fn next_page(&self) -> Self {
+        self.clone().with_page(self.page + 1)
+    }
+
+    /// The same search, one page back (never before page 1).
+    pub fn previous_page(&self) -> Self {
+        self.clone().with_page(self.page.saturating_sub(1))
+    }
+
+    /// 1-based index of the first hit on this page.
+    pub fn first_index(&self) -> usize {
+        (self.page.max(1) - 1) * self.page_size + 1
+    }
+
+    /// 1-based index of the last hit on this page.
+    pub fn last_index(&self) -> usize {
+        self.page.max(1) * self.page_size
+    }
+
+    /// yt-dlp's pseudo-URL for every hit up to the end of this page, e.g.
+    /// `ytsearch20:lofi beats`.
+    pub fn target(&self) -> String {
+        format!(
+            "{}{}:{}",
+            self.provider.prefix(),
+            self.last_index(),
+            self.terms.trim()
+        )
+    }
+
+    /// The yt-dlp arguments that fetch just this page.
+    pub fn ytdlp_args(&self) -> Vec<String> {
+        let mut args = vec!["--dump-json".to_string(), "--no-warnings".to_string()];
+        if self.flat {
+            args.push("--flat-playlist".to_string());
+        }
+        args.extend([
+            "--playlist-start".to_string(),
+            self.first_index().to_string(),
+            "--playlist-end".to_string(),
+            self.last_index().to_string(),
+            self.target(),
+        ]);
+        args
+    }
+}
+
+/// A page of search hits.
+#[derive(Debug, Clone, Default)]
+pub struct SearchPage {
+    pub results: Vec<VideoInfo>,
+    /// 1-based page number.
+    pub page: usize,
+    /// A full page came back, so the next one probably has hits too.
+    pub has_more: bool,
+}
+
+/// Parse yt-dlp's one-object-per-line search output. Lines that are not a
+/// video (warnings, broken JSON) are skipped. Flat entries carry a
+/// `thumbnails` list rather than `thumbnail` and a `channel` rather than an
+/// `uploader`; both are folded into the usual fields.
+pub fn parse_search_output(stdout: &str) -> Vec<VideoInfo> {
+    stdout
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with('{'))
+        .filter_map(|line| {
+            let value: Value = match serde_json::from_str(line) {
+                Ok(value) => value,
+                Err(e) => {
+                    debug!("Skipping unparseable search line: {}", e);
+                    return None;
+                }
+            };
+            let mut info: VideoInfo = match serde_json::from_value(value.clone()) {
+                Ok(info) => info,
+                Err(e) => {
+                    debug!("Skipping search entry without video fields: {}", e);
+                    return None;
+                }
+            };
+            info.normalize_url();
+            if info.thumbnail.is_none() {
+                // yt-dlp lists thumbnails smallest first.
+                info.thumbnail = value["thumbnails"]
+                    .as_array()
+                    .and_then(|thumbs| thumbs.iter().rev().find_map(|t| t["url"].as_str()))
+                    .map(str::to_string);
+            }
+            if info.uploader.is_none() {
+                info.uploader = value["channel"].as_str().map(str::to_string);
+            }
+            Some(info)
+        })
+        .collect()
+}
+
+/// `m:ss`, or `h:mm:ss` for an hour or more.
+pub fn format_clock(seconds: u64) -> String {
+    let (h, m, s) = (seconds / 3600, (seconds % 3600) / 60, seconds % 60);
+    if h > 0 {
+        format!("{h}:{m:02}:{s:02}")
+    } else {
+        format!("{m}:{s:02}")
+    }
+}
+
+/// The "uploader · duration · views" line shown under a hit; parts the
+/// search did not return are left out.
+pub fn result_details(info: &VideoInfo) -> String {
+    let mut parts = Vec::new();
+    if let Some(uploader) = &info.uploader {
+        parts.push(uploader.clone());
+    }
+    if let Some(duration) = info.duration {
+        parts.push(format_clock(duration));
+    }
+    if let Some(views) = info.view_count {
+        parts.push(format!("{views} views"));
+    }
+    parts.join(" · ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_paged_flat_arguments() {
+        let query = SearchQuery::new(" lofi beats ")
+            .with_provider(SearchProvider::SoundCloud)
+            .with_page_size(5)
+            .with_page(3);
+        assert_eq!(query.target(), "scsearch15:lofi beats");
+        assert_eq!(
+            query.ytdlp_args(),
+            [
+                "--dump-json",
+                "--no-warnings",
+                "--flat-playlist",
+                "--playlist-start",
+                "11",
+                "--playlist-end",
+                "15",
+                "scsearch15:lofi beats"
+            ]
+        );
+
+        let full = query.with_flat(false).previous_page();
+        assert_eq!(full.page, 2);
+        assert!(!full.ytdlp_args().contains(&"--flat-playlist".to_string()));
+        assert_eq!(SearchQuery::new("x").previous_page().page, 1);
+    }
+
+    #[test]
+    fn providers_parse_by_name_or_prefix() {
+        assert_eq!("YouTube".parse(), Ok(SearchProvider::YouTube));
+        assert_eq!("scsearch".parse(), Ok(SearchProvider::SoundCloud));
+        assert_eq!("youtube-date".parse(), Ok(SearchProvider::YouTubeByDate));
+        let err = "vimeo".parse::<SearchProvider>().unwrap_err();
+        assert!(err.contains("soundcloud"), "{err}");
+    }
+
+    #[test]
+    fn clock_switches_to_hours_past_an_hour() {
+        assert_eq!(format_clock(59), "0:59");
+        assert_eq!(format_clock(212), "3:32");
+        assert_eq!(format_clock(3723), "1:02:03");
+    }
+
+    #[test]
+    fn details_skip_missing_parts() {
+        let mut info = VideoInfo {
+            uploader: Some("Rick Astley".to_string()),
+            duration: Some(212),
+            ..Default::default()
+        };
+        assert_eq!(result_details(&info), "Rick Astley · 3:32");
+        info.view_count = Some(42);
+        info.uploader = None;
+        assert_eq!(result_details(&info), "3:32 · 42 views");
+    }
+
+    #[test]
+    fn parses_every_line_of_flat_output() {
+        let results = parse_search_output(include_str!(
+            "../../tests/fixtures/search/ytsearch_flat.jsonl"
+        ));
+        assert_eq!(results.len(), 3);
+
+        let first = &results[0];
+        assert_eq!(first.id, "dQw4w9WgXcQ");
+        assert_eq!(first.url, "https://www.youtube.com/watch?v=dQw4w9WgXcQ");
+        assert_eq!(first.duration, Some(212));
+        assert_eq!(first.uploader.as_deref(), Some("Rick Astley"));
+        assert_eq!(
+            first.thumbnail.as_deref(),
+            Some("https://i.ytimg.com/vi/dQw4w9WgXcQ/hqdefault.jpg?sqp=large")
+        );
+        assert!(results[2].thumbnail.is_none());
+    }
+}
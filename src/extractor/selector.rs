@@ -0,0 +1,1019 @@
+//! yt-dlp-compatible format selection (`-f`) and sorting (`-S`), evaluated
+//! natively over [`Format`]s so that native downloads pick what the yt-dlp
+//! path would.
+//!
+//! Selectors support `best`/`worst` (`b`/`w`), `bestvideo`/`bestaudio`
+//! (`bv`/`ba`, with `bv*`/`ba*` for any format carrying video/audio) and their
+//! `worst` forms, file extensions (`mp4`, `m4a`, ...), exact format ids,
+//! `[key op value]` filters, `+` merges, `/` fallbacks and `( )` groups, e.g.
+//! `bv*[height<=1080][vcodec^=avc1]+ba[ext=m4a]/b[height<=1080]/b`.
+//!
+//! Sort keys are `res`, `fps`, `br`, `vbr`, `abr`, `size`, `ext`, `vcodec`,
+//! `acodec`, `hasvid` and `hasaud`; a `+` prefix prefers the smallest value and
+//! `key:value` sets a preferred value or limit (`res:1080`, `vcodec:h264`).
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use super::formats::{self, StreamKind};
+use super::models::Format;
+
+/// Extensions that select the best video+audio format in that container.
+const VIDEO_EXTS: [&str; 5] = ["mp4", "webm", "flv", "3gp", "mov"];
+/// Extensions that select the best audio-only format in that container.
+const AUDIO_EXTS: [&str; 7] = ["m4a", "mp3", "ogg", "aac", "wav", "opus", "flac"];
+/// Containers `-f` cannot select by name. yt-dlp would read them as format
+/// ids that no site uses, so `-f mkv` is rejected rather than silently
+/// matching nothing.
+const UNSELECTABLE_EXTS: [&str; 10] = [
+    "mkv", "avi", "wmv", "m4v", "mpg", "mpeg", "ts", "mka", "wma", "aiff",
+];
+
+/// A parsed `-f` expression. Displays as the text it was parsed from, which is
+/// what yt-dlp is given.
+#[derive(Debug, Clone)]
+pub struct FormatSelector {
+    source: String,
+    root: Node,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Pick(Pick),
+    /// The inner selector, evaluated over only the formats passing every filter.
+    Filtered(Box<Node>, Vec<Filter>),
+    Merge(Vec<Node>),
+    Fallback(Vec<Node>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Pick {
+    Best(Streams),
+    Worst(Streams),
+    Ext(String),
+    Id(String),
+}
+
+/// Which formats a `best`/`worst` pick considers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Streams {
+    Muxed,
+    VideoOnly,
+    AudioOnly,
+    WithVideo,
+    WithAudio,
+    Any,
+}
+
+impl Streams {
+    fn admits(self, format: &Format) -> bool {
+        let kind = format.kind();
+        match self {
+            Streams::Muxed => kind == StreamKind::Muxed,
+            Streams::VideoOnly => kind == StreamKind::VideoOnly,
+            Streams::AudioOnly => kind == StreamKind::AudioOnly,
+            Streams::WithVideo => kind != StreamKind::AudioOnly,
+            Streams::WithAudio => kind != StreamKind::VideoOnly,
+            Streams::Any => true,
+        }
+    }
+}
+
+impl Pick {
+    fn from_token(token: &str) -> Result<Self, String> {
+        let streams = match token.to_ascii_lowercase().as_str() {
+            "best" | "b" | "worst" | "w" => Streams::Muxed,
+            "best*" | "b*" | "worst*" | "w*" => Streams::Any,
+            "bestvideo" | "bv" | "worstvideo" | "wv" => Streams::VideoOnly,
+            "bestvideo*" | "bv*" | "worstvideo*" | "wv*" => Streams::WithVideo,
+            "bestaudio" | "ba" | "worstaudio" | "wa" => Streams::AudioOnly,
+            "bestaudio*" | "ba*" | "worstaudio*" | "wa*" => Streams::WithAudio,
+            lower if VIDEO_EXTS.contains(&lower) || AUDIO_EXTS.contains(&lower) => {
+                return Ok(Pick::Ext(lower.to_string()))
+            }
+            lower if UNSELECTABLE_EXTS.contains(&lower) => {
+                return Err(format!(
+                    "'{token}' cannot be selected by extension; filter with [ext={lower}] \
+                     or pick a container for the result (--container)"
+                ))
+            }
+            _ if token.contains('*') => return Err(format!("unknown format keyword '{token}'")),
+            _ => return Ok(Pick::Id(token.to_string())),
+        };
+        Ok(if token.starts_with(['w', 'W']) {
+            Pick::Worst(streams)
+        } else {
+            Pick::Best(streams)
+        })
+    }
+
+    fn pick<'a>(&self, formats: &[&'a Format], sort: &FormatSort) -> Option<&'a Format> {
+        let best = |admits: &dyn Fn(&Format) -> bool| {
+            formats
+                .iter()
+                .copied()
+                .filter(|f| admits(f))
+                .max_by(|a, b| sort.compare(a, b))
+        };
+        match self {
+            Pick::Best(streams) => best(&|f| streams.admits(f)),
+            Pick::Worst(streams) => formats
+                .iter()
+                .copied()
+                .filter(|f| streams.admits(f))
+                .min_by(|a, b| sort.compare(a, b)),
+            Pick::Ext(ext) => {
+                let streams = if AUDIO_EXTS.contains(&ext.as_str()) {
+                    Streams::AudioOnly
+                } else {
+                    Streams::Muxed
+                };
+                best(&|f| f.ext.eq_ignore_ascii_case(ext) && streams.admits(f))
+            }
+            Pick::Id(id) => formats.iter().copied().find(|f| f.format_id == *id),
+        }
+    }
+}
+
+/// `[key op value]`: numeric keys compare as numbers (sizes may carry a
+/// `K`/`M`/`G` suffix), string keys with `=`, `^=`, `$=`, `*=` or `~=`
+/// (regex), each negatable with `!`. A `?` after the operator also admits
+/// formats that lack the field.
+#[derive(Debug, Clone, PartialEq)]
+struct Filter {
+    key: String,
+    op: Op,
+    negate: bool,
+    optional: bool,
+    value: FilterValue,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    StartsWith,
+    EndsWith,
+    Contains,
+    Matches,
+}
+
+#[derive(Debug, Clone)]
+enum FilterValue {
+    Number(f64),
+    Text(String),
+    Regex(regex::Regex),
+}
+
+impl PartialEq for FilterValue {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (FilterValue::Number(a), FilterValue::Number(b)) => a == b,
+            (FilterValue::Text(a), FilterValue::Text(b)) => a == b,
+            (FilterValue::Regex(a), FilterValue::Regex(b)) => a.as_str() == b.as_str(),
+            _ => false,
+        }
+    }
+}
+
+const NUMERIC_KEYS: [&str; 7] = ["height", "width", "fps", "tbr", "vbr", "abr", "filesize"];
+const TEXT_KEYS: [&str; 8] = [
+    "ext",
+    "vcodec",
+    "acodec",
+    "format_id",
+    "format_note",
+    "resolution",
+    "language",
+    "dynamic_range",
+];
+
+fn numeric_field(format: &Format, key: &str) -> Option<f64> {
+    match key {
+        "height" => format.height.map(f64::from),
+        "width" => format.width.map(f64::from),
+        "fps" => format.fps.map(f64::from),
+        "tbr" => format.tbr.map(f64::from),
+        "vbr" => format.vbr.map(f64::from),
+        "abr" => format.abr.map(f64::from),
+        "filesize" => format.filesize.map(|s| s as f64),
+        _ => None,
+    }
+}
+
+fn text_field<'a>(format: &'a Format, key: &str) -> Option<&'a str> {
+    match key {
+        "ext" => Some(format.ext.as_str()).filter(|e| !e.is_empty()),
+        "vcodec" => format.vcodec.as_deref(),
+        "acodec" => format.acodec.as_deref(),
+        "format_id" => Some(format.format_id.as_str()),
+        "format_note" => format.format_note.as_deref(),
+        "resolution" => format.resolution.as_deref(),
+        "language" => format.language.as_deref(),
+        "dynamic_range" => format.dynamic_range.as_deref(),
+        _ => None,
+    }
+}
+
+/// `100`, `1.5M`, `500KiB`, `2g` → a number (binary multiples, like yt-dlp).
+fn parse_number(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let digits = value.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    let unit = value[digits.len()..].to_ascii_lowercase();
+    let scale = match unit.trim_end_matches('b').trim_end_matches('i') {
+        "" => 1.0,
+        "k" => 1024.0,
+        "m" => 1024.0 * 1024.0,
+        "g" => 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    digits.parse::<f64>().ok().map(|n| n * scale)
+}
+
+impl Filter {
+    fn admits(&self, format: &Format) -> bool {
+        let outcome = match &self.value {
+            FilterValue::Number(expected) => {
+                numeric_field(format, &self.key).map(|actual| match self.op {
+                    Op::Eq => actual == *expected,
+                    Op::Ne => actual != *expected,
+                    Op::Lt => actual < *expected,
+                    Op::Le => actual <= *expected,
+                    Op::Gt => actual > *expected,
+                    Op::Ge => actual >= *expected,
+                    _ => false,
+                })
+            }
+            FilterValue::Text(expected) => {
+                text_field(format, &self.key).map(|actual| match self.op {
+                    Op::Eq => actual == expected,
+                    Op::Ne => actual != expected,
+                    Op::StartsWith => actual.starts_with(expected.as_str()),
+                    Op::EndsWith => actual.ends_with(expected.as_str()),
+                    Op::Contains => actual.contains(expected.as_str()),
+                    _ => false,
+                })
+            }
+            FilterValue::Regex(re) => text_field(format, &self.key).map(|a| re.is_match(a)),
+        };
+        match outcome {
+            Some(matched) => matched != self.negate,
+            None => self.optional,
+        }
+    }
+}
+
+impl Node {
+    /// Every format id the selector names, in order.
+    fn ids<'a>(&'a self, out: &mut Vec<&'a str>) {
+        match self {
+            Node::Pick(Pick::Id(id)) => out.push(id),
+            Node::Pick(_) => {}
+            Node::Filtered(inner, _) => inner.ids(out),
+            Node::Merge(nodes) | Node::Fallback(nodes) => {
+                nodes.iter().for_each(|node| node.ids(out))
+            }
+        }
+    }
+
+    fn eval<'a>(&self, formats: &[&'a Format], sort: &FormatSort) -> Option<Vec<&'a Format>> {
+        match self {
+            Node::Pick(pick) => pick.pick(formats, sort).map(|f| vec![f]),
+            Node::Filtered(inner, filters) => {
+                let kept: Vec<&Format> = formats
+                    .iter()
+                    .copied()
+                    .filter(|f| filters.iter().all(|filter| filter.admits(f)))
+                    .collect();
+                inner.eval(&kept, sort)
+            }
+            Node::Merge(parts) => {
+                let mut picked = Vec::new();
+                for part in parts {
+                    picked.extend(part.eval(formats, sort)?);
+                }
+                Some(picked)
+            }
+            Node::Fallback(alternatives) => {
+                alternatives.iter().find_map(|alt| alt.eval(formats, sort))
+            }
+        }
+    }
+}
+
+impl FormatSelector {
+    /// What the native path downloads without a selector: it cannot merge
+    /// streams itself, so the best progressive format comes first.
+    pub fn native_default() -> Self {
+        "b/bv*/ba".parse().expect("the default selector parses")
+    }
+
+    /// The formats the selector picks from `formats` (several for a merge),
+    /// or `None` when nothing matches. Storyboards are never picked.
+    pub fn select<'a>(&self, formats: &'a [Format], sort: &FormatSort) -> Option<Vec<&'a Format>> {
+        let candidates: Vec<&Format> = formats.iter().filter(|f| !f.is_storyboard()).collect();
+        self.root.eval(&candidates, sort)
+    }
+
+    /// The format ids the selector names that none of `formats` has, which
+    /// usually means a typo rather than a fallback doing its job.
+    pub fn unknown_ids(&self, formats: &[Format]) -> Vec<&str> {
+        let mut ids = Vec::new();
+        self.root.ids(&mut ids);
+        ids.retain(|id| !formats.iter().any(|f| f.format_id == *id));
+        ids
+    }
+
+    /// [`select`](Self::select), with a merge folded into one format whose id
+    /// is the `+`-joined ids.
+    pub fn pick(&self, formats: &[Format], sort: &FormatSort) -> Option<Format> {
+        self.select(formats, sort)
+            .map(|picked| formats::merged(&picked))
+    }
+}
+
+impl fmt::Display for FormatSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl PartialEq for FormatSelector {
+    fn eq(&self, other: &Self) -> bool {
+        self.root == other.root
+    }
+}
+
+impl FromStr for FormatSelector {
+    type Err = String;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            chars: source.chars().collect(),
+            pos: 0,
+        };
+        let root = parser.fallback()?;
+        parser.skip_whitespace();
+        if let Some(c) = parser.peek() {
+            return Err(parser.error(&format!("unexpected '{c}'")));
+        }
+        Ok(Self {
+            source: source.trim().to_string(),
+            root,
+        })
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn error(&self, message: &str) -> String {
+        format!(
+            "invalid format selector at position {}: {message}",
+            self.pos + 1
+        )
+    }
+
+    fn fallback(&mut self) -> Result<Node, String> {
+        let mut alternatives = vec![self.merge()?];
+        while self.eat('/') {
+            alternatives.push(self.merge()?);
+        }
+        Ok(if alternatives.len() == 1 {
+            alternatives.remove(0)
+        } else {
+            Node::Fallback(alternatives)
+        })
+    }
+
+    fn merge(&mut self) -> Result<Node, String> {
+        let mut parts = vec![self.term()?];
+        while self.eat('+') {
+            parts.push(self.term()?);
+        }
+        Ok(if parts.len() == 1 {
+            parts.remove(0)
+        } else {
+            Node::Merge(parts)
+        })
+    }
+
+    fn term(&mut self) -> Result<Node, String> {
+        self.skip_whitespace();
+        let node = match self.peek() {
+            Some('(') => {
+                self.pos += 1;
+                let inner = self.fallback()?;
+                if !self.eat(')') {
+                    return Err(self.error("missing ')'"));
+                }
+                inner
+            }
+            // A bare filter applies to `best`.
+            Some('[') => Node::Pick(Pick::Best(Streams::Muxed)),
+            Some(',') => return Err(self.error("',' (several downloads) is not supported")),
+            Some(c) if !"/+)]".contains(c) => {
+                let start = self.pos;
+                Node::Pick(Pick::from_token(&self.token()).map_err(|e| {
+                    self.pos = start;
+                    self.error(&e)
+                })?)
+            }
+            Some(c) => return Err(self.error(&format!("expected a format, found '{c}'"))),
+            None => return Err(self.error("expected a format")),
+        };
+        let mut filters = Vec::new();
+        while self.eat('[') {
+            filters.push(self.filter()?);
+        }
+        Ok(if filters.is_empty() {
+            node
+        } else {
+            Node::Filtered(Box::new(node), filters)
+        })
+    }
+
+    fn token(&mut self) -> String {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| !c.is_whitespace() && !"/+[](),".contains(c))
+        {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    fn filter(&mut self) -> Result<Filter, String> {
+        self.skip_whitespace();
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            self.pos += 1;
+        }
+        let key: String = self.chars[start..self.pos].iter().collect();
+        let numeric = NUMERIC_KEYS.contains(&key.as_str());
+        if !numeric && !TEXT_KEYS.contains(&key.as_str()) {
+            return Err(self.error(&format!("unknown filter field '{key}'")));
+        }
+
+        self.skip_whitespace();
+        let negate =
+            !numeric && self.peek() == Some('!') && self.chars.get(self.pos + 1) != Some(&'=');
+        if negate {
+            self.pos += 1;
+        }
+        let rest: String = self.chars[self.pos..].iter().take(2).collect();
+        let (op, len) = match rest.as_str() {
+            "!=" => (Op::Ne, 2),
+            "<=" => (Op::Le, 2),
+            ">=" => (Op::Ge, 2),
+            "^=" => (Op::StartsWith, 2),
+            "$=" => (Op::EndsWith, 2),
+            "*=" => (Op::Contains, 2),
+            "~=" => (Op::Matches, 2),
+            s if s.starts_with('=') => (Op::Eq, 1),
+            s if s.starts_with('<') => (Op::Lt, 1),
+            s if s.starts_with('>') => (Op::Gt, 1),
+            _ => return Err(self.error(&format!("expected an operator after '{key}'"))),
+        };
+        self.pos += len;
+        let optional = self.peek() == Some('?');
+        if optional {
+            self.pos += 1;
+        }
+
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c != ']') {
+            self.pos += 1;
+        }
+        if self.peek() != Some(']') {
+            return Err(self.error("missing ']'"));
+        }
+        let raw: String = self.chars[start..self.pos].iter().collect();
+        self.pos += 1;
+        let raw = raw.trim();
+
+        let value = if numeric {
+            if !matches!(op, Op::Eq | Op::Ne | Op::Lt | Op::Le | Op::Gt | Op::Ge) {
+                return Err(self.error(&format!("'{key}' is compared as a number")));
+            }
+            FilterValue::Number(
+                parse_number(raw).ok_or_else(|| self.error(&format!("'{raw}' is not a number")))?,
+            )
+        } else {
+            match op {
+                Op::Lt | Op::Le | Op::Gt | Op::Ge => {
+                    return Err(self.error(&format!("'{key}' is compared as text")))
+                }
+                Op::Matches => FilterValue::Regex(
+                    regex::Regex::new(raw.trim_matches(['"', '\'']))
+                        .map_err(|e| self.error(&e.to_string()))?,
+                ),
+                _ => FilterValue::Text(raw.trim_matches(['"', '\'']).to_string()),
+            }
+        };
+        Ok(Filter {
+            key,
+            op,
+            negate,
+            optional,
+            value,
+        })
+    }
+}
+
+/// A parsed `-S` list. Its fields decide first; the defaults
+/// (`res,fps,br,size`) break the remaining ties.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FormatSort {
+    fields: Vec<SortField>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct SortField {
+    key: SortKey,
+    /// `+key`: smaller is better.
+    ascending: bool,
+    /// `key:value`: a limit for numbers, a preferred value otherwise.
+    preferred: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SortKey {
+    Res,
+    Fps,
+    Br,
+    Vbr,
+    Abr,
+    Size,
+    Ext,
+    Vcodec,
+    Acodec,
+    HasVideo,
+    HasAudio,
+    Hdr,
+}
+
+impl SortKey {
+    const ALL: [(&'static str, SortKey); 12] = [
+        ("res", SortKey::Res),
+        ("fps", SortKey::Fps),
+        ("br", SortKey::Br),
+        ("vbr", SortKey::Vbr),
+        ("abr", SortKey::Abr),
+        ("size", SortKey::Size),
+        ("ext", SortKey::Ext),
+        ("vcodec", SortKey::Vcodec),
+        ("acodec", SortKey::Acodec),
+        ("hasvid", SortKey::HasVideo),
+        ("hasaud", SortKey::HasAudio),
+        ("hdr", SortKey::Hdr),
+    ];
+
+    fn name(self) -> &'static str {
+        Self::ALL
+            .iter()
+            .find(|(_, key)| *key == self)
+            .map(|(name, _)| *name)
+            .unwrap_or_default()
+    }
+
+    fn is_numeric(self) -> bool {
+        matches!(
+            self,
+            SortKey::Res | SortKey::Fps | SortKey::Br | SortKey::Vbr | SortKey::Abr | SortKey::Size
+        )
+    }
+
+    fn number(self, format: &Format) -> Option<f64> {
+        match self {
+            SortKey::Res => format.height.or(format.width).map(f64::from),
+            SortKey::Fps => format.fps.map(f64::from),
+            SortKey::Br => format.tbr.map(f64::from),
+            SortKey::Vbr => format.vbr.map(f64::from),
+            SortKey::Abr => format.abr.map(f64::from),
+            SortKey::Size => format.filesize.map(|s| s as f64),
+            _ => None,
+        }
+    }
+}
+
+/// Video codecs, best first.
+const VCODEC_RANK: [&str; 6] = ["av1", "vp9", "h265", "h264", "vp8", "h263"];
+/// Audio codecs, best first.
+const ACODEC_RANK: [&str; 8] = ["flac", "opus", "vorbis", "aac", "mp3", "eac3", "ac3", "dts"];
+/// Containers, best first.
+const EXT_RANK: [&str; 9] = [
+    "mp4", "mov", "webm", "flv", "m4a", "aac", "mp3", "ogg", "opus",
+];
+
+/// Dynamic ranges, best first, as yt-dlp orders them.
+const HDR_RANK: [&str; 6] = ["dv", "hdr12", "hdr10+", "hdr10", "hlg", "sdr"];
+
+/// The codec family of a codec string: `avc1.640028` → `h264`,
+/// `mp4a.40.2` → `aac`, `vp09.00.40.08` → `vp9`.
+pub fn codec_family(codec: &str) -> &str {
+    let codec = codec.trim();
+    let lower = codec.to_ascii_lowercase();
+    let base = lower.split('.').next().unwrap_or_default();
+    match base {
+        "avc1" | "avc3" | "h264" | "avc" => "h264",
+        "hev1" | "hvc1" | "h265" | "hevc" => "h265",
+        "vp09" | "vp9" => "vp9",
+        "av01" | "av1" => "av1",
+        "vp08" | "vp8" => "vp8",
+        "mp4a" | "aac" => "aac",
+        "ec-3" | "eac3" => "eac3",
+        "ac-3" | "ac3" => "ac3",
+        "mp3" | "mp4a.6b" => "mp3",
+        "opus" => "opus",
+        "vorbis" => "vorbis",
+        "flac" => "flac",
+        "dts" | "dtsc" => "dts",
+        _ => codec.split('.').next().unwrap_or(codec),
+    }
+}
+
+/// Higher is better: the preferred value, then the table's order, then
+/// anything unknown.
+fn rank(value: Option<&str>, table: &[&str], preferred: Option<&str>) -> f64 {
+    let Some(value) = value else {
+        return f64::NEG_INFINITY;
+    };
+    if preferred.is_some_and(|p| p.eq_ignore_ascii_case(value)) {
+        return table.len() as f64 + 1.0;
+    }
+    table
+        .iter()
+        .position(|entry| entry.eq_ignore_ascii_case(value))
+        .map_or(0.0, |i| (table.len() - i) as f64)
+}
+
+impl SortField {
+    /// Higher is better.
+    fn score(&self, format: &Format) -> f64 {
+        let preferred = self.preferred.as_deref();
+        let score = match self.key {
+            // `ext:mp4:m4a` prefers mp4 video and m4a audio.
+            SortKey::Ext => {
+                let (video, audio) = match preferred.and_then(|p| p.split_once(':')) {
+                    Some((video, audio)) => (Some(video), Some(audio)),
+                    None => (preferred, preferred),
+                };
+                rank(
+                    Some(format.ext.as_str()).filter(|e| !e.is_empty()),
+                    &EXT_RANK,
+                    if format.kind() == StreamKind::AudioOnly {
+                        audio
+                    } else {
+                        video
+                    },
+                )
+            }
+            SortKey::Vcodec => rank(
+                format
+                    .vcodec
+                    .as_deref()
+                    .filter(|c| *c != "none")
+                    .map(codec_family),
+                &VCODEC_RANK,
+                preferred.map(codec_family),
+            ),
+            SortKey::Acodec => rank(
+                format
+                    .acodec
+                    .as_deref()
+                    .filter(|c| *c != "none")
+                    .map(codec_family),
+                &ACODEC_RANK,
+                preferred.map(codec_family),
+            ),
+            SortKey::HasVideo => (format.kind() != StreamKind::AudioOnly) as u8 as f64,
+            SortKey::HasAudio => (format.kind() != StreamKind::VideoOnly) as u8 as f64,
+            SortKey::Hdr => rank(format.dynamic_range.as_deref(), &HDR_RANK, preferred),
+            numeric => {
+                let Some(value) = numeric.number(format) else {
+                    return f64::NEG_INFINITY;
+                };
+                let limit = preferred.and_then(parse_number);
+                return match (limit, self.ascending) {
+                    // Largest within the limit, then the closest above it.
+                    (Some(limit), false) if value > limit => -value,
+                    // Smallest at or above the limit, then the closest below.
+                    (Some(limit), true) if value < limit => value - 1e15,
+                    (_, true) => -value,
+                    (_, false) => value,
+                };
+            }
+        };
+        if self.ascending && score.is_finite() {
+            -score
+        } else {
+            score
+        }
+    }
+}
+
+impl FormatSort {
+    fn defaults() -> [SortField; 4] {
+        [SortKey::Res, SortKey::Fps, SortKey::Br, SortKey::Size].map(|key| SortField {
+            key,
+            ascending: false,
+            preferred: None,
+        })
+    }
+
+    /// `Greater` when `a` is the better format.
+    pub fn compare(&self, a: &Format, b: &Format) -> Ordering {
+        self.fields
+            .iter()
+            .chain(Self::defaults().iter())
+            .map(|field| field.score(a).total_cmp(&field.score(b)))
+            .find(|order| order.is_ne())
+            .unwrap_or(Ordering::Equal)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// This sort, then `other`'s fields for keys it does not already use.
+    pub fn then(&self, other: &FormatSort) -> FormatSort {
+        let mut fields = self.fields.clone();
+        for field in &other.fields {
+            if !fields.iter().any(|f| f.key == field.key) {
+                fields.push(field.clone());
+            }
+        }
+        FormatSort { fields }
+    }
+}
+
+impl fmt::Display for FormatSort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fields: Vec<String> = self
+            .fields
+            .iter()
+            .map(|field| {
+                let mut text = String::new();
+                if field.ascending {
+                    text.push('+');
+                }
+                text.push_str(field.key.name());
+                if let Some(preferred) = &field.preferred {
+                    text.push(':');
+                    text.push_str(preferred);
+                }
+                text
+            })
+            .collect();
+        f.write_str(&fields.join(","))
+    }
+}
+
+impl FromStr for FormatSort {
+    type Err = String;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let mut fields = Vec::new();
+        for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (ascending, part) = match part.strip_prefix('+') {
+                Some(rest) => (true, rest),
+                None => (false, part),
+            };
+            let (name, preferred) = match part.split_once(':') {
+                Some((name, value)) => (name, Some(value.trim().to_string())),
+                None => (part, None),
+            };
+            let key = SortKey::ALL
+                .iter()
+                .find(|(known, _)| known.eq_ignore_ascii_case(name.trim()))
+                .map(|(_, key)| *key)
+                .ok_or_else(|| {
+                    let known: Vec<_> = SortKey::ALL.iter().map(|(n, _)| *n).collect();
+                    format!("unknown sort field '{name}' (one of {})", known.join(", "))
+                })?;
+            if let Some(value) = preferred.as_deref().filter(|_| key.is_numeric()) {
+                if parse_number(value).is_none() {
+                    return Err(format!("'{value}' is not a limit for '{name}'"));
+                }
+            }
+            fields.push(SortField {
+                key,
+                ascending,
+                preferred,
+            });
+        }
+        Ok(Self { fields })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(id: &str, ext: &str, vcodec: &str, acodec: &str, height: Option<u32>) -> Format {
+        Format {
+            format_id: id.to_string(),
+            ext: ext.to_string(),
+            vcodec: Some(vcodec.to_string()),
+            acodec: Some(acodec.to_string()),
+            height,
+            width: height.map(|h| h * 16 / 9),
+            ..Default::default()
+        }
+    }
+
+    /// A YouTube-like list, worst to best.
+    fn formats() -> Vec<Format> {
+        vec![
+            Format {
+                format_note: Some("storyboard".to_string()),
+                ..format("sb0", "mhtml", "none", "none", Some(90))
+            },
+            Format {
+                abr: Some(48.0),
+                tbr: Some(48.0),
+                ..format("139", "m4a", "none", "mp4a.40.5", None)
+            },
+            Format {
+                abr: Some(129.0),
+                tbr: Some(129.0),
+                ..format("140", "m4a", "none", "mp4a.40.2", None)
+            },
+            Format {
+                abr: Some(135.0),
+                tbr: Some(135.0),
+                ..format("251", "webm", "none", "opus", None)
+            },
+            format("18", "mp4", "avc1.42001E", "mp4a.40.2", Some(360)),
+            format("22", "mp4", "avc1.64001F", "mp4a.40.2", Some(720)),
+            Format {
+                tbr: Some(1200.0),
+                filesize: Some(40 * 1024 * 1024),
+                ..format("136", "mp4", "avc1.4d401f", "none", Some(720))
+            },
+            Format {
+                tbr: Some(1000.0),
+                filesize: Some(30 * 1024 * 1024),
+                ..format("247", "webm", "vp9", "none", Some(720))
+            },
+            Format {
+                fps: Some(30.0),
+                ..format("137", "mp4", "avc1.640028", "none", Some(1080))
+            },
+            Format {
+                fps: Some(60.0),
+                ..format("399", "mp4", "av01.0.08M.08", "none", Some(1080))
+            },
+            format("313", "webm", "vp9", "none", Some(2160)),
+        ]
+    }
+
+    fn pick(selector: &str) -> Option<String> {
+        pick_sorted(selector, "")
+    }
+
+    fn pick_sorted(selector: &str, sort: &str) -> Option<String> {
+        let selector: FormatSelector = selector.parse().unwrap();
+        selector
+            .pick(&formats(), &sort.parse().unwrap())
+            .map(|f| f.format_id)
+    }
+
+    #[test]
+    fn keywords_pick_the_best_and_worst_of_each_kind() {
+        assert_eq!(pick("best").as_deref(), Some("22"));
+        assert_eq!(pick("worst").as_deref(), Some("18"));
+        assert_eq!(pick("bv").as_deref(), Some("313"));
+        assert_eq!(pick("wv").as_deref(), Some("247"));
+        assert_eq!(pick("ba").as_deref(), Some("251"));
+        assert_eq!(pick("wa").as_deref(), Some("139"));
+        assert_eq!(pick("bv*").as_deref(), Some("313"));
+        assert_eq!(pick("m4a").as_deref(), Some("140"));
+        assert_eq!(pick("mp4").as_deref(), Some("22"));
+        assert_eq!(pick("137").as_deref(), Some("137"));
+        assert_eq!(pick("sb0"), None, "storyboards are never picked");
+    }
+
+    #[test]
+    fn filters_merges_and_fallbacks_compose() {
+        assert_eq!(
+            pick("bv[height<=1080][fps>30]+ba[ext=m4a]").as_deref(),
+            Some("399+140")
+        );
+        assert_eq!(
+            pick("bv*[vcodec^=avc1][height<=?1080]+ba/b").as_deref(),
+            Some("137+251")
+        );
+        assert_eq!(
+            pick("bv[vcodec!^=avc1][vcodec!^=av01]").as_deref(),
+            Some("313")
+        );
+        assert_eq!(
+            pick("(bv[height>4000]/bv[height=720])+ba").as_deref(),
+            Some("136+251")
+        );
+        assert_eq!(pick("bv[filesize<35M]").as_deref(), Some("247"));
+        assert_eq!(pick("bv[vcodec~='^(avc1|vp9)']").as_deref(), Some("313"));
+        assert_eq!(pick("[height<=480]").as_deref(), Some("18"));
+        // Missing fields fail a filter unless it says `?`.
+        assert_eq!(pick("ba[height<=720]"), None);
+        assert_eq!(pick("ba[height<=?720]").as_deref(), Some("251"));
+        assert_eq!(pick("bv[height>4000]/nope/ba").as_deref(), Some("251"));
+        assert_eq!(pick("bv[height>4000]"), None);
+    }
+
+    #[test]
+    fn sort_keys_reorder_the_choice() {
+        assert_eq!(pick_sorted("bv", "res:1080").as_deref(), Some("399"));
+        assert_eq!(pick_sorted("bv", "res:1080,+fps").as_deref(), Some("137"));
+        assert_eq!(pick_sorted("bv", "vcodec:h264").as_deref(), Some("137"));
+        assert_eq!(pick_sorted("bv", "+res").as_deref(), Some("136"));
+        assert_eq!(
+            pick_sorted("bv[height=720]", "+size").as_deref(),
+            Some("247")
+        );
+        assert_eq!(pick_sorted("ba", "acodec:aac").as_deref(), Some("140"));
+        assert_eq!(pick_sorted("ba", "ext:m4a").as_deref(), Some("140"));
+
+        let sort: FormatSort = " +size, res:720 ,vcodec:avc1 ".parse().unwrap();
+        assert_eq!(sort.to_string(), "+size,res:720,vcodec:avc1");
+        assert!("loudness".parse::<FormatSort>().is_err());
+        assert!("res:tall".parse::<FormatSort>().is_err());
+    }
+
+    #[test]
+    fn malformed_selectors_are_rejected() {
+        for bad in [
+            "",
+            "bv+",
+            "best[height<<720]",
+            "best[height<=tall]",
+            "best[colour=red]",
+            "best[ext>mp4]",
+            "(bv/ba",
+            "bv[height<=720",
+            "bv,ba",
+            "bv[vcodec~=(]",
+            "avi",
+            "mkv",
+            "bv*+MKV",
+            "bx*",
+        ] {
+            assert!(bad.parse::<FormatSelector>().is_err(), "{bad:?} parsed");
+        }
+        let err = "mkv".parse::<FormatSelector>().unwrap_err();
+        assert!(err.contains("[ext=mkv]"), "{err}");
+        assert!("bv[ext=mkv]/b".parse::<FormatSelector>().is_ok());
+
+        let typo: FormatSelector = "1377+140/nope/b".parse().unwrap();
+        assert_eq!(typo.unknown_ids(&formats()), ["1377", "nope"]);
+        let selector: FormatSelector = " bv*+ba/b ".parse().unwrap();
+        assert_eq!(selector.to_string(), "bv*+ba/b");
+    }
+
+    #[test]
+    fn native_default_prefers_progressive_formats() {
+        let sort = FormatSort::default();
+        let default = FormatSelector::native_default();
+        assert_eq!(default.pick(&formats(), &sort).unwrap().format_id, "22");
+        let split: Vec<Format> = formats()
+            .into_iter()
+            .filter(|f| f.kind() != StreamKind::Muxed)
+            .collect();
+        assert_eq!(default.pick(&split, &sort).unwrap().format_id, "313");
+        assert!(default.pick(&[], &sort).is_none());
+    }
+}
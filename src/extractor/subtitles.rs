@@ -0,0 +1,230 @@
+//! Subtitle tracks: the ones extraction found (`--list-subs`, the GUI
+//! format picker) and which of them to download, in what format, embedded
+//! or as sidecar files.
+
+use super::models::VideoInfo;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// A subtitle track of a video.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubtitleTrack {
+    pub language: String,
+    pub name: Option<String>,
+    /// Auto-generated captions rather than uploaded subtitles.
+    pub automatic: bool,
+    /// The formats the site serves it in (`vtt`, `srv3`, ...).
+    pub exts: Vec<String>,
+}
+
+/// Every track of `info`: uploaded subtitles first, then auto-generated
+/// captions, each by language. yt-dlp's `live_chat` replay is not a
+/// subtitle and is left out.
+pub fn subtitle_tracks(info: &VideoInfo) -> Vec<SubtitleTrack> {
+    let uploaded = info.subtitles.iter().map(|track| (track, false));
+    let automatic = info.automatic_captions.iter().map(|track| (track, true));
+    uploaded
+        .chain(automatic)
+        .filter(|((language, _), _)| language.as_str() != "live_chat")
+        .map(|((language, files), automatic)| SubtitleTrack {
+            language: language.clone(),
+            name: files.iter().find_map(|f| f.name.clone()),
+            automatic,
+            exts: files.iter().map(|f| f.ext.clone()).collect(),
+        })
+        .collect()
+}
+
+/// One line for the GUI: the uploaded languages and the auto-generated
+/// ones (counted when there are many). `None` without any track.
+pub fn summary(info: &VideoInfo) -> Option<String> {
+    let tracks = subtitle_tracks(info);
+    let languages = |automatic: bool| -> Vec<&str> {
+        tracks
+            .iter()
+            .filter(|t| t.automatic == automatic)
+            .map(|t| t.language.as_str())
+            .collect()
+    };
+    let mut parts = Vec::new();
+    let uploaded = languages(false);
+    if !uploaded.is_empty() {
+        parts.push(format!("Subtitles: {}", uploaded.join(", ")));
+    }
+    let automatic = languages(true);
+    match automatic.len() {
+        0 => {}
+        1..=5 => parts.push(format!("Auto-generated: {}", automatic.join(", "))),
+        n => parts.push(format!("Auto-generated: {n} languages")),
+    }
+    (!parts.is_empty()).then(|| parts.join(" · "))
+}
+
+/// The `--list-subs` table.
+pub fn subtitle_table(info: &VideoInfo) -> String {
+    let mut table = format!("{:<12} {:<15} {:<24} FORMATS\n", "LANGUAGE", "KIND", "NAME");
+    for track in subtitle_tracks(info) {
+        table.push_str(&format!(
+            "{:<12} {:<15} {:<24} {}\n",
+            track.language,
+            if track.automatic {
+                "auto-generated"
+            } else {
+                "subtitles"
+            },
+            track.name.unwrap_or_default(),
+            track.exts.join(", ")
+        ));
+    }
+    table
+}
+
+/// Format subtitles are converted to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SubtitleFormat {
+    Srt,
+    Vtt,
+    Ass,
+}
+
+impl SubtitleFormat {
+    pub const ALL: [SubtitleFormat; 3] = [
+        SubtitleFormat::Srt,
+        SubtitleFormat::Vtt,
+        SubtitleFormat::Ass,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SubtitleFormat::Srt => "srt",
+            SubtitleFormat::Vtt => "vtt",
+            SubtitleFormat::Ass => "ass",
+        }
+    }
+}
+
+impl fmt::Display for SubtitleFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SubtitleFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        SubtitleFormat::ALL
+            .into_iter()
+            .find(|f| f.as_str().eq_ignore_ascii_case(value.trim()))
+            .ok_or_else(|| format!("unknown subtitle format '{value}' (one of srt, vtt, ass)"))
+    }
+}
+
+/// Which subtitle tracks to download and how.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SubtitleOptions {
+    /// Languages to download, as yt-dlp patterns (`en`, `es.*`); empty =
+    /// every language.
+    pub languages: Vec<String>,
+    /// Also download auto-generated captions.
+    pub auto_generated: bool,
+    /// Convert to this format; `None` keeps what the site serves.
+    pub format: Option<SubtitleFormat>,
+    /// Embed the tracks into the media file instead of writing sidecars.
+    pub embed: bool,
+}
+
+impl SubtitleOptions {
+    pub fn with_languages(mut self, languages: Vec<String>) -> Self {
+        self.languages = languages;
+        self
+    }
+
+    pub fn with_auto_generated(mut self, auto_generated: bool) -> Self {
+        self.auto_generated = auto_generated;
+        self
+    }
+
+    pub fn with_format(mut self, format: SubtitleFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    pub fn with_embed(mut self, embed: bool) -> Self {
+        self.embed = embed;
+        self
+    }
+
+    /// The `--sub-langs` value.
+    pub fn sub_langs(&self) -> String {
+        if self.languages.is_empty() {
+            "all".to_string()
+        } else {
+            self.languages.join(",")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extractor::models::SubtitleFile;
+
+    fn file(ext: &str, name: &str) -> SubtitleFile {
+        SubtitleFile {
+            ext: ext.to_string(),
+            url: format!("https://example.com/subs.{ext}"),
+            name: Some(name.to_string()),
+        }
+    }
+
+    #[test]
+    fn tracks_list_uploaded_subtitles_before_captions() {
+        let mut info = VideoInfo::default();
+        info.subtitles.insert(
+            "es".to_string(),
+            vec![file("vtt", "Spanish"), file("srv3", "Spanish")],
+        );
+        info.subtitles
+            .insert("live_chat".to_string(), vec![file("json", "Chat")]);
+        info.automatic_captions.insert(
+            "en".to_string(),
+            vec![file("vtt", "English (auto-generated)")],
+        );
+
+        let tracks = subtitle_tracks(&info);
+        assert_eq!(tracks.len(), 2);
+        assert_eq!(tracks[0].language, "es");
+        assert!(!tracks[0].automatic);
+        assert_eq!(tracks[0].exts, ["vtt", "srv3"]);
+        assert_eq!(tracks[1].language, "en");
+        assert!(tracks[1].automatic);
+
+        assert_eq!(
+            summary(&info).as_deref(),
+            Some("Subtitles: es · Auto-generated: en")
+        );
+        assert_eq!(summary(&VideoInfo::default()), None);
+
+        let table = subtitle_table(&info);
+        assert_eq!(table.lines().count(), 3);
+        assert!(table.lines().nth(2).unwrap().contains("auto-generated"));
+    }
+
+    #[test]
+    fn options_choose_languages_and_format() {
+        assert_eq!(SubtitleOptions::default().sub_langs(), "all");
+        let options = SubtitleOptions::default()
+            .with_languages(vec!["en".to_string(), "es.*".to_string()])
+            .with_format(SubtitleFormat::Srt);
+        assert_eq!(options.sub_langs(), "en,es.*");
+        assert_eq!(
+            "ASS".parse::<SubtitleFormat>().unwrap(),
+            SubtitleFormat::Ass
+        );
+        assert!("sub".parse::<SubtitleFormat>().is_err());
+    }
+}
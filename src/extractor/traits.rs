@@ -1,6 +1,10 @@
+use crate::extractor::batch::BatchItem;
 use crate::extractor::models::{Format, VideoInfo};
+use crate::extractor::search::{SearchPage, SearchQuery};
+use crate::utils::RustloaderError;
 use anyhow::Result;
 use async_trait::async_trait;
+use tokio::sync::mpsc;
 
 /// Core trait for all video extractors
 ///
@@ -8,8 +12,9 @@ use async_trait::async_trait;
 /// (Native, yt-dlp, WASM plugin, etc.).
 #[async_trait]
 pub trait Extractor: Send + Sync {
-    /// Returns a unique identifier for this extractor (e.g., "native-youtube", "ytdlp-fallback")
-    fn id(&self) -> &'static str;
+    /// Returns a unique identifier for this extractor (e.g., "native-youtube", "ytdlp-fallback",
+    /// or a site rule's `id`)
+    fn id(&self) -> &str;
 
     /// Checks if this extractor can handle the given URL
     ///
@@ -19,13 +24,27 @@ pub trait Extractor: Send + Sync {
     /// Extracts video information
     async fn extract_info(&self, url: &str) -> Result<VideoInfo>;
 
+    /// Extracts many URLs, sending each one's result on `results` as soon as
+    /// it is ready. Stops early if the receiver goes away. The default
+    /// extracts them one at a time; extractors with a cheaper bulk path
+    /// override it.
+    async fn extract_batch(&self, urls: &[String], results: mpsc::Sender<BatchItem>) {
+        for url in urls {
+            let result = self.extract_info(url).await;
+            if results.send((url.clone(), result)).await.is_err() {
+                break;
+            }
+        }
+    }
+
     /// Extracts playlist information (optional, default implementation returns an error)
     async fn extract_playlist(&self, _url: &str) -> Result<Vec<VideoInfo>> {
         // Default implementation for extractors that don't support playlists
-        Err(anyhow::anyhow!(
+        Err(RustloaderError::NotSupported(format!(
             "Playlist extraction not supported by {}",
             self.id()
         ))
+        .into())
     }
 
     /// Gets available formats (usually calls extract_info internally)
@@ -36,4 +55,9 @@ pub trait Extractor: Send + Sync {
 
     /// Resolves the direct download URL for a specific format
     async fn get_direct_url(&self, url: &str, format_id: &str) -> Result<String>;
+
+    /// Searches a site for videos (optional, default implementation returns an error)
+    async fn search(&self, _query: &SearchQuery) -> Result<SearchPage> {
+        Err(RustloaderError::NotSupported(format!("Search not supported by {}", self.id())).into())
+    }
 }
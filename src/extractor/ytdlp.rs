@@ -3,15 +3,20 @@
 //! This module handles video information extraction using yt-dlp.
 //! It supports both bundled yt-dlp (in macOS .app bundles) and system-installed yt-dlp.
 
+use crate::extractor::batch::BatchItem;
 use crate::extractor::models::{Format, VideoInfo};
+use crate::extractor::search::{parse_search_output, SearchPage, SearchQuery};
 use crate::extractor::traits::Extractor;
 use crate::utils::error::RustloaderError;
-use anyhow::Result;
+use anyhow::{Context, Result};
 use async_trait::async_trait;
+use futures::StreamExt;
 use serde_json;
 use std::path::PathBuf;
-use std::process::Command;
+use std::process::{Command, Stdio};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::process::Command as AsyncCommand;
+use tokio::sync::mpsc;
 use tracing::{debug, error, info, warn};
 
 /// Upper bound for a single yt-dlp extraction subprocess. Generous so slow but
@@ -20,6 +25,14 @@ use tracing::{debug, error, info, warn};
 /// constant for now; could be made configurable later.
 const EXTRACTION_TIMEOUT: tokio::time::Duration = tokio::time::Duration::from_secs(60);
 
+/// URLs per yt-dlp process in a batch extraction. Small enough that a slow
+/// URL holds up only a few others and the workers stay evenly loaded.
+const BATCH_CHUNK_SIZE: usize = 25;
+
+/// yt-dlp processes a batch extraction runs at once, unless overridden with
+/// [`YtDlpExtractor::with_batch_workers`].
+pub const DEFAULT_BATCH_WORKERS: usize = 4;
+
 /// Run a prepared yt-dlp command and capture its output, bounded by
 /// [`EXTRACTION_TIMEOUT`]. See [`run_bounded`] for the mechanism.
 async fn run_ytdlp_bounded(cmd: AsyncCommand) -> Result<std::process::Output> {
@@ -58,15 +71,17 @@ async fn run_bounded(
 pub struct YtDlpExtractor {
     ytdlp_path: PathBuf,
     cookies: crate::utils::CookieConfig,
+    batch_workers: usize,
 }
 
 impl YtDlpExtractor {
     /// Initialize extractor and verify yt-dlp availability
     ///
     /// Search order:
-    /// 1. Bundled yt-dlp (inside .app bundle for macOS)
-    /// 2. System PATH
-    /// 3. Common installation paths (Homebrew, etc.)
+    /// 1. Managed yt-dlp (installed by Rustloader into its data dir)
+    /// 2. Bundled yt-dlp (inside .app bundle for macOS)
+    /// 3. System PATH
+    /// 4. Common installation paths (Homebrew, etc.)
     pub fn new() -> Result<Self> {
         let ytdlp_path = match find_ytdlp() {
             Some(path) => {
@@ -82,6 +97,7 @@ impl YtDlpExtractor {
         Ok(Self {
             ytdlp_path,
             cookies: crate::utils::CookieConfig::default(),
+            batch_workers: DEFAULT_BATCH_WORKERS,
         })
     }
 
@@ -93,6 +109,12 @@ impl YtDlpExtractor {
         self
     }
 
+    /// Set how many yt-dlp processes a batch extraction may run at once.
+    pub fn with_batch_workers(mut self, workers: usize) -> Self {
+        self.batch_workers = workers.max(1);
+        self
+    }
+
     /// Extract video information without downloading
     /// Uses: yt-dlp --dump-json --no-download
     pub async fn extract_info_impl(&self, url: &str) -> Result<VideoInfo> {
@@ -102,6 +124,9 @@ impl YtDlpExtractor {
         cmd.args(self.cookies.to_args())
             .arg("--dump-json")
             .arg("--no-download")
+            // Upcoming streams have no formats yet; list them anyway so they
+            // can be recorded once they start.
+            .arg("--ignore-no-formats-error")
             .arg("--no-warnings")
             .arg(url);
         let output = run_ytdlp_bounded(cmd).await?;
@@ -113,10 +138,135 @@ impl YtDlpExtractor {
         }
 
         let json_str = String::from_utf8(output.stdout)?;
-        let mut video_info: VideoInfo = serde_json::from_str(&json_str)?;
-        video_info.normalize_url();
+        parse_info(&json_str, url)
+    }
+
+    /// Extract many URLs with as few yt-dlp processes as possible, sending
+    /// each URL's result as soon as its line is printed. The URLs are split
+    /// into chunks of [`BATCH_CHUNK_SIZE`], each fed to one process; at most
+    /// `batch_workers` processes run at a time.
+    /// Uses: yt-dlp --batch-file - --dump-json --no-download --ignore-errors
+    pub async fn extract_batch_impl(&self, urls: &[String], results: mpsc::Sender<BatchItem>) {
+        debug!(
+            "Batch extracting {} URLs with up to {} yt-dlp processes",
+            urls.len(),
+            self.batch_workers
+        );
+        futures::stream::iter(urls.chunks(BATCH_CHUNK_SIZE))
+            .for_each_concurrent(self.batch_workers, |chunk| {
+                let results = results.clone();
+                async move {
+                    // Nobody is listening any more; don't start another process.
+                    if results.is_closed() {
+                        return;
+                    }
+                    self.run_batch_chunk(chunk.to_vec(), &results).await;
+                }
+            })
+            .await;
+    }
+
+    /// Extract one chunk of a batch with a single yt-dlp process. Every URL
+    /// gets a result: the ones the process never answered fail with the
+    /// reason it stopped.
+    async fn run_batch_chunk(&self, urls: Vec<String>, results: &mpsc::Sender<BatchItem>) {
+        let mut tracker = BatchTracker::new(urls.clone());
+        let reason = match self.stream_batch(&urls, &mut tracker, results).await {
+            Ok(true) => "yt-dlp printed nothing for this URL".to_string(),
+            Ok(false) => return,
+            Err(e) => {
+                error!("yt-dlp batch extraction failed: {}", e);
+                e.to_string()
+            }
+        };
+        for item in tracker.finish(&reason) {
+            if results.send(item).await.is_err() {
+                return;
+            }
+        }
+    }
+
+    /// Run yt-dlp over `urls` and send each result the tracker can attribute
+    /// as it arrives. Returns `Ok(false)` once the receiver has gone away.
+    /// Unlike single extractions there is no bound on the whole run, only on
+    /// the wait for each line: a process that goes quiet for
+    /// [`EXTRACTION_TIMEOUT`] is killed.
+    async fn stream_batch(
+        &self,
+        urls: &[String],
+        tracker: &mut BatchTracker,
+        results: &mpsc::Sender<BatchItem>,
+    ) -> Result<bool> {
+        let mut cmd = AsyncCommand::new(&self.ytdlp_path);
+        cmd.args(self.cookies.to_args())
+            .arg("--batch-file")
+            .arg("-")
+            .arg("--dump-json")
+            .arg("--no-download")
+            .arg("--ignore-no-formats-error")
+            // Keep going past a failed URL; its error goes to stderr.
+            .arg("--ignore-errors")
+            // One line per URL, even for a video URL that names a playlist.
+            .arg("--no-playlist")
+            .arg("--no-warnings")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        let mut child = cmd.spawn().context("Failed to start yt-dlp")?;
+
+        let mut stdin = child.stdin.take().context("yt-dlp stdin unavailable")?;
+        let list = format!("{}\n", urls.join("\n"));
+        // Written from its own task: yt-dlp may already be printing results
+        // while a long list is still going in. Dropping stdin ends the list.
+        let writer = tokio::spawn(async move { stdin.write_all(list.as_bytes()).await });
+
+        let mut stdout =
+            BufReader::new(child.stdout.take().context("yt-dlp stdout unavailable")?).lines();
+        let mut stderr =
+            BufReader::new(child.stderr.take().context("yt-dlp stderr unavailable")?).lines();
+        let (mut stdout_open, mut stderr_open) = (true, true);
+        while stdout_open || stderr_open {
+            let next = tokio::time::timeout(EXTRACTION_TIMEOUT, async {
+                tokio::select! {
+                    line = stdout.next_line(), if stdout_open => (true, line),
+                    line = stderr.next_line(), if stderr_open => (false, line),
+                }
+            })
+            .await;
+            let Ok((from_stdout, line)) = next else {
+                warn!(
+                    "yt-dlp batch extraction was silent for {}s — killing the subprocess",
+                    EXTRACTION_TIMEOUT.as_secs()
+                );
+                return Err(anyhow::anyhow!(
+                    "yt-dlp batch extraction stalled for {}s (subprocess killed)",
+                    EXTRACTION_TIMEOUT.as_secs()
+                ));
+            };
+            let item = match (from_stdout, line?) {
+                (true, Some(line)) => tracker.json_line(&line),
+                (false, Some(line)) => tracker.error_line(&line),
+                (true, None) => {
+                    stdout_open = false;
+                    None
+                }
+                (false, None) => {
+                    stderr_open = false;
+                    None
+                }
+            };
+            if let Some(item) = item {
+                if results.send(item).await.is_err() {
+                    return Ok(false);
+                }
+            }
+        }
 
-        Ok(video_info)
+        let _ = writer.await;
+        let status = child.wait().await?;
+        debug!("yt-dlp batch of {} URLs exited with {}", urls.len(), status);
+        Ok(true)
     }
 
     /// Extract playlist information
@@ -138,28 +288,9 @@ impl YtDlpExtractor {
             return Err(RustloaderError::ExtractionError(error_msg.to_string()).into());
         }
 
+        // Flat playlist lines have the same shape as flat search hits.
         let json_str = String::from_utf8(output.stdout)?;
-        let lines = json_str.lines();
-        let mut videos = Vec::new();
-
-        for line in lines {
-            if line.trim().is_empty() {
-                continue;
-            }
-
-            match serde_json::from_str::<VideoInfo>(line) {
-                Ok(mut video) => {
-                    video.normalize_url();
-                    videos.push(video);
-                }
-                Err(e) => {
-                    error!("Failed to parse video info: {}", e);
-                    // Continue with other videos
-                }
-            }
-        }
-
-        Ok(videos)
+        Ok(parse_search_output(&json_str))
     }
 
     /// Get available formats for a video
@@ -168,18 +299,16 @@ impl YtDlpExtractor {
         Ok(video_info.formats)
     }
 
-    /// Search videos (YouTube)
-    /// Uses: yt-dlp "ytsearch{count}:{query}"
-    pub async fn search(&self, query: &str, count: usize) -> Result<Vec<VideoInfo>> {
-        debug!("Searching for: {} (count: {})", query, count);
-
-        let search_query = format!("ytsearch{}:{}", count, query);
+    /// Search a site through its yt-dlp search prefix, one page at a time
+    /// Uses: yt-dlp --dump-json [--flat-playlist] --playlist-start/-end "{prefix}{n}:{terms}"
+    pub async fn search_impl(&self, query: &SearchQuery) -> Result<SearchPage> {
+        debug!(
+            "Searching {} for: {} (page {})",
+            query.provider, query.terms, query.page
+        );
 
         let mut cmd = AsyncCommand::new(&self.ytdlp_path);
-        cmd.args(self.cookies.to_args())
-            .arg("--dump-json")
-            .arg("--no-warnings")
-            .arg(search_query);
+        cmd.args(self.cookies.to_args()).args(query.ytdlp_args());
         let output = run_ytdlp_bounded(cmd).await?;
 
         if !output.status.success() {
@@ -189,10 +318,12 @@ impl YtDlpExtractor {
         }
 
         let json_str = String::from_utf8(output.stdout)?;
-        let mut video_info: VideoInfo = serde_json::from_str(&json_str)?;
-        video_info.normalize_url();
-
-        Ok(vec![video_info])
+        let results = parse_search_output(&json_str);
+        Ok(SearchPage {
+            has_more: results.len() >= query.page_size,
+            page: query.page,
+            results,
+        })
     }
 
     /// Get direct download URL for a video with specific format
@@ -225,7 +356,7 @@ impl YtDlpExtractor {
 
 #[async_trait]
 impl Extractor for YtDlpExtractor {
-    fn id(&self) -> &'static str {
+    fn id(&self) -> &str {
         "yt-dlp"
     }
 
@@ -239,6 +370,10 @@ impl Extractor for YtDlpExtractor {
         self.extract_info_impl(url).await
     }
 
+    async fn extract_batch(&self, urls: &[String], results: mpsc::Sender<BatchItem>) {
+        self.extract_batch_impl(urls, results).await
+    }
+
     async fn extract_playlist(&self, url: &str) -> Result<Vec<VideoInfo>> {
         self.extract_playlist_impl(url).await
     }
@@ -250,6 +385,131 @@ impl Extractor for YtDlpExtractor {
     async fn get_direct_url(&self, url: &str, format_id: &str) -> Result<String> {
         self.get_direct_url_impl(url, format_id).await
     }
+
+    async fn search(&self, query: &SearchQuery) -> Result<SearchPage> {
+        self.search_impl(query).await
+    }
+}
+
+/// Parse yt-dlp's `--dump-json` output for `url`. A video without formats is
+/// an error, unless it is live or upcoming and will be recorded instead.
+fn parse_info(json: &str, url: &str) -> Result<VideoInfo> {
+    let mut video_info: VideoInfo = serde_json::from_str(json)?;
+    video_info.normalize_url();
+    if video_info.formats.is_empty() && !video_info.live().needs_recording() {
+        return Err(
+            RustloaderError::ExtractionError(format!("No formats available for {url}")).into(),
+        );
+    }
+    Ok(video_info)
+}
+
+/// Matches a batch run's output back to the URLs it was given. yt-dlp prints
+/// one JSON line per video on stdout and an `ERROR: [site] id: reason` line
+/// per failure on stderr. The two streams are read separately, so each line
+/// is matched by what it names rather than by when it arrived.
+struct BatchTracker {
+    /// URLs without a result yet, in batch order.
+    pending: Vec<String>,
+    /// Errors that named no pending URL, in the order they were printed.
+    unmatched: Vec<String>,
+    /// JSON lines that named no pending URL.
+    unmatched_json: Vec<String>,
+}
+
+/// Whether `url` names the video ID `id` as a whole path segment or query
+/// value (`/watch?v=<id>`, `/video/<id>`, `/<id>.html`), not merely somewhere
+/// inside a longer one.
+fn names_id(url: &str, id: &str) -> bool {
+    url.split(['/', '?', '&', '=', '#'])
+        .any(|part| part == id || part.rsplit_once('.').is_some_and(|(stem, _)| stem == id))
+}
+
+impl BatchTracker {
+    fn new(urls: Vec<String>) -> Self {
+        Self {
+            pending: urls,
+            unmatched: Vec::new(),
+            unmatched_json: Vec::new(),
+        }
+    }
+
+    fn take(&mut self, matches: impl Fn(&str) -> bool) -> Option<String> {
+        let i = self.pending.iter().position(|url| matches(url))?;
+        Some(self.pending.remove(i))
+    }
+
+    /// A stdout line. yt-dlp echoes the URL it was given as `original_url`;
+    /// failing that the page URL or video ID is matched. A line that names
+    /// no pending URL is held for [`Self::finish`] rather than pinned on a
+    /// guess. Lines that are not JSON are ignored.
+    fn json_line(&mut self, line: &str) -> Option<BatchItem> {
+        let value: serde_json::Value = serde_json::from_str(line).ok()?;
+        let field = |key: &str| value.get(key).and_then(|v| v.as_str()).map(str::to_string);
+        let original = field("original_url");
+        let webpage = field("webpage_url");
+        let id = field("id").filter(|id| !id.is_empty());
+        let url = original
+            .and_then(|u| self.take(|p| p == u))
+            .or_else(|| webpage.and_then(|u| self.take(|p| p == u)))
+            .or_else(|| id.and_then(|id| self.take(|p| names_id(p, &id))));
+        let Some(url) = url else {
+            self.unmatched_json.push(line.to_string());
+            return None;
+        };
+        let result = parse_info(line, &url);
+        Some((url, result))
+    }
+
+    /// A stderr line. An `ERROR:` naming a pending URL, or the video ID in
+    /// one, fails that URL; any other error is held for [`Self::finish`].
+    fn error_line(&mut self, line: &str) -> Option<BatchItem> {
+        let message = line.strip_prefix("ERROR:")?.trim();
+        let id = message
+            .strip_prefix('[')
+            .and_then(|rest| rest.split_once(']'))
+            .and_then(|(_, rest)| rest.split_once(':'))
+            .map(|(id, _)| id.trim())
+            .filter(|id| !id.is_empty());
+        let url = self
+            .take(|p| message.contains(p))
+            .or_else(|| id.and_then(|id| self.take(|p| names_id(p, id))));
+        match url {
+            Some(url) => Some((
+                url,
+                Err(RustloaderError::ExtractionError(message.to_string()).into()),
+            )),
+            None => {
+                self.unmatched.push(message.to_string());
+                None
+            }
+        }
+    }
+
+    /// The run is over. A single held JSON line goes to the single URL still
+    /// pending, the only pairing that is not a guess; otherwise held errors
+    /// go to the URLs still pending, in order, and any URL left after that
+    /// fails with `reason`.
+    fn finish(mut self, reason: &str) -> Vec<BatchItem> {
+        if let ([url], [line]) = (self.pending.as_slice(), self.unmatched_json.as_slice()) {
+            return vec![(url.clone(), parse_info(line, url))];
+        }
+        if !self.unmatched_json.is_empty() {
+            warn!(
+                "{} yt-dlp result(s) matched none of the {} URL(s) still pending",
+                self.unmatched_json.len(),
+                self.pending.len()
+            );
+        }
+        let mut errors = std::mem::take(&mut self.unmatched).into_iter();
+        self.pending
+            .into_iter()
+            .map(|url| {
+                let message = errors.next().unwrap_or_else(|| format!("{reason} ({url})"));
+                (url, Err(RustloaderError::ExtractionError(message).into()))
+            })
+            .collect()
+    }
 }
 
 impl Default for YtDlpExtractor {
@@ -370,6 +630,35 @@ fn find_aria2c_in_common_paths() -> Option<PathBuf> {
     None
 }
 
+/// Find `ffmpeg`: one the user had Rustloader install (see
+/// [`crate::utils::tools`]), then the system `PATH` and common install
+/// locations. Like aria2c it is never bundled.
+pub fn find_ffmpeg() -> Option<PathBuf> {
+    if let Some(path) = crate::utils::tools::managed_binary(crate::utils::tools::Tool::Ffmpeg) {
+        return Some(path);
+    }
+    if let Ok(path) = which::which("ffmpeg") {
+        return Some(path);
+    }
+
+    let common_paths: &[&str] = if cfg!(target_os = "macos") {
+        &[
+            "/opt/homebrew/bin/ffmpeg",
+            "/usr/local/bin/ffmpeg",
+            "/usr/bin/ffmpeg",
+        ]
+    } else if cfg!(target_os = "linux") {
+        &[
+            "/usr/bin/ffmpeg",
+            "/usr/local/bin/ffmpeg",
+            "/snap/bin/ffmpeg",
+        ]
+    } else {
+        &[]
+    };
+    common_paths.iter().map(PathBuf::from).find(|p| p.exists())
+}
+
 // ============================================================
 // Tests
 // ============================================================
@@ -438,6 +727,152 @@ mod tests {
         assert_eq!(String::from_utf8_lossy(&out.stdout).trim(), "hello");
     }
 
+    const VIDEO: &str = r#"{"id":"abc123","title":"A","original_url":"https://v.test/watch?v=abc123","webpage_url":"https://v.test/watch?v=abc123","formats":[{"format_id":"18","ext":"mp4","url":"https://cdn.test/a"}]}"#;
+
+    #[test]
+    fn batch_tracker_matches_lines_by_what_they_name() {
+        let urls = vec![
+            "https://v.test/watch?v=zzz999".to_string(),
+            "https://v.test/watch?v=abc123".to_string(),
+            "https://other.test/x".to_string(),
+        ];
+        let mut tracker = BatchTracker::new(urls);
+
+        // Out of order: the second URL's JSON comes first.
+        let (url, result) = tracker.json_line(VIDEO).unwrap();
+        assert_eq!(url, "https://v.test/watch?v=abc123");
+        assert_eq!(result.unwrap().title, "A");
+
+        let (url, result) = tracker
+            .error_line("ERROR: [site] zzz999: Video unavailable")
+            .unwrap();
+        assert_eq!(url, "https://v.test/watch?v=zzz999");
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Video unavailable"));
+
+        // Progress chatter and errors naming nothing are not results.
+        assert!(tracker.error_line("[site] Downloading webpage").is_none());
+        assert!(tracker.error_line("ERROR: Unable to connect").is_none());
+        assert!(tracker.json_line("not json").is_none());
+
+        let rest = tracker.finish("nothing printed");
+        assert_eq!(rest.len(), 1);
+        assert_eq!(rest[0].0, "https://other.test/x");
+        let err = rest[0].1.as_ref().unwrap_err().to_string();
+        assert!(err.contains("Unable to connect"), "{err}");
+    }
+
+    #[test]
+    fn batch_tracker_fails_unanswered_urls_with_the_reason() {
+        let tracker = BatchTracker::new(vec![
+            "https://a.test/1".to_string(),
+            "https://a.test/2".to_string(),
+        ]);
+        let rest = tracker.finish("stalled");
+        assert_eq!(rest.len(), 2);
+        let err = rest[1].1.as_ref().unwrap_err().to_string();
+        assert!(
+            err.contains("stalled") && err.contains("https://a.test/2"),
+            "{err}"
+        );
+        // An ID is matched as a whole segment, not inside a longer one, and
+        // a line that names no pending URL is held instead of guessed.
+        let mut tracker = BatchTracker::new(vec![
+            "https://v.test/watch?v=abc1234".to_string(),
+            "https://v.test/video/xabc123".to_string(),
+        ]);
+        let unnamed = r#"{"id":"abc123","title":"A","formats":[{"format_id":"18","ext":"mp4","url":"https://cdn.test/a"}]}"#;
+        assert!(tracker.json_line(unnamed).is_none());
+        assert!(tracker
+            .error_line("ERROR: [site] abc: Video unavailable")
+            .is_none());
+        let rest = tracker.finish("stalled");
+        assert_eq!(rest.len(), 2);
+        assert!(rest.iter().all(|(_, result)| result.is_err()));
+        assert!(names_id("https://v.test/v/abc123.html", "abc123"));
+        assert!(names_id("https://v.test/watch?v=abc123&t=1", "abc123"));
+        assert!(!names_id("https://v.test/watch?v=abc1234", "abc123"));
+
+        // With one URL and one held line left there is nothing to guess.
+        let mut tracker = BatchTracker::new(vec!["https://short.test/s/q".to_string()]);
+        assert!(tracker.json_line(unnamed).is_none());
+        let rest = tracker.finish("stalled");
+        assert_eq!(rest[0].1.as_ref().unwrap().title, "A");
+
+        // A video without formats is a failure, as with a single extraction.
+        let mut tracker = BatchTracker::new(vec!["https://a.test/3".to_string()]);
+        let (_, result) = tracker
+            .json_line(r#"{"id":"3","title":"T","original_url":"https://a.test/3"}"#)
+            .unwrap();
+        assert!(result.is_err());
+    }
+
+    /// A stand-in yt-dlp that reads the batch list from stdin: URLs ending
+    /// in `bad` fail on stderr, URLs ending in `quiet` print nothing, and the
+    /// rest print a one-format video.
+    #[cfg(unix)]
+    fn fake_ytdlp(dir: &std::path::Path) -> PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+        let path = dir.join("yt-dlp");
+        std::fs::write(
+            &path,
+            r#"#!/bin/sh
+while read -r url; do
+  id="${url##*/}"
+  case "$url" in
+    *bad) echo "ERROR: [test] $id: Video unavailable" >&2 ;;
+    *quiet) ;;
+    *) printf '{"id":"%s","title":"Video %s","original_url":"%s","formats":[{"format_id":"18","ext":"mp4","url":"https://cdn.test/%s"}]}
+' "$id" "$id" "$url" "$id" ;;
+  esac
+done
+"#,
+        )
+        .unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn batch_extraction_streams_one_result_per_url() {
+        let dir = tempfile::tempdir().unwrap();
+        let extractor = YtDlpExtractor {
+            ytdlp_path: fake_ytdlp(dir.path()),
+            cookies: crate::utils::CookieConfig::default(),
+            batch_workers: 2,
+        };
+        // More than one chunk, so several processes run.
+        let mut urls: Vec<String> = (0..BATCH_CHUNK_SIZE + 5)
+            .map(|i| format!("https://v.test/{i}"))
+            .collect();
+        urls.push("https://v.test/7bad".to_string());
+        urls.push("https://v.test/8quiet".to_string());
+
+        let (tx, mut rx) = mpsc::channel(8);
+        let (_, received) = tokio::join!(extractor.extract_batch_impl(&urls, tx), async {
+            let mut received = std::collections::HashMap::new();
+            while let Some((url, result)) = rx.recv().await {
+                assert!(received.insert(url, result).is_none(), "one result per URL");
+            }
+            received
+        });
+
+        assert_eq!(received.len(), urls.len());
+        let ok = received.values().filter(|r| r.is_ok()).count();
+        assert_eq!(ok, BATCH_CHUNK_SIZE + 5);
+        assert_eq!(
+            received["https://v.test/3"].as_ref().unwrap().title,
+            "Video 3"
+        );
+        let bad = received["https://v.test/7bad"].as_ref().unwrap_err();
+        assert!(bad.to_string().contains("Video unavailable"));
+        let quiet = received["https://v.test/8quiet"].as_ref().unwrap_err();
+        assert!(quiet.to_string().contains("printed nothing"));
+    }
+
     #[test]
     fn test_find_in_common_paths() {
         let result = find_in_common_paths();
@@ -1,20 +1,35 @@
 //! Main GUI application
 #![allow(dead_code, unused_imports, unused_variables, unused_mut)]
 
-use crate::backend::{BackendActor, BackendCommand, BackendEvent};
+use crate::backend::{self, BackendActor, BackendCommand, BackendEvent, MediaProfile};
 use crate::database::{initialize_database, DatabaseManager, DownloadRecord};
-use crate::extractor::VideoInfo;
+use crate::downloader::{live, ChapterMode, LiveOptions, ThumbnailCache};
+use crate::extractor::chapters::format_timestamp;
+use crate::extractor::formats::{self, StreamKind};
+use crate::extractor::playlist::{
+    is_playlist_url, parse_date, parse_duration, parse_index_ranges, split_urls,
+};
+use crate::extractor::preferences::parse_video_codec;
+use crate::extractor::{
+    Container, DynamicRange, ExtractionStage, ExtractorStats, FormatPreferences, Playlist,
+    PlaylistSelection, SearchPage, SearchProvider, SearchQuery, SubtitleFormat, SubtitleOptions,
+    VideoInfo,
+};
 use crate::gui::clipboard;
 use crate::gui::clipboard_monitor::ClipboardWatch;
 use std::time::Instant;
 // DownloadProgressData defined below
-use crate::queue::TaskStatus;
+use crate::queue::{ArchivePolicy, TaskBatch, TaskStatus};
 use crate::utils::config::{AppSettings, VideoQuality};
+use crate::utils::tools::{Tool, ToolManager, ToolSource};
 
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use iced::{executor, Application, Command, Element, Subscription, Theme};
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tokio::runtime::Runtime;
 use tokio::sync::mpsc;
 use tracing::error;
@@ -36,24 +51,63 @@ pub struct RustloaderApp {
 
     // Download tasks
     active_downloads: Vec<DownloadTaskUI>,
+    /// The backend's thumbnail cache (its default location), read for rows.
+    thumbnails: ThumbnailCache,
 
     // Download history (Shape-3 PR-2): a read/delete-only projection of the
     // persisted `downloads` table (#34). Never a second authority over live
     // task state — the queue above remains authoritative for in-flight work.
     history: Vec<DownloadRecord>,
+    /// Cached thumbnails of the history records, by record id.
+    history_thumbnails: HashMap<String, PathBuf>,
     history_loading: bool,
     history_error: Option<String>,
 
+    // Search: the form, the last query sent (for paging) and its results.
+    search_input: String,
+    search_provider: SearchProvider,
+    search_flat: bool,
+    search_query: Option<SearchQuery>,
+    search_page: Option<SearchPage>,
+    is_searching: bool,
+    search_error: Option<String>,
+
+    // Playlist picker: the expanded playlist being narrowed down before it is
+    // queued, and whether an expansion is in flight.
+    playlist_picker: Option<PlaylistPicker>,
+    is_expanding_playlist: bool,
+
+    // Format picker: an extracted video with several formats, waiting for the
+    // user to choose what to download.
+    format_picker: Option<FormatPicker>,
+
+    // Subscriptions: the add form, the stored list (refreshed on each visit
+    // and after every change) and whether an add is in flight.
+    subscription_form: SubscriptionForm,
+    subscriptions: Vec<backend::Subscription>,
+    subscriptions_error: Option<String>,
+    is_adding_subscription: bool,
+
     // Settings
     download_location: String,
     max_concurrent: usize,
     segments_per_download: usize,
     quality: VideoQuality,
     /// Browser to read cookies from for authenticated sites (yt-dlp
-    /// `--cookies-from-browser`); empty = none.
+    /// `--cookies-from-browser`, optionally `browser:profile`); empty = none.
     cookies_from_browser: String,
-    /// Browsers detected on this machine (for the Settings cookies dropdown).
-    cookie_browser_options: Vec<String>,
+    /// Browsers and their profiles detected on this machine (for the
+    /// Settings cookies dropdowns).
+    cookie_browser_options: Vec<crate::utils::cookies::DetectedBrowser>,
+    format_preferences: FormatPreferencesForm,
+    download_subtitles: bool,
+    /// Subtitle languages as typed, comma-separated; blank = every language.
+    subtitle_languages: String,
+    /// The other subtitle options; `languages` is filled from the text above
+    /// on save.
+    subtitle_options: SubtitleOptions,
+    chapter_mode: ChapterMode,
+    live_options: LiveOptionsForm,
 
     // Clipboard monitoring (opt-in, default OFF). While enabled, a timer
     // subscription polls the clipboard; a newly copied http(s) URL is surfaced
@@ -64,15 +118,36 @@ pub struct RustloaderApp {
     clipboard_watch: ClipboardWatch,
     detected_url: Option<String>,
 
-    // Flags
-    is_extracting: bool,
+    // A download held back because the archive says it was downloaded
+    // before; re-sent with `ArchivePolicy::Force` if the user confirms.
+    pending_redownload: Option<PendingRedownload>,
+
+    // Extractor diagnostics shown in Settings; refreshed on each visit.
+    extractor_stats: Vec<ExtractorStats>,
+    // The managed yt-dlp shown in Settings; refreshed on each visit.
+    ytdlp_status: YtDlpStatus,
+
+    // Extractions in flight, oldest first.
+    extractions: Vec<ExtractionJobUI>,
     url_error: Option<String>,
 }
 
+/// A `StartDownload` the backend answered with `AlreadyDownloaded`.
+#[derive(Debug, Clone)]
+struct PendingRedownload {
+    video_info: Box<VideoInfo>,
+    output_path: PathBuf,
+    format_id: Option<String>,
+}
+
 /// Application view
 #[derive(Debug, Clone, PartialEq)]
 pub enum View {
     Main,
+    Search,
+    Playlist,
+    Formats,
+    Subscriptions,
     Settings,
     History,
 }
@@ -156,6 +231,388 @@ pub struct DownloadTaskUI {
     pub last_progress_at: Instant,       // v0.6.0: For stall detection
     pub was_resumed_after_failure: bool, // v0.6.0: Track retry attempts
     pub error_dismissed: bool,           // v0.7.0: User dismissed error display
+    pub batch: Option<TaskBatch>,        // Playlist the task was expanded from
+    pub thumbnail: Option<PathBuf>,      // Cached thumbnail, once fetched
+    pub live: Option<LiveProgress>,      // Live stream being waited for or recorded
+}
+
+/// A running extraction, as shown above the downloads.
+#[derive(Debug, Clone)]
+pub struct ExtractionJobUI {
+    pub id: String,
+    pub url: String,
+    pub stage: ExtractionStage,
+}
+
+/// An expanded playlist and the user's choice of entries. The filter fields
+/// are raw text inputs; `apply_filters` turns them into a [`PlaylistSelection`]
+/// and re-ticks the matching entries, which can then be toggled one by one.
+#[derive(Debug, Clone, Default)]
+pub struct PlaylistPicker {
+    pub playlist: Playlist,
+    /// One flag per entry, in playlist order.
+    pub selected: Vec<bool>,
+    pub items: String,
+    pub min_duration: String,
+    pub max_duration: String,
+    pub date_after: String,
+    pub date_before: String,
+    pub title_pattern: String,
+    pub error: Option<String>,
+}
+
+impl PlaylistPicker {
+    /// Every entry starts selected, except those already downloaded.
+    pub fn new(playlist: Playlist) -> Self {
+        let selected = (1..=playlist.entries.len())
+            .map(|index| !playlist.archived.contains(&index))
+            .collect();
+        Self {
+            playlist,
+            selected,
+            ..Default::default()
+        }
+    }
+
+    /// The selection described by the filter inputs; blank inputs are unset.
+    pub fn selection(&self) -> Result<PlaylistSelection, String> {
+        let filled = |s: &str| Some(s.trim()).filter(|s| !s.is_empty()).map(str::to_string);
+        let mut selection = PlaylistSelection::all();
+        if let Some(items) = filled(&self.items) {
+            selection = selection.with_items(parse_index_ranges(&items)?);
+        }
+        if let Some(min) = filled(&self.min_duration) {
+            selection = selection.with_min_duration(parse_duration(&min)?);
+        }
+        if let Some(max) = filled(&self.max_duration) {
+            selection = selection.with_max_duration(parse_duration(&max)?);
+        }
+        if let Some(after) = filled(&self.date_after) {
+            selection = selection.with_date_after(parse_date(&after)?);
+        }
+        if let Some(before) = filled(&self.date_before) {
+            selection = selection.with_date_before(parse_date(&before)?);
+        }
+        if let Some(pattern) = filled(&self.title_pattern) {
+            let re =
+                regex::Regex::new(&pattern).map_err(|e| format!("invalid title pattern: {e}"))?;
+            selection = selection.with_title_pattern(re);
+        }
+        Ok(selection)
+    }
+
+    /// Re-tick exactly the entries the filters keep, leaving already
+    /// downloaded ones unticked.
+    pub fn apply_filters(&mut self) {
+        match self.selection() {
+            Ok(selection) => {
+                let keep = selection.apply(&self.playlist.entries);
+                for (i, selected) in self.selected.iter_mut().enumerate() {
+                    let index = i + 1;
+                    *selected = keep.contains(&index) && !self.playlist.archived.contains(&index);
+                }
+                self.error = None;
+            }
+            Err(e) => self.error = Some(e),
+        }
+    }
+
+    /// 1-based indices of the ticked entries.
+    pub fn selected_indices(&self) -> Vec<usize> {
+        self.selected
+            .iter()
+            .enumerate()
+            .filter(|(_, selected)| **selected)
+            .map(|(i, _)| i + 1)
+            .collect()
+    }
+}
+
+/// An extracted video waiting for its formats to be picked. `video` is a
+/// video+audio or video-only format, `audio` an audio-only one; both set are
+/// downloaded and merged, neither leaves the choice to the backend.
+#[derive(Debug, Clone, Default)]
+pub struct FormatPicker {
+    pub video_info: VideoInfo,
+    pub video: Option<String>,
+    pub audio: Option<String>,
+}
+
+impl FormatPicker {
+    /// Nothing picked: downloading now takes the best available format.
+    pub fn new(video_info: VideoInfo) -> Self {
+        Self {
+            video_info,
+            ..Default::default()
+        }
+    }
+
+    /// Pick or unpick `format_id`. A video+audio format replaces both picks;
+    /// an audio-only one only combines with a video-only one.
+    pub fn toggle(&mut self, format_id: &str) {
+        let Some(kind) = self.kind_of(format_id) else {
+            return;
+        };
+        let flip = |slot: &mut Option<String>| {
+            *slot = match slot.as_deref() {
+                Some(id) if id == format_id => None,
+                _ => Some(format_id.to_string()),
+            };
+        };
+        match kind {
+            StreamKind::Muxed => {
+                self.audio = None;
+                flip(&mut self.video);
+            }
+            StreamKind::VideoOnly => flip(&mut self.video),
+            StreamKind::AudioOnly => {
+                if self.video.as_deref().and_then(|id| self.kind_of(id)) == Some(StreamKind::Muxed)
+                {
+                    self.video = None;
+                }
+                flip(&mut self.audio);
+            }
+        }
+    }
+
+    pub fn is_picked(&self, format_id: &str) -> bool {
+        self.video.as_deref() == Some(format_id) || self.audio.as_deref() == Some(format_id)
+    }
+
+    /// The `format_id` to download with (`video+audio` for a pair).
+    pub fn format_id(&self) -> Option<String> {
+        formats::selection_id(self.video.as_deref(), self.audio.as_deref())
+    }
+
+    /// Provisional output extension: the picked format's own, `mp4` for a
+    /// merge or an automatic choice.
+    pub fn extension(&self) -> String {
+        self.format_id()
+            .filter(|id| !formats::is_merge(id))
+            .and_then(|id| formats::resolve(&self.video_info, &id).ok())
+            .map(|format| format.ext)
+            .filter(|ext| !ext.is_empty())
+            .unwrap_or_else(|| "mp4".to_string())
+    }
+
+    fn kind_of(&self, format_id: &str) -> Option<StreamKind> {
+        self.video_info
+            .formats
+            .iter()
+            .find(|f| f.format_id == format_id)
+            .map(|f| f.kind())
+    }
+}
+
+/// Playlist-picker filter inputs.
+#[derive(Debug, Clone)]
+pub enum PlaylistField {
+    Items,
+    MinDuration,
+    MaxDuration,
+    DateAfter,
+    DateBefore,
+    TitlePattern,
+}
+
+/// The add-subscription form. Quality and interval are raw text inputs;
+/// `subscription` parses them.
+#[derive(Debug, Clone, Default)]
+pub struct SubscriptionForm {
+    pub url: String,
+    /// Max height, e.g. `1080`; blank = best.
+    pub quality: String,
+    pub profile: MediaProfile,
+    /// Blank = organize into the library.
+    pub target_dir: String,
+    pub mirror: bool,
+    /// Minutes between checks; blank = hourly.
+    pub interval_minutes: String,
+    pub download_existing: bool,
+}
+
+impl SubscriptionForm {
+    /// The subscription the form describes.
+    pub fn subscription(&self) -> Result<backend::Subscription, String> {
+        let url = self.url.trim();
+        if url.is_empty() {
+            return Err("Enter a channel or playlist URL".to_string());
+        }
+        let mut subscription = backend::Subscription::new(url)
+            .with_profile(self.profile)
+            .with_mirror(self.mirror);
+        if let Some(quality) = Some(self.quality.trim()).filter(|q| !q.is_empty()) {
+            let height = quality
+                .trim_end_matches('p')
+                .parse::<u32>()
+                .map_err(|_| format!("'{quality}' is not a video height"))?;
+            subscription = subscription.with_quality(height);
+        }
+        if let Some(dir) = Some(self.target_dir.trim()).filter(|d| !d.is_empty()) {
+            subscription = subscription.with_target_dir(dir);
+        }
+        if let Some(minutes) = Some(self.interval_minutes.trim()).filter(|m| !m.is_empty()) {
+            let minutes = minutes
+                .parse::<u64>()
+                .map_err(|_| format!("'{minutes}' is not a number of minutes"))?;
+            subscription = subscription.with_interval(Duration::from_secs(minutes * 60));
+        }
+        Ok(subscription)
+    }
+}
+
+/// The Settings format-preference inputs. Height and the lists are raw
+/// text (lists comma-separated); `preferences` parses them.
+#[derive(Debug, Clone, Default)]
+pub struct FormatPreferencesForm {
+    /// Max height, e.g. `1440`; blank = best.
+    pub max_height: String,
+    /// Video codecs to prefer, best first, e.g. `h264, vp9`.
+    pub video_codecs: String,
+    pub avoid_codecs: String,
+    pub container: Option<Container>,
+    pub dynamic_range: Option<DynamicRange>,
+    /// Audio languages, best first, e.g. `es, en`.
+    pub audio_languages: String,
+}
+
+fn split_list(text: &str) -> impl Iterator<Item = &str> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+}
+
+impl FormatPreferencesForm {
+    pub fn from_preferences(preferences: &FormatPreferences) -> Self {
+        Self {
+            max_height: preferences
+                .max_height
+                .map(|h| h.to_string())
+                .unwrap_or_default(),
+            video_codecs: preferences.video_codecs.join(", "),
+            avoid_codecs: preferences.avoid_codecs.join(", "),
+            container: preferences.container,
+            dynamic_range: preferences.dynamic_range,
+            audio_languages: preferences.audio_languages.join(", "),
+        }
+    }
+
+    /// The preferences the form describes.
+    pub fn preferences(&self) -> Result<FormatPreferences, String> {
+        let max_height = match self.max_height.trim() {
+            "" => None,
+            height => Some(
+                height
+                    .trim_end_matches('p')
+                    .parse::<u32>()
+                    .map_err(|_| format!("'{height}' is not a video height"))?,
+            ),
+        };
+        let codecs = |text: &str| {
+            split_list(text)
+                .map(parse_video_codec)
+                .collect::<Result<Vec<_>, _>>()
+        };
+        Ok(FormatPreferences {
+            max_height,
+            video_codecs: codecs(&self.video_codecs)?,
+            avoid_codecs: codecs(&self.avoid_codecs)?,
+            container: self.container,
+            dynamic_range: self.dynamic_range,
+            audio_languages: split_list(&self.audio_languages)
+                .map(str::to_string)
+                .collect(),
+        })
+    }
+}
+
+/// The Settings live-stream inputs. The limits are raw text (a duration
+/// such as `2:00:00`, a size such as `4G`); blank = no limit.
+#[derive(Debug, Clone, Default)]
+pub struct LiveOptionsForm {
+    pub from_start: bool,
+    pub wait_for_start: bool,
+    pub max_duration: String,
+    pub max_size: String,
+}
+
+impl LiveOptionsForm {
+    pub fn from_options(options: &LiveOptions) -> Self {
+        Self {
+            from_start: options.from_start,
+            wait_for_start: options.wait_for_start,
+            max_duration: options
+                .max_duration
+                .map(|secs| format_timestamp(secs as f64))
+                .unwrap_or_default(),
+            max_size: options.max_size.map(live::format_size).unwrap_or_default(),
+        }
+    }
+
+    /// The options the form describes.
+    pub fn options(&self) -> Result<LiveOptions, String> {
+        let limit = |text: &str, parse: fn(&str) -> Result<u64, String>| match text.trim() {
+            "" => Ok(None),
+            value => parse(value).map(Some),
+        };
+        Ok(LiveOptions {
+            from_start: self.from_start,
+            wait_for_start: self.wait_for_start,
+            max_duration: limit(&self.max_duration, parse_duration)?,
+            max_size: limit(&self.max_size, live::parse_size)?,
+        })
+    }
+}
+
+/// The managed yt-dlp, as Settings shows it.
+#[derive(Debug, Clone, Default)]
+pub struct YtDlpStatus {
+    /// Version of the managed install; `None` = the system's yt-dlp is used.
+    pub version: Option<String>,
+    /// The version a rollback restores.
+    pub previous: Option<String>,
+    /// An update or rollback is running.
+    pub busy: bool,
+    /// The user was warned that the update cannot be signature-checked; the
+    /// next press installs anyway.
+    pub confirm_unsigned: bool,
+}
+
+impl YtDlpStatus {
+    pub fn load() -> Self {
+        let manager = ToolManager::default();
+        Self {
+            version: manager.installed(Tool::YtDlp).map(|t| t.version),
+            previous: manager.previous_version(Tool::YtDlp),
+            busy: false,
+            confirm_unsigned: false,
+        }
+    }
+}
+
+/// Live-stream limit text inputs.
+#[derive(Debug, Clone)]
+pub enum LiveLimitField {
+    MaxDuration,
+    MaxSize,
+}
+
+/// Format-preference text inputs.
+#[derive(Debug, Clone)]
+pub enum FormatPreferenceField {
+    MaxHeight,
+    VideoCodecs,
+    AvoidCodecs,
+    AudioLanguages,
+}
+
+/// Subscription-form text inputs.
+#[derive(Debug, Clone)]
+pub enum SubscriptionField {
+    Url,
+    Quality,
+    TargetDir,
+    IntervalMinutes,
 }
 
 /// Progress data transfer object
@@ -166,6 +623,18 @@ pub struct DownloadProgressData {
     pub downloaded: u64,
     pub total: u64,
     pub eta: Option<u64>,
+    /// Set for live streams, which have no percentage to report.
+    pub live: Option<LiveProgress>,
+}
+
+/// Where a live stream task is instead of a percentage.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LiveProgress {
+    /// Waiting for a scheduled stream or premiere (to start at `starts_at`,
+    /// when the site says).
+    Waiting { starts_at: Option<DateTime<Utc>> },
+    /// Recording, for `elapsed` seconds so far.
+    Recording { elapsed: u64 },
 }
 
 /// Application messages
@@ -174,6 +643,8 @@ pub enum Message {
     // Input events
     UrlInputChanged(String),
     DownloadButtonPressed,
+    RefreshButtonPressed,
+    CancelExtraction(String),
     PasteFromClipboard,
     ClearUrlInput,
 
@@ -186,6 +657,7 @@ pub enum Message {
     PauseDownload(String),
     ResumeDownload(String),
     CancelDownload(String),
+    StopRecording(String), // End a live recording, keeping what was recorded
     RemoveCompleted(String),
     ClearAllCompleted,
     ResumeAll,
@@ -200,9 +672,46 @@ pub enum Message {
 
     // View navigation
     SwitchToMain,
+    SwitchToSearch,
     SwitchToSettings,
     SwitchToHistory,
 
+    // Search
+    SearchInputChanged(String),
+    SearchProviderChanged(SearchProvider),
+    SearchFlatToggled(bool),
+    SearchSubmitted,
+    SearchNextPage,
+    SearchPreviousPage,
+    /// Download the hit at this index on the current results page.
+    EnqueueSearchResult(usize),
+
+    // Playlist picker
+    PlaylistFieldChanged(PlaylistField, String),
+    PlaylistApplyFilters,
+    /// Tick or untick the entry at this 0-based position.
+    PlaylistEntryToggled(usize, bool),
+    PlaylistSelectAll(bool),
+    PlaylistEnqueue,
+    PlaylistCancel,
+
+    // Format picker
+    FormatToggled(String),
+    FormatDownload,
+    FormatCancel,
+
+    // Subscriptions
+    SwitchToSubscriptions,
+    SubscriptionFieldChanged(SubscriptionField, String),
+    SubscriptionProfileChanged(MediaProfile),
+    SubscriptionMirrorToggled(bool),
+    SubscriptionDownloadExistingToggled(bool),
+    AddSubscription,
+    /// Enable or disable the subscription with this id.
+    SubscriptionEnabledToggled(String, bool),
+    CheckSubscription(String),
+    RemoveSubscription(String),
+
     // Download history (Shape-3 PR-2)
     RefreshHistory,
     HistoryLoaded(Result<Vec<DownloadRecord>, String>),
@@ -217,7 +726,26 @@ pub enum Message {
     SegmentsChanged(usize),
     QualityChanged(String),
     CookiesFromBrowserChanged(String),
+    FormatPreferenceChanged(FormatPreferenceField, String),
+    /// `None` = any container.
+    ContainerChanged(Option<Container>),
+    /// `None` = no SDR/HDR preference.
+    DynamicRangeChanged(Option<DynamicRange>),
+    SubtitlesToggled(bool),
+    SubtitleLanguagesChanged(String),
+    AutoSubtitlesToggled(bool),
+    /// `None` = keep the site's format.
+    SubtitleFormatChanged(Option<SubtitleFormat>),
+    EmbedSubtitlesToggled(bool),
+    ChapterModeChanged(ChapterMode),
+    LiveFromStartToggled(bool),
+    LiveWaitToggled(bool),
+    LiveLimitChanged(LiveLimitField, String),
     ClipboardMonitoringToggled(bool),
+    UpdateYtDlp,
+    RollBackYtDlp,
+    /// The installed version, or why installing failed.
+    YtDlpInstalled(Result<String, String>),
     SaveSettings,
     SettingsSaved(Result<(), String>),
 
@@ -226,10 +754,106 @@ pub enum Message {
     ConfirmDetectedUrl, // User accepted the detected URL — queue it
     DismissDetectedUrl, // User declined the detected URL
 
+    // Download archive
+    ConfirmRedownload, // Download an already-downloaded video again
+    DismissRedownload, // Leave it out
+
     // System
     Tick, // For periodic UI updates
 }
 
+impl RustloaderApp {
+    /// Queue `video_info` in the download folder, asking before downloading
+    /// it again.
+    fn start_download(&mut self, video_info: VideoInfo, format_id: Option<String>, ext: &str) {
+        let output_path = PathBuf::from(&self.download_location)
+            .join(format!("{}.{ext}", sanitize_filename(&video_info.title)));
+        self.status_message = format!("Starting download: {}", video_info.title);
+        let _ = self.backend_sender.try_send(BackendCommand::StartDownload {
+            video_info: Box::new(video_info),
+            output_path,
+            format_id,
+            archive: ArchivePolicy::Ask,
+        });
+    }
+
+    /// Send `query` to the backend and remember it for paging.
+    fn send_search(&mut self, query: SearchQuery) {
+        self.is_searching = true;
+        self.search_error = None;
+        self.status_message = format!("Searching {} for \"{}\"...", query.provider, query.terms);
+        let _ = self.backend_sender.try_send(BackendCommand::Search {
+            query: query.clone(),
+        });
+        self.search_query = Some(query);
+    }
+
+    /// Start adding a pasted or detected URL: playlists are expanded into the
+    /// picker, a pasted list of URLs is queued as one batch, anything else is
+    /// extracted and auto-started.
+    fn add_url(&mut self, url: String) {
+        let urls = split_urls(&url);
+        if urls.len() > 1 {
+            self.status_message = format!("Queueing {} URLs...", urls.len());
+            self.url_input.clear();
+            let _ = self.backend_sender.try_send(BackendCommand::EnqueueUrls {
+                urls,
+                output_dir: PathBuf::from(&self.download_location),
+            });
+        } else if is_playlist_url(&url) {
+            self.is_expanding_playlist = true;
+            self.status_message = "Listing playlist entries...".to_string();
+            let _ = self
+                .backend_sender
+                .try_send(BackendCommand::ExpandPlaylist { url });
+        } else {
+            self.status_message = "Extracting video information...".to_string();
+            self.extract(url, false);
+        }
+    }
+
+    /// Start an extraction job for `url`, alongside any already running.
+    fn extract(&mut self, url: String, refresh: bool) {
+        let id = Uuid::new_v4().to_string();
+        self.extractions.push(ExtractionJobUI {
+            id: id.clone(),
+            url: url.clone(),
+            stage: ExtractionStage::Queued,
+        });
+        let command = if refresh {
+            BackendCommand::RefreshInfo { id, url }
+        } else {
+            BackendCommand::ExtractInfo { id, url }
+        };
+        let _ = self.backend_sender.try_send(command);
+    }
+
+    /// Handle `job`'s result: offer a format choice when there is one,
+    /// otherwise start the download right away.
+    fn extraction_finished(&mut self, job: ExtractionJobUI, result: Result<VideoInfo, String>) {
+        match result {
+            Ok(video_info) => {
+                // Leave alone a URL typed in since.
+                if self.url_input == job.url {
+                    self.url_input.clear();
+                }
+                self.url_error = None;
+                if formats::pickable(&video_info).len() > 1 {
+                    self.status_message = format!("Choose a format: {}", video_info.title);
+                    self.format_picker = Some(FormatPicker::new(video_info));
+                    self.current_view = View::Formats;
+                } else {
+                    self.start_download(video_info, None, "mp4");
+                }
+            }
+            Err(e) => {
+                self.url_error = Some(make_error_user_friendly(&e));
+                self.status_message = format!("Extraction failed: {}", job.url);
+            }
+        }
+    }
+}
+
 impl Application for RustloaderApp {
     type Executor = iced::executor::Default;
     type Message = Message;
@@ -289,19 +913,46 @@ impl Application for RustloaderApp {
                 .map(|w| format!("⚠️  {w}"))
                 .unwrap_or_else(|| "Ready".to_string()),
             active_downloads: Vec::new(),
+            thumbnails: ThumbnailCache::default(),
             history: Vec::new(),
+            history_thumbnails: HashMap::new(),
             history_loading: false,
             history_error: None,
+            search_input: String::new(),
+            search_provider: SearchProvider::default(),
+            search_flat: true,
+            search_query: None,
+            search_page: None,
+            is_searching: false,
+            search_error: None,
+            playlist_picker: None,
+            is_expanding_playlist: false,
+            format_picker: None,
+            subscription_form: SubscriptionForm::default(),
+            subscriptions: Vec::new(),
+            subscriptions_error: None,
+            is_adding_subscription: false,
             download_location: settings.download_location.to_string_lossy().to_string(),
             max_concurrent: settings.max_concurrent,
             segments_per_download: settings.segments,
             quality: settings.quality,
             cookies_from_browser: settings.cookies_from_browser.clone().unwrap_or_default(),
-            cookie_browser_options: crate::utils::cookies::detect_browsers(),
+            cookie_browser_options: crate::utils::cookies::detect_browser_profiles(),
+            format_preferences: FormatPreferencesForm::from_preferences(
+                &settings.format_preferences,
+            ),
+            download_subtitles: settings.download_subtitles,
+            subtitle_languages: settings.subtitle_options.languages.join(", "),
+            subtitle_options: settings.subtitle_options.clone(),
+            chapter_mode: settings.chapter_mode,
+            live_options: LiveOptionsForm::from_options(&settings.live_options),
             clipboard_monitoring: settings.clipboard_monitoring,
             clipboard_watch: ClipboardWatch::new(),
             detected_url: None,
-            is_extracting: false,
+            pending_redownload: None,
+            extractor_stats: Vec::new(),
+            ytdlp_status: YtDlpStatus::load(),
+            extractions: Vec::new(),
             url_error: None,
         };
 
@@ -322,19 +973,31 @@ impl Application for RustloaderApp {
             }
 
             Message::DownloadButtonPressed => {
-                if !self.url_input.is_empty() && !self.is_extracting {
-                    self.is_extracting = true;
-                    self.status_message = "Extracting video information...".to_string();
+                if !self.url_input.is_empty() && !self.is_expanding_playlist {
+                    self.add_url(self.url_input.clone());
+                }
+                Command::none()
+            }
 
-                    // Send extract command to backend
-                    let url = self.url_input.clone();
-                    let _ = self
-                        .backend_sender
-                        .try_send(BackendCommand::ExtractInfo { url });
+            Message::RefreshButtonPressed => {
+                if !self.url_input.is_empty() {
+                    self.status_message = "Refreshing video information...".to_string();
+                    self.extract(self.url_input.clone(), true);
                 }
                 Command::none()
             }
 
+            Message::CancelExtraction(id) => {
+                // Dropped here at once; the backend's ExtractionCancelled
+                // (or a result that raced it) then finds nothing to update.
+                self.extractions.retain(|job| job.id != id);
+                self.status_message = "Extraction cancelled".to_string();
+                let _ = self
+                    .backend_sender
+                    .try_send(BackendCommand::CancelExtraction(id));
+                Command::none()
+            }
+
             Message::PasteFromClipboard => {
                 match clipboard::get_clipboard_content() {
                     Ok(content) => {
@@ -365,40 +1028,26 @@ impl Application for RustloaderApp {
                 // the `downloads` table (#34 remains the sole writer).
                 let mut should_refresh_history = false;
                 match *event {
-                    BackendEvent::ExtractionStarted => {
-                        self.is_extracting = true;
-                        self.status_message = "Extracting video information...".to_string();
+                    BackendEvent::ExtractionProgress { id, stage } => {
+                        if let Some(job) = self.extractions.iter_mut().find(|job| job.id == id) {
+                            self.status_message = format!("{}: {}", stage, job.url);
+                            job.stage = stage;
+                        }
                     }
-                    BackendEvent::ExtractionCompleted(result) => {
-                        self.is_extracting = false;
-                        match result {
-                            Ok(video_info) => {
-                                // Auto-start download logic
-                                let output_path = PathBuf::from(&self.download_location)
-                                    .join(format!("{}.mp4", sanitize_filename(&video_info.title)));
-
-                                self.url_input.clear();
-                                self.url_error = None;
-                                self.status_message =
-                                    format!("Starting download: {}", video_info.title);
-
-                                // Send start command
-                                let _ =
-                                    self.backend_sender.try_send(BackendCommand::StartDownload {
-                                        video_info: Box::new(video_info),
-                                        output_path,
-                                        format_id: None, // Auto format
-                                    });
-                            }
-                            Err(e) => {
-                                self.url_error = Some(make_error_user_friendly(&e));
-                                self.status_message = "Extraction failed".to_string();
-                            }
+                    BackendEvent::ExtractionCancelled { id } => {
+                        self.extractions.retain(|job| job.id != id);
+                    }
+                    BackendEvent::ExtractionCompleted { id, result } => {
+                        // Results of cancelled jobs are dropped.
+                        if let Some(i) = self.extractions.iter().position(|job| job.id == id) {
+                            let job = self.extractions.remove(i);
+                            self.extraction_finished(job, result);
                         }
                     }
                     BackendEvent::DownloadStarted {
                         task_id,
                         video_info,
+                        batch,
                     } => {
                         let task_ui = DownloadTaskUI {
                             id: task_id.clone(),
@@ -415,6 +1064,9 @@ impl Application for RustloaderApp {
                             last_progress_at: Instant::now(),
                             was_resumed_after_failure: false,
                             error_dismissed: false,
+                            batch,
+                            thumbnail: self.thumbnails.cached(&task_id),
+                            live: None,
                         };
                         self.active_downloads.push(task_ui);
                         self.status_message = format!("Added to queue: {}", video_info.title);
@@ -435,10 +1087,18 @@ impl Application for RustloaderApp {
                                 task.downloaded_mb = data.downloaded as f64 / (1024.0 * 1024.0);
                                 task.total_mb = data.total as f64 / (1024.0 * 1024.0);
                                 task.eta_seconds = data.eta;
+                                task.live = data.live;
                                 task.last_progress_at = Instant::now(); // stall detection
                             }
                         }
                     }
+                    BackendEvent::ThumbnailReady { task_id, path } => {
+                        if let Some(task) =
+                            self.active_downloads.iter_mut().find(|t| t.id == task_id)
+                        {
+                            task.thumbnail = Some(path);
+                        }
+                    }
                     BackendEvent::DownloadCompleted { task_id, file_path } => {
                         if let Some(task) =
                             self.active_downloads.iter_mut().find(|t| t.id == task_id)
@@ -472,6 +1132,77 @@ impl Application for RustloaderApp {
                             task.status = status;
                         }
                     }
+                    BackendEvent::SearchCompleted(result) => {
+                        self.is_searching = false;
+                        match result {
+                            Ok(page) => {
+                                self.search_error = None;
+                                self.status_message = format!(
+                                    "Search page {}: {} result(s)",
+                                    page.page,
+                                    page.results.len()
+                                );
+                                self.search_page = Some(page);
+                            }
+                            Err(e) => {
+                                self.search_error = Some(make_error_user_friendly(&e));
+                            }
+                        }
+                    }
+                    BackendEvent::PlaylistExpanded(result) => {
+                        self.is_expanding_playlist = false;
+                        match result {
+                            Ok(playlist) => {
+                                self.url_input.clear();
+                                self.url_error = None;
+                                self.status_message = format!(
+                                    "Playlist \"{}\": {} entries",
+                                    playlist.title,
+                                    playlist.entries.len()
+                                );
+                                self.playlist_picker = Some(PlaylistPicker::new(playlist));
+                                self.current_view = View::Playlist;
+                            }
+                            Err(e) => {
+                                self.url_error = Some(make_error_user_friendly(&e));
+                                self.status_message = "Playlist listing failed".to_string();
+                            }
+                        }
+                    }
+                    BackendEvent::AlreadyDownloaded {
+                        video_info,
+                        output_path,
+                        format_id,
+                        record,
+                    } => {
+                        self.status_message = format!(
+                            "Already downloaded on {}: {}",
+                            record.added_at.format("%Y-%m-%d"),
+                            video_info.title
+                        );
+                        self.pending_redownload = Some(PendingRedownload {
+                            video_info,
+                            output_path,
+                            format_id,
+                        });
+                        self.current_view = View::Main;
+                    }
+                    BackendEvent::SubscriptionsLoaded(result) => {
+                        self.is_adding_subscription = false;
+                        match result {
+                            Ok(subscriptions) => {
+                                self.subscriptions = subscriptions;
+                                self.subscriptions_error = None;
+                            }
+                            Err(e) => self.subscriptions_error = Some(e),
+                        }
+                    }
+                    BackendEvent::SubscriptionChecked(report) => {
+                        self.status_message = report.to_string();
+                    }
+                    BackendEvent::ExtractorDiagnostics(stats) => {
+                        self.extractor_stats = stats;
+                    }
                     BackendEvent::Error(e) => {
                         self.status_message = format!("Error: {}", e);
                     }
@@ -508,6 +1239,15 @@ impl Application for RustloaderApp {
                 Command::none()
             }
 
+            Message::StopRecording(task_id) => {
+                // The row completes (or fails) through the usual events once
+                // the recording is finalized.
+                let _ = self
+                    .backend_sender
+                    .try_send(BackendCommand::StopRecording(task_id));
+                Command::none()
+            }
+
             Message::RemoveCompleted(task_id) => {
                 self.active_downloads.retain(|t| t.id != task_id);
                 let _ = self
@@ -562,10 +1302,9 @@ impl Application for RustloaderApp {
                     self.url_input = url;
                     self.status_message = "Task reset - re-adding...".to_string();
 
-                    // Trigger extraction for the URL
-                    let _ = self.backend_sender.try_send(BackendCommand::ExtractInfo {
-                        url: self.url_input.clone(),
-                    });
+                    // Trigger a fresh extraction: the cached one may be what
+                    // the failed task was working from.
+                    self.extract(self.url_input.clone(), true);
                 }
                 Command::none()
             }
@@ -634,8 +1373,259 @@ impl Application for RustloaderApp {
                 Command::none()
             }
 
+            Message::SwitchToSearch => {
+                self.current_view = View::Search;
+                Command::none()
+            }
+
+            Message::SearchInputChanged(input) => {
+                self.search_input = input;
+                Command::none()
+            }
+
+            Message::SearchProviderChanged(provider) => {
+                self.search_provider = provider;
+                Command::none()
+            }
+
+            Message::SearchFlatToggled(flat) => {
+                self.search_flat = flat;
+                Command::none()
+            }
+
+            Message::SearchSubmitted => {
+                if !self.search_input.trim().is_empty() && !self.is_searching {
+                    let query = SearchQuery::new(self.search_input.trim())
+                        .with_provider(self.search_provider)
+                        .with_flat(self.search_flat);
+                    self.send_search(query);
+                }
+                Command::none()
+            }
+
+            Message::SearchNextPage => {
+                if let Some(query) = self.search_query.as_ref().map(SearchQuery::next_page) {
+                    self.send_search(query);
+                }
+                Command::none()
+            }
+
+            Message::SearchPreviousPage => {
+                if let Some(query) = self.search_query.as_ref().map(SearchQuery::previous_page) {
+                    self.send_search(query);
+                }
+                Command::none()
+            }
+
+            Message::EnqueueSearchResult(index) => {
+                let hit = self
+                    .search_page
+                    .as_ref()
+                    .and_then(|page| page.results.get(index))
+                    .cloned();
+                if let Some(info) = hit {
+                    if info.formats.is_empty() {
+                        // Flat hits carry no formats: same add path as a
+                        // pasted URL (extract, then auto-start).
+                        self.status_message = format!("Extracting: {}", info.title);
+                        self.extract(info.url, false);
+                    } else {
+                        let output_path = PathBuf::from(&self.download_location)
+                            .join(format!("{}.mp4", sanitize_filename(&info.title)));
+                        self.status_message = format!("Starting download: {}", info.title);
+                        let _ = self.backend_sender.try_send(BackendCommand::StartDownload {
+                            video_info: Box::new(info),
+                            output_path,
+                            format_id: None,
+                            archive: ArchivePolicy::Ask,
+                        });
+                    }
+                }
+                Command::none()
+            }
+
+            Message::PlaylistFieldChanged(field, value) => {
+                if let Some(picker) = &mut self.playlist_picker {
+                    let target = match field {
+                        PlaylistField::Items => &mut picker.items,
+                        PlaylistField::MinDuration => &mut picker.min_duration,
+                        PlaylistField::MaxDuration => &mut picker.max_duration,
+                        PlaylistField::DateAfter => &mut picker.date_after,
+                        PlaylistField::DateBefore => &mut picker.date_before,
+                        PlaylistField::TitlePattern => &mut picker.title_pattern,
+                    };
+                    *target = value;
+                }
+                Command::none()
+            }
+
+            Message::PlaylistApplyFilters => {
+                if let Some(picker) = &mut self.playlist_picker {
+                    picker.apply_filters();
+                }
+                Command::none()
+            }
+
+            Message::PlaylistEntryToggled(position, selected) => {
+                if let Some(flag) = self
+                    .playlist_picker
+                    .as_mut()
+                    .and_then(|picker| picker.selected.get_mut(position))
+                {
+                    *flag = selected;
+                }
+                Command::none()
+            }
+
+            Message::PlaylistSelectAll(selected) => {
+                if let Some(picker) = &mut self.playlist_picker {
+                    picker.selected.fill(selected);
+                }
+                Command::none()
+            }
+
+            Message::PlaylistEnqueue => {
+                if let Some(picker) = self.playlist_picker.take() {
+                    let indices = picker.selected_indices();
+                    self.status_message = format!(
+                        "Queuing {} of {} entries from \"{}\"...",
+                        indices.len(),
+                        picker.playlist.entries.len(),
+                        picker.playlist.title
+                    );
+                    let _ = self
+                        .backend_sender
+                        .try_send(BackendCommand::EnqueuePlaylist {
+                            playlist: Box::new(picker.playlist),
+                            indices,
+                            output_dir: PathBuf::from(&self.download_location),
+                            // The picker unticks archived entries, so a
+                            // ticked one is wanted again.
+                            archive: ArchivePolicy::Force,
+                        });
+                }
+                self.current_view = View::Main;
+                Command::none()
+            }
+
+            Message::PlaylistCancel => {
+                self.playlist_picker = None;
+                self.current_view = View::Main;
+                Command::none()
+            }
+
+            Message::FormatToggled(format_id) => {
+                if let Some(picker) = &mut self.format_picker {
+                    picker.toggle(&format_id);
+                }
+                Command::none()
+            }
+
+            Message::FormatDownload => {
+                if let Some(picker) = self.format_picker.take() {
+                    let ext = picker.extension();
+                    let format_id = picker.format_id();
+                    self.start_download(picker.video_info, format_id, &ext);
+                }
+                self.current_view = View::Main;
+                Command::none()
+            }
+
+            Message::FormatCancel => {
+                self.format_picker = None;
+                self.current_view = View::Main;
+                self.status_message = "Ready".to_string();
+                Command::none()
+            }
+
+            Message::SwitchToSubscriptions => {
+                self.current_view = View::Subscriptions;
+                let _ = self
+                    .backend_sender
+                    .try_send(BackendCommand::ListSubscriptions);
+                Command::none()
+            }
+
+            Message::SubscriptionFieldChanged(field, value) => {
+                let form = &mut self.subscription_form;
+                let target = match field {
+                    SubscriptionField::Url => &mut form.url,
+                    SubscriptionField::Quality => &mut form.quality,
+                    SubscriptionField::TargetDir => &mut form.target_dir,
+                    SubscriptionField::IntervalMinutes => &mut form.interval_minutes,
+                };
+                *target = value;
+                self.subscriptions_error = None;
+                Command::none()
+            }
+
+            Message::SubscriptionProfileChanged(profile) => {
+                self.subscription_form.profile = profile;
+                Command::none()
+            }
+
+            Message::SubscriptionMirrorToggled(mirror) => {
+                self.subscription_form.mirror = mirror;
+                Command::none()
+            }
+
+            Message::SubscriptionDownloadExistingToggled(download_existing) => {
+                self.subscription_form.download_existing = download_existing;
+                Command::none()
+            }
+
+            Message::AddSubscription => {
+                if !self.is_adding_subscription {
+                    match self.subscription_form.subscription() {
+                        Ok(subscription) => {
+                            self.is_adding_subscription = true;
+                            self.status_message = format!("Subscribing to {}...", subscription.url);
+                            let _ = self
+                                .backend_sender
+                                .try_send(BackendCommand::AddSubscription {
+                                    subscription: Box::new(subscription),
+                                    download_existing: self.subscription_form.download_existing,
+                                });
+                            self.subscription_form = SubscriptionForm::default();
+                        }
+                        Err(e) => self.subscriptions_error = Some(e),
+                    }
+                }
+                Command::none()
+            }
+
+            Message::SubscriptionEnabledToggled(id, enabled) => {
+                if let Some(subscription) = self.subscriptions.iter().find(|s| s.id == id) {
+                    let mut subscription = subscription.clone();
+                    subscription.enabled = enabled;
+                    let _ = self
+                        .backend_sender
+                        .try_send(BackendCommand::UpdateSubscription(Box::new(subscription)));
+                }
+                Command::none()
+            }
+
+            Message::CheckSubscription(id) => {
+                self.status_message = "Checking subscription...".to_string();
+                let _ = self
+                    .backend_sender
+                    .try_send(BackendCommand::CheckSubscription(id));
+                Command::none()
+            }
+
+            Message::RemoveSubscription(id) => {
+                let _ = self
+                    .backend_sender
+                    .try_send(BackendCommand::RemoveSubscription(id));
+                Command::none()
+            }
+
             Message::SwitchToSettings => {
                 self.current_view = View::Settings;
+                self.ytdlp_status = YtDlpStatus::load();
+                let _ = self
+                    .backend_sender
+                    .try_send(BackendCommand::RequestDiagnostics);
                 Command::none()
             }
 
@@ -657,6 +1647,10 @@ impl Application for RustloaderApp {
                 self.history_loading = false;
                 match result {
                     Ok(records) => {
+                        self.history_thumbnails = records
+                            .iter()
+                            .filter_map(|r| Some((r.id.clone(), self.thumbnails.cached(&r.id)?)))
+                            .collect();
                         self.history = records;
                         self.history_error = None;
                     }
@@ -672,9 +1666,12 @@ impl Application for RustloaderApp {
                 // above. This deletes the DB record only — not the downloaded
                 // file.
                 self.history.retain(|r| r.id != id);
+                self.history_thumbnails.remove(&id);
                 let db_manager = Arc::clone(&self.db_manager);
+                let thumbnails = self.thumbnails.clone();
                 Command::perform(
                     async move {
+                        thumbnails.remove(&id).await;
                         db_manager
                             .delete_download(&id)
                             .await
@@ -747,6 +1744,78 @@ impl Application for RustloaderApp {
                 Command::none()
             }
 
+            Message::FormatPreferenceChanged(field, value) => {
+                let form = &mut self.format_preferences;
+                let target = match field {
+                    FormatPreferenceField::MaxHeight => &mut form.max_height,
+                    FormatPreferenceField::VideoCodecs => &mut form.video_codecs,
+                    FormatPreferenceField::AvoidCodecs => &mut form.avoid_codecs,
+                    FormatPreferenceField::AudioLanguages => &mut form.audio_languages,
+                };
+                *target = value;
+                Command::none()
+            }
+
+            Message::ContainerChanged(container) => {
+                self.format_preferences.container = container;
+                Command::none()
+            }
+
+            Message::DynamicRangeChanged(range) => {
+                self.format_preferences.dynamic_range = range;
+                Command::none()
+            }
+
+            Message::SubtitlesToggled(enabled) => {
+                self.download_subtitles = enabled;
+                Command::none()
+            }
+
+            Message::SubtitleLanguagesChanged(value) => {
+                self.subtitle_languages = value;
+                Command::none()
+            }
+
+            Message::AutoSubtitlesToggled(enabled) => {
+                self.subtitle_options.auto_generated = enabled;
+                Command::none()
+            }
+
+            Message::SubtitleFormatChanged(format) => {
+                self.subtitle_options.format = format;
+                Command::none()
+            }
+
+            Message::EmbedSubtitlesToggled(enabled) => {
+                self.subtitle_options.embed = enabled;
+                Command::none()
+            }
+
+            Message::ChapterModeChanged(mode) => {
+                self.chapter_mode = mode;
+                Command::none()
+            }
+
+            Message::LiveFromStartToggled(enabled) => {
+                self.live_options.from_start = enabled;
+                Command::none()
+            }
+
+            Message::LiveWaitToggled(enabled) => {
+                self.live_options.wait_for_start = enabled;
+                Command::none()
+            }
+
+            Message::LiveLimitChanged(field, value) => {
+                let form = &mut self.live_options;
+                let target = match field {
+                    LiveLimitField::MaxDuration => &mut form.max_duration,
+                    LiveLimitField::MaxSize => &mut form.max_size,
+                };
+                *target = value;
+                Command::none()
+            }
+
             Message::ClipboardMonitoringToggled(enabled) => {
                 self.clipboard_monitoring = enabled;
                 if enabled {
@@ -784,11 +1853,7 @@ impl Application for RustloaderApp {
                     // Same add path as DownloadButtonPressed: extraction via
                     // the backend actor, which then auto-starts the download
                     // (I-2: GUI never drives the engine directly).
-                    self.is_extracting = true;
-                    self.status_message = "Extracting video information...".to_string();
-                    let _ = self
-                        .backend_sender
-                        .try_send(BackendCommand::ExtractInfo { url });
+                    self.add_url(url);
                 }
                 Command::none()
             }
@@ -798,7 +1863,41 @@ impl Application for RustloaderApp {
                 Command::none()
             }
 
+            Message::ConfirmRedownload => {
+                if let Some(pending) = self.pending_redownload.take() {
+                    self.status_message =
+                        format!("Starting download: {}", pending.video_info.title);
+                    let _ = self.backend_sender.try_send(BackendCommand::StartDownload {
+                        video_info: pending.video_info,
+                        output_path: pending.output_path,
+                        format_id: pending.format_id,
+                        archive: ArchivePolicy::Force,
+                    });
+                }
+                Command::none()
+            }
+
+            Message::DismissRedownload => {
+                self.pending_redownload = None;
+                self.status_message = "Ready".to_string();
+                Command::none()
+            }
+
             Message::SaveSettings => {
+                let format_preferences = match self.format_preferences.preferences() {
+                    Ok(preferences) => preferences,
+                    Err(e) => {
+                        self.status_message = format!("Failed to save settings: {e}");
+                        return Command::none();
+                    }
+                };
+                let live_options = match self.live_options.options() {
+                    Ok(options) => options,
+                    Err(e) => {
+                        self.status_message = format!("Failed to save settings: {e}");
+                        return Command::none();
+                    }
+                };
                 let settings = AppSettings {
                     download_location: PathBuf::from(&self.download_location),
                     segments: self.segments_per_download,
@@ -817,6 +1916,16 @@ impl Application for RustloaderApp {
                     },
                     cookies_file: None,
                     clipboard_monitoring: self.clipboard_monitoring,
+                    format_preferences,
+                    download_subtitles: self.download_subtitles,
+                    subtitle_options: SubtitleOptions {
+                        languages: split_list(&self.subtitle_languages)
+                            .map(str::to_string)
+                            .collect(),
+                        ..self.subtitle_options.clone()
+                    },
+                    chapter_mode: self.chapter_mode,
+                    live_options,
                 };
 
                 // Save settings to database. The result is surfaced (see
@@ -834,6 +1943,53 @@ impl Application for RustloaderApp {
                 )
             }
 
+            Message::UpdateYtDlp | Message::RollBackYtDlp => {
+                let rollback = matches!(message, Message::RollBackYtDlp);
+                if !rollback && !self.ytdlp_status.confirm_unsigned {
+                    // yt-dlp signs its releases with GPG, which we cannot
+                    // check; only install once the user has said so.
+                    self.ytdlp_status.confirm_unsigned = true;
+                    return Command::none();
+                }
+                self.ytdlp_status.busy = true;
+                self.status_message = if rollback {
+                    "Rolling yt-dlp back...".to_string()
+                } else {
+                    "Updating yt-dlp...".to_string()
+                };
+                Command::perform(
+                    async move {
+                        let manager = ToolManager::default();
+                        let installed = if rollback {
+                            manager.rollback(Tool::YtDlp).await
+                        } else {
+                            manager
+                                .install(Tool::YtDlp, &ToolSource::ytdlp().insecure(), None)
+                                .await
+                        };
+                        installed.map(|t| t.version).map_err(|e| format!("{e:#}"))
+                    },
+                    Message::YtDlpInstalled,
+                )
+            }
+
+            Message::YtDlpInstalled(result) => {
+                let was_managed = self.ytdlp_status.version.is_some();
+                self.ytdlp_status = YtDlpStatus::load();
+                self.status_message = match result {
+                    // Downloads find the new binary right away; the extractor
+                    // resolved its yt-dlp at launch.
+                    Ok(version) if !was_managed => {
+                        format!(
+                            "yt-dlp {version} installed. Restart Rustloader to use it everywhere."
+                        )
+                    }
+                    Ok(version) => format!("Now using yt-dlp {version}"),
+                    Err(e) => format!("Could not update yt-dlp: {e}"),
+                };
+                Command::none()
+            }
+
             Message::SettingsSaved(result) => {
                 match result {
                     Ok(()) => {
@@ -947,6 +2103,28 @@ impl Application for RustloaderApp {
                     .width(Length::Fill)
                     .padding(12)
                     .on_press(Message::SwitchToMain),
+                button(text("Search").size(16))
+                    .style(iced::theme::Button::Custom(Box::new(
+                        if self.current_view == View::Search {
+                            theme::SidebarButtonStyle::Active
+                        } else {
+                            theme::SidebarButtonStyle::Inactive
+                        }
+                    )))
+                    .width(Length::Fill)
+                    .padding(12)
+                    .on_press(Message::SwitchToSearch),
+                button(text("Subscriptions").size(16))
+                    .style(iced::theme::Button::Custom(Box::new(
+                        if self.current_view == View::Subscriptions {
+                            theme::SidebarButtonStyle::Active
+                        } else {
+                            theme::SidebarButtonStyle::Inactive
+                        }
+                    )))
+                    .width(Length::Fill)
+                    .padding(12)
+                    .on_press(Message::SwitchToSubscriptions),
                 button(text("History").size(16))
                     .style(iced::theme::Button::Custom(Box::new(
                         if self.current_view == View::History {
@@ -992,11 +2170,49 @@ impl Application for RustloaderApp {
                     &self.url_input,
                     &self.active_downloads,
                     &self.status_message,
-                    self.is_extracting,
+                    self.is_expanding_playlist,
+                    &self.extractions,
                     self.url_error.as_deref(),
                     quality_str,
                     self.segments_per_download,
                     self.detected_url.as_deref(),
+                    self.pending_redownload
+                        .as_ref()
+                        .map(|p| p.video_info.title.as_str()),
+                )
+            }
+            View::Search => {
+                use crate::gui::views::search_view;
+                search_view(
+                    &self.search_input,
+                    self.search_provider,
+                    self.search_flat,
+                    self.search_page.as_ref(),
+                    self.is_searching,
+                    self.search_error.as_deref(),
+                )
+            }
+            View::Playlist => match &self.playlist_picker {
+                Some(picker) => {
+                    use crate::gui::views::playlist_view;
+                    playlist_view(picker)
+                }
+                None => text("No playlist loaded").into(),
+            },
+            View::Formats => match &self.format_picker {
+                Some(picker) => {
+                    use crate::gui::views::format_view;
+                    format_view(picker)
+                }
+                None => text("No video loaded").into(),
+            },
+            View::Subscriptions => {
+                use crate::gui::views::subscriptions_view;
+                subscriptions_view(
+                    &self.subscription_form,
+                    &self.subscriptions,
+                    self.is_adding_subscription,
+                    self.subscriptions_error.as_deref(),
                 )
             }
             View::Settings => {
@@ -1007,13 +2223,22 @@ impl Application for RustloaderApp {
                     self.segments_per_download,
                     &self.cookies_from_browser,
                     &self.cookie_browser_options,
+                    &self.format_preferences,
+                    self.download_subtitles,
+                    &self.subtitle_languages,
+                    &self.subtitle_options,
+                    self.chapter_mode,
+                    &self.live_options,
                     self.clipboard_monitoring,
+                    &self.ytdlp_status,
+                    &self.extractor_stats,
                 )
             }
             View::History => {
                 use crate::gui::views::history_view;
                 history_view(
                     &self.history,
+                    &self.history_thumbnails,
                     self.history_loading,
                     self.history_error.as_deref(),
                 )
@@ -1107,6 +2332,39 @@ async fn load_settings_from_db(db_manager: &DatabaseManager) -> Result<AppSettin
         }
     }
 
+    // Load format preferences (absent/unparsable => none)
+    if let Some(value) = db_manager.get_setting("format_preferences").await? {
+        if let Ok(preferences) = serde_json::from_str(&value) {
+            settings.format_preferences = preferences;
+        }
+    }
+
+    // Load subtitle settings (absent/unparsable => off, every language)
+    if let Some(value) = db_manager.get_setting("download_subtitles").await? {
+        if let Ok(val) = value.parse::<bool>() {
+            settings.download_subtitles = val;
+        }
+    }
+    if let Some(value) = db_manager.get_setting("subtitle_options").await? {
+        if let Ok(options) = serde_json::from_str(&value) {
+            settings.subtitle_options = options;
+        }
+    }
+
+    // Load the chapter mode (absent/unparsable => keep files as downloaded)
+    if let Some(value) = db_manager.get_setting("chapter_mode").await? {
+        if let Ok(mode) = value.parse() {
+            settings.chapter_mode = mode;
+        }
+    }
+
+    // Load live-stream options (absent/unparsable => wait, no limits)
+    if let Some(value) = db_manager.get_setting("live_options").await? {
+        if let Ok(options) = serde_json::from_str(&value) {
+            settings.live_options = options;
+        }
+    }
+
     // Load clipboard monitoring (absent/unparsable => default OFF)
     if let Some(value) = db_manager.get_setting("clipboard_monitoring").await? {
         if let Ok(val) = value.parse::<bool>() {
@@ -1146,6 +2404,37 @@ async fn save_settings_to_db(db_manager: &DatabaseManager, settings: &AppSetting
         )
         .await?;
 
+    db_manager
+        .save_setting(
+            "format_preferences",
+            &serde_json::to_string(&settings.format_preferences)?,
+        )
+        .await?;
+
+    db_manager
+        .save_setting(
+            "download_subtitles",
+            &settings.download_subtitles.to_string(),
+        )
+        .await?;
+    db_manager
+        .save_setting(
+            "subtitle_options",
+            &serde_json::to_string(&settings.subtitle_options)?,
+        )
+        .await?;
+
+    db_manager
+        .save_setting("chapter_mode", settings.chapter_mode.as_str())
+        .await?;
+
+    db_manager
+        .save_setting(
+            "live_options",
+            &serde_json::to_string(&settings.live_options)?,
+        )
+        .await?;
+
     db_manager
         .save_setting(
             "clipboard_monitoring",
@@ -1185,6 +2474,18 @@ mod settings_tests {
             segments: 12,
             cookies_from_browser: Some("firefox".to_string()),
             clipboard_monitoring: true,
+            format_preferences: crate::extractor::FormatPreferences::default()
+                .with_max_height(2160)
+                .with_audio_languages(vec!["es".to_string()]),
+            download_subtitles: true,
+            subtitle_options: crate::extractor::SubtitleOptions::default()
+                .with_languages(vec!["en".to_string()])
+                .with_format(crate::extractor::SubtitleFormat::Srt)
+                .with_embed(true),
+            chapter_mode: crate::downloader::ChapterMode::Split,
+            live_options: crate::downloader::LiveOptions::default()
+                .with_from_start(true)
+                .with_max_duration(7200),
             ..AppSettings::default()
         };
 
@@ -1204,7 +2505,233 @@ mod settings_tests {
         assert_eq!(loaded.max_concurrent, 7);
         assert_eq!(loaded.segments, 12);
         assert!(loaded.clipboard_monitoring);
+        assert_eq!(loaded.format_preferences, settings.format_preferences);
+        assert!(loaded.download_subtitles);
+        assert_eq!(loaded.subtitle_options, settings.subtitle_options);
+        assert_eq!(loaded.chapter_mode, settings.chapter_mode);
+        assert_eq!(loaded.live_options, settings.live_options);
 
         std::fs::remove_file(&db_path).ok();
     }
 }
+
+#[cfg(test)]
+mod format_preferences_form_tests {
+    use super::FormatPreferencesForm;
+    use crate::extractor::{Container, FormatPreferences};
+
+    #[test]
+    fn form_round_trips_preferences_and_rejects_bad_inputs() {
+        let mut form = FormatPreferencesForm {
+            max_height: "1440p".to_string(),
+            video_codecs: "avc1, vp9".to_string(),
+            avoid_codecs: "av1,".to_string(),
+            container: Some(Container::Mkv),
+            dynamic_range: None,
+            audio_languages: " es , en".to_string(),
+        };
+        let preferences = form.preferences().unwrap();
+        assert_eq!(
+            preferences,
+            FormatPreferences::default()
+                .with_max_height(1440)
+                .with_video_codecs(vec!["h264".to_string(), "vp9".to_string()])
+                .with_avoid_codecs(vec!["av1".to_string()])
+                .with_container(Container::Mkv)
+                .with_audio_languages(vec!["es".to_string(), "en".to_string()])
+        );
+        let reloaded = FormatPreferencesForm::from_preferences(&preferences);
+        assert_eq!(reloaded.video_codecs, "h264, vp9");
+        assert_eq!(reloaded.preferences().unwrap(), preferences);
+
+        form.video_codecs = "divx".to_string();
+        assert!(form.preferences().unwrap_err().contains("divx"));
+        form.video_codecs.clear();
+        form.max_height = "tall".to_string();
+        assert!(form.preferences().is_err());
+        assert!(FormatPreferencesForm::default()
+            .preferences()
+            .unwrap()
+            .is_empty());
+    }
+}
+
+#[cfg(test)]
+mod live_options_form_tests {
+    use super::LiveOptionsForm;
+    use crate::downloader::LiveOptions;
+
+    #[test]
+    fn form_round_trips_limits_and_rejects_bad_inputs() {
+        let mut form = LiveOptionsForm {
+            from_start: true,
+            wait_for_start: false,
+            max_duration: "1:30:00".to_string(),
+            max_size: " 4G ".to_string(),
+        };
+        let options = form.options().unwrap();
+        assert_eq!(
+            options,
+            LiveOptions::default()
+                .with_from_start(true)
+                .with_wait_for_start(false)
+                .with_max_duration(5400)
+                .with_max_size(4 * 1024 * 1024 * 1024)
+        );
+        let reloaded = LiveOptionsForm::from_options(&options);
+        assert_eq!(reloaded.max_duration, "1:30:00");
+        assert_eq!(reloaded.max_size, "4G");
+        assert_eq!(reloaded.options().unwrap(), options);
+
+        form.max_size = "huge".to_string();
+        assert!(form.options().unwrap_err().contains("huge"));
+        assert_eq!(
+            LiveOptionsForm::from_options(&LiveOptions::default())
+                .options()
+                .unwrap(),
+            LiveOptions::default()
+        );
+    }
+}
+
+#[cfg(test)]
+mod subscription_form_tests {
+    use super::{MediaProfile, SubscriptionForm};
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    #[test]
+    fn form_builds_a_subscription_and_rejects_bad_inputs() {
+        let mut form = SubscriptionForm {
+            url: " https://soundcloud.com/artist/sets/album ".to_string(),
+            quality: "720p".to_string(),
+            profile: MediaProfile::Audio,
+            target_dir: "/music".to_string(),
+            mirror: true,
+            interval_minutes: "30".to_string(),
+            download_existing: false,
+        };
+        let subscription = form.subscription().unwrap();
+        assert_eq!(subscription.url, "https://soundcloud.com/artist/sets/album");
+        assert_eq!(subscription.quality, Some(720));
+        assert_eq!(subscription.profile, MediaProfile::Audio);
+        assert_eq!(subscription.target_dir, Some(PathBuf::from("/music")));
+        assert!(subscription.mirror);
+        assert_eq!(subscription.interval, Duration::from_secs(30 * 60));
+
+        form.quality = "HD".to_string();
+        assert!(form.subscription().unwrap_err().contains("HD"));
+        form.quality.clear();
+        form.interval_minutes = "often".to_string();
+        assert!(form.subscription().is_err());
+        assert!(SubscriptionForm::default().subscription().is_err());
+    }
+}
+
+#[cfg(test)]
+mod playlist_picker_tests {
+    use super::PlaylistPicker;
+    use crate::extractor::{Playlist, VideoInfo};
+
+    fn picker() -> PlaylistPicker {
+        let entries = [("Intro", 60), ("Live set", 3600), ("Outro", 120)]
+            .into_iter()
+            .map(|(title, duration)| VideoInfo {
+                title: title.to_string(),
+                duration: Some(duration),
+                ..Default::default()
+            })
+            .collect();
+        PlaylistPicker::new(Playlist::new("https://example.com/list", entries))
+    }
+
+    #[test]
+    fn filters_retick_entries_and_bad_input_is_reported() {
+        let mut picker = picker();
+        assert_eq!(picker.selected_indices(), [1, 2, 3]);
+
+        picker.items = "1-2".to_string();
+        picker.max_duration = "5:00".to_string();
+        picker.apply_filters();
+        assert_eq!(picker.selected_indices(), [1]);
+
+        // Manual ticks survive until the filters are applied again.
+        picker.selected[2] = true;
+        assert_eq!(picker.selected_indices(), [1, 3]);
+
+        picker.title_pattern = "(".to_string();
+        picker.apply_filters();
+        assert!(picker.error.as_deref().unwrap().contains("title pattern"));
+        assert_eq!(
+            picker.selected_indices(),
+            [1, 3],
+            "a bad filter changes nothing"
+        );
+    }
+
+    #[test]
+    fn archived_entries_start_and_stay_unticked() {
+        let mut playlist = picker().playlist;
+        playlist.archived = vec![2];
+        let mut picker = PlaylistPicker::new(playlist);
+        assert_eq!(picker.selected_indices(), [1, 3]);
+
+        picker.items = "2-3".to_string();
+        picker.apply_filters();
+        assert_eq!(picker.selected_indices(), [3]);
+
+        // Ticking it by hand downloads it again.
+        picker.selected[1] = true;
+        assert_eq!(picker.selected_indices(), [2, 3]);
+    }
+}
+
+#[cfg(test)]
+mod format_picker_tests {
+    use super::FormatPicker;
+    use crate::extractor::{Format, VideoInfo};
+
+    fn picker() -> FormatPicker {
+        let format = |id: &str, ext: &str, vcodec: &str, acodec: &str| Format {
+            format_id: id.to_string(),
+            ext: ext.to_string(),
+            vcodec: Some(vcodec.to_string()),
+            acodec: Some(acodec.to_string()),
+            ..Default::default()
+        };
+        FormatPicker::new(VideoInfo {
+            formats: vec![
+                format("18", "mp4", "avc1", "mp4a"),
+                format("137", "mp4", "avc1", "none"),
+                format("251", "webm", "none", "opus"),
+            ],
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn video_and_audio_picks_combine_into_a_merge() {
+        let mut picker = picker();
+        assert_eq!(picker.format_id(), None);
+        assert_eq!(picker.extension(), "mp4");
+
+        picker.toggle("137");
+        picker.toggle("251");
+        assert_eq!(picker.format_id().as_deref(), Some("137+251"));
+        assert!(picker.is_picked("137") && picker.is_picked("251"));
+        assert_eq!(picker.extension(), "mp4");
+
+        // A video+audio format replaces both picks...
+        picker.toggle("18");
+        assert_eq!(picker.format_id().as_deref(), Some("18"));
+        // ...and an audio-only pick replaces it in turn.
+        picker.toggle("251");
+        assert_eq!(picker.format_id().as_deref(), Some("251"));
+        assert_eq!(picker.extension(), "webm");
+
+        picker.toggle("251");
+        assert_eq!(picker.format_id(), None);
+        picker.toggle("unknown");
+        assert_eq!(picker.format_id(), None);
+    }
+}
@@ -1,7 +1,8 @@
 //! Download item component
 
-use crate::gui::app::{DownloadTaskUI, FailureCategory, Message};
-use crate::gui::components::progress_bar;
+use crate::extractor::chapters::format_timestamp;
+use crate::gui::app::{DownloadTaskUI, FailureCategory, LiveProgress, Message};
+use crate::gui::components::{progress_bar, thumbnail};
 use iced::widget::{button, column, container, row, text, Space};
 use iced::{Alignment, Color, Element, Length};
 use std::time::Duration;
@@ -14,13 +15,20 @@ pub fn download_item(task: &DownloadTaskUI) -> Element<'static, Message> {
     use crate::gui::theme;
     use iced::Theme;
 
-    // v0.6.0: Stall detection - Downloading with no progress for STALL_THRESHOLD_SECS
+    // v0.6.0: Stall detection - Downloading with no progress for STALL_THRESHOLD_SECS.
+    // A live stream waiting to start has nothing to report for hours.
     let is_stalled = task.status == "Downloading"
+        && task.live.is_none()
         && task.last_progress_at.elapsed() > Duration::from_secs(STALL_THRESHOLD_SECS);
 
     // Determine display status (may differ from backend status)
     let display_status = if is_stalled {
         "⚠ Stalled".to_string()
+    } else if task.status == "Downloading" && task.live.is_some() {
+        match task.live {
+            Some(LiveProgress::Recording { .. }) => "● REC".to_string(),
+            _ => "Waiting".to_string(),
+        }
     } else if task.was_resumed_after_failure && task.status == "Downloading" {
         "Retrying...".to_string()
     } else {
@@ -29,6 +37,7 @@ pub fn download_item(task: &DownloadTaskUI) -> Element<'static, Message> {
 
     let status_color = match task.status.as_str() {
         "Downloading" if is_stalled => theme::WARNING,
+        "Downloading" if matches!(task.live, Some(LiveProgress::Recording { .. })) => theme::DANGER,
         "Downloading" => theme::ACCENT,
         "Paused" | "Pausing..." | "Resuming..." => theme::WARNING,
         "Completed" => theme::SUCCESS,
@@ -52,6 +61,26 @@ pub fn download_item(task: &DownloadTaskUI) -> Element<'static, Message> {
         ]
     } else {
         match task.status.as_str() {
+            // A recording has nothing to resume from: it is stopped (and
+            // kept) or cancelled.
+            "Downloading" if matches!(task.live, Some(LiveProgress::Recording { .. })) => row![
+                button(text("Stop").size(12))
+                    .on_press(Message::StopRecording(task.id.clone()))
+                    .padding([6, 12])
+                    .style(iced::theme::Button::Custom(Box::new(theme::PrimaryButton))),
+                button(text("Cancel").size(12))
+                    .on_press(Message::CancelDownload(task.id.clone()))
+                    .padding([6, 12])
+                    .style(iced::theme::Button::Custom(Box::new(
+                        theme::DestructiveButton
+                    ))),
+            ],
+            "Downloading" if task.live.is_some() => row![button(text("Cancel").size(12))
+                .on_press(Message::CancelDownload(task.id.clone()))
+                .padding([6, 12])
+                .style(iced::theme::Button::Custom(Box::new(
+                    theme::DestructiveButton
+                ))),],
             "Downloading" => row![
                 button(text("Pause").size(12))
                     .on_press(Message::PauseDownload(task.id.clone()))
@@ -208,13 +237,23 @@ pub fn download_item(task: &DownloadTaskUI) -> Element<'static, Message> {
 
     let is_active = task.status == "Downloading" && !is_stalled;
 
-    content = content
-        .push(progress_bar(
+    // A live stream has no percentage: show how long it has recorded, or
+    // when it starts, instead of a progress bar.
+    content = match (task.status.as_str(), task.live) {
+        ("Downloading" | "Waiting", Some(live)) => content.push(
+            text(live_caption(live))
+                .size(12)
+                .style(iced::theme::Text::Color(theme::ACCENT)),
+        ),
+        _ => content.push(progress_bar(
             task.progress,
             task.eta_seconds,
             is_active,
             is_stalled,
-        ))
+        )),
+    };
+
+    content = content
         .push(
             row![
                 text(speed_text).size(12).style(theme::TEXT_SECONDARY),
@@ -231,7 +270,11 @@ pub fn download_item(task: &DownloadTaskUI) -> Element<'static, Message> {
         .spacing(12)
         .width(Length::Fill);
 
-    container(content)
+    let row_content = row![thumbnail(task.thumbnail.as_deref()), content]
+        .spacing(16)
+        .align_items(Alignment::Start);
+
+    container(row_content)
         .padding(16)
         .width(Length::Fill)
         .style(iced::theme::Container::Custom(Box::new(
@@ -239,3 +282,19 @@ pub fn download_item(task: &DownloadTaskUI) -> Element<'static, Message> {
         )))
         .into()
 }
+
+/// "Recording for 1:02:03", or when a waiting stream starts.
+fn live_caption(live: LiveProgress) -> String {
+    match live {
+        LiveProgress::Recording { elapsed } => {
+            format!("Recording for {}", format_timestamp(elapsed as f64))
+        }
+        LiveProgress::Waiting {
+            starts_at: Some(at),
+        } => format!(
+            "Starts {}",
+            at.with_timezone(&chrono::Local).format("%b %-d at %H:%M")
+        ),
+        LiveProgress::Waiting { starts_at: None } => "Waiting for the stream to start".to_string(),
+    }
+}
@@ -2,8 +2,10 @@
 
 use crate::database::DownloadRecord;
 use crate::gui::app::Message;
+use crate::gui::components::thumbnail;
 use iced::widget::{button, column, container, row, text, Space};
 use iced::{Alignment, Element, Length};
+use std::path::Path;
 
 /// Row title: the download's title, falling back to its URL when the title
 /// is empty (e.g. a record saved before extraction resolved a real title).
@@ -35,8 +37,12 @@ pub fn history_row_timestamp(record: &DownloadRecord) -> String {
         .to_string()
 }
 
-/// Create a single row for a persisted download-history record.
-pub fn history_item(record: &DownloadRecord) -> Element<'static, Message> {
+/// Create a single row for a persisted download-history record, with its
+/// cached thumbnail when there is one.
+pub fn history_item(
+    record: &DownloadRecord,
+    thumbnail_path: Option<&Path>,
+) -> Element<'static, Message> {
     use crate::gui::theme;
 
     let status_color = match record.status.as_str() {
@@ -105,7 +111,11 @@ pub fn history_item(record: &DownloadRecord) -> Element<'static, Message> {
         .spacing(12)
         .width(Length::Fill);
 
-    container(content)
+    let row_content = row![thumbnail(thumbnail_path), content]
+        .spacing(16)
+        .align_items(Alignment::Start);
+
+    container(row_content)
         .padding(16)
         .width(Length::Fill)
         .style(iced::theme::Container::Custom(Box::new(
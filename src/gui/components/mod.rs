@@ -3,10 +3,14 @@
 pub mod download_item;
 pub mod history_item;
 pub mod progress_bar;
+pub mod search_result;
+pub mod thumbnail;
 pub mod url_input;
 
 // Re-export for convenience
 pub use download_item::download_item;
 pub use history_item::history_item;
 pub use progress_bar::progress_bar;
+pub use search_result::search_result;
+pub use thumbnail::thumbnail;
 pub use url_input::url_input;
@@ -0,0 +1,50 @@
+//! Search-result card component
+
+use crate::extractor::search::result_details;
+use crate::extractor::VideoInfo;
+use crate::gui::app::Message;
+use iced::widget::{button, column, container, row, text, Space};
+use iced::{Alignment, Element, Length};
+
+/// Create a card for one search hit; `index` is its position on the page.
+pub fn search_result(index: usize, info: &VideoInfo) -> Element<'static, Message> {
+    use crate::gui::theme;
+
+    let title = if info.title.is_empty() {
+        info.url.clone()
+    } else {
+        info.title.clone()
+    };
+
+    let content = column![
+        text(title)
+            .size(16)
+            .width(Length::Fill)
+            .style(theme::TEXT_PRIMARY),
+        text(result_details(info))
+            .size(12)
+            .style(iced::theme::Text::Color(theme::TEXT_SECONDARY)),
+        row![
+            text(info.url.clone())
+                .size(12)
+                .style(iced::theme::Text::Color(theme::TEXT_SECONDARY)),
+            Space::with_width(Length::Fill),
+            button(text("Download").size(12))
+                .on_press(Message::EnqueueSearchResult(index))
+                .padding([6, 12])
+                .style(iced::theme::Button::Custom(Box::new(theme::PrimaryButton))),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center),
+    ]
+    .spacing(8)
+    .width(Length::Fill);
+
+    container(content)
+        .padding(16)
+        .width(Length::Fill)
+        .style(iced::theme::Container::Custom(Box::new(
+            theme::GlassContainer,
+        )))
+        .into()
+}
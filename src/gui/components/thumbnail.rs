@@ -0,0 +1,24 @@
+//! Thumbnail component
+
+use crate::gui::app::Message;
+use iced::widget::{container, image, Space};
+use iced::{Element, Length};
+use std::path::Path;
+
+/// Width of a row thumbnail; the height keeps the usual 16:9.
+const WIDTH: f32 = 112.0;
+const HEIGHT: f32 = 63.0;
+
+/// A download's cached thumbnail, or an empty slot of the same size so rows
+/// line up while it is still being fetched.
+pub fn thumbnail(path: Option<&Path>) -> Element<'static, Message> {
+    match path {
+        Some(path) => container(
+            image(image::Handle::from_path(path))
+                .width(Length::Fixed(WIDTH))
+                .height(Length::Fixed(HEIGHT)),
+        )
+        .into(),
+        None => Space::new(Length::Fixed(WIDTH), Length::Fixed(HEIGHT)).into(),
+    }
+}
@@ -11,6 +11,7 @@ pub mod views;
 
 // Re-export for convenience
 pub use app::DownloadProgressData;
+pub use app::LiveProgress;
 pub use app::Message;
 pub use app::RustloaderApp;
 pub use app::View;
@@ -0,0 +1,130 @@
+//! Format picker view implementation
+//!
+//! Lists an extracted video's formats — resolution, codecs, fps, bitrate and
+//! size — grouped into video+audio, video-only and audio-only, under a line
+//! naming its subtitle languages. A video-only and an audio-only pick are
+//! downloaded and merged; picking nothing leaves the choice to the backend.
+
+use crate::extractor::formats::{self, StreamKind};
+use crate::extractor::subtitles;
+use crate::gui::app::{FormatPicker, Message};
+use iced::widget::{button, checkbox, column, container, row, scrollable, text, Space};
+use iced::{Alignment, Element, Length};
+
+/// Create the format picker view.
+pub fn format_view(picker: &FormatPicker) -> Element<'static, Message> {
+    use crate::gui::theme;
+
+    let header = row![
+        button(text("← Back").size(16))
+            .on_press(Message::FormatCancel)
+            .padding([8, 16])
+            .style(iced::theme::Button::Custom(Box::new(
+                theme::SecondaryButton
+            ))),
+        Space::with_width(Length::Fill),
+        text(picker.video_info.title.clone())
+            .size(24)
+            .style(iced::theme::Text::Color(theme::TEXT_PRIMARY)),
+        Space::with_width(Length::Fill),
+        Space::with_width(Length::Fixed(80.0)), // Balance the back button
+    ]
+    .spacing(10)
+    .align_items(Alignment::Center);
+
+    let mut sections = column![].spacing(8);
+    if let Some(summary) = subtitles::summary(&picker.video_info) {
+        sections = sections.push(
+            text(summary)
+                .size(12)
+                .style(iced::theme::Text::Color(theme::TEXT_SECONDARY)),
+        );
+    }
+    for (kind, heading) in [
+        (StreamKind::Muxed, "Video + audio"),
+        (
+            StreamKind::VideoOnly,
+            "Video only (pick an audio stream to merge)",
+        ),
+        (StreamKind::AudioOnly, "Audio only"),
+    ] {
+        let group: Vec<_> = formats::pickable(&picker.video_info)
+            .into_iter()
+            .filter(|f| f.kind() == kind)
+            .collect();
+        if group.is_empty() {
+            continue;
+        }
+        sections = sections.push(
+            text(heading)
+                .size(14)
+                .style(iced::theme::Text::Color(theme::TEXT_SECONDARY)),
+        );
+        // Best first; the extractor lists worst to best.
+        for format in group.into_iter().rev() {
+            let id = format.format_id.clone();
+            sections = sections.push(
+                container(
+                    row![
+                        checkbox(format!("{} · {}", id, format.ext), picker.is_picked(&id))
+                            .on_toggle(move |_| Message::FormatToggled(id.clone()))
+                            .text_size(14)
+                            .width(Length::FillPortion(1)),
+                        text(formats::describe(format))
+                            .size(12)
+                            .width(Length::FillPortion(3))
+                            .style(iced::theme::Text::Color(theme::TEXT_SECONDARY)),
+                    ]
+                    .spacing(10)
+                    .align_items(Alignment::Center),
+                )
+                .padding([8, 12])
+                .width(Length::Fill)
+                .style(iced::theme::Container::Custom(Box::new(
+                    theme::GlassContainer,
+                ))),
+            );
+        }
+    }
+
+    let choice = match picker.format_id() {
+        Some(id) if formats::is_merge(&id) => format!("Merge {id}"),
+        Some(id) => format!("Format {id}"),
+        None => "Best available (automatic)".to_string(),
+    };
+    let footer = row![
+        Space::with_width(Length::Fill),
+        text(choice)
+            .size(12)
+            .style(iced::theme::Text::Color(theme::TEXT_SECONDARY)),
+        button(text("Download").size(14))
+            .on_press(Message::FormatDownload)
+            .padding([10, 20])
+            .style(iced::theme::Button::Custom(Box::new(theme::PrimaryButton))),
+    ]
+    .spacing(10)
+    .align_items(Alignment::Center);
+
+    let content = column![header]
+        .spacing(16)
+        .push(
+            scrollable(sections)
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .style(iced::theme::Scrollable::Custom(Box::new(
+                    theme::ScrollableStyle,
+                ))),
+        )
+        .push(footer)
+        .padding(32)
+        .width(Length::Fill)
+        .height(Length::Fill);
+
+    container(content)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .style(iced::theme::Container::Custom(Box::new(
+            theme::MainGradientContainer,
+        )))
+        .into()
+}
@@ -10,10 +10,13 @@ use crate::gui::app::Message;
 use crate::gui::components::history_item;
 use iced::widget::{button, column, container, row, scrollable, text, Space};
 use iced::{Alignment, Element, Length};
+use std::collections::HashMap;
+use std::path::PathBuf;
 
 /// Create the download-history view.
 pub fn history_view(
     history: &[DownloadRecord],
+    thumbnails: &HashMap<String, PathBuf>,
     is_loading: bool,
     load_error: Option<&str>,
 ) -> Element<'static, Message> {
@@ -95,7 +98,10 @@ pub fn history_view(
     } else {
         let mut history_col = column![].spacing(16);
         for record in history {
-            history_col = history_col.push(history_item(record));
+            history_col = history_col.push(history_item(
+                record,
+                thumbnails.get(&record.id).map(PathBuf::as_path),
+            ));
         }
 
         scrollable(history_col)
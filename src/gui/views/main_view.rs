@@ -1,9 +1,10 @@
 //! Main view implementation - Light Theme
 
-use crate::gui::app::{DownloadTaskUI, Message};
+use crate::gui::app::{DownloadTaskUI, ExtractionJobUI, Message};
 use crate::gui::components::{download_item, url_input};
 use iced::widget::{button, column, container, pick_list, row, scrollable, slider, text, Space};
 use iced::{Alignment, Element, Length};
+use std::collections::HashSet;
 
 /// Create the main view
 #[allow(clippy::too_many_arguments)] // Mirrors the app-state fields it renders
@@ -11,11 +12,13 @@ pub fn main_view(
     url_value: &str,
     downloads: &[DownloadTaskUI],
     _status_message: &str,
-    is_extracting: bool,
+    is_expanding_playlist: bool,
+    extractions: &[ExtractionJobUI],
     url_error: Option<&str>,
     quality: &str,
     segments: usize,
     detected_url: Option<&str>,
+    already_downloaded: Option<&str>,
 ) -> Element<'static, Message> {
     use crate::gui::theme;
 
@@ -35,22 +38,35 @@ pub fn main_view(
             // Download button row
             row![
                 Space::with_width(Length::Fill),
+                // Re-extract, ignoring any cached result (e.g. after the
+                // page changed or its stream links expired).
+                button(text("Refresh").size(16))
+                    .on_press_maybe(if !url_value.is_empty() {
+                        Some(Message::RefreshButtonPressed)
+                    } else {
+                        None
+                    })
+                    .padding([16, 24])
+                    .style(iced::theme::Button::Custom(Box::new(
+                        theme::SecondaryButton
+                    ))),
                 button(
-                    text(if is_extracting {
-                        "Extracting..."
+                    text(if is_expanding_playlist {
+                        "Listing..."
                     } else {
                         "Download"
                     })
                     .size(16)
                 )
-                .on_press_maybe(if !url_value.is_empty() && !is_extracting {
+                .on_press_maybe(if !url_value.is_empty() && !is_expanding_playlist {
                     Some(Message::DownloadButtonPressed)
                 } else {
                     None
                 })
                 .padding([16, 32])
                 .style(iced::theme::Button::Custom(Box::new(theme::PrimaryButton))),
-            ],
+            ]
+            .spacing(12),
             // Info row with interactive dropdowns
             row![
                 // Quality dropdown
@@ -163,8 +179,34 @@ pub fn main_view(
         .align_items(Alignment::Center)]
         .spacing(24);
 
+        // Playlist entries are listed together under one header, at the
+        // position of the batch's first task.
+        let mut listed_batches = HashSet::new();
         for task in downloads {
-            downloads_col = downloads_col.push(download_item(task));
+            match &task.batch {
+                None => downloads_col = downloads_col.push(download_item(task)),
+                Some(batch) if listed_batches.insert(batch.id.clone()) => {
+                    let members: Vec<_> = downloads
+                        .iter()
+                        .filter(|t| t.batch.as_ref().is_some_and(|b| b.id == batch.id))
+                        .collect();
+                    let done = members.iter().filter(|t| t.status == "Completed").count();
+                    let mut group = column![text(format!(
+                        "Playlist: {} ({}/{} done)",
+                        batch.name,
+                        done,
+                        members.len()
+                    ))
+                    .size(16)
+                    .style(iced::theme::Text::Color(theme::GRAY_800))]
+                    .spacing(12);
+                    for member in members {
+                        group = group.push(download_item(member));
+                    }
+                    downloads_col = downloads_col.push(group);
+                }
+                Some(_) => {} // Already listed with its batch
+            }
         }
 
         scrollable(downloads_col)
@@ -193,7 +235,7 @@ pub fn main_view(
                 .spacing(4)
                 .width(Length::Fill),
                 button(text("Download").size(14))
-                    .on_press_maybe(if is_extracting {
+                    .on_press_maybe(if is_expanding_playlist {
                         None
                     } else {
                         Some(Message::ConfirmDetectedUrl)
@@ -217,15 +259,92 @@ pub fn main_view(
         )))
     });
 
+    // Extractions in flight, each with its stage and a way to give up on it.
+    let extractions_panel = (!extractions.is_empty()).then(|| {
+        let mut list = column![].spacing(8);
+        for job in extractions {
+            list = list.push(
+                row![
+                    column![
+                        text(job.stage.to_string())
+                            .size(14)
+                            .style(iced::theme::Text::Color(theme::GRAY_800)),
+                        text(job.url.clone())
+                            .size(12)
+                            .style(iced::theme::Text::Color(theme::GRAY_500)),
+                    ]
+                    .spacing(4)
+                    .width(Length::Fill),
+                    button(text("Cancel").size(14))
+                        .on_press(Message::CancelExtraction(job.id.clone()))
+                        .padding([8, 16])
+                        .style(iced::theme::Button::Custom(Box::new(
+                            theme::SecondaryButton
+                        ))),
+                ]
+                .spacing(12)
+                .align_items(Alignment::Center),
+            );
+        }
+        container(list)
+            .padding(16)
+            .width(Length::Fill)
+            .style(iced::theme::Container::Custom(Box::new(
+                theme::GlassContainer,
+            )))
+    });
+
+    // Download-archive hit: the video was downloaded before, so nothing was
+    // queued until the user says so.
+    let archive_banner = already_downloaded.map(|title| {
+        container(
+            row![
+                column![
+                    text("Already downloaded — download it again?")
+                        .size(14)
+                        .style(iced::theme::Text::Color(theme::GRAY_800)),
+                    text(title.to_string())
+                        .size(12)
+                        .style(iced::theme::Text::Color(theme::GRAY_500)),
+                ]
+                .spacing(4)
+                .width(Length::Fill),
+                button(text("Download again").size(14))
+                    .on_press(Message::ConfirmRedownload)
+                    .padding([8, 16])
+                    .style(iced::theme::Button::Custom(Box::new(theme::PrimaryButton))),
+                button(text("Skip").size(14))
+                    .on_press(Message::DismissRedownload)
+                    .padding([8, 16])
+                    .style(iced::theme::Button::Custom(Box::new(
+                        theme::SecondaryButton
+                    ))),
+            ]
+            .spacing(12)
+            .align_items(Alignment::Center),
+        )
+        .padding(16)
+        .width(Length::Fill)
+        .style(iced::theme::Container::Custom(Box::new(
+            theme::GlassContainer,
+        )))
+    });
+
     // Main content
     let mut content = column![hero_section]
         .spacing(32)
         .width(Length::Fill)
         .height(Length::Fill)
         .padding([32, 32, 32, 32]);
+    if let Some(panel) = extractions_panel {
+        content = content.push(panel);
+    }
     if let Some(banner) = clipboard_banner {
         content = content.push(banner);
     }
+    if let Some(banner) = archive_banner {
+        content = content.push(banner);
+    }
     content.push(downloads_section).into()
 }
 
@@ -1,10 +1,18 @@
 //! GUI views
 
+pub mod format_view;
 pub mod history_view;
 pub mod main_view;
+pub mod playlist_view;
+pub mod search_view;
 pub mod settings_view;
+pub mod subscriptions_view;
 
 // Re-export for convenience
+pub use format_view::format_view;
 pub use history_view::history_view;
 pub use main_view::main_view;
+pub use playlist_view::playlist_view;
+pub use search_view::search_view;
 pub use settings_view::settings_view;
+pub use subscriptions_view::subscriptions_view;
@@ -0,0 +1,199 @@
+//! Playlist view implementation
+//!
+//! Lists an expanded playlist's entries with a checkbox each. Index ranges
+//! and the duration, date and title filters re-tick the matching entries;
+//! the ticked ones are queued as one batch. Entries already in the download
+//! archive start unticked.
+
+use crate::extractor::search::result_details;
+use crate::gui::app::{Message, PlaylistField, PlaylistPicker};
+use iced::widget::{button, checkbox, column, container, row, scrollable, text, text_input, Space};
+use iced::{Alignment, Element, Length};
+
+/// Create the playlist picker view.
+pub fn playlist_view(picker: &PlaylistPicker) -> Element<'static, Message> {
+    use crate::gui::theme;
+
+    let selected_count = picker.selected.iter().filter(|s| **s).count();
+    let total = picker.playlist.entries.len();
+
+    let header = row![
+        button(text("← Back").size(16))
+            .on_press(Message::PlaylistCancel)
+            .padding([8, 16])
+            .style(iced::theme::Button::Custom(Box::new(
+                theme::SecondaryButton
+            ))),
+        Space::with_width(Length::Fill),
+        text(picker.playlist.title.clone())
+            .size(24)
+            .style(iced::theme::Text::Color(theme::TEXT_PRIMARY)),
+        Space::with_width(Length::Fill),
+        Space::with_width(Length::Fixed(80.0)), // Balance the back button
+    ]
+    .spacing(10)
+    .align_items(Alignment::Center);
+
+    let field = |placeholder: &str, value: &str, kind: PlaylistField| {
+        text_input(placeholder, value)
+            .on_input(move |v| Message::PlaylistFieldChanged(kind.clone(), v))
+            .on_submit(Message::PlaylistApplyFilters)
+            .padding(10)
+            .size(14)
+            .style(iced::theme::TextInput::Custom(Box::new(theme::InputStyle)))
+    };
+
+    let filters = column![
+        row![
+            field("Items, e.g. 1-10,15", &picker.items, PlaylistField::Items)
+                .width(Length::FillPortion(2)),
+            field(
+                "Title regex",
+                &picker.title_pattern,
+                PlaylistField::TitlePattern
+            )
+            .width(Length::FillPortion(2)),
+        ]
+        .spacing(10),
+        row![
+            field(
+                "Min length (m:ss)",
+                &picker.min_duration,
+                PlaylistField::MinDuration
+            )
+            .width(Length::Fill),
+            field(
+                "Max length (m:ss)",
+                &picker.max_duration,
+                PlaylistField::MaxDuration
+            )
+            .width(Length::Fill),
+            field(
+                "After (YYYY-MM-DD)",
+                &picker.date_after,
+                PlaylistField::DateAfter
+            )
+            .width(Length::Fill),
+            field(
+                "Before (YYYY-MM-DD)",
+                &picker.date_before,
+                PlaylistField::DateBefore
+            )
+            .width(Length::Fill),
+            button(text("Apply").size(14))
+                .on_press(Message::PlaylistApplyFilters)
+                .padding([10, 16])
+                .style(iced::theme::Button::Custom(Box::new(
+                    theme::SecondaryButton
+                ))),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center),
+    ]
+    .spacing(10);
+
+    let filter_error = picker.error.as_ref().map(|e| {
+        text(e.clone())
+            .size(13)
+            .style(iced::theme::Text::Color(theme::DANGER))
+    });
+
+    let mut entries = column![].spacing(8);
+    for (position, (entry, selected)) in picker
+        .playlist
+        .entries
+        .iter()
+        .zip(&picker.selected)
+        .enumerate()
+    {
+        let title = if entry.title.is_empty() {
+            entry.url.clone()
+        } else {
+            entry.title.clone()
+        };
+        let mut details = result_details(entry);
+        if let Some(date) = &entry.upload_date {
+            if !details.is_empty() {
+                details.push_str(" · ");
+            }
+            details.push_str(date);
+        }
+        if picker.playlist.archived.contains(&(position + 1)) {
+            if !details.is_empty() {
+                details.push_str(" · ");
+            }
+            details.push_str("✓ downloaded");
+        }
+        entries = entries.push(
+            container(
+                row![
+                    checkbox(format!("{:>3}. {}", position + 1, title), *selected)
+                        .on_toggle(move |on| Message::PlaylistEntryToggled(position, on))
+                        .text_size(14)
+                        .width(Length::Fill),
+                    text(details)
+                        .size(12)
+                        .style(iced::theme::Text::Color(theme::TEXT_SECONDARY)),
+                ]
+                .spacing(10)
+                .align_items(Alignment::Center),
+            )
+            .padding([8, 12])
+            .width(Length::Fill)
+            .style(iced::theme::Container::Custom(Box::new(
+                theme::GlassContainer,
+            ))),
+        );
+    }
+
+    let footer = row![
+        button(text("Select All").size(12))
+            .on_press(Message::PlaylistSelectAll(true))
+            .padding([6, 12])
+            .style(iced::theme::Button::Custom(Box::new(
+                theme::SecondaryButton
+            ))),
+        button(text("Select None").size(12))
+            .on_press(Message::PlaylistSelectAll(false))
+            .padding([6, 12])
+            .style(iced::theme::Button::Custom(Box::new(
+                theme::SecondaryButton
+            ))),
+        Space::with_width(Length::Fill),
+        text(format!("{selected_count} of {total} selected"))
+            .size(12)
+            .style(iced::theme::Text::Color(theme::TEXT_SECONDARY)),
+        button(text(format!("Download {selected_count}")).size(14))
+            .on_press_maybe((selected_count > 0).then_some(Message::PlaylistEnqueue))
+            .padding([10, 20])
+            .style(iced::theme::Button::Custom(Box::new(theme::PrimaryButton))),
+    ]
+    .spacing(10)
+    .align_items(Alignment::Center);
+
+    let mut content = column![header, filters].spacing(16);
+    if let Some(error) = filter_error {
+        content = content.push(error);
+    }
+    let content = content
+        .push(
+            scrollable(entries)
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .style(iced::theme::Scrollable::Custom(Box::new(
+                    theme::ScrollableStyle,
+                ))),
+        )
+        .push(footer)
+        .padding(32)
+        .width(Length::Fill)
+        .height(Length::Fill);
+
+    container(content)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .style(iced::theme::Container::Custom(Box::new(
+            theme::MainGradientContainer,
+        )))
+        .into()
+}
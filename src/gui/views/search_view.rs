@@ -0,0 +1,171 @@
+//! Search view implementation
+//!
+//! Searches a site through the backend (yt-dlp search prefixes) and lists one
+//! page of hits at a time; each card's Download button goes through the same
+//! extract-then-enqueue path as a pasted URL.
+
+use crate::extractor::{SearchPage, SearchProvider};
+use crate::gui::app::Message;
+use crate::gui::components::search_result;
+use iced::widget::{
+    button, column, container, pick_list, row, scrollable, text, text_input, toggler, Space,
+};
+use iced::{Alignment, Element, Length};
+
+/// Create the search view.
+pub fn search_view(
+    search_input: &str,
+    provider: SearchProvider,
+    flat: bool,
+    page: Option<&SearchPage>,
+    is_searching: bool,
+    search_error: Option<&str>,
+) -> Element<'static, Message> {
+    use crate::gui::theme;
+
+    let header = row![
+        button(text("← Back").size(16))
+            .on_press(Message::SwitchToMain)
+            .padding([8, 16])
+            .style(iced::theme::Button::Custom(Box::new(
+                theme::SecondaryButton
+            ))),
+        Space::with_width(Length::Fill),
+        text("Search")
+            .size(24)
+            .style(iced::theme::Text::Color(theme::TEXT_PRIMARY)),
+        Space::with_width(Length::Fill),
+        Space::with_width(Length::Fixed(80.0)), // Balance the back button
+    ]
+    .spacing(10)
+    .align_items(Alignment::Center);
+
+    let can_search = !search_input.trim().is_empty() && !is_searching;
+    let search_row = row![
+        text_input("Search videos...", search_input)
+            .on_input(Message::SearchInputChanged)
+            .on_submit(Message::SearchSubmitted)
+            .padding(12)
+            .width(Length::Fill)
+            .style(iced::theme::TextInput::Custom(Box::new(theme::InputStyle))),
+        pick_list(
+            SearchProvider::ALL.to_vec(),
+            Some(provider),
+            Message::SearchProviderChanged
+        )
+        .text_size(14)
+        .padding([10, 12]),
+        button(
+            text(if is_searching {
+                "Searching..."
+            } else {
+                "Search"
+            })
+            .size(14)
+        )
+        .on_press_maybe(can_search.then_some(Message::SearchSubmitted))
+        .padding([12, 20])
+        .style(iced::theme::Button::Custom(Box::new(theme::PrimaryButton))),
+    ]
+    .spacing(10)
+    .align_items(Alignment::Center);
+
+    let options_row = row![toggler(
+        Some("Fast mode (list results without resolving each video)".to_string()),
+        flat,
+        Message::SearchFlatToggled,
+    )
+    .width(Length::Shrink)
+    .spacing(8)];
+
+    let body: Element<'static, Message> = match (search_error, page) {
+        (Some(error), _) => container(
+            column![
+                text("Search failed")
+                    .size(16)
+                    .style(iced::theme::Text::Color(theme::DANGER)),
+                text(error.to_string())
+                    .size(13)
+                    .style(iced::theme::Text::Color(theme::TEXT_SECONDARY)),
+            ]
+            .spacing(10)
+            .align_items(Alignment::Center),
+        )
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .center_x()
+        .center_y()
+        .into(),
+        (None, Some(page)) if !page.results.is_empty() => {
+            let mut results = column![].spacing(16);
+            for (index, info) in page.results.iter().enumerate() {
+                results = results.push(search_result(index, info));
+            }
+
+            let pager = row![
+                button(text("← Previous").size(12))
+                    .on_press_maybe(
+                        (page.page > 1 && !is_searching).then_some(Message::SearchPreviousPage)
+                    )
+                    .padding([6, 12])
+                    .style(iced::theme::Button::Custom(Box::new(
+                        theme::SecondaryButton
+                    ))),
+                Space::with_width(Length::Fill),
+                text(format!("Page {}", page.page))
+                    .size(12)
+                    .style(iced::theme::Text::Color(theme::TEXT_SECONDARY)),
+                Space::with_width(Length::Fill),
+                button(text("Next →").size(12))
+                    .on_press_maybe(
+                        (page.has_more && !is_searching).then_some(Message::SearchNextPage)
+                    )
+                    .padding([6, 12])
+                    .style(iced::theme::Button::Custom(Box::new(
+                        theme::SecondaryButton
+                    ))),
+            ]
+            .align_items(Alignment::Center);
+
+            column![
+                scrollable(results)
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .style(iced::theme::Scrollable::Custom(Box::new(
+                        theme::ScrollableStyle,
+                    ))),
+                pager,
+            ]
+            .spacing(12)
+            .into()
+        }
+        (None, page) => container(
+            text(match (is_searching, page) {
+                (true, _) => "Searching...",
+                (false, Some(_)) => "No results",
+                (false, None) => "Search YouTube, SoundCloud and more",
+            })
+            .size(16)
+            .style(iced::theme::Text::Color(theme::GRAY_500)),
+        )
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .center_x()
+        .center_y()
+        .into(),
+    };
+
+    let content = column![header, search_row, options_row, body]
+        .spacing(16)
+        .padding(32)
+        .width(Length::Fill)
+        .height(Length::Fill);
+
+    container(content)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .style(iced::theme::Container::Custom(Box::new(
+            theme::MainGradientContainer,
+        )))
+        .into()
+}
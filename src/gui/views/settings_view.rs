@@ -5,14 +5,31 @@ use iced::widget::{
 };
 use iced::{Alignment, Element, Length};
 
+use crate::downloader::ChapterMode;
+use crate::extractor::{Container, DynamicRange, ExtractorStats, SubtitleFormat, SubtitleOptions};
+use crate::gui::app::{
+    FormatPreferenceField, FormatPreferencesForm, LiveLimitField, LiveOptionsForm, Message,
+    YtDlpStatus,
+};
+use crate::utils::cookies::{split_browser_source, DetectedBrowser};
+
 /// Create the settings view
+#[allow(clippy::too_many_arguments)] // Mirrors the app-state fields it renders
 pub fn settings_view(
     download_location: &str,
     max_concurrent: usize,
     segments: usize,
     cookies_from_browser: &str,
-    detected_browsers: &[String],
+    detected_browsers: &[DetectedBrowser],
+    format_preferences: &FormatPreferencesForm,
+    download_subtitles: bool,
+    subtitle_languages: &str,
+    subtitle_options: &SubtitleOptions,
+    chapter_mode: ChapterMode,
+    live_options: &LiveOptionsForm,
     clipboard_monitoring: bool,
+    ytdlp_status: &YtDlpStatus,
+    extractor_stats: &[ExtractorStats],
 ) -> Element<'static, crate::gui::app::Message> {
     // Header with back button
     let header = row![
@@ -115,26 +132,309 @@ pub fn settings_view(
     ]
     .spacing(10);
 
+    // Format preferences — what to pick when a video has several formats.
+    // Lists are comma-separated, best first.
+    const ANY_LABEL: &str = "Any";
+    let labelled_input =
+        |label: &str, placeholder: &str, value: &str, field: FormatPreferenceField| {
+            row![
+                text(label.to_string())
+                    .size(14)
+                    .width(Length::FillPortion(1))
+                    .style(iced::theme::Text::Color(crate::gui::theme::TEXT_SECONDARY)),
+                text_input(placeholder, value)
+                    .on_input(move |v| Message::FormatPreferenceChanged(field.clone(), v))
+                    .padding(10)
+                    .width(Length::FillPortion(2))
+                    .style(iced::theme::TextInput::Custom(Box::new(
+                        crate::gui::theme::InputStyle
+                    ))),
+            ]
+            .spacing(10)
+            .align_items(Alignment::Center)
+        };
+    let mut container_options = vec![ANY_LABEL.to_string()];
+    container_options.extend(Container::ALL.iter().map(|c| c.to_string()));
+    let mut range_options = vec![ANY_LABEL.to_string()];
+    range_options.extend(
+        DynamicRange::ALL
+            .iter()
+            .map(|r| r.to_string().to_uppercase()),
+    );
+    let labelled_pick = |label: &str, pick: Element<'static, Message>| {
+        row![
+            text(label.to_string())
+                .size(14)
+                .width(Length::FillPortion(1))
+                .style(iced::theme::Text::Color(crate::gui::theme::TEXT_SECONDARY)),
+            container(pick).width(Length::FillPortion(2)),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center)
+    };
+    let format_section = column![
+        text("Format Preferences")
+            .size(16)
+            .style(iced::theme::Text::Color(crate::gui::theme::TEXT_PRIMARY)),
+        text("Used when a video offers several formats. Lists are comma-separated, best first. Applies on next launch.")
+            .size(13)
+            .style(iced::theme::Text::Color(crate::gui::theme::TEXT_SECONDARY)),
+        labelled_input(
+            "Maximum height",
+            "best (e.g. 1440)",
+            &format_preferences.max_height,
+            FormatPreferenceField::MaxHeight,
+        ),
+        labelled_input(
+            "Preferred codecs",
+            "e.g. h264, vp9",
+            &format_preferences.video_codecs,
+            FormatPreferenceField::VideoCodecs,
+        ),
+        labelled_input(
+            "Never use codecs",
+            "e.g. av1",
+            &format_preferences.avoid_codecs,
+            FormatPreferenceField::AvoidCodecs,
+        ),
+        labelled_input(
+            "Audio languages",
+            "e.g. es, en",
+            &format_preferences.audio_languages,
+            FormatPreferenceField::AudioLanguages,
+        ),
+        labelled_pick(
+            "Container",
+            pick_list(
+                container_options,
+                Some(
+                    format_preferences
+                        .container
+                        .map_or(ANY_LABEL.to_string(), |c| c.to_string()),
+                ),
+                |choice| Message::ContainerChanged(choice.parse().ok()),
+            )
+            .width(Length::Fill)
+            .padding(10)
+            .into(),
+        ),
+        labelled_pick(
+            "Dynamic range",
+            pick_list(
+                range_options,
+                Some(
+                    format_preferences
+                        .dynamic_range
+                        .map_or(ANY_LABEL.to_string(), |r| r.to_string().to_uppercase()),
+                ),
+                |choice| Message::DynamicRangeChanged(choice.parse().ok()),
+            )
+            .width(Length::Fill)
+            .padding(10)
+            .into(),
+        ),
+    ]
+    .spacing(10);
+
+    // Subtitles — sidecar files next to the video (moved along with it when
+    // it is organized) or embedded into it.
+    const ORIGINAL_LABEL: &str = "Original";
+    let mut subtitle_format_options = vec![ORIGINAL_LABEL.to_string()];
+    subtitle_format_options.extend(
+        SubtitleFormat::ALL
+            .iter()
+            .map(|f| f.to_string().to_uppercase()),
+    );
+    let subtitles_section = column![
+        text("Subtitles")
+            .size(16)
+            .style(iced::theme::Text::Color(crate::gui::theme::TEXT_PRIMARY)),
+        text("Download subtitles with every video. Applies on next launch.")
+            .size(13)
+            .style(iced::theme::Text::Color(crate::gui::theme::TEXT_SECONDARY)),
+        toggler(
+            Some("Download subtitles".to_string()),
+            download_subtitles,
+            Message::SubtitlesToggled,
+        )
+        .width(Length::Shrink)
+        .spacing(8),
+        labelled_pick(
+            "Languages",
+            text_input("all (e.g. en, es.*)", subtitle_languages)
+                .on_input(Message::SubtitleLanguagesChanged)
+                .padding(10)
+                .style(iced::theme::TextInput::Custom(Box::new(
+                    crate::gui::theme::InputStyle
+                )))
+                .into(),
+        ),
+        labelled_pick(
+            "Format",
+            pick_list(
+                subtitle_format_options,
+                Some(
+                    subtitle_options
+                        .format
+                        .map_or(ORIGINAL_LABEL.to_string(), |f| f.to_string().to_uppercase()),
+                ),
+                |choice| Message::SubtitleFormatChanged(choice.parse().ok()),
+            )
+            .width(Length::Fill)
+            .padding(10)
+            .into(),
+        ),
+        toggler(
+            Some("Include auto-generated captions".to_string()),
+            subtitle_options.auto_generated,
+            Message::AutoSubtitlesToggled,
+        )
+        .width(Length::Shrink)
+        .spacing(8),
+        toggler(
+            Some("Embed into the video file".to_string()),
+            subtitle_options.embed,
+            Message::EmbedSubtitlesToggled,
+        )
+        .width(Length::Shrink)
+        .spacing(8),
+    ]
+    .spacing(10);
+
+    let chapters_section = column![
+        text("Chapters")
+            .size(16)
+            .style(iced::theme::Text::Color(crate::gui::theme::TEXT_PRIMARY)),
+        text("For videos with chapters, such as DJ sets and lectures. Applies on next launch.")
+            .size(13)
+            .style(iced::theme::Text::Color(crate::gui::theme::TEXT_SECONDARY)),
+        pick_list(
+            &ChapterMode::ALL[..],
+            Some(chapter_mode),
+            Message::ChapterModeChanged,
+        )
+        .width(Length::Fill)
+        .padding(10),
+    ]
+    .spacing(10);
+
+    let limit_input = |label: &str, placeholder: &str, value: &str, field: LiveLimitField| {
+        row![
+            text(label.to_string())
+                .size(14)
+                .width(Length::FillPortion(1))
+                .style(iced::theme::Text::Color(crate::gui::theme::TEXT_SECONDARY)),
+            text_input(placeholder, value)
+                .on_input(move |v| Message::LiveLimitChanged(field.clone(), v))
+                .padding(10)
+                .width(Length::FillPortion(2))
+                .style(iced::theme::TextInput::Custom(Box::new(
+                    crate::gui::theme::InputStyle
+                ))),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center)
+    };
+    let live_section = column![
+        text("Live Streams")
+            .size(16)
+            .style(iced::theme::Text::Color(crate::gui::theme::TEXT_PRIMARY)),
+        text("Live streams are recorded until they end, a limit is reached or you stop them. Applies on next launch.")
+            .size(13)
+            .style(iced::theme::Text::Color(crate::gui::theme::TEXT_SECONDARY)),
+        toggler(
+            Some("Record from the beginning when the site allows".to_string()),
+            live_options.from_start,
+            Message::LiveFromStartToggled,
+        )
+        .width(Length::Shrink)
+        .spacing(8),
+        toggler(
+            Some("Wait for scheduled streams and premieres".to_string()),
+            live_options.wait_for_start,
+            Message::LiveWaitToggled,
+        )
+        .width(Length::Shrink)
+        .spacing(8),
+        limit_input(
+            "Stop after",
+            "no limit (e.g. 2:00:00)",
+            &live_options.max_duration,
+            LiveLimitField::MaxDuration,
+        ),
+        limit_input(
+            "Stop at size",
+            "no limit (e.g. 4G)",
+            &live_options.max_size,
+            LiveLimitField::MaxSize,
+        ),
+    ]
+    .spacing(10);
+
     // Cookies section — for sites that need authentication (e.g. YouTube's
     // "Sign in to confirm you're not a bot"). A dropdown of detected browsers
     // (plus "None") replaces free-text so the value is always valid for yt-dlp.
+    // A second dropdown picks one of the browser's profiles; the setting
+    // holds both as yt-dlp's `browser:profile`.
     const NONE_LABEL: &str = "None";
+    const ANY_PROFILE_LABEL: &str = "Last used profile";
+    let (browser, profile) = split_browser_source(cookies_from_browser);
     let mut cookie_options: Vec<String> = vec![NONE_LABEL.to_string()];
-    cookie_options.extend(detected_browsers.iter().cloned());
+    cookie_options.extend(detected_browsers.iter().map(|b| b.name.clone()));
     // Keep a previously-saved browser selectable even if detection missed it.
-    if !cookies_from_browser.is_empty() && !cookie_options.iter().any(|o| o == cookies_from_browser)
-    {
-        cookie_options.push(cookies_from_browser.to_string());
+    if !browser.is_empty() && !cookie_options.iter().any(|o| o == browser) {
+        cookie_options.push(browser.to_string());
     }
-    let selected = if cookies_from_browser.is_empty() {
+    let selected = if browser.is_empty() {
         NONE_LABEL.to_string()
     } else {
-        cookies_from_browser.to_string()
+        browser.to_string()
     };
     let detected_note = if detected_browsers.is_empty() {
         "No browsers detected — choose None, or install/sign in to a browser.".to_string()
     } else {
-        format!("Detected: {}.", detected_browsers.join(", "))
+        let names: Vec<&str> = detected_browsers.iter().map(|b| b.name.as_str()).collect();
+        format!("Detected: {}.", names.join(", "))
+    };
+
+    // (label, setting value) for each profile of the selected browser.
+    let mut profiles: Vec<(String, String)> = detected_browsers
+        .iter()
+        .filter(|b| b.name == browser)
+        .flat_map(|b| {
+            b.profiles
+                .iter()
+                .map(|p| (p.label(), p.cookie_source(&b.name)))
+        })
+        .collect();
+    if let Some(saved) = profile {
+        if !profiles
+            .iter()
+            .any(|(_, value)| value == cookies_from_browser)
+        {
+            profiles.push((saved.to_string(), cookies_from_browser.to_string()));
+        }
+    }
+    let profile_picker: Element<'static, Message> = if profiles.is_empty() {
+        column![].into()
+    } else {
+        let selected_profile = profiles
+            .iter()
+            .find(|(_, value)| value == cookies_from_browser)
+            .map_or(ANY_PROFILE_LABEL.to_string(), |(label, _)| label.clone());
+        let mut labels = vec![ANY_PROFILE_LABEL.to_string()];
+        labels.extend(profiles.iter().map(|(label, _)| label.clone()));
+        let browser = browser.to_string();
+        pick_list(labels, Some(selected_profile), move |label| {
+            let value = profiles
+                .iter()
+                .find(|(l, _)| *l == label)
+                .map_or_else(|| browser.clone(), |(_, value)| value.clone());
+            crate::gui::app::Message::CookiesFromBrowserChanged(value)
+        })
+        .width(Length::Fill)
+        .padding(10)
+        .into()
     };
     let cookies_section = column![
         text("YouTube / Authenticated Sites")
@@ -152,6 +452,7 @@ pub fn settings_view(
         })
         .width(Length::Fill)
         .padding(10),
+        profile_picker,
         text(detected_note)
             .size(11)
             .style(iced::theme::Text::Color(crate::gui::theme::TEXT_SECONDARY)),
@@ -185,6 +486,101 @@ pub fn settings_view(
     ]
     .spacing(10);
 
+    // Extractor diagnostics — one line per extractor in chain order, so a
+    // site that "stopped working" can be traced to the extractor failing it.
+    let mut diagnostics_rows = column![].spacing(6);
+    for stats in extractor_stats {
+        let latency = stats
+            .average_latency
+            .map(|d| format!("{} ms avg", d.as_millis()))
+            .unwrap_or_else(|| "unused".to_string());
+        let state = if stats.circuit_open {
+            " · bypassed (failing)"
+        } else {
+            ""
+        };
+        diagnostics_rows = diagnostics_rows.push(
+            column![
+                row![
+                    text(stats.id.clone())
+                        .size(13)
+                        .style(iced::theme::Text::Color(crate::gui::theme::TEXT_PRIMARY)),
+                    Space::with_width(Length::Fill),
+                    text(format!(
+                        "{} ok · {} failed · {}{}",
+                        stats.successes, stats.failures, latency, state
+                    ))
+                    .size(12)
+                    .style(iced::theme::Text::Color(crate::gui::theme::TEXT_SECONDARY)),
+                ],
+                text(stats.last_error.clone().unwrap_or_default())
+                    .size(11)
+                    .style(iced::theme::Text::Color(crate::gui::theme::TEXT_SECONDARY)),
+            ]
+            .spacing(2),
+        );
+    }
+    // yt-dlp breaks whenever sites change; a managed copy is kept current
+    // from its official releases (checksum-verified), with one-step rollback.
+    let ytdlp_summary = match &ytdlp_status.version {
+        Some(version) => format!("Using Rustloader's yt-dlp {version}."),
+        None => "Using the yt-dlp installed on this system.".to_string(),
+    };
+    let update_label = if ytdlp_status.busy {
+        "Updating..."
+    } else if ytdlp_status.confirm_unsigned {
+        "Install anyway"
+    } else {
+        "Update yt-dlp"
+    };
+    let mut tool_buttons = row![button(text(update_label).size(14))
+        .on_press_maybe((!ytdlp_status.busy).then_some(Message::UpdateYtDlp))
+        .padding([8, 16])
+        .style(iced::theme::Button::Custom(Box::new(
+            crate::gui::theme::SecondaryButton
+        )))]
+    .spacing(10);
+    if let Some(previous) = &ytdlp_status.previous {
+        tool_buttons = tool_buttons.push(
+            button(text(format!("Roll back to {previous}")).size(14))
+                .on_press_maybe((!ytdlp_status.busy).then_some(Message::RollBackYtDlp))
+                .padding([8, 16])
+                .style(iced::theme::Button::Custom(Box::new(
+                    crate::gui::theme::SecondaryButton,
+                ))),
+        );
+    }
+    let unsigned_warning = if ytdlp_status.confirm_unsigned && !ytdlp_status.busy {
+        "yt-dlp's release signature can't be verified here; the download is only \
+         checked against checksums fetched over HTTPS."
+    } else {
+        ""
+    };
+    let tools_section = column![
+        text("yt-dlp")
+            .size(16)
+            .style(iced::theme::Text::Color(crate::gui::theme::TEXT_PRIMARY)),
+        text(ytdlp_summary)
+            .size(13)
+            .style(iced::theme::Text::Color(crate::gui::theme::TEXT_SECONDARY)),
+        text(unsigned_warning)
+            .size(12)
+            .style(iced::theme::Text::Color(crate::gui::theme::TEXT_SECONDARY)),
+        tool_buttons,
+    ]
+    .spacing(10);
+
+    let diagnostics_section = column![
+        text("Extractor Diagnostics")
+            .size(16)
+            .style(iced::theme::Text::Color(crate::gui::theme::TEXT_PRIMARY)),
+        text("Extractors are tried in this order; one that keeps failing is bypassed for a while.")
+            .size(13)
+            .style(iced::theme::Text::Color(crate::gui::theme::TEXT_SECONDARY)),
+        diagnostics_rows,
+    ]
+    .spacing(10);
+
     // Save button
     let save_button = button(text("Save Settings").size(16))
         .on_press(crate::gui::app::Message::SaveSettings)
@@ -205,8 +601,14 @@ pub fn settings_view(
                     download_location_section,
                     performance_section,
                     quality_section,
+                    format_section,
+                    subtitles_section,
+                    chapters_section,
+                    live_section,
                     cookies_section,
                     clipboard_section,
+                    tools_section,
+                    diagnostics_section,
                 ]
                 .spacing(24)
             )
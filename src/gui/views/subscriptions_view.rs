@@ -0,0 +1,189 @@
+//! Subscriptions view implementation
+//!
+//! A form to follow a channel or playlist with its own quality, profile,
+//! folder and interval, and the list of followed sources with an enable
+//! toggle, "Check now" and remove.
+
+use crate::backend::{MediaProfile, Subscription};
+use crate::gui::app::{Message, SubscriptionField, SubscriptionForm};
+use iced::widget::{
+    button, checkbox, column, container, pick_list, row, scrollable, text, text_input, Space,
+};
+use iced::{Alignment, Element, Length};
+
+/// Create the subscriptions view.
+pub fn subscriptions_view(
+    form: &SubscriptionForm,
+    subscriptions: &[Subscription],
+    is_adding: bool,
+    error: Option<&str>,
+) -> Element<'static, Message> {
+    use crate::gui::theme;
+
+    let title = text("Subscriptions")
+        .size(24)
+        .style(iced::theme::Text::Color(theme::TEXT_PRIMARY));
+
+    let field = |placeholder: &str, value: &str, kind: SubscriptionField| {
+        text_input(placeholder, value)
+            .on_input(move |v| Message::SubscriptionFieldChanged(kind.clone(), v))
+            .on_submit(Message::AddSubscription)
+            .padding(10)
+            .size(14)
+            .style(iced::theme::TextInput::Custom(Box::new(theme::InputStyle)))
+    };
+
+    let add_label = if is_adding { "Adding..." } else { "Subscribe" };
+    let add_form = column![
+        row![
+            field("Channel or playlist URL", &form.url, SubscriptionField::Url).width(Length::Fill),
+            button(text(add_label).size(14))
+                .on_press_maybe((!is_adding).then_some(Message::AddSubscription))
+                .padding([10, 20])
+                .style(iced::theme::Button::Custom(Box::new(theme::PrimaryButton))),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center),
+        row![
+            pick_list(
+                &MediaProfile::ALL[..],
+                Some(form.profile),
+                Message::SubscriptionProfileChanged
+            )
+            .padding(10)
+            .text_size(14),
+            field(
+                "Max height, e.g. 1080",
+                &form.quality,
+                SubscriptionField::Quality
+            )
+            .width(Length::FillPortion(1)),
+            field(
+                "Check every (minutes)",
+                &form.interval_minutes,
+                SubscriptionField::IntervalMinutes
+            )
+            .width(Length::FillPortion(1)),
+            field(
+                "Folder (blank = library)",
+                &form.target_dir,
+                SubscriptionField::TargetDir
+            )
+            .width(Length::FillPortion(2)),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center),
+        row![
+            checkbox("Mirror (track removed items)", form.mirror)
+                .on_toggle(Message::SubscriptionMirrorToggled)
+                .text_size(14),
+            checkbox("Download existing items", form.download_existing)
+                .on_toggle(Message::SubscriptionDownloadExistingToggled)
+                .text_size(14),
+        ]
+        .spacing(20),
+    ]
+    .spacing(10);
+
+    let mut list = column![].spacing(8);
+    if subscriptions.is_empty() {
+        list = list.push(
+            text("No subscriptions yet")
+                .size(14)
+                .style(iced::theme::Text::Color(theme::TEXT_SECONDARY)),
+        );
+    }
+    for subscription in subscriptions {
+        let id = subscription.id.clone();
+        let mut details = vec![
+            subscription.profile.to_string(),
+            format!("every {} min", subscription.interval.as_secs() / 60),
+        ];
+        if let Some(height) = subscription.quality {
+            details.push(format!("≤{height}p"));
+        }
+        if subscription.mirror {
+            details.push("mirror".to_string());
+        }
+        if let Some(dir) = &subscription.target_dir {
+            details.push(dir.display().to_string());
+        }
+        details.push(match subscription.last_checked {
+            Some(at) => format!("checked {}", at.format("%Y-%m-%d %H:%M")),
+            None => "never checked".to_string(),
+        });
+
+        list = list.push(
+            container(
+                row![
+                    checkbox("", subscription.enabled).on_toggle({
+                        let id = id.clone();
+                        move |on| Message::SubscriptionEnabledToggled(id.clone(), on)
+                    }),
+                    column![
+                        text(subscription.name.clone())
+                            .size(15)
+                            .style(iced::theme::Text::Color(theme::TEXT_PRIMARY)),
+                        text(subscription.url.clone())
+                            .size(12)
+                            .style(iced::theme::Text::Color(theme::TEXT_SECONDARY)),
+                        text(details.join(" · "))
+                            .size(12)
+                            .style(iced::theme::Text::Color(theme::TEXT_SECONDARY)),
+                    ]
+                    .spacing(2)
+                    .width(Length::Fill),
+                    button(text("Check now").size(12))
+                        .on_press(Message::CheckSubscription(id.clone()))
+                        .padding([6, 12])
+                        .style(iced::theme::Button::Custom(Box::new(
+                            theme::SecondaryButton
+                        ))),
+                    button(text("Remove").size(12))
+                        .on_press(Message::RemoveSubscription(id))
+                        .padding([6, 12])
+                        .style(iced::theme::Button::Custom(Box::new(
+                            theme::SecondaryButton
+                        ))),
+                ]
+                .spacing(10)
+                .align_items(Alignment::Center),
+            )
+            .padding([8, 12])
+            .width(Length::Fill)
+            .style(iced::theme::Container::Custom(Box::new(
+                theme::GlassContainer,
+            ))),
+        );
+    }
+
+    let mut content = column![title, add_form].spacing(16);
+    if let Some(error) = error {
+        content = content.push(
+            text(error.to_string())
+                .size(13)
+                .style(iced::theme::Text::Color(theme::DANGER)),
+        );
+    }
+    let content = content
+        .push(Space::with_height(8))
+        .push(
+            scrollable(list)
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .style(iced::theme::Scrollable::Custom(Box::new(
+                    theme::ScrollableStyle,
+                ))),
+        )
+        .padding(32)
+        .width(Length::Fill)
+        .height(Length::Fill);
+
+    container(content)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .style(iced::theme::Container::Custom(Box::new(
+            theme::MainGradientContainer,
+        )))
+        .into()
+}
@@ -11,6 +11,7 @@ pub mod queue;
 pub mod utils;
 
 // Re-export main types for easier use
+pub use backend::{Rustloader, RustloaderBuilder};
 pub use downloader::{DownloadConfig, DownloadEngine, DownloadProgress, DownloadStatus};
 pub use extractor::{Format, HybridExtractor, VideoInfo, YtDlpExtractor};
 pub use gui::{Message, RustloaderApp, View};
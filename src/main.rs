@@ -52,25 +52,20 @@ fn main() -> Result<()> {
 fn check_ytdlp_installed() -> Vec<String> {
     use rustloader::utils::depcheck;
 
-    // Try common macOS yt-dlp installation paths when launched from Finder/Dock
-    // (where PATH may not include user-installed Python binaries)
-    let possible_paths = [
-        "yt-dlp", // Try PATH first
-        "/usr/local/bin/yt-dlp",
-        "/opt/homebrew/bin/yt-dlp",
-        "/Library/Frameworks/Python.framework/Versions/3.12/bin/yt-dlp",
-        "/Library/Frameworks/Python.framework/Versions/3.11/bin/yt-dlp",
-        "/Library/Frameworks/Python.framework/Versions/3.10/bin/yt-dlp",
-    ];
-
+    // The same lookup the extractor uses: Rustloader's managed install,
+    // then a bundled copy, PATH and common install locations (which matter
+    // when launched from Finder/Dock, where PATH is minimal).
     let mut ytdlp_version: Option<String> = None;
-    for path in &possible_paths {
-        if let Ok(output) = Command::new(path).arg("--version").output() {
+    if let Some(path) = rustloader::extractor::ytdlp::find_ytdlp() {
+        if let Ok(output) = Command::new(&path).arg("--version").output() {
             if output.status.success() {
                 let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
-                println!("✓ yt-dlp found at: {} (version {})", path, version);
+                println!(
+                    "✓ yt-dlp found at: {} (version {})",
+                    path.display(),
+                    version
+                );
                 ytdlp_version = Some(version);
-                break;
             }
         }
     }
@@ -81,7 +76,8 @@ fn check_ytdlp_installed() -> Vec<String> {
         eprintln!("WARNING: yt-dlp not found in common locations");
         eprintln!("The app will run, but video extraction will fail.");
         eprintln!("Please install yt-dlp:");
-        eprintln!("  pip install yt-dlp");
+        eprintln!("  rustloader tools update (or Settings → Update yt-dlp)");
+        eprintln!("  or: pip install yt-dlp");
         eprintln!("  or: brew install yt-dlp");
         eprintln!("  or visit: https://github.com/yt-dlp/yt-dlp");
     }
@@ -0,0 +1,406 @@
+//! Download archive: which videos were already downloaded.
+//!
+//! Entries are keyed by extractor plus video ID, the same pair yt-dlp writes
+//! to its `--download-archive` file (`youtube dQw4w9WgXcQ`), so archives can
+//! be imported from and exported to yt-dlp. Completed tasks are recorded as
+//! they finish, and the library's metadata records can be backfilled.
+
+use crate::database::{ArchiveRecord, DatabaseManager};
+use crate::extractor::cache::normalize_url;
+use crate::extractor::VideoInfo;
+use crate::utils::error::RustloaderError;
+use crate::utils::tools::sha256_hex;
+use crate::utils::{FileOrganizer, MetadataManager};
+use anyhow::{Context, Result};
+use chrono::Utc;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// What to do when a video is already in the archive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ArchivePolicy {
+    /// Leave it out.
+    #[default]
+    Skip,
+    /// Hold it back and let the caller confirm (by adding it again with
+    /// `Force`).
+    Ask,
+    /// Download it again anyway.
+    Force,
+}
+
+impl ArchivePolicy {
+    /// The outcome for a video found in the archive, or `None` to queue it
+    /// anyway.
+    pub fn on_archived(self, record: ArchiveRecord) -> Option<AddOutcome> {
+        match self {
+            ArchivePolicy::Skip => Some(AddOutcome::Skipped(record)),
+            ArchivePolicy::Ask => Some(AddOutcome::NeedsConfirmation(record)),
+            ArchivePolicy::Force => None,
+        }
+    }
+}
+
+impl fmt::Display for ArchivePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ArchivePolicy::Skip => "skip",
+            ArchivePolicy::Ask => "ask",
+            ArchivePolicy::Force => "force",
+        })
+    }
+}
+
+impl FromStr for ArchivePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "skip" => Ok(ArchivePolicy::Skip),
+            "ask" => Ok(ArchivePolicy::Ask),
+            "force" => Ok(ArchivePolicy::Force),
+            _ => Err(format!(
+                "unknown archive mode '{s}' (expected skip, ask or force)"
+            )),
+        }
+    }
+}
+
+/// What adding a task did.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AddOutcome {
+    /// Queued under this task ID.
+    Queued(String),
+    /// Already downloaded; left out (`ArchivePolicy::Skip`).
+    Skipped(ArchiveRecord),
+    /// Already downloaded; not queued until the caller confirms
+    /// (`ArchivePolicy::Ask`).
+    NeedsConfirmation(ArchiveRecord),
+}
+
+impl AddOutcome {
+    /// The queued task's ID; an archived video is an `AlreadyDownloaded`
+    /// error.
+    pub fn into_task_id(self) -> Result<String> {
+        match self {
+            AddOutcome::Queued(task_id) => Ok(task_id),
+            AddOutcome::Skipped(record) | AddOutcome::NeedsConfirmation(record) => {
+                Err(RustloaderError::AlreadyDownloaded(describe(&record)).into())
+            }
+        }
+    }
+}
+
+/// `title (extractor id)`, or just the key for imported entries.
+fn describe(record: &ArchiveRecord) -> String {
+    let key = ArchiveKey::new(&record.extractor, &record.video_id);
+    if record.title.is_empty() {
+        key.to_string()
+    } else {
+        format!("{} ({key})", record.title)
+    }
+}
+
+/// Extractors whose video IDs are not unique across sites.
+const URL_KEYED_EXTRACTORS: &[&str] = &["direct", "generic"];
+
+/// An archive key: lower-case extractor name plus video ID.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ArchiveKey {
+    pub extractor: String,
+    pub video_id: String,
+}
+
+impl ArchiveKey {
+    /// The extractor name is normalized the way yt-dlp's archive spells it
+    /// (`Youtube` and `youtube` match; `twitch:vod` becomes `twitchvod`).
+    pub fn new(extractor: &str, video_id: &str) -> Self {
+        Self {
+            extractor: extractor
+                .chars()
+                .filter(|c| *c != ':' && !c.is_whitespace())
+                .collect::<String>()
+                .to_lowercase(),
+            video_id: video_id.trim().to_string(),
+        }
+    }
+
+    /// The key for an extracted video. Flat playlist entries often lack the
+    /// extractor name, so it falls back to the site the URL points at. A
+    /// video without an ID cannot be archived.
+    ///
+    /// The `direct` and `generic` extractors name videos after the file or
+    /// page (`video`, `index`), which unrelated sites share, so their key is
+    /// a hash of the normalized URL instead.
+    pub fn for_video(info: &VideoInfo) -> Option<Self> {
+        if info.id.trim().is_empty() {
+            return None;
+        }
+        let extractor = info
+            .extractor
+            .clone()
+            .filter(|e| !e.trim().is_empty())
+            .unwrap_or_else(|| FileOrganizer::detect_source_platform(&info.url));
+        let key = Self::new(&extractor, &info.id);
+        if !URL_KEYED_EXTRACTORS.contains(&key.extractor.as_str()) {
+            return Some(key);
+        }
+        if info.url.trim().is_empty() {
+            return None;
+        }
+        let digest = sha256_hex(normalize_url(&info.url).as_bytes());
+        Some(Self::new(&key.extractor, &digest[..16]))
+    }
+
+    /// Parse one line of a yt-dlp archive file; blank and malformed lines
+    /// are `None`.
+    pub fn parse_line(line: &str) -> Option<Self> {
+        let mut parts = line.split_whitespace();
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(extractor), Some(video_id), None) => Some(Self::new(extractor, video_id)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ArchiveKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.extractor, self.video_id)
+    }
+}
+
+/// The archive, stored in the application database.
+#[derive(Clone)]
+pub struct DownloadArchive {
+    db: Arc<DatabaseManager>,
+}
+
+impl DownloadArchive {
+    pub fn new(db: Arc<DatabaseManager>) -> Self {
+        Self { db }
+    }
+
+    /// The archive entry for `info`, if it was downloaded before.
+    pub async fn lookup(&self, info: &VideoInfo) -> Result<Option<ArchiveRecord>> {
+        match ArchiveKey::for_video(info) {
+            Some(key) => {
+                self.db
+                    .get_archive_entry(&key.extractor, &key.video_id)
+                    .await
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Record `info` as downloaded. Returns whether it was new.
+    pub async fn record(&self, info: &VideoInfo) -> Result<bool> {
+        let Some(key) = ArchiveKey::for_video(info) else {
+            return Ok(false);
+        };
+        self.db
+            .save_archive_entry(&ArchiveRecord {
+                extractor: key.extractor,
+                video_id: key.video_id,
+                title: info.title.clone(),
+                url: info.url.clone(),
+                added_at: Utc::now(),
+            })
+            .await
+    }
+
+    /// Forget a video so it is no longer skipped.
+    pub async fn forget(&self, key: &ArchiveKey) -> Result<()> {
+        self.db
+            .delete_archive_entry(&key.extractor, &key.video_id)
+            .await
+    }
+
+    /// Every archived video, oldest first.
+    pub async fn entries(&self) -> Result<Vec<ArchiveRecord>> {
+        self.db.get_archive_entries().await
+    }
+
+    /// Backfill from the library's metadata records (downloads made before
+    /// the archive existed). Returns how many entries were new.
+    pub async fn import_metadata(&self, metadata: &MetadataManager) -> Result<usize> {
+        let mut added = 0;
+        for record in metadata.list_all().await? {
+            let key = ArchiveKey::new(&record.source_platform, &record.video_id);
+            if key.video_id.is_empty() {
+                continue;
+            }
+            let entry = ArchiveRecord {
+                extractor: key.extractor,
+                video_id: key.video_id,
+                title: record.title,
+                url: record.url,
+                added_at: record.download_date,
+            };
+            if self.db.save_archive_entry(&entry).await? {
+                added += 1;
+            }
+        }
+        Ok(added)
+    }
+
+    /// Merge a yt-dlp archive file. Returns how many entries were new.
+    pub async fn import_ytdlp(&self, path: &Path) -> Result<usize> {
+        let contents = tokio::fs::read_to_string(path)
+            .await
+            .with_context(|| format!("Failed to read archive {}", path.display()))?;
+        let mut added = 0;
+        for key in contents.lines().filter_map(ArchiveKey::parse_line) {
+            let entry = ArchiveRecord {
+                extractor: key.extractor,
+                video_id: key.video_id,
+                title: String::new(),
+                url: String::new(),
+                added_at: Utc::now(),
+            };
+            if self.db.save_archive_entry(&entry).await? {
+                added += 1;
+            }
+        }
+        Ok(added)
+    }
+
+    /// Write the archive as a yt-dlp archive file. Returns the entry count.
+    pub async fn export_ytdlp(&self, path: &Path) -> Result<usize> {
+        let entries = self.entries().await?;
+        let contents: String = entries
+            .iter()
+            .map(|e| format!("{}\n", ArchiveKey::new(&e.extractor, &e.video_id)))
+            .collect();
+        tokio::fs::write(path, contents)
+            .await
+            .with_context(|| format!("Failed to write archive {}", path.display()))?;
+        Ok(entries.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::initialize_database;
+
+    async fn archive(dir: &Path) -> DownloadArchive {
+        let url = format!("sqlite://{}?mode=rwc", dir.join("archive.db").display());
+        let pool = initialize_database(&url).await.unwrap();
+        DownloadArchive::new(Arc::new(DatabaseManager::new(pool)))
+    }
+
+    fn video(id: &str, extractor: Option<&str>, url: &str) -> VideoInfo {
+        VideoInfo {
+            id: id.to_string(),
+            title: format!("Video {id}"),
+            url: url.to_string(),
+            extractor: extractor.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn keys_match_ytdlp_archive_lines() {
+        let full = video("dQw4w9WgXcQ", Some("Youtube"), "");
+        let flat = video(
+            "dQw4w9WgXcQ",
+            None,
+            "https://www.youtube.com/watch?v=dQw4w9WgXcQ",
+        );
+        let key = ArchiveKey::for_video(&full).unwrap();
+        assert_eq!(key.to_string(), "youtube dQw4w9WgXcQ");
+        assert_eq!(ArchiveKey::for_video(&flat), Some(key.clone()));
+        assert_eq!(
+            ArchiveKey::parse_line("  youtube   dQw4w9WgXcQ "),
+            Some(key)
+        );
+        assert_eq!(
+            ArchiveKey::new("twitch:vod", "v1").to_string(),
+            "twitchvod v1"
+        );
+        assert!(ArchiveKey::parse_line("").is_none());
+        assert!(ArchiveKey::parse_line("youtube").is_none());
+        assert!(ArchiveKey::for_video(&video("", Some("youtube"), "")).is_none());
+    }
+
+    #[test]
+    fn direct_and_generic_videos_are_keyed_by_url() {
+        let a = video("video", Some("direct"), "https://a.com/1/video.mp4");
+        let b = video("video", Some("direct"), "https://b.org/x/video.mp4");
+        let key = ArchiveKey::for_video(&a).unwrap();
+        assert_eq!(key.extractor, "direct");
+        assert_ne!(Some(key.clone()), ArchiveKey::for_video(&b));
+        // The same file behind a trivially different link is the same video.
+        let again = video("video", Some("direct"), "https://A.com/1/video.mp4#t=5");
+        assert_eq!(ArchiveKey::for_video(&again), Some(key));
+
+        let index = |url: &str| ArchiveKey::for_video(&video("index", Some("generic"), url));
+        assert_ne!(index("https://a.com/"), index("https://b.org/"));
+        assert!(index("").is_none());
+    }
+
+    #[test]
+    fn policies_decide_what_happens_to_archived_videos() {
+        let record = ArchiveRecord {
+            extractor: "youtube".to_string(),
+            video_id: "abc".to_string(),
+            title: "Clip".to_string(),
+            url: String::new(),
+            added_at: Utc::now(),
+        };
+        assert!(ArchivePolicy::Force.on_archived(record.clone()).is_none());
+        let skipped = ArchivePolicy::Skip.on_archived(record.clone()).unwrap();
+        assert!(matches!(skipped, AddOutcome::Skipped(_)));
+        let err = skipped.into_task_id().unwrap_err();
+        assert!(err.to_string().contains("Clip (youtube abc)"));
+        assert!(matches!(
+            ArchivePolicy::Ask.on_archived(record),
+            Some(AddOutcome::NeedsConfirmation(_))
+        ));
+        assert_eq!("ASK".parse(), Ok(ArchivePolicy::Ask));
+        assert!("maybe".parse::<ArchivePolicy>().is_err());
+    }
+
+    #[tokio::test]
+    async fn records_and_round_trips_ytdlp_archive_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = archive(dir.path()).await;
+
+        let clip = video(
+            "abc",
+            Some("youtube"),
+            "https://www.youtube.com/watch?v=abc",
+        );
+        assert!(archive.lookup(&clip).await.unwrap().is_none());
+        assert!(archive.record(&clip).await.unwrap());
+        assert!(!archive.record(&clip).await.unwrap(), "already archived");
+        assert_eq!(
+            archive.lookup(&clip).await.unwrap().unwrap().title,
+            "Video abc"
+        );
+
+        let imported = dir.path().join("yt-dlp-archive.txt");
+        std::fs::write(&imported, "youtube abc\nvimeo 12345\n\nnot a line at all\n").unwrap();
+        assert_eq!(archive.import_ytdlp(&imported).await.unwrap(), 1);
+        assert!(archive
+            .lookup(&video("12345", Some("Vimeo"), ""))
+            .await
+            .unwrap()
+            .is_some());
+
+        let exported = dir.path().join("export.txt");
+        assert_eq!(archive.export_ytdlp(&exported).await.unwrap(), 2);
+        assert_eq!(
+            std::fs::read_to_string(&exported).unwrap(),
+            "youtube abc\nvimeo 12345\n"
+        );
+
+        archive
+            .forget(&ArchiveKey::new("youtube", "abc"))
+            .await
+            .unwrap();
+        assert!(archive.lookup(&clip).await.unwrap().is_none());
+    }
+}
@@ -1,4 +1,5 @@
 use crate::extractor::{Format, VideoInfo};
+use crate::queue::TaskBatch;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
@@ -23,6 +24,15 @@ pub enum QueueEvent {
         format: Box<Format>,
         output_path: PathBuf,
         timestamp: DateTime<Utc>,
+        /// Absent in logs written before playlist batches existed.
+        #[serde(default)]
+        batch: Option<TaskBatch>,
+        /// Absent in logs written before per-task target folders existed.
+        #[serde(default)]
+        target_dir: Option<PathBuf>,
+        /// Absent in logs written before the format picker existed.
+        #[serde(default)]
+        requested_format: Option<String>,
     },
     /// A task started downloading
     TaskStarted {
@@ -7,20 +7,28 @@
     unused_mut
 )]
 
-use super::{EventLog, QueueEvent};
+use super::{AddOutcome, ArchivePolicy, DownloadArchive, EventLog, QueueEvent};
+use crate::database::ArchiveRecord;
+use crate::downloader::chapters::{chapter_info, embed_chapters, split_chapters};
 use crate::downloader::resume_guard::{remove_sidecar, sidecar_path};
-use crate::downloader::{DownloadEngine, DownloadProgress};
+use crate::downloader::thumbnail::embed_cover_art;
+use crate::downloader::{
+    CallbackSink, ChapterMode, DownloadEngine, DownloadProgress, DownloadStatus, ProgressSink,
+    SharedProgressSink, ThumbnailCache, WatchSink,
+};
 use crate::extractor::{Format, VideoInfo};
 use crate::utils::error::RustloaderError;
 use crate::utils::{ContentType, FileOrganizer, MetadataManager, VideoMetadata};
 use anyhow::Result;
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::{mpsc, Mutex};
+use tokio::sync::{broadcast, mpsc, Mutex};
 use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
 
 /// Download queue manager with concurrent download support
@@ -32,6 +40,22 @@ pub struct QueueManager {
     file_organizer: Arc<FileOrganizer>,
     metadata_manager: Arc<MetadataManager>,
     event_log: Arc<EventLog>,
+    progress_events: broadcast::Sender<TaskProgress>,
+    observers: Arc<std::sync::Mutex<HashMap<String, Vec<SharedProgressSink>>>>,
+    archive: Option<Arc<DownloadArchive>>,
+    thumbnails: Option<Arc<ThumbnailCache>>,
+    chapter_mode: ChapterMode,
+}
+
+/// Capacity of the queue-wide progress broadcast; lagging subscribers skip
+/// ahead instead of slowing transfers down.
+const PROGRESS_EVENT_CAPACITY: usize = 256;
+
+/// A progress update tagged with the task it belongs to.
+#[derive(Debug, Clone)]
+pub struct TaskProgress {
+    pub task_id: String,
+    pub progress: DownloadProgress,
 }
 
 /// Download task
@@ -44,6 +68,27 @@ pub struct DownloadTask {
     pub status: TaskStatus,
     pub progress: Option<DownloadProgress>,
     pub added_at: DateTime<Utc>,
+    /// The playlist batch this task was expanded from, if any.
+    pub batch: Option<TaskBatch>,
+    /// Move the finished file into this folder as-is instead of organizing
+    /// it into the library.
+    pub target_dir: Option<PathBuf>,
+    /// The format the user picked (a yt-dlp `-f` selection such as `18` or
+    /// `137+140`), passed on when the download goes through yt-dlp. `None`
+    /// leaves the choice to the engine.
+    pub requested_format: Option<String>,
+}
+
+/// Groups the tasks expanded from one playlist so the queue can show (and
+/// organize) them together.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskBatch {
+    /// Shared by every task of the batch.
+    pub id: String,
+    /// The playlist title.
+    pub name: String,
+    /// 1-based position of the entry in the playlist.
+    pub index: usize,
 }
 
 /// Task status
@@ -51,6 +96,9 @@ pub struct DownloadTask {
 pub enum TaskStatus {
     #[default]
     Queued,
+    /// A scheduled stream or premiere that has not started yet. It holds no
+    /// download slot and is queued again once it starts.
+    Waiting,
     Downloading,
     Paused,
     Completed,
@@ -64,6 +112,8 @@ struct DownloadHandle {
     join_handle: JoinHandle<()>,
     progress_handle: JoinHandle<()>,
     cancel_tx: mpsc::Sender<()>,
+    /// Ends a live recording, keeping what was recorded (cancel discards it).
+    stop: CancellationToken,
     // snapshot of the task for monitoring
     task: DownloadTask,
 }
@@ -85,6 +135,68 @@ impl QueueManager {
             file_organizer: Arc::new(file_organizer),
             metadata_manager: Arc::new(metadata_manager),
             event_log,
+            progress_events: broadcast::channel(PROGRESS_EVENT_CAPACITY).0,
+            observers: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            archive: None,
+            thumbnails: None,
+            chapter_mode: ChapterMode::None,
+        }
+    }
+
+    /// Check new tasks against `archive` and record finished ones in it.
+    pub fn with_archive(mut self, archive: Arc<DownloadArchive>) -> Self {
+        self.archive = Some(archive);
+        self
+    }
+
+    /// Give finished files their thumbnail from `thumbnails`: embedded as
+    /// cover art, as a sidecar, and as playlist/series artwork.
+    pub fn with_thumbnails(mut self, thumbnails: Arc<ThumbnailCache>) -> Self {
+        self.thumbnails = Some(thumbnails);
+        self
+    }
+
+    /// Embed the chapters of finished videos as chapter markers, or split
+    /// the videos into one file per chapter.
+    pub fn with_chapter_mode(mut self, chapter_mode: ChapterMode) -> Self {
+        self.chapter_mode = chapter_mode;
+        self
+    }
+
+    /// The archive entry for `video_info`, if it was downloaded before. A
+    /// failed lookup is logged and treated as not downloaded.
+    pub async fn archived(&self, video_info: &VideoInfo) -> Option<ArchiveRecord> {
+        let archive = self.archive.as_ref()?;
+        archive.lookup(video_info).await.unwrap_or_else(|e| {
+            warn!("Download archive lookup failed: {}", e);
+            None
+        })
+    }
+
+    /// Subscribe to progress updates of every task, as the engine reports
+    /// them. A subscriber that falls behind sees `Lagged` and skips ahead.
+    pub fn subscribe_progress(&self) -> broadcast::Receiver<TaskProgress> {
+        self.progress_events.subscribe()
+    }
+
+    /// Attach a sink to one task's progress. It stays attached across
+    /// pause/resume and is dropped when the task is removed or cleared.
+    pub fn observe_task(&self, task_id: &str, sink: SharedProgressSink) {
+        self.observers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .entry(task_id.to_string())
+            .or_default()
+            .push(sink);
+    }
+
+    fn drop_observers<'a>(&self, task_ids: impl IntoIterator<Item = &'a str>) {
+        let mut observers = self
+            .observers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        for task_id in task_ids {
+            observers.remove(task_id);
         }
     }
 
@@ -105,6 +217,9 @@ impl QueueManager {
                     format,
                     output_path,
                     timestamp,
+                    batch,
+                    target_dir,
+                    requested_format,
                 } => {
                     // Create task with fully restored format
                     tasks.insert(
@@ -117,6 +232,9 @@ impl QueueManager {
                             status: TaskStatus::Queued,
                             progress: None,
                             added_at: timestamp,
+                            batch,
+                            target_dir,
+                            requested_format,
                         },
                     );
                 }
@@ -181,8 +299,34 @@ impl QueueManager {
         Ok(())
     }
 
-    /// Add task to queue
+    /// Add task to queue. A video that is already in the download archive
+    /// is an `AlreadyDownloaded` error; see [`add_task_with`](Self::add_task_with)
+    /// to choose per call.
     pub async fn add_task(&self, task: DownloadTask) -> Result<String> {
+        self.add_task_with(task, ArchivePolicy::Skip)
+            .await?
+            .into_task_id()
+    }
+
+    /// Add task to queue, deciding with `policy` what happens if its video
+    /// was downloaded before.
+    pub async fn add_task_with(
+        &self,
+        task: DownloadTask,
+        policy: ArchivePolicy,
+    ) -> Result<AddOutcome> {
+        if policy != ArchivePolicy::Force {
+            if let Some(record) = self.archived(&task.video_info).await {
+                if let Some(outcome) = policy.on_archived(record) {
+                    info!(
+                        "Not queuing {}: already downloaded ({})",
+                        task.video_info.title, policy
+                    );
+                    return Ok(outcome);
+                }
+            }
+        }
+
         let task_id = task.id.clone();
 
         debug!("📥 [QUEUE] add_task called for: {}", task_id);
@@ -191,6 +335,9 @@ impl QueueManager {
         let log_video_info = task.video_info.clone();
         let log_format = task.format.clone();
         let log_output_path = task.output_path.clone();
+        let log_batch = task.batch.clone();
+        let log_target_dir = task.target_dir.clone();
+        let log_requested_format = task.requested_format.clone();
 
         // Add to queue
         {
@@ -223,13 +370,16 @@ impl QueueManager {
                 format: Box::new(log_format),
                 output_path: log_output_path,
                 timestamp: Utc::now(),
+                batch: log_batch,
+                target_dir: log_target_dir,
+                requested_format: log_requested_format,
             })
             .await
         {
             error!("Failed to log TaskAdded event: {}", e);
         }
 
-        Ok(task_id)
+        Ok(AddOutcome::Queued(task_id))
     }
 
     /// Start processing queue
@@ -352,6 +502,18 @@ impl QueueManager {
         Ok(())
     }
 
+    /// End the live recording of `task_id` now. Unlike cancelling, what was
+    /// recorded so far is finalized and filed like a finished download.
+    pub async fn stop_recording(&self, task_id: &str) -> Result<()> {
+        let active = self.active_downloads.lock().await;
+        let handle = active
+            .get(task_id)
+            .ok_or_else(|| RustloaderError::TaskNotFound(task_id.to_string()))?;
+        info!("Stopping the recording of task {}", task_id);
+        handle.stop.cancel();
+        Ok(())
+    }
+
     /// Cancel task
     pub async fn cancel_task(&self, task_id: &str) -> Result<()> {
         // LOCKING HIERARCHY: queue (Level 2) -> active (Level 1)
@@ -412,9 +574,24 @@ impl QueueManager {
         queue.iter().cloned().collect()
     }
 
+    /// Current status of one task, if it is in the queue.
+    pub async fn task_status(&self, task_id: &str) -> Option<TaskStatus> {
+        let queue = self.queue.lock().await;
+        queue
+            .iter()
+            .find(|t| t.id == task_id)
+            .map(|t| t.status.clone())
+    }
+
     /// Clear completed tasks
     pub async fn clear_completed(&self) -> Result<()> {
         let mut queue = self.queue.lock().await;
+        self.drop_observers(
+            queue
+                .iter()
+                .filter(|task| task.status == TaskStatus::Completed)
+                .map(|task| task.id.as_str()),
+        );
         queue.retain(|task| task.status != TaskStatus::Completed);
 
         let mut active = self.active_downloads.lock().await;
@@ -472,6 +649,7 @@ impl QueueManager {
             let mut active = self.active_downloads.lock().await;
             active.remove(task_id);
         }
+        self.drop_observers([task_id]);
 
         info!("Removed task {} from queue and active downloads", task_id);
 
@@ -583,6 +761,33 @@ impl QueueManager {
             }
         }
 
+        // Scheduled streams wait here rather than in a download slot, and
+        // are queued again once they are due to start.
+        let now = Utc::now();
+        let wait_for_start = self.engine.live_options().wait_for_start;
+        for task in queue.iter_mut() {
+            let starts_at = task
+                .video_info
+                .scheduled_start()
+                .filter(|at| wait_for_start && *at > now);
+            match (&task.status, starts_at) {
+                (TaskStatus::Queued, Some(starts_at)) => {
+                    info!("⏳ [QUEUE] Task {} waits for {}", task.id, starts_at);
+                    task.status = TaskStatus::Waiting;
+                    let mut waiting = DownloadProgress::new(0, 1);
+                    waiting.status = DownloadStatus::Waiting;
+                    waiting.starts_at = Some(starts_at);
+                    task.progress = Some(waiting.clone());
+                    let _ = self.progress_events.send(TaskProgress {
+                        task_id: task.id.clone(),
+                        progress: waiting,
+                    });
+                }
+                (TaskStatus::Waiting, None) => task.status = TaskStatus::Queued,
+                _ => {}
+            }
+        }
+
         // Count active downloads from state
         let active_count = queue
             .iter()
@@ -618,6 +823,7 @@ impl QueueManager {
                     join_handle: tokio::spawn(async {}), // Dummy, will be replaced
                     progress_handle: tokio::spawn(async {}), // Dummy, will be replaced
                     cancel_tx,
+                    stop: CancellationToken::new(),
                     task: task.clone(),
                 };
                 active.insert(task.id.clone(), placeholder_handle);
@@ -671,16 +877,39 @@ impl QueueManager {
         let task_id = task.id.clone();
         let output_path = task.output_path.clone();
         let url = task.format.url.clone();
+        let requested_format = task.requested_format.clone();
 
         info!("💾 [DOWNLOAD] start_download called for: {}", task_id);
         debug!("   - URL: {}", url);
         debug!("   - Output: {:?}", output_path);
 
-        // Create channels for progress updates and cancellation
-        let (progress_tx, mut progress_rx) = mpsc::channel::<DownloadProgress>(100);
+        // Progress flows engine → sink without back-pressure: the queue's
+        // own snapshot only needs the latest value (watch), while the
+        // queue-wide broadcast and any per-task observers see every update.
+        let (watch_sink, mut progress_rx) = WatchSink::channel();
+        let progress_events = self.progress_events.clone();
+        let observers = Arc::clone(&self.observers);
+        let task_id_for_sink = task_id.clone();
+        let progress_sink = CallbackSink::new(move |progress: &DownloadProgress| {
+            watch_sink.report(progress);
+            let _ = progress_events.send(TaskProgress {
+                task_id: task_id_for_sink.clone(),
+                progress: progress.clone(),
+            });
+            let task_observers = observers
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .get(&task_id_for_sink)
+                .cloned()
+                .unwrap_or_default();
+            for observer in task_observers {
+                observer.report(progress);
+            }
+        });
         let (cancel_tx, mut cancel_rx) = mpsc::channel::<()>(1);
+        let stop = CancellationToken::new();
 
-        debug!("   - Created progress channel");
+        debug!("   - Created progress sink");
         debug!("   - Starting download engine...");
 
         // Update task status
@@ -698,6 +927,9 @@ impl QueueManager {
         // Clone file organizer and metadata manager for the spawned task
         let file_organizer = Arc::clone(&self.file_organizer);
         let metadata_manager = Arc::clone(&self.metadata_manager);
+        let archive = self.archive.clone();
+        let thumbnails = self.thumbnails.clone();
+        let chapter_mode = self.chapter_mode;
         let event_log = Arc::clone(&self.event_log); // CLONE EVENT LOG
 
         // Clone a snapshot of the task to keep in the active map
@@ -722,7 +954,11 @@ impl QueueManager {
                 task_id_for_progress
             );
             loop {
-                match progress_rx.recv().await {
+                let update = match progress_rx.changed().await {
+                    Ok(()) => progress_rx.borrow_and_update().clone(),
+                    Err(_) => None,
+                };
+                match update {
                     Some(progress) => {
                         debug!(
                             "🔁 [PROGRESS] Received for {}: {:.2}%",
@@ -760,6 +996,7 @@ impl QueueManager {
                                     join_handle: dummy_join,
                                     progress_handle: dummy_progress_handle,
                                     cancel_tx: dummy_cancel_tx,
+                                    stop: CancellationToken::new(),
                                     task: task_snapshot_for_progress.clone(),
                                 },
                             );
@@ -775,7 +1012,7 @@ impl QueueManager {
                     }
                     None => {
                         debug!(
-                            "📡 [PROGRESS] progress_rx closed (None) for: {}",
+                            "📡 [PROGRESS] progress sink dropped for: {}",
                             task_id_for_progress
                         );
                         break;
@@ -813,6 +1050,7 @@ impl QueueManager {
         // Prepare a clone of the task id for the spawned closure so we don't move the
         // original `task_id_for_spawn` (we need it later for a debug print).
         let task_id_for_closure = task_id_for_spawn.clone();
+        let stop_for_handle = stop.clone();
 
         let join_handle = tokio::spawn(async move {
             debug!(
@@ -824,7 +1062,30 @@ impl QueueManager {
             let mut cancelled = false;
 
             // Create a future that completes when either the download finishes or is cancelled
-            let download_task = engine.download(&url, &output_path, progress_tx.clone());
+            // Live streams and scheduled premieres are recorded until they
+            // end (or are stopped) rather than downloaded.
+            let download_task = async {
+                if task.video_info.live().needs_recording() {
+                    engine
+                        .record_live(
+                            &task.video_info,
+                            &output_path,
+                            requested_format.as_deref(),
+                            stop,
+                            progress_sink,
+                        )
+                        .await
+                } else {
+                    engine
+                        .download_format(
+                            &url,
+                            &output_path,
+                            requested_format.as_deref(),
+                            progress_sink,
+                        )
+                        .await
+                }
+            };
             let cancel_task = cancel_rx.recv();
 
             tokio::select! {
@@ -852,6 +1113,8 @@ impl QueueManager {
                             match Self::organize_completed_file_static(
                                 file_organizer.clone(),
                                 metadata_manager.clone(),
+                                thumbnails.as_deref(),
+                                chapter_mode,
                                 &task,
                                 &saved_path,
                             ).await {
@@ -861,6 +1124,12 @@ impl QueueManager {
                                     task.status = TaskStatus::Completed;
                                     info!("Task {} completed and organized successfully", task_id_for_closure);
 
+                                    if let Some(archive) = &archive {
+                                        if let Err(e) = archive.record(&task.video_info).await {
+                                            warn!("Failed to record {} in the download archive: {}", task_id_for_closure, e);
+                                        }
+                                    }
+
                                     // LOG EVENT
                                     let _ = event_log.log(QueueEvent::TaskCompleted {
                                         task_id: task_id_for_closure.clone(),
@@ -882,6 +1151,12 @@ impl QueueManager {
 
                                     warn!("Task {} completed but organization failed: {}. File at: {:?}",
                                           task_id_for_closure, e, saved_path);
+
+                                    if let Some(archive) = &archive {
+                                        if let Err(e) = archive.record(&task.video_info).await {
+                                            warn!("Failed to record {} in the download archive: {}", task_id_for_closure, e);
+                                        }
+                                    }
                                 }
                             }
                         }
@@ -965,6 +1240,7 @@ impl QueueManager {
                 handle.join_handle = join_handle;
                 handle.progress_handle = progress_handler;
                 handle.cancel_tx = cancel_tx;
+                handle.stop = stop_for_handle;
                 handle.task = task_for_handle.clone();
                 debug!(
                     "✅ [DOWNLOAD] Updated active_downloads entry for: {}",
@@ -1052,6 +1328,8 @@ impl QueueManager {
     async fn organize_completed_file_static(
         file_organizer: Arc<FileOrganizer>,
         metadata_manager: Arc<MetadataManager>,
+        thumbnails: Option<&ThumbnailCache>,
+        chapter_mode: ChapterMode,
         task: &DownloadTask,
         downloaded_file_path: &std::path::Path,
     ) -> Result<PathBuf> {
@@ -1073,30 +1351,105 @@ impl QueueManager {
         let quality = Self::determine_quality_string_static(&task.format);
         debug!("   - Detected quality: {}", quality);
 
-        // Determine content type (default to Video for now)
-        let content_type = ContentType::Video;
+        let content_type = task.content_type();
 
-        // Organize the file (move to proper location)
-        let final_path = file_organizer
-            .organize_file(
+        let chapters = &task.video_info.chapters;
+        let parts = match chapter_mode {
+            ChapterMode::Split if !chapters.is_empty() => split_chapters(
                 downloaded_file_path,
                 &task.video_info,
-                &quality,
-                &content_type,
+                &downloaded_file_path.with_extension("chapters"),
             )
             .await
-            .map_err(|e| {
-                error!("❌ [ORGANIZE] Failed to organize file: {}", e);
-                anyhow::anyhow!("Failed to organize file: {}", e)
-            })?;
-
-        info!("✅ [ORGANIZE] File organized at: {:?}", final_path);
+            .unwrap_or_else(|e| {
+                warn!(
+                    "Could not split {:?} into chapters: {}",
+                    downloaded_file_path, e
+                );
+                Vec::new()
+            }),
+            ChapterMode::Embed => {
+                match embed_chapters(downloaded_file_path, chapters, task.video_info.duration).await
+                {
+                    Ok(true) => debug!("Embedded {} chapters", chapters.len()),
+                    Ok(false) => {}
+                    Err(e) => warn!("Could not embed chapters: {}", e),
+                }
+                Vec::new()
+            }
+            _ => Vec::new(),
+        };
 
-        // Get file size
-        let file_size = tokio::fs::metadata(&final_path)
-            .await
-            .map(|m| m.len())
-            .unwrap_or(0);
+        // Organize the file (move to proper location), unless the task asked
+        // for a specific folder. Chapters of a split video go together, as a
+        // playlist named after the video.
+        let (final_path, file_size) = if parts.is_empty() {
+            let final_path = Self::place_file(
+                &file_organizer,
+                thumbnails,
+                task,
+                &task.video_info,
+                &quality,
+                &content_type,
+                task.target_dir.as_deref(),
+                downloaded_file_path,
+            )
+            .await?;
+            let file_size = tokio::fs::metadata(&final_path)
+                .await
+                .map(|m| m.len())
+                .unwrap_or(0);
+            (final_path, file_size)
+        } else {
+            let collection = ContentType::Playlist {
+                name: task.video_info.title.clone(),
+            };
+            let target_dir = task
+                .target_dir
+                .as_ref()
+                .map(|dir| dir.join(FileOrganizer::sanitize_filename(&task.video_info.title)));
+            let mut placed = Vec::with_capacity(parts.len());
+            let mut file_size = 0;
+            for (index, part) in parts.iter().enumerate() {
+                let path = match Self::place_file(
+                    &file_organizer,
+                    thumbnails,
+                    task,
+                    &chapter_info(&task.video_info, index),
+                    &quality,
+                    &collection,
+                    target_dir.as_deref(),
+                    part,
+                )
+                .await
+                {
+                    Ok(path) => path,
+                    Err(e) => {
+                        // Leave no half a video in the library behind a
+                        // failed task; the download itself is still there.
+                        warn!(
+                            "Placing chapter {} of {:?} failed; removing the {} placed before it",
+                            index + 1,
+                            downloaded_file_path,
+                            placed.len()
+                        );
+                        Self::remove_placed_parts(&placed, &parts).await;
+                        return Err(e);
+                    }
+                };
+                file_size += tokio::fs::metadata(&path)
+                    .await
+                    .map(|m| m.len())
+                    .unwrap_or(0);
+                placed.push(path);
+            }
+            let _ = tokio::fs::remove_file(downloaded_file_path).await;
+            if let Some(dir) = parts[0].parent() {
+                let _ = tokio::fs::remove_dir(dir).await;
+            }
+            info!("✅ [ORGANIZE] Split into {} chapter files", placed.len());
+            (placed.swap_remove(0), file_size)
+        };
 
         // Extract video ID
         let video_id = FileOrganizer::extract_video_id(&task.video_info.url)
@@ -1140,6 +1493,113 @@ impl QueueManager {
         Ok(final_path)
     }
 
+    /// Undo a split that could not be placed in full: delete the chapter
+    /// files already placed (with their thumbnails, and their folder once
+    /// empty) and the split parts not yet moved.
+    async fn remove_placed_parts(placed: &[PathBuf], parts: &[PathBuf]) {
+        for path in placed {
+            let _ = tokio::fs::remove_file(path).await;
+            let sidecar = path.with_extension("jpg");
+            if &sidecar != path {
+                let _ = tokio::fs::remove_file(sidecar).await;
+            }
+        }
+        if let Some(dir) = placed.first().and_then(|p| p.parent()) {
+            let _ = tokio::fs::remove_dir(dir).await;
+        }
+        for part in parts {
+            let _ = tokio::fs::remove_file(part).await;
+        }
+        if let Some(dir) = parts.first().and_then(|p| p.parent()) {
+            let _ = tokio::fs::remove_dir(dir).await;
+        }
+    }
+
+    /// Move `file` (the task's video, or one of its chapters, described by
+    /// `info`) into `target_dir`, or where the organizer files
+    /// `content_type`, and give it the task's thumbnail.
+    #[allow(clippy::too_many_arguments)]
+    async fn place_file(
+        file_organizer: &FileOrganizer,
+        thumbnails: Option<&ThumbnailCache>,
+        task: &DownloadTask,
+        info: &VideoInfo,
+        quality: &str,
+        content_type: &ContentType,
+        target_dir: Option<&Path>,
+        file: &Path,
+    ) -> Result<PathBuf> {
+        let final_path = match target_dir {
+            Some(dir) => Self::move_into_dir(file, dir).await?,
+            None => file_organizer
+                .organize_file(file, info, quality, content_type)
+                .await
+                .map_err(|e| {
+                    error!("❌ [ORGANIZE] Failed to organize file: {}", e);
+                    anyhow::anyhow!("Failed to organize file: {}", e)
+                })?,
+        };
+
+        info!("✅ [ORGANIZE] File organized at: {:?}", final_path);
+
+        if let Some(thumbnails) = thumbnails {
+            let collection = target_dir.is_none().then_some(content_type);
+            Self::apply_thumbnail(thumbnails, file_organizer, task, &final_path, collection).await;
+        }
+        Ok(final_path)
+    }
+
+    /// Embed the task's thumbnail into `file` and write it next to it (and
+    /// as the `collection`'s artwork). Best-effort: a missing thumbnail or
+    /// ffmpeg never fails the download.
+    async fn apply_thumbnail(
+        thumbnails: &ThumbnailCache,
+        file_organizer: &FileOrganizer,
+        task: &DownloadTask,
+        file: &Path,
+        collection: Option<&ContentType>,
+    ) {
+        let cover = match thumbnails.fetch(&task.id, &task.video_info).await {
+            Ok(cover) => cover,
+            Err(e) => {
+                debug!("No thumbnail for task {}: {}", task.id, e);
+                return;
+            }
+        };
+        match embed_cover_art(file, &cover).await {
+            Ok(true) => debug!("Embedded cover art into {:?}", file),
+            Ok(false) => {}
+            Err(e) => warn!("Could not embed cover art into {:?}: {}", file, e),
+        }
+        FileOrganizer::write_thumbnail_sidecar(file, &cover).await;
+        if let Some(content_type) = collection {
+            file_organizer
+                .write_collection_art(content_type, &cover)
+                .await;
+        }
+    }
+
+    /// Move a finished file into `dir`, keeping its name. Already in place is
+    /// a no-op.
+    async fn move_into_dir(file: &Path, dir: &Path) -> Result<PathBuf> {
+        let file_name = file
+            .file_name()
+            .ok_or_else(|| anyhow::anyhow!("Downloaded file has no name: {:?}", file))?;
+        let target = dir.join(file_name);
+        if target == file {
+            return Ok(target);
+        }
+        tokio::fs::create_dir_all(dir).await?;
+        if let Err(e) = tokio::fs::rename(file, &target).await {
+            // Across filesystems a rename fails; copy and remove instead.
+            debug!("rename into {:?} failed ({}); copying", dir, e);
+            tokio::fs::copy(file, &target).await?;
+            tokio::fs::remove_file(file).await?;
+        }
+        FileOrganizer::move_subtitle_sidecars(file, &target).await;
+        Ok(target)
+    }
+
     /// Extract quality string from format (static version)
     fn determine_quality_string_static(format: &Format) -> String {
         // Try height first
@@ -1184,6 +1644,32 @@ impl DownloadTask {
             status: TaskStatus::Queued,
             progress: None,
             added_at: Utc::now(),
+            batch: None,
+            target_dir: None,
+            requested_format: None,
+        }
+    }
+
+    /// Deliver the finished file to `dir` instead of the organized library.
+    pub fn with_target_dir(mut self, dir: PathBuf) -> Self {
+        self.target_dir = Some(dir);
+        self
+    }
+
+    /// Mark the task as one entry of a playlist batch.
+    pub fn with_batch(mut self, batch: TaskBatch) -> Self {
+        self.batch = Some(batch);
+        self
+    }
+
+    /// How the finished file is organized: playlist entries go under their
+    /// playlist's folder, everything else is a plain video.
+    pub fn content_type(&self) -> ContentType {
+        match &self.batch {
+            Some(batch) => ContentType::Playlist {
+                name: batch.name.clone(),
+            },
+            None => ContentType::Video,
         }
     }
 }
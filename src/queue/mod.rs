@@ -1,5 +1,7 @@
+pub mod archive;
 pub mod events;
 pub mod manager;
 
+pub use archive::{AddOutcome, ArchiveKey, ArchivePolicy, DownloadArchive};
 pub use events::{EventLog, QueueEvent};
-pub use manager::{DownloadTask, QueueManager, TaskStatus};
+pub use manager::{DownloadTask, QueueManager, TaskBatch, TaskProgress, TaskStatus};
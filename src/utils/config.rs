@@ -1,6 +1,8 @@
 //! Application configuration
 #![allow(unused_imports)]
 
+use crate::downloader::{ChapterMode, LiveOptions};
+use crate::extractor::{FormatPreferences, SubtitleOptions};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
@@ -44,6 +46,27 @@ pub struct AppSettings {
     /// confirmation). Privacy-sensitive, so it defaults to OFF.
     #[serde(default)]
     pub clipboard_monitoring: bool,
+
+    /// Height, codec, container, HDR and audio-language preferences for
+    /// every download.
+    #[serde(default)]
+    pub format_preferences: FormatPreferences,
+
+    /// Download subtitles with every video, as `subtitle_options` says.
+    #[serde(default)]
+    pub download_subtitles: bool,
+
+    /// Subtitle languages, format and embedding.
+    #[serde(default)]
+    pub subtitle_options: SubtitleOptions,
+
+    /// Embed the chapters of finished videos, or split them per chapter.
+    #[serde(default)]
+    pub chapter_mode: ChapterMode,
+
+    /// How live streams are recorded and whether to wait for scheduled ones.
+    #[serde(default)]
+    pub live_options: LiveOptions,
 }
 
 impl Default for AppSettings {
@@ -62,6 +85,11 @@ impl Default for AppSettings {
             cookies_from_browser: None,
             cookies_file: None,
             clipboard_monitoring: false,
+            format_preferences: FormatPreferences::default(),
+            download_subtitles: false,
+            subtitle_options: SubtitleOptions::default(),
+            chapter_mode: ChapterMode::None,
+            live_options: LiveOptions::default(),
         }
     }
 }
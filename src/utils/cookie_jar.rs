@@ -0,0 +1,785 @@
+//! Cookie jar for the native download engine's own HTTP requests.
+//!
+//! [`CookieConfig`] only becomes yt-dlp arguments; requests the engine makes
+//! itself (the probe, segments, simple downloads) would otherwise go out
+//! without cookies and get a 403 or a login page from authenticated sites.
+//! [`CookieJar`] loads the same sources — a Netscape `cookies.txt` and, where
+//! the store is readable without the browser's keyring, the browser's own
+//! cookie database (Firefox) — and serves them to `reqwest` as a
+//! [`CookieStore`], scoped by domain, path and `Secure` the way a browser
+//! would. Cookies set by responses are kept too, for the rest of the session.
+//!
+//! The jar is shared (`Arc`) by every client that should send the user's
+//! cookies, and [`CookieJar::watch`] reloads it when the source changes.
+
+use crate::utils::cookies::{
+    detect_browser_profiles_in, split_browser_source, BrowserDirs, CookieConfig,
+};
+use anyhow::{Context, Result};
+use publicsuffix::Psl;
+use reqwest::cookie::CookieStore;
+use reqwest::header::HeaderValue;
+use reqwest::Url;
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::{ConnectOptions, Row};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{debug, info, warn};
+
+/// One cookie, as stored in `cookies.txt` or a browser's database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cookie {
+    /// Lower-case host or domain, without a leading dot.
+    pub domain: String,
+    /// Also sent to subdomains of `domain` (a `.domain` entry); otherwise
+    /// host-only.
+    pub include_subdomains: bool,
+    pub path: String,
+    /// Only sent over `https`.
+    pub secure: bool,
+    /// Unix seconds; `None` for a session cookie.
+    pub expires: Option<i64>,
+    pub name: String,
+    pub value: String,
+}
+
+impl Cookie {
+    /// Whether this cookie goes with a request to `url` at `now` (Unix
+    /// seconds), following RFC 6265's domain- and path-match rules.
+    pub fn matches(&self, url: &Url, now: i64) -> bool {
+        let Some(host) = url.host_str() else {
+            return false;
+        };
+        let host = host.to_ascii_lowercase();
+        let domain_ok = host == self.domain
+            || (self.include_subdomains
+                && host
+                    .strip_suffix(self.domain.as_str())
+                    .is_some_and(|rest| rest.ends_with('.')));
+        domain_ok
+            && path_matches(url.path(), &self.path)
+            && (!self.secure || url.scheme() == "https")
+            && self.expires.is_none_or(|expires| expires > now)
+    }
+
+    fn same_slot(&self, other: &Cookie) -> bool {
+        self.name == other.name && self.domain == other.domain && self.path == other.path
+    }
+}
+
+/// RFC 6265 path-match: `cookie_path` is `request_path` or a prefix of it
+/// that ends at a `/`.
+fn path_matches(request_path: &str, cookie_path: &str) -> bool {
+    match request_path.strip_prefix(cookie_path) {
+        Some("") => true,
+        Some(rest) => cookie_path.ends_with('/') || rest.starts_with('/'),
+        None => false,
+    }
+}
+
+/// The default path for a cookie set without one: the request path up to,
+/// not including, its last `/` (RFC 6265 §5.1.4).
+fn default_path(url: &Url) -> String {
+    match url.path().rfind('/') {
+        Some(0) | None => "/".to_string(),
+        Some(i) => url.path()[..i].to_string(),
+    }
+}
+
+/// Parse a Netscape/Mozilla `cookies.txt`: tab-separated `domain`,
+/// `include-subdomains`, `path`, `secure`, `expires`, `name`, `value` per
+/// line. Comments and malformed lines are skipped; the `#HttpOnly_` prefix
+/// curl and yt-dlp write is understood.
+pub fn parse_netscape(text: &str) -> Vec<Cookie> {
+    text.lines()
+        .filter_map(|line| {
+            let line = line.trim_end_matches(['\r', '\n']);
+            let line = line.strip_prefix("#HttpOnly_").unwrap_or(line);
+            if line.starts_with('#') || line.trim().is_empty() {
+                return None;
+            }
+            let fields: Vec<&str> = line.split('\t').collect();
+            let [domain, subdomains, path, secure, expires, name, value] = fields[..] else {
+                return None;
+            };
+            let expires: i64 = expires.trim().parse().ok()?;
+            Some(Cookie {
+                include_subdomains: subdomains.eq_ignore_ascii_case("TRUE")
+                    || domain.starts_with('.'),
+                domain: domain.trim_start_matches('.').to_ascii_lowercase(),
+                path: if path.is_empty() { "/" } else { path }.to_string(),
+                secure: secure.eq_ignore_ascii_case("TRUE"),
+                expires: (expires > 0).then_some(expires),
+                name: name.to_string(),
+                value: value.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Parse one `Set-Cookie` header received from `url`. Returns `None` for a
+/// malformed header or one whose `Domain` does not cover `url`'s host.
+fn parse_set_cookie(header: &str, url: &Url, now: i64) -> Option<Cookie> {
+    let host = url.host_str()?.to_ascii_lowercase();
+    let mut parts = header.split(';');
+    let (name, value) = parts.next()?.split_once('=')?;
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+    let mut cookie = Cookie {
+        domain: host.clone(),
+        include_subdomains: false,
+        path: default_path(url),
+        secure: false,
+        expires: None,
+        name: name.to_string(),
+        value: value.trim().trim_matches('"').to_string(),
+    };
+    let mut max_age = None;
+    for attribute in parts {
+        let (key, value) = attribute.split_once('=').unwrap_or((attribute, ""));
+        let value = value.trim();
+        match key.trim().to_ascii_lowercase().as_str() {
+            "domain" if !value.is_empty() => {
+                let domain = value.trim_start_matches('.').to_ascii_lowercase();
+                let covers = host == domain
+                    || host
+                        .strip_suffix(domain.as_str())
+                        .is_some_and(|rest| rest.ends_with('.'));
+                if !covers {
+                    return None;
+                }
+                // A cookie for a public suffix (`com`, `co.uk`,
+                // `github.io`) would reach every site under it. Like
+                // browsers, keep it for its own host only, if it is one.
+                if is_public_suffix(&domain) {
+                    if domain != host {
+                        return None;
+                    }
+                    continue;
+                }
+                cookie.domain = domain;
+                cookie.include_subdomains = true;
+            }
+            "path" if value.starts_with('/') => cookie.path = value.to_string(),
+            "secure" => cookie.secure = true,
+            "max-age" => max_age = value.parse::<i64>().ok(),
+            "expires" => cookie.expires = parse_cookie_date(value),
+            _ => {}
+        }
+    }
+    // Max-Age wins over Expires; zero or less expires the cookie now.
+    if let Some(seconds) = max_age {
+        cookie.expires = Some(now.saturating_add(seconds.max(0)));
+    }
+    Some(cookie)
+}
+
+/// Parse an `Expires` date the forgiving way RFC 6265 (5.1.1) asks for,
+/// which takes RFC 1123 (`Wed, 21 Oct 2015 07:28:00 GMT`), the dashed
+/// Netscape and RFC 850 forms (`Wed, 21-Oct-2015 07:28:00 GMT`,
+/// `Wednesday, 21-Oct-15 07:28:00 GMT`) and asctime alike.
+fn parse_cookie_date(value: &str) -> Option<i64> {
+    let delimiter = |c: char| matches!(c, '\t' | ' '..='/' | ';'..='@' | '['..='`' | '{'..='~');
+    let (mut time, mut day, mut month, mut year) = (None, None, None, None);
+    for token in value.split(delimiter).filter(|t| !t.is_empty()) {
+        if time.is_none() {
+            if let Some(found) = cookie_time(token) {
+                time = Some(found);
+                continue;
+            }
+        }
+        if day.is_none() {
+            if let Some(found) = leading_digits(token, 1, 2) {
+                day = Some(found);
+                continue;
+            }
+        }
+        if month.is_none() {
+            if let Some(found) = cookie_month(token) {
+                month = Some(found);
+                continue;
+            }
+        }
+        if year.is_none() {
+            year = leading_digits(token, 2, 4);
+        }
+    }
+    let year = match year? {
+        year @ 70..=99 => year + 1900,
+        year @ 0..=69 => year + 2000,
+        year => year,
+    };
+    let (hour, minute, second) = time?;
+    if year < 1601 {
+        return None;
+    }
+    let date = chrono::NaiveDate::from_ymd_opt(i32::try_from(year).ok()?, month?, day?)?;
+    Some(
+        date.and_hms_opt(hour, minute, second)?
+            .and_utc()
+            .timestamp(),
+    )
+}
+
+/// The `min..=max` digits `token` starts with, if it does not go on with
+/// another digit.
+fn leading_digits(token: &str, min: usize, max: usize) -> Option<u32> {
+    let len = token
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(token.len());
+    if (min..=max).contains(&len) {
+        token[..len].parse().ok()
+    } else {
+        None
+    }
+}
+
+/// `hh:mm:ss`, each one or two digits.
+fn cookie_time(token: &str) -> Option<(u32, u32, u32)> {
+    let mut fields = token.splitn(3, ':');
+    let mut field = |whole: bool| {
+        let field = fields.next()?;
+        let value = leading_digits(field, 1, 2)?;
+        (!whole || field.bytes().all(|b| b.is_ascii_digit())).then_some(value)
+    };
+    Some((field(true)?, field(true)?, field(false)?))
+}
+
+/// The month a token starting with its English name (`Oct`, `october`)
+/// names, 1 to 12.
+fn cookie_month(token: &str) -> Option<u32> {
+    const MONTHS: [&str; 12] = [
+        "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+    ];
+    let prefix = token.get(..3)?.to_ascii_lowercase();
+    let index = MONTHS.iter().position(|month| *month == prefix)?;
+    u32::try_from(index + 1).ok()
+}
+
+/// Whether `domain` is a public suffix, going by the vendored Public
+/// Suffix List. Names under no listed rule count by their last label, as
+/// the list's implicit `*` rule says.
+fn is_public_suffix(domain: &str) -> bool {
+    static LIST: OnceLock<publicsuffix::List> = OnceLock::new();
+    let list = LIST.get_or_init(|| {
+        include_str!("../../assets/public_suffix_list.dat")
+            .parse()
+            .expect("the vendored public suffix list parses")
+    });
+    list.suffix(domain.as_bytes())
+        .is_some_and(|suffix| suffix.as_bytes() == domain.as_bytes())
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+/// Where a jar's cookies come from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Source {
+    /// A Netscape `cookies.txt`.
+    File(PathBuf),
+    /// A Firefox profile's `cookies.sqlite`.
+    Firefox(PathBuf),
+}
+
+impl Source {
+    fn path(&self) -> &Path {
+        match self {
+            Source::File(path) | Source::Firefox(path) => path,
+        }
+    }
+
+    async fn load(&self) -> Result<Vec<Cookie>> {
+        match self {
+            Source::File(path) => {
+                let text = tokio::fs::read_to_string(path)
+                    .await
+                    .with_context(|| format!("Failed to read cookie file {}", path.display()))?;
+                Ok(parse_netscape(&text))
+            }
+            Source::Firefox(path) => read_firefox_cookies(path).await,
+        }
+    }
+}
+
+/// Read a Firefox `cookies.sqlite`. Firefox keeps the live database locked,
+/// so a copy (with its write-ahead log) is read instead.
+async fn read_firefox_cookies(db: &Path) -> Result<Vec<Cookie>> {
+    let dir = std::env::temp_dir().join(format!("rustloader-cookies-{}", uuid::Uuid::new_v4()));
+    tokio::fs::create_dir_all(&dir).await?;
+    let result = read_firefox_copy(db, &dir).await;
+    let _ = tokio::fs::remove_dir_all(&dir).await;
+    result
+}
+
+async fn read_firefox_copy(db: &Path, dir: &Path) -> Result<Vec<Cookie>> {
+    let copy = dir.join("cookies.sqlite");
+    tokio::fs::copy(db, &copy)
+        .await
+        .with_context(|| format!("Failed to copy {}", db.display()))?;
+    let mut wal = db.as_os_str().to_owned();
+    wal.push("-wal");
+    let _ = tokio::fs::copy(PathBuf::from(wal), dir.join("cookies.sqlite-wal")).await;
+
+    let mut conn = SqliteConnectOptions::new()
+        .filename(&copy)
+        .connect()
+        .await
+        .with_context(|| format!("Failed to open {}", db.display()))?;
+    let rows = sqlx::query("SELECT host, path, isSecure, expiry, name, value FROM moz_cookies")
+        .fetch_all(&mut conn)
+        .await?;
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let host: String = row.get("host");
+            let expiry: i64 = row.get("expiry");
+            Cookie {
+                include_subdomains: host.starts_with('.'),
+                domain: host.trim_start_matches('.').to_ascii_lowercase(),
+                path: row.get("path"),
+                secure: row.get::<i64, _>("isSecure") != 0,
+                // Newer Firefox versions store milliseconds.
+                expires: Some(if expiry > 100_000_000_000 {
+                    expiry / 1000
+                } else {
+                    expiry
+                }),
+                name: row.get("name"),
+                value: row.get("value"),
+            }
+        })
+        .collect())
+}
+
+/// The `cookies.sqlite` of the Firefox `profile` — a profile directory
+/// name or a path — or, without one, of the profile used most recently.
+pub fn firefox_cookie_db(dirs: &BrowserDirs, profile: Option<&str>) -> Option<PathBuf> {
+    if let Some(path) = profile.map(Path::new).filter(|p| p.is_absolute()) {
+        return Some(path.join("cookies.sqlite")).filter(|db| db.exists());
+    }
+    detect_browser_profiles_in(dirs)
+        .into_iter()
+        .filter(|browser| browser.name == "firefox")
+        .flat_map(|browser| browser.profiles)
+        .filter(|p| profile.is_none_or(|name| p.name == name))
+        .map(|p| p.path.join("cookies.sqlite"))
+        .filter_map(|db| Some((std::fs::metadata(&db).ok()?.modified().ok()?, db)))
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, db)| db)
+}
+
+/// What a source held when it was last read.
+#[derive(Debug)]
+struct Loaded {
+    source: Source,
+    modified: Option<SystemTime>,
+    cookies: Vec<Cookie>,
+}
+
+/// Cookies for the native engine's requests. See the [module docs](self).
+#[derive(Debug, Default)]
+pub struct CookieJar {
+    loaded: RwLock<Vec<Loaded>>,
+    /// Cookies set by responses this session; never written back to a
+    /// source, and they take precedence over loaded ones with the same
+    /// name, domain and path.
+    received: RwLock<Vec<Cookie>>,
+}
+
+impl CookieJar {
+    /// An empty jar that only keeps cookies set by responses.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A jar holding `cookies`, with no source to reload from.
+    pub fn with_cookies(cookies: Vec<Cookie>) -> Self {
+        Self {
+            received: RwLock::new(cookies),
+            ..Self::default()
+        }
+    }
+
+    /// Load the sources `config` names: its cookie file and, for Firefox, the
+    /// browser's cookie database. A source that cannot be read is logged and
+    /// left out; other browsers' stores are encrypted or in a private
+    /// format, so their cookies stay with yt-dlp.
+    pub async fn load(config: &CookieConfig) -> Self {
+        let mut sources = Vec::new();
+        if let Some(file) = &config.file {
+            sources.push(Source::File(file.clone()));
+        }
+        match config.from_browser.as_deref().map(split_browser_source) {
+            None => {}
+            Some(("firefox", profile)) => {
+                match BrowserDirs::current().and_then(|dirs| firefox_cookie_db(&dirs, profile)) {
+                    Some(db) => sources.push(Source::Firefox(db)),
+                    None => warn!("No Firefox cookie database found; native requests send no browser cookies"),
+                }
+            }
+            Some((browser, _)) => info!(
+                "Cookies from {} are only passed to yt-dlp; native requests cannot read that browser's store",
+                browser
+            ),
+        }
+
+        let mut loaded = Vec::new();
+        for source in sources {
+            match source.load().await {
+                Ok(cookies) => {
+                    debug!(
+                        "Loaded {} cookies from {}",
+                        cookies.len(),
+                        source.path().display()
+                    );
+                    loaded.push(Loaded {
+                        modified: modified(source.path()).await,
+                        source,
+                        cookies,
+                    });
+                }
+                Err(e) => warn!("Could not load cookies: {:#}", e),
+            }
+        }
+        Self {
+            loaded: RwLock::new(loaded),
+            ..Self::default()
+        }
+    }
+
+    /// The `Cookie` header value for a request to `url`, if any cookie
+    /// applies. Longer paths come first, as browsers send them.
+    pub fn header_for(&self, url: &Url) -> Option<String> {
+        let now = unix_now();
+        let received = self.received.read().unwrap();
+        let loaded = self.loaded.read().unwrap();
+        let mut cookies: Vec<&Cookie> = received
+            .iter()
+            .chain(
+                loaded
+                    .iter()
+                    .flat_map(|l| &l.cookies)
+                    .filter(|c| !received.iter().any(|r| r.same_slot(c))),
+            )
+            .filter(|c| c.matches(url, now))
+            .collect();
+        if cookies.is_empty() {
+            return None;
+        }
+        cookies.sort_by_key(|c| std::cmp::Reverse(c.path.len()));
+        Some(
+            cookies
+                .iter()
+                .map(|c| format!("{}={}", c.name, c.value))
+                .collect::<Vec<_>>()
+                .join("; "),
+        )
+    }
+
+    /// Re-read every source whose file changed since it was loaded. Returns
+    /// whether anything was reloaded; a source that fails to load keeps its
+    /// previous cookies.
+    pub async fn reload_if_changed(&self) -> bool {
+        let known: Vec<(Source, Option<SystemTime>)> = self
+            .loaded
+            .read()
+            .unwrap()
+            .iter()
+            .map(|l| (l.source.clone(), l.modified))
+            .collect();
+
+        let mut reloaded = false;
+        for (source, known) in known {
+            let current = modified(source.path()).await;
+            if current == known {
+                continue;
+            }
+            match source.load().await {
+                Ok(cookies) => {
+                    info!(
+                        "Reloaded {} cookies from {}",
+                        cookies.len(),
+                        source.path().display()
+                    );
+                    let mut loaded = self.loaded.write().unwrap();
+                    if let Some(l) = loaded.iter_mut().find(|l| l.source == source) {
+                        l.cookies = cookies;
+                        l.modified = current;
+                    }
+                    reloaded = true;
+                }
+                Err(e) => warn!("Could not reload cookies: {:#}", e),
+            }
+        }
+        reloaded
+    }
+
+    /// Check the sources for changes every `interval`, for as long as the
+    /// jar is in use elsewhere.
+    pub fn watch(self: &Arc<Self>, interval: Duration) {
+        if self.loaded.read().unwrap().is_empty() {
+            return;
+        }
+        let jar = Arc::downgrade(self);
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                let Some(jar) = jar.upgrade() else {
+                    break;
+                };
+                jar.reload_if_changed().await;
+            }
+        });
+    }
+}
+
+async fn modified(path: &Path) -> Option<SystemTime> {
+    tokio::fs::metadata(path).await.ok()?.modified().ok()
+}
+
+impl CookieStore for CookieJar {
+    fn set_cookies(&self, headers: &mut dyn Iterator<Item = &HeaderValue>, url: &Url) {
+        let now = unix_now();
+        let mut received = self.received.write().unwrap();
+        for header in headers {
+            let Some(cookie) = header
+                .to_str()
+                .ok()
+                .and_then(|h| parse_set_cookie(h, url, now))
+            else {
+                continue;
+            };
+            received.retain(|c| !c.same_slot(&cookie));
+            received.push(cookie);
+        }
+    }
+
+    fn cookies(&self, url: &Url) -> Option<HeaderValue> {
+        HeaderValue::from_str(&self.header_for(url)?).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COOKIES_TXT: &str = "# Netscape HTTP Cookie File\n\
+        .example.com\tTRUE\t/\tFALSE\t0\tsession\tall\n\
+        media.example.com\tFALSE\t/private\tTRUE\t4102444800\ttoken\tsecret\n\
+        #HttpOnly_.example.com\tTRUE\t/\tFALSE\t4102444800\tauth\tyes\n\
+        old.example.com\tFALSE\t/\tFALSE\t1000\texpired\tgone\n\
+        broken line\n";
+
+    fn url(s: &str) -> Url {
+        Url::parse(s).unwrap()
+    }
+
+    #[test]
+    fn parses_netscape_files() {
+        let cookies = parse_netscape(COOKIES_TXT);
+        assert_eq!(cookies.len(), 4);
+        assert_eq!(cookies[0].domain, "example.com");
+        assert!(cookies[0].include_subdomains);
+        assert_eq!(cookies[0].expires, None);
+        assert!(cookies[1].secure && !cookies[1].include_subdomains);
+        assert_eq!(cookies[2].name, "auth");
+    }
+
+    #[test]
+    fn scopes_cookies_by_domain_path_scheme_and_expiry() {
+        let jar = CookieJar::with_cookies(parse_netscape(COOKIES_TXT));
+        let header = |u: &str| jar.header_for(&url(u));
+
+        assert_eq!(
+            header("https://media.example.com/private/clip.mp4").as_deref(),
+            Some("token=secret; session=all; auth=yes")
+        );
+        // Host-only and secure: not for another host, nor over http.
+        assert_eq!(
+            header("http://media.example.com/private/a").as_deref(),
+            Some("session=all; auth=yes")
+        );
+        assert_eq!(
+            header("https://cdn.example.com/private/a").as_deref(),
+            Some("session=all; auth=yes")
+        );
+        // `/private` does not cover `/privateer`.
+        assert!(!header("https://media.example.com/privateer")
+            .unwrap()
+            .contains("token"));
+        // No suffix matches across a label boundary, and expired cookies go.
+        assert_eq!(header("https://notexample.com/"), None);
+        assert!(!header("https://old.example.com/")
+            .unwrap()
+            .contains("expired"));
+    }
+
+    #[test]
+    fn cookies_for_public_suffixes_are_rejected() {
+        let jar = CookieJar::new();
+        let set = [
+            HeaderValue::from_static("tld=1; Domain=com; Path=/"),
+            HeaderValue::from_static("cctld=1; Domain=.co.uk; Path=/"),
+            HeaderValue::from_static("ok=1; Domain=example.co.uk; Path=/"),
+        ];
+        jar.set_cookies(&mut set.iter(), &url("https://x.example.co.uk/"));
+        let pages = [
+            HeaderValue::from_static("pages=1; Domain=github.io; Path=/"),
+            HeaderValue::from_static("site=1; Domain=user.github.io; Path=/"),
+        ];
+        jar.set_cookies(&mut pages.iter(), &url("https://user.github.io/"));
+
+        assert_eq!(jar.header_for(&url("https://other.com/")), None);
+        assert_eq!(jar.header_for(&url("https://other.co.uk/")), None);
+        assert_eq!(jar.header_for(&url("https://other.github.io/")), None);
+        assert_eq!(
+            jar.header_for(&url("https://www.example.co.uk/"))
+                .as_deref(),
+            Some("ok=1")
+        );
+        assert_eq!(
+            jar.header_for(&url("https://user.github.io/")).as_deref(),
+            Some("site=1")
+        );
+        assert!(is_public_suffix("com"));
+        assert!(is_public_suffix("unlisted-tld"));
+        assert!(!is_public_suffix("example.com"));
+    }
+
+    #[test]
+    fn keeps_cookies_set_by_responses() {
+        let jar = CookieJar::new();
+        let from = url("https://www.example.com/watch/page");
+        let set = [
+            HeaderValue::from_static("sid=1; Path=/; Secure; HttpOnly"),
+            HeaderValue::from_static("pref=dark; Domain=.example.com"),
+            HeaderValue::from_static("evil=1; Domain=other.com"),
+            HeaderValue::from_static("gone=1; Max-Age=0"),
+        ];
+        jar.set_cookies(&mut set.iter(), &from);
+
+        assert_eq!(
+            jar.header_for(&url("https://www.example.com/watch/x"))
+                .as_deref(),
+            Some("pref=dark; sid=1")
+        );
+        // Without a Path the cookie is scoped to the page's directory.
+        assert_eq!(
+            jar.header_for(&url("https://www.example.com/x")).as_deref(),
+            Some("sid=1")
+        );
+        // Only the Domain cookie reaches a sibling host.
+        assert_eq!(
+            jar.header_for(&url("https://api.example.com/watch/x"))
+                .as_deref(),
+            Some("pref=dark")
+        );
+
+        // A new value replaces the old one.
+        let update = [HeaderValue::from_static("sid=2; Path=/")];
+        jar.set_cookies(&mut update.iter(), &from);
+        assert!(jar
+            .header_for(&url("https://www.example.com/"))
+            .unwrap()
+            .contains("sid=2"));
+    }
+
+    #[test]
+    fn expiry_dates_parse_in_every_form_servers_send() {
+        let expected = Some(1_445_412_480);
+        for date in [
+            "Wed, 21 Oct 2015 07:28:00 GMT",
+            "Wed, 21-Oct-2015 07:28:00 GMT",
+            "Wednesday, 21-Oct-15 07:28:00 GMT",
+            "Wed Oct 21 07:28:00 2015",
+        ] {
+            assert_eq!(parse_cookie_date(date), expected, "{date}");
+        }
+        assert_eq!(parse_cookie_date("Wed, 21 Oct 2015"), None);
+        assert_eq!(parse_cookie_date("Wed, 31 Feb 2015 07:28:00 GMT"), None);
+        assert_eq!(parse_cookie_date("soon"), None);
+
+        let from = url("https://example.com/");
+        let cookie = parse_set_cookie("a=1; Expires=Wed, 21-Oct-2015 07:28:00 GMT", &from, 0);
+        assert_eq!(cookie.unwrap().expires, expected);
+        // A huge Max-Age is far in the future, not an overflow.
+        let cookie = parse_set_cookie("a=1; Max-Age=9223372036854775807", &from, 1_000);
+        assert_eq!(cookie.unwrap().expires, Some(i64::MAX));
+    }
+
+    #[tokio::test]
+    async fn reloads_the_file_when_it_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("cookies.txt");
+        std::fs::write(&file, "example.com\tFALSE\t/\tFALSE\t0\tv\t1\n").unwrap();
+        let jar = CookieJar::load(&CookieConfig::new(None, Some(file.clone()))).await;
+        let page = url("https://example.com/");
+        assert_eq!(jar.header_for(&page).as_deref(), Some("v=1"));
+        assert!(!jar.reload_if_changed().await);
+
+        std::fs::write(&file, "example.com\tFALSE\t/\tFALSE\t0\tv\t2\n").unwrap();
+        let later = SystemTime::now() + Duration::from_secs(5);
+        std::fs::File::options()
+            .write(true)
+            .open(&file)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+        assert!(jar.reload_if_changed().await);
+        assert_eq!(jar.header_for(&page).as_deref(), Some("v=2"));
+    }
+
+    #[tokio::test]
+    async fn reads_firefox_cookie_databases() {
+        let home = tempfile::tempdir().unwrap();
+        let profile = home.path().join(".mozilla/firefox/abcd.default-release");
+        std::fs::create_dir_all(&profile).unwrap();
+        let db = profile.join("cookies.sqlite");
+        let mut conn = SqliteConnectOptions::new()
+            .filename(&db)
+            .create_if_missing(true)
+            .connect()
+            .await
+            .unwrap();
+        sqlx::query(
+            "CREATE TABLE moz_cookies (host TEXT, path TEXT, isSecure INTEGER, \
+             expiry INTEGER, name TEXT, value TEXT)",
+        )
+        .execute(&mut conn)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO moz_cookies VALUES ('.site.test', '/', 1, 4102444800000, 'ff', 'on')",
+        )
+        .execute(&mut conn)
+        .await
+        .unwrap();
+        drop(conn);
+
+        let dirs = BrowserDirs::under_home(home.path());
+        assert_eq!(firefox_cookie_db(&dirs, None), Some(db.clone()));
+        assert_eq!(
+            firefox_cookie_db(&dirs, Some("abcd.default-release")),
+            Some(db.clone())
+        );
+        assert_eq!(firefox_cookie_db(&dirs, Some("work")), None);
+        assert_eq!(firefox_cookie_db(&dirs, profile.to_str()), Some(db.clone()));
+        let cookies = read_firefox_cookies(&db).await.unwrap();
+        assert_eq!(cookies.len(), 1);
+        assert_eq!(cookies[0].expires, Some(4102444800));
+        let jar = CookieJar::with_cookies(cookies);
+        assert_eq!(
+            jar.header_for(&url("https://www.site.test/v")).as_deref(),
+            Some("ff=on")
+        );
+        assert_eq!(jar.header_for(&url("http://www.site.test/v")), None);
+    }
+}
@@ -14,43 +14,343 @@ pub const SUPPORTED_BROWSERS: &[&str] = &[
     "firefox", "chrome", "safari", "edge", "brave", "chromium", "opera", "vivaldi",
 ];
 
-/// Detect which supported browsers appear installed by probing their
-/// profile/cookie locations under `home`. Returns names in
-/// [`SUPPORTED_BROWSERS`] order. Pure (takes `home`) so it is unit-testable.
-pub fn detect_browsers_in(home: &Path) -> Vec<String> {
-    let lib = home.join("Library");
-    // (browser, any-of these relative-to-~/Library paths exists)
-    let candidates: &[(&str, &[&str])] = &[
-        ("firefox", &["Application Support/Firefox/Profiles"]),
-        ("chrome", &["Application Support/Google/Chrome"]),
-        (
-            "safari",
-            &[
-                "Containers/com.apple.Safari/Data/Library/Cookies",
-                "Cookies/Cookies.binarycookies",
-            ],
-        ),
-        ("edge", &["Application Support/Microsoft Edge"]),
-        (
-            "brave",
-            &["Application Support/BraveSoftware/Brave-Browser"],
-        ),
-        ("chromium", &["Application Support/Chromium"]),
-        ("opera", &["Application Support/com.operasoftware.Opera"]),
-        ("vivaldi", &["Application Support/Vivaldi"]),
-    ];
-    candidates
+/// Where browser profiles are looked for. Windows keeps them under
+/// `%APPDATA%` (Firefox, Opera) and `%LOCALAPPDATA%` (Chromium browsers);
+/// everything else is relative to the home directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrowserDirs {
+    pub home: PathBuf,
+    pub appdata: PathBuf,
+    pub local_appdata: PathBuf,
+}
+
+impl BrowserDirs {
+    /// `home`, with the Windows folders at their default places under it.
+    pub fn under_home(home: &Path) -> Self {
+        Self {
+            home: home.to_path_buf(),
+            appdata: home.join("AppData/Roaming"),
+            local_appdata: home.join("AppData/Local"),
+        }
+    }
+
+    /// The current user's directories, honouring `%APPDATA%` and
+    /// `%LOCALAPPDATA%` when they are set.
+    pub fn current() -> Option<Self> {
+        let mut dirs = Self::under_home(&dirs::home_dir()?);
+        if let Some(appdata) = std::env::var_os("APPDATA") {
+            dirs.appdata = appdata.into();
+        }
+        if let Some(local) = std::env::var_os("LOCALAPPDATA") {
+            dirs.local_appdata = local.into();
+        }
+        Some(dirs)
+    }
+}
+
+/// Which base directory a [`Location`] is relative to.
+#[derive(Debug, Clone, Copy)]
+enum Base {
+    Home,
+    AppData,
+    LocalAppData,
+}
+
+/// How a browser lays out its profiles under a location.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Layout {
+    /// One directory per profile, each with a `cookies.sqlite`.
+    Firefox,
+    /// `Default` and `Profile N` directories, each with a `Preferences` file.
+    Chromium,
+    /// The location is the only profile.
+    Single,
+}
+
+/// One place a browser may be installed.
+struct Location {
+    browser: &'static str,
+    base: Base,
+    path: &'static str,
+    /// Set for Flatpak and Snap installs, which yt-dlp does not look in on
+    /// its own: their profiles are passed to it by path.
+    sandbox: Option<&'static str>,
+}
+
+const fn at(browser: &'static str, base: Base, path: &'static str) -> Location {
+    Location {
+        browser,
+        base,
+        path,
+        sandbox: None,
+    }
+}
+
+const fn sandboxed(browser: &'static str, kind: &'static str, path: &'static str) -> Location {
+    Location {
+        browser,
+        base: Base::Home,
+        path,
+        sandbox: Some(kind),
+    }
+}
+
+/// Profile locations on macOS, Linux (native, Flatpak and Snap) and Windows.
+const LOCATIONS: &[Location] = &[
+    at(
+        "firefox",
+        Base::Home,
+        "Library/Application Support/Firefox/Profiles",
+    ),
+    at("firefox", Base::Home, ".mozilla/firefox"),
+    sandboxed(
+        "firefox",
+        "Flatpak",
+        ".var/app/org.mozilla.firefox/.mozilla/firefox",
+    ),
+    sandboxed("firefox", "Snap", "snap/firefox/common/.mozilla/firefox"),
+    at("firefox", Base::AppData, "Mozilla/Firefox/Profiles"),
+    at(
+        "chrome",
+        Base::Home,
+        "Library/Application Support/Google/Chrome",
+    ),
+    at("chrome", Base::Home, ".config/google-chrome"),
+    sandboxed(
+        "chrome",
+        "Flatpak",
+        ".var/app/com.google.Chrome/config/google-chrome",
+    ),
+    at("chrome", Base::LocalAppData, "Google/Chrome/User Data"),
+    at(
+        "safari",
+        Base::Home,
+        "Library/Containers/com.apple.Safari/Data/Library/Cookies",
+    ),
+    at(
+        "safari",
+        Base::Home,
+        "Library/Cookies/Cookies.binarycookies",
+    ),
+    at(
+        "edge",
+        Base::Home,
+        "Library/Application Support/Microsoft Edge",
+    ),
+    at("edge", Base::Home, ".config/microsoft-edge"),
+    sandboxed(
+        "edge",
+        "Flatpak",
+        ".var/app/com.microsoft.Edge/config/microsoft-edge",
+    ),
+    at("edge", Base::LocalAppData, "Microsoft/Edge/User Data"),
+    at(
+        "brave",
+        Base::Home,
+        "Library/Application Support/BraveSoftware/Brave-Browser",
+    ),
+    at("brave", Base::Home, ".config/BraveSoftware/Brave-Browser"),
+    sandboxed(
+        "brave",
+        "Flatpak",
+        ".var/app/com.brave.Browser/config/BraveSoftware/Brave-Browser",
+    ),
+    sandboxed(
+        "brave",
+        "Snap",
+        "snap/brave/current/.config/BraveSoftware/Brave-Browser",
+    ),
+    at(
+        "brave",
+        Base::LocalAppData,
+        "BraveSoftware/Brave-Browser/User Data",
+    ),
+    at(
+        "chromium",
+        Base::Home,
+        "Library/Application Support/Chromium",
+    ),
+    at("chromium", Base::Home, ".config/chromium"),
+    sandboxed(
+        "chromium",
+        "Flatpak",
+        ".var/app/org.chromium.Chromium/config/chromium",
+    ),
+    sandboxed("chromium", "Snap", "snap/chromium/common/chromium"),
+    at("chromium", Base::LocalAppData, "Chromium/User Data"),
+    at(
+        "opera",
+        Base::Home,
+        "Library/Application Support/com.operasoftware.Opera",
+    ),
+    at("opera", Base::Home, ".config/opera"),
+    sandboxed("opera", "Flatpak", ".var/app/com.opera.Opera/config/opera"),
+    sandboxed("opera", "Snap", "snap/opera/current/.config/opera"),
+    at("opera", Base::AppData, "Opera Software/Opera Stable"),
+    at("vivaldi", Base::Home, "Library/Application Support/Vivaldi"),
+    at("vivaldi", Base::Home, ".config/vivaldi"),
+    sandboxed(
+        "vivaldi",
+        "Flatpak",
+        ".var/app/com.vivaldi.Vivaldi/config/vivaldi",
+    ),
+    at("vivaldi", Base::LocalAppData, "Vivaldi/User Data"),
+];
+
+fn layout(browser: &str) -> Layout {
+    match browser {
+        "firefox" => Layout::Firefox,
+        "safari" | "opera" => Layout::Single,
+        _ => Layout::Chromium,
+    }
+}
+
+/// A browser profile found on disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrowserProfile {
+    /// The profile's directory name, e.g. `Default` or `abcd.default-release`.
+    pub name: String,
+    pub path: PathBuf,
+    /// `Flatpak` or `Snap` for a sandboxed install.
+    pub sandbox: Option<&'static str>,
+}
+
+impl BrowserProfile {
+    /// How the profile is shown in a picker.
+    pub fn label(&self) -> String {
+        match self.sandbox {
+            Some(kind) => format!("{} ({kind})", self.name),
+            None => self.name.clone(),
+        }
+    }
+
+    /// The `--cookies-from-browser` value selecting this profile of
+    /// `browser`: yt-dlp's `BROWSER:PROFILE`, with the profile as a path
+    /// for sandboxed installs yt-dlp would not find by name.
+    pub fn cookie_source(&self, browser: &str) -> String {
+        match self.sandbox {
+            Some(_) => format!("{browser}:{}", self.path.display()),
+            None => format!("{browser}:{}", self.name),
+        }
+    }
+}
+
+/// An installed browser and the profiles it has.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DetectedBrowser {
+    pub name: String,
+    /// Empty for browsers without named profiles (Safari, Opera).
+    pub profiles: Vec<BrowserProfile>,
+}
+
+/// The profiles under one installed location of a browser.
+fn profiles_at(root: &Path, location: &Location) -> Vec<BrowserProfile> {
+    let is_profile = |dir: &Path| match layout(location.browser) {
+        Layout::Firefox => dir.join("cookies.sqlite").exists() || dir.join("prefs.js").exists(),
+        Layout::Chromium => dir.join("Preferences").exists(),
+        Layout::Single => false,
+    };
+    let Ok(entries) = std::fs::read_dir(root) else {
+        return Vec::new();
+    };
+    let mut profiles: Vec<BrowserProfile> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|dir| dir.is_dir() && is_profile(dir))
+        .map(|dir| BrowserProfile {
+            name: dir
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string(),
+            path: dir,
+            sandbox: location.sandbox,
+        })
+        .collect();
+    // `Default` first, then the rest by name.
+    profiles.sort_by_key(|p| (p.name != "Default", p.name.clone()));
+    profiles
+}
+
+/// Detect installed browsers and their profiles under `dirs`, in
+/// [`SUPPORTED_BROWSERS`] order. A browser counts as installed when any of
+/// its profile locations exists, even if no profile could be listed. Pure
+/// (takes `dirs`) so it is unit-testable.
+pub fn detect_browser_profiles_in(dirs: &BrowserDirs) -> Vec<DetectedBrowser> {
+    SUPPORTED_BROWSERS
         .iter()
-        .filter(|(_, paths)| paths.iter().any(|p| lib.join(p).exists()))
-        .map(|(name, _)| name.to_string())
+        .filter_map(|&browser| {
+            let mut installed = false;
+            let mut profiles = Vec::new();
+            for location in LOCATIONS.iter().filter(|l| l.browser == browser) {
+                let base = match location.base {
+                    Base::Home => &dirs.home,
+                    Base::AppData => &dirs.appdata,
+                    Base::LocalAppData => &dirs.local_appdata,
+                };
+                let root = base.join(location.path);
+                if root.exists() {
+                    installed = true;
+                    profiles.extend(profiles_at(&root, location));
+                }
+            }
+            installed.then(|| DetectedBrowser {
+                name: browser.to_string(),
+                profiles,
+            })
+        })
+        .collect()
+}
+
+/// Detect installed browsers and their profiles for the current user.
+pub fn detect_browser_profiles() -> Vec<DetectedBrowser> {
+    match BrowserDirs::current() {
+        Some(dirs) => detect_browser_profiles_in(&dirs),
+        None => Vec::new(),
+    }
+}
+
+/// Detect which supported browsers appear installed under `home`. Returns
+/// names in [`SUPPORTED_BROWSERS`] order.
+pub fn detect_browsers_in(home: &Path) -> Vec<String> {
+    detect_browser_profiles_in(&BrowserDirs::under_home(home))
+        .into_iter()
+        .map(|b| b.name)
         .collect()
 }
 
 /// Detect installed browsers under the current user's home directory.
 pub fn detect_browsers() -> Vec<String> {
-    match dirs::home_dir() {
-        Some(home) => detect_browsers_in(&home),
-        None => Vec::new(),
+    detect_browser_profiles()
+        .into_iter()
+        .map(|b| b.name)
+        .collect()
+}
+
+/// Split a `--cookies-from-browser` value, yt-dlp's
+/// `BROWSER[+KEYRING][:PROFILE][::CONTAINER]`, into the browser name and
+/// the profile (a name or a path), if any.
+pub fn split_browser_source(source: &str) -> (&str, Option<&str>) {
+    let source = source.split_once("::").map_or(source, |(rest, _)| rest);
+    let (browser, profile) = match source.split_once(':') {
+        Some((browser, profile)) => (browser, Some(profile).filter(|p| !p.is_empty())),
+        None => (source, None),
+    };
+    let browser = browser.split_once('+').map_or(browser, |(name, _)| name);
+    (browser.trim(), profile)
+}
+
+/// Check a `--cookies-from-browser` value: the browser must be one yt-dlp
+/// supports. Profiles are not checked; yt-dlp reports a missing one.
+pub fn parse_browser_source(source: &str) -> Result<String, String> {
+    let (browser, _) = split_browser_source(source);
+    if SUPPORTED_BROWSERS.contains(&browser.to_ascii_lowercase().as_str()) {
+        Ok(source.to_string())
+    } else {
+        Err(format!(
+            "unknown browser '{browser}' (expected one of: {})",
+            SUPPORTED_BROWSERS.join(", ")
+        ))
     }
 }
 
@@ -62,8 +362,9 @@ pub fn detect_browsers() -> Vec<String> {
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct CookieConfig {
     /// `--cookies-from-browser <browser>` — e.g. `chrome`, `firefox`, `safari`,
-    /// `edge`, `brave`. yt-dlp reads cookies directly from that browser's
-    /// profile.
+    /// `edge`, `brave`, optionally with a profile (`firefox:work`, see
+    /// [`split_browser_source`]). yt-dlp reads cookies directly from that
+    /// browser's profile.
     pub from_browser: Option<String>,
     /// `--cookies <file>` — path to a Netscape-format `cookies.txt` file.
     pub file: Option<PathBuf>,
@@ -172,6 +473,92 @@ mod tests {
         let _ = std::fs::remove_dir_all(&tmp);
     }
 
+    #[test]
+    fn detects_linux_profiles_including_flatpak_and_snap() {
+        let home = tempfile::tempdir().unwrap();
+        let home = home.path();
+        let make = |dir: &str, file: &str| {
+            std::fs::create_dir_all(home.join(dir)).unwrap();
+            std::fs::write(home.join(dir).join(file), "").unwrap();
+        };
+        make(".mozilla/firefox/abcd.default-release", "prefs.js");
+        make(".mozilla/firefox/efgh.work", "cookies.sqlite");
+        std::fs::create_dir_all(home.join(".mozilla/firefox/Crash Reports")).unwrap();
+        make(".config/google-chrome/Profile 1", "Preferences");
+        make(".config/google-chrome/Default", "Preferences");
+        make(
+            ".var/app/org.chromium.Chromium/config/chromium/Default",
+            "Preferences",
+        );
+        make(
+            "snap/firefox/common/.mozilla/firefox/snap.default",
+            "prefs.js",
+        );
+
+        let found = detect_browser_profiles_in(&BrowserDirs::under_home(home));
+        let names: Vec<&str> = found.iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(names, ["firefox", "chrome", "chromium"]);
+
+        let firefox: Vec<String> = found[0].profiles.iter().map(|p| p.label()).collect();
+        assert_eq!(
+            firefox,
+            ["abcd.default-release", "efgh.work", "snap.default (Snap)"]
+        );
+        assert_eq!(
+            found[0].profiles[1].cookie_source("firefox"),
+            "firefox:efgh.work"
+        );
+        // yt-dlp is given sandboxed profiles by path.
+        let snap = home.join("snap/firefox/common/.mozilla/firefox/snap.default");
+        assert_eq!(
+            found[0].profiles[2].cookie_source("firefox"),
+            format!("firefox:{}", snap.display())
+        );
+
+        let chrome: Vec<&str> = found[1].profiles.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(chrome, ["Default", "Profile 1"]);
+        assert_eq!(found[2].profiles[0].sandbox, Some("Flatpak"));
+    }
+
+    #[test]
+    fn detects_windows_profiles_under_appdata() {
+        let home = tempfile::tempdir().unwrap();
+        let dirs = BrowserDirs {
+            home: home.path().join("home"),
+            appdata: home.path().join("Roaming"),
+            local_appdata: home.path().join("Local"),
+        };
+        let brave = dirs
+            .local_appdata
+            .join("BraveSoftware/Brave-Browser/User Data/Default");
+        std::fs::create_dir_all(&brave).unwrap();
+        std::fs::write(brave.join("Preferences"), "").unwrap();
+        std::fs::create_dir_all(dirs.appdata.join("Opera Software/Opera Stable")).unwrap();
+
+        let found = detect_browser_profiles_in(&dirs);
+        let names: Vec<&str> = found.iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(names, ["brave", "opera"]);
+        assert_eq!(found[0].profiles[0].cookie_source("brave"), "brave:Default");
+        // Opera has a single profile, so there is nothing to pick.
+        assert!(found[1].profiles.is_empty());
+    }
+
+    #[test]
+    fn browser_sources_split_into_browser_and_profile() {
+        assert_eq!(split_browser_source("chrome"), ("chrome", None));
+        assert_eq!(
+            split_browser_source("chrome:Profile 1"),
+            ("chrome", Some("Profile 1"))
+        );
+        assert_eq!(
+            split_browser_source("chromium+gnomekeyring:Default::none"),
+            ("chromium", Some("Default"))
+        );
+        assert_eq!(split_browser_source("firefox::Work"), ("firefox", None));
+        assert!(parse_browser_source("Firefox:work").is_ok());
+        assert!(parse_browser_source("netscape").is_err());
+    }
+
     #[test]
     fn both_emit_both_flags() {
         let c = CookieConfig::new(
@@ -1,7 +1,7 @@
 //! Lightweight, non-blocking dependency health checks.
 //!
 //! These only emit WARN-level guidance — they never block startup or
-//! auto-update. They catch the two failure modes that previously produced a
+//! auto-update; updating is the user's call (see [`crate::utils::tools`]). They catch the two failure modes that previously produced a
 //! cryptic YouTube `403` with no hint: a yt-dlp that is suspiciously old, and a
 //! missing JavaScript runtime (modern yt-dlp needs one to solve YouTube's JS
 //! challenges).
@@ -46,7 +46,7 @@ pub fn health_warnings(
             if age > YTDLP_STALE_DAYS {
                 warnings.push(format!(
                     "yt-dlp {version} is {age} days old; YouTube extraction may fail. \
-                     Update it (e.g. `pip install -U yt-dlp` or `brew upgrade yt-dlp`)."
+                     Update it from Settings → Update yt-dlp or with `rustloader tools update`."
                 ));
             }
         }
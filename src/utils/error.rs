@@ -34,6 +34,14 @@ pub enum RustloaderError {
 
     #[error("Operation failed: {0}")]
     OperationFailed(String),
+
+    #[error("Already downloaded: {0}")]
+    AlreadyDownloaded(String),
+
+    /// The extractor does not implement this operation at all (as opposed to
+    /// failing at it), so the next one in the chain should be asked.
+    #[error("{0}")]
+    NotSupported(String),
 }
 
 /// Map a raw error string (from yt-dlp / reqwest / the engine) to a concise,
@@ -3,19 +3,23 @@
 
 pub mod bundle_paths;
 pub mod config;
+pub mod cookie_jar;
 pub mod cookies;
 pub mod depcheck;
 pub mod error;
 pub mod metadata;
 pub mod organizer;
 pub mod platform;
+pub mod tools;
 
 // Re-export for convenience
 pub use bundle_paths::{
     get_app_support_dir, get_database_path, get_download_file_path, get_downloads_dir,
 };
 pub use config::AppSettings;
+pub use cookie_jar::CookieJar;
 pub use cookies::CookieConfig;
 pub use error::{make_error_user_friendly, RustloaderError};
 pub use metadata::{MetadataManager, MetadataStats, VideoMetadata};
 pub use organizer::{ContentType, FileOrganizer, OrganizationSettings, OrganizeMode, QualityTier};
+pub use platform::sanitize_filename;
@@ -7,6 +7,9 @@ use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use tokio::fs;
 
+/// Extensions of subtitle sidecars, which travel with their media file.
+pub const SUBTITLE_EXTS: [&str; 6] = ["srt", "vtt", "ass", "ssa", "ttml", "srv3"];
+
 /// Main file organizer with configurable settings
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileOrganizer {
@@ -37,7 +40,7 @@ pub enum OrganizeMode {
 }
 
 /// Content type classification
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ContentType {
     Video,
     Audio,
@@ -80,7 +83,12 @@ impl FileOrganizer {
         // (home/Downloads, then a temp dir) when the OS reports no Downloads
         // directory — e.g. on headless CI runners — instead of failing.
         let base_dir = crate::utils::get_downloads_dir().join("Rustloader");
+        Self::with_base_dir(base_dir, settings).await
+    }
 
+    /// Initialize an organizer rooted at an explicit library directory
+    /// instead of `~/Downloads/Rustloader` (embedders and tests).
+    pub async fn with_base_dir(base_dir: PathBuf, settings: OrganizationSettings) -> Result<Self> {
         let organizer = Self { base_dir, settings };
 
         // Create directory structure on initialization
@@ -286,6 +294,7 @@ impl FileOrganizer {
             fs::rename(temp_path, &target_path)
                 .await
                 .context("Failed to move file to organized location")?;
+            Self::move_subtitle_sidecars(temp_path, &target_path).await;
             return Ok(target_path);
         }
 
@@ -293,11 +302,132 @@ impl FileOrganizer {
         fs::rename(temp_path, &target_path)
             .await
             .context("Failed to move file to organized location")?;
+        Self::move_subtitle_sidecars(temp_path, &target_path).await;
 
         eprintln!("✅ [ORGANIZER] File organized successfully");
         Ok(target_path)
     }
 
+    /// Move the subtitle sidecars yt-dlp wrote next to `media`
+    /// (`<stem>.<lang>.<ext>`) beside its new location `target`, renamed to
+    /// its stem. Best-effort: a sidecar that cannot be moved stays behind.
+    /// Returns the moved sidecars' new paths.
+    pub async fn move_subtitle_sidecars(media: &Path, target: &Path) -> Vec<PathBuf> {
+        let (Some(stem), Some(target_stem)) = (
+            media.file_stem().and_then(|s| s.to_str()),
+            target.file_stem().and_then(|s| s.to_str()),
+        ) else {
+            return Vec::new();
+        };
+        let dir = match media.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        let target_dir = target.parent().unwrap_or(dir);
+        let Ok(mut entries) = fs::read_dir(dir).await else {
+            return Vec::new();
+        };
+        let prefix = format!("{stem}.");
+        let mut moved = Vec::new();
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+            let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            // `<lang>.<ext>` after the media stem.
+            let Some(rest) = name.strip_prefix(&prefix) else {
+                continue;
+            };
+            let is_sidecar = rest.rsplit_once('.').is_some_and(|(lang, ext)| {
+                !lang.is_empty() && SUBTITLE_EXTS.contains(&ext.to_ascii_lowercase().as_str())
+            });
+            if !is_sidecar {
+                continue;
+            }
+            let new_path = target_dir.join(format!("{target_stem}.{rest}"));
+            if new_path == path {
+                continue;
+            }
+            let result = match fs::rename(&path, &new_path).await {
+                Ok(()) => Ok(()),
+                // Across filesystems a rename fails; copy and remove instead.
+                Err(_) => match fs::copy(&path, &new_path).await {
+                    Ok(_) => fs::remove_file(&path).await,
+                    Err(e) => Err(e),
+                },
+            };
+            match result {
+                Ok(()) => moved.push(new_path),
+                Err(e) => eprintln!("⚠️  [ORGANIZER] Could not move subtitles {:?}: {}", path, e),
+            }
+        }
+        moved
+    }
+
+    /// Copy `cover` next to `media` as `<stem>.jpg`, the per-file artwork
+    /// media libraries pick up. Best-effort; returns the sidecar's path.
+    pub async fn write_thumbnail_sidecar(media: &Path, cover: &Path) -> Option<PathBuf> {
+        let sidecar = media.with_extension("jpg");
+        if sidecar == media {
+            return None;
+        }
+        match fs::copy(cover, &sidecar).await {
+            Ok(_) => Some(sidecar),
+            Err(e) => {
+                eprintln!(
+                    "⚠️  [ORGANIZER] Could not write thumbnail {:?}: {}",
+                    sidecar, e
+                );
+                None
+            }
+        }
+    }
+
+    /// Where a collection's own artwork goes: `poster.jpg` at the top of a
+    /// series, `folder.jpg` at the top of a playlist. `None` for single
+    /// videos and audio.
+    pub fn collection_art_path(&self, content_type: &ContentType) -> Option<PathBuf> {
+        match content_type {
+            ContentType::Playlist { name } => Some(
+                self.base_dir
+                    .join("Playlists")
+                    .join(Self::sanitize_filename(name))
+                    .join("folder.jpg"),
+            ),
+            ContentType::Series { name, .. } => Some(
+                self.base_dir
+                    .join("Series")
+                    .join(Self::sanitize_filename(name))
+                    .join("poster.jpg"),
+            ),
+            ContentType::Video | ContentType::Audio => None,
+        }
+    }
+
+    /// Use `cover` as the collection's artwork unless it already has some,
+    /// so the first downloaded entry's thumbnail stands for the collection.
+    /// Best-effort; returns the path when it was written.
+    pub async fn write_collection_art(
+        &self,
+        content_type: &ContentType,
+        cover: &Path,
+    ) -> Option<PathBuf> {
+        let path = self.collection_art_path(content_type)?;
+        if path.exists() {
+            return None;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await.ok()?;
+        }
+        match fs::copy(cover, &path).await {
+            Ok(_) => Some(path),
+            Err(e) => {
+                eprintln!("⚠️  [ORGANIZER] Could not write {:?}: {}", path, e);
+                None
+            }
+        }
+    }
+
     /// Determine video quality tier from quality string
     pub fn determine_quality_tier(quality: &str) -> QualityTier {
         let quality_lower = quality.to_lowercase();
@@ -473,6 +603,82 @@ impl FileOrganizer {
 mod tests {
     use super::*;
 
+    #[tokio::test]
+    async fn subtitle_sidecars_follow_the_media_file() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let library = temp.path().join("Library");
+        std::fs::create_dir_all(&library).unwrap();
+        for name in [
+            "Talk.mp4",
+            "Talk.en.srt",
+            "Talk.es-419.vtt",
+            "Talk.info.json",
+            "Talk 2.en.srt",
+        ] {
+            std::fs::write(temp.path().join(name), name).unwrap();
+        }
+        let target = library.join("YouTube - Talk [1080p].mp4");
+        std::fs::rename(temp.path().join("Talk.mp4"), &target).unwrap();
+
+        let mut moved =
+            FileOrganizer::move_subtitle_sidecars(&temp.path().join("Talk.mp4"), &target).await;
+        moved.sort();
+        assert_eq!(
+            moved,
+            [
+                library.join("YouTube - Talk [1080p].en.srt"),
+                library.join("YouTube - Talk [1080p].es-419.vtt"),
+            ]
+        );
+        // Other files — and other videos' subtitles — stay put.
+        assert!(temp.path().join("Talk.info.json").exists());
+        assert!(temp.path().join("Talk 2.en.srt").exists());
+        assert!(!temp.path().join("Talk.en.srt").exists());
+    }
+
+    #[tokio::test]
+    async fn thumbnails_become_sidecars_and_collection_art() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let organizer =
+            FileOrganizer::with_base_dir(temp.path().join("Library"), Default::default())
+                .await
+                .unwrap();
+        let cover = temp.path().join("cover.jpg");
+        std::fs::write(&cover, "jpeg").unwrap();
+        let media = temp.path().join("Talk.mkv");
+
+        assert_eq!(
+            FileOrganizer::write_thumbnail_sidecar(&media, &cover).await,
+            Some(temp.path().join("Talk.jpg"))
+        );
+
+        let playlist = ContentType::Playlist {
+            name: "Mix: 2024".to_string(),
+        };
+        let folder = organizer.base_dir.join("Playlists/Mix_ 2024/folder.jpg");
+        assert_eq!(
+            organizer.write_collection_art(&playlist, &cover).await,
+            Some(folder.clone())
+        );
+        // The first entry's art stays.
+        assert_eq!(
+            organizer.write_collection_art(&playlist, &cover).await,
+            None
+        );
+        assert!(folder.exists());
+
+        let series = ContentType::Series {
+            name: "Show".to_string(),
+            season: 2,
+            episode: 5,
+        };
+        assert_eq!(
+            organizer.collection_art_path(&series),
+            Some(organizer.base_dir.join("Series/Show/poster.jpg"))
+        );
+        assert_eq!(organizer.collection_art_path(&ContentType::Video), None);
+    }
+
     #[test]
     fn test_sanitize_filename() {
         assert_eq!(
@@ -120,6 +120,12 @@ pub fn log_dir() -> PathBuf {
 
 /// Returns the path to yt-dlp executable
 pub fn ytdlp_path() -> Option<PathBuf> {
+    // 0. A managed install (Settings → Update yt-dlp, `rustloader tools
+    //    update`) is the one the user chose to keep current.
+    if let Some(managed) = crate::utils::tools::managed_binary(crate::utils::tools::Tool::YtDlp) {
+        return Some(managed);
+    }
+
     // 1. Check if bundled (relative to executable)
     if let Ok(exe_path) = std::env::current_exe() {
         if let Some(exe_dir) = exe_path.parent() {
@@ -167,10 +173,34 @@ pub fn exe_extension() -> &'static str {
     }
 }
 
+/// Sanitize a video title into a safe single-path-component filename.
+pub fn sanitize_filename(title: &str) -> String {
+    let cleaned: String = title
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' | '\0' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    let trimmed = cleaned.trim().trim_matches('.').trim();
+    if trimmed.is_empty() {
+        "rustloader_download".to_string()
+    } else {
+        trimmed.chars().take(180).collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn sanitize_filename_strips_separators() {
+        assert_eq!(sanitize_filename("a/b:c?"), "a_b_c_");
+        assert_eq!(sanitize_filename("   "), "rustloader_download");
+    }
+
     #[test]
     fn test_app_data_dir_exists_or_creatable() {
         let dir = app_data_dir();
@@ -0,0 +1,633 @@
+//! Managed yt-dlp and ffmpeg: release binaries Rustloader downloads into its
+//! data directory, verifies and keeps up to date, so a stale or missing
+//! system install is one click (or `rustloader tools update`) away from
+//! fixed.
+//!
+//! A release is a directory of assets next to a `SHA2-256SUMS` file, laid
+//! out like GitHub releases: `<base>/download/<tag>/<asset>`, with
+//! `<base>/latest/download/<asset>` redirecting to the newest tag. Every
+//! binary is checked against the checksum file before it is installed. A
+//! source pinned to an Ed25519 key must also serve `SHA2-256SUMS.sig`, the
+//! raw signature of the checksum file. yt-dlp's own releases are signed with
+//! GPG instead, which Rustloader cannot check, so a source without a pinned
+//! key is refused unless it is explicitly marked [`insecure`]: its checksums
+//! are then only as trusted as the HTTPS connection they came over.
+//!
+//! [`insecure`]: ToolSource::insecure
+//!
+//! Each tool lives in `<tools dir>/<tool>/`: the binary, the one it
+//! replaced (`<binary>.previous`, for rollback) and `versions.json`.
+//! Rustloader never ships ffmpeg; it is only fetched when asked for, from a
+//! release server the user names.
+
+use crate::utils::get_app_support_dir;
+use anyhow::{Context, Result};
+use ring::signature::{UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+use tokio::fs;
+use tracing::{info, warn};
+
+/// The checksum file of every release.
+pub const SUMS_FILE: &str = "SHA2-256SUMS";
+
+/// yt-dlp's official releases.
+pub const YTDLP_RELEASES: &str = "https://github.com/yt-dlp/yt-dlp/releases";
+
+/// Binaries are tens of megabytes; allow a slow connection time to finish.
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// A program Rustloader can install and update itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tool {
+    YtDlp,
+    Ffmpeg,
+}
+
+impl Tool {
+    pub const ALL: [Tool; 2] = [Tool::YtDlp, Tool::Ffmpeg];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Tool::YtDlp => "yt-dlp",
+            Tool::Ffmpeg => "ffmpeg",
+        }
+    }
+
+    /// The installed file name: `yt-dlp`, or `yt-dlp.exe` on Windows.
+    pub fn binary_name(self) -> String {
+        format!(
+            "{}{}",
+            self.as_str(),
+            crate::utils::platform::exe_extension()
+        )
+    }
+
+    /// The release asset built for this platform. yt-dlp's are its
+    /// standalone builds (no Python needed); ffmpeg release servers are
+    /// expected to name theirs `ffmpeg-<os>-<arch>`.
+    pub fn asset_name(self) -> String {
+        let (os, arch) = (std::env::consts::OS, std::env::consts::ARCH);
+        match self {
+            Tool::YtDlp => match (os, arch) {
+                ("windows", "x86") => "yt-dlp_x86.exe".to_string(),
+                ("windows", "aarch64") => "yt-dlp_arm64.exe".to_string(),
+                ("windows", _) => "yt-dlp.exe".to_string(),
+                ("macos", _) => "yt-dlp_macos".to_string(),
+                ("linux", "aarch64") => "yt-dlp_linux_aarch64".to_string(),
+                ("linux", "arm") => "yt-dlp_linux_armv7l".to_string(),
+                ("linux", _) => "yt-dlp_linux".to_string(),
+                _ => "yt-dlp".to_string(),
+            },
+            Tool::Ffmpeg => format!(
+                "ffmpeg-{os}-{arch}{}",
+                crate::utils::platform::exe_extension()
+            ),
+        }
+    }
+}
+
+impl fmt::Display for Tool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Tool {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Tool::ALL
+            .into_iter()
+            .find(|t| t.as_str().eq_ignore_ascii_case(value.trim()))
+            .ok_or_else(|| format!("unknown tool '{value}' (one of yt-dlp, ffmpeg)"))
+    }
+}
+
+/// Where a tool's releases come from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolSource {
+    /// Release root: `<base>/download/<tag>/...` and `<base>/latest/...`.
+    pub base_url: String,
+    /// Ed25519 key the checksum file must be signed with, if any.
+    pub public_key: Option<Vec<u8>>,
+    /// Install from this source even without a `public_key` to check.
+    pub allow_unsigned: bool,
+}
+
+impl ToolSource {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            public_key: None,
+            allow_unsigned: false,
+        }
+    }
+
+    /// yt-dlp's GitHub releases.
+    pub fn ytdlp() -> Self {
+        Self::new(YTDLP_RELEASES)
+    }
+
+    pub fn with_public_key(mut self, key: Vec<u8>) -> Self {
+        self.public_key = Some(key);
+        self
+    }
+
+    /// Accept releases whose checksum file is not signed by a pinned key
+    /// (`--insecure`).
+    pub fn insecure(mut self) -> Self {
+        self.allow_unsigned = true;
+        self
+    }
+
+    /// The URL of `asset` in release `tag`, or in the latest release.
+    pub fn asset_url(&self, tag: Option<&str>, asset: &str) -> String {
+        match tag {
+            Some(tag) => format!("{}/download/{tag}/{asset}", self.base_url),
+            None => format!("{}/latest/download/{asset}", self.base_url),
+        }
+    }
+}
+
+/// An installed managed tool.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstalledTool {
+    pub tool: Tool,
+    pub version: String,
+    pub path: PathBuf,
+}
+
+/// `versions.json`: what is installed and what a rollback returns to.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Versions {
+    current: Option<String>,
+    previous: Option<String>,
+}
+
+/// `name -> sha256` from a `sha256sum`-style file (`<hex>  <name>`, with an
+/// optional `*` marking binary mode).
+pub fn parse_sums(text: &str) -> HashMap<String, String> {
+    text.lines()
+        .filter_map(|line| {
+            let (hash, name) = line.trim().split_once(char::is_whitespace)?;
+            let name = name.trim().trim_start_matches('*');
+            (hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()))
+                .then(|| (name.to_string(), hash.to_ascii_lowercase()))
+        })
+        .collect()
+}
+
+/// Lowercase hex SHA-256 of `bytes`.
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// Decode a hex-encoded Ed25519 public key (as `--public-key` takes it).
+pub fn parse_public_key(hex: &str) -> Result<Vec<u8>, String> {
+    let hex = hex.trim();
+    let invalid = || format!("'{hex}' is not a hex-encoded Ed25519 public key");
+    if hex.len() != 64 {
+        return Err(invalid());
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| invalid()))
+        .collect()
+}
+
+/// Check that `signature` is `public_key`'s Ed25519 signature of `message`.
+pub fn verify_signature(public_key: &[u8], message: &[u8], signature: &[u8]) -> Result<()> {
+    UnparsedPublicKey::new(&ED25519, public_key)
+        .verify(message, signature)
+        .map_err(|_| anyhow::anyhow!("{SUMS_FILE} is not signed by the pinned key"))
+}
+
+/// The release tag in a resolved asset URL (`.../download/<tag>/<asset>`),
+/// which is how a request for the latest release learns its version.
+fn tag_of(url: &reqwest::Url) -> Option<String> {
+    let segments: Vec<&str> = url.path_segments()?.collect();
+    let at = segments.iter().rposition(|s| *s == "download")?;
+    let tag = segments.get(at + 1)?;
+    (segments.len() > at + 2 && !tag.is_empty() && *tag != "latest").then(|| tag.to_string())
+}
+
+/// Installs, updates and rolls back managed tools.
+#[derive(Debug, Clone)]
+pub struct ToolManager {
+    dir: PathBuf,
+    client: reqwest::Client,
+}
+
+impl Default for ToolManager {
+    /// `<app support dir>/tools`.
+    fn default() -> Self {
+        Self::new(default_tools_dir())
+    }
+}
+
+/// `<app support dir>/tools`, where managed tools are installed.
+pub fn default_tools_dir() -> PathBuf {
+    get_app_support_dir().join("tools")
+}
+
+/// Where the managed `tool` is (or would be) installed under `dir`.
+pub fn binary_path_in(dir: &Path, tool: Tool) -> PathBuf {
+    dir.join(tool.as_str()).join(tool.binary_name())
+}
+
+impl ToolManager {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(DOWNLOAD_TIMEOUT)
+            .build()
+            .expect("Failed to create HTTP client");
+        Self {
+            dir: dir.into(),
+            client,
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Where the managed `tool` is (or would be) installed.
+    pub fn binary_path(&self, tool: Tool) -> PathBuf {
+        binary_path_in(&self.dir, tool)
+    }
+
+    fn previous_path(&self, tool: Tool) -> PathBuf {
+        let mut path = self.binary_path(tool).into_os_string();
+        path.push(".previous");
+        PathBuf::from(path)
+    }
+
+    fn versions_path(&self, tool: Tool) -> PathBuf {
+        self.dir.join(tool.as_str()).join("versions.json")
+    }
+
+    fn versions(&self, tool: Tool) -> Versions {
+        std::fs::read_to_string(self.versions_path(tool))
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    async fn save_versions(&self, tool: Tool, versions: &Versions) -> Result<()> {
+        fs::write(self.versions_path(tool), serde_json::to_string(versions)?).await?;
+        Ok(())
+    }
+
+    /// The managed `tool`, if it is installed.
+    pub fn installed(&self, tool: Tool) -> Option<InstalledTool> {
+        let path = self.binary_path(tool);
+        if !path.is_file() {
+            return None;
+        }
+        Some(InstalledTool {
+            tool,
+            version: self
+                .versions(tool)
+                .current
+                .unwrap_or_else(|| "unknown".to_string()),
+            path,
+        })
+    }
+
+    /// The version a [`rollback`](Self::rollback) of `tool` would restore.
+    pub fn previous_version(&self, tool: Tool) -> Option<String> {
+        self.previous_path(tool)
+            .is_file()
+            .then(|| self.versions(tool).previous)
+            .flatten()
+    }
+
+    async fn fetch(&self, url: &str) -> Result<(reqwest::Url, Vec<u8>)> {
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .with_context(|| format!("Failed to download {url}"))?;
+        let resolved = response.url().clone();
+        let bytes = response
+            .bytes()
+            .await
+            .with_context(|| format!("Failed to download {url}"))?;
+        Ok((resolved, bytes.to_vec()))
+    }
+
+    /// Install release `version` of `tool` from `source` (the latest release
+    /// when `None`), keeping the binary it replaces for rollback. Nothing
+    /// changes unless the download matches its checksum (and signature).
+    pub async fn install(
+        &self,
+        tool: Tool,
+        source: &ToolSource,
+        version: Option<&str>,
+    ) -> Result<InstalledTool> {
+        if source.public_key.is_none() && !source.allow_unsigned {
+            anyhow::bail!(
+                "{} has no pinned signing key, so its releases cannot be verified; \
+                 pass --insecure to trust its checksums over HTTPS alone",
+                source.base_url
+            );
+        }
+        let asset = tool.asset_name();
+        let (sums_url, sums) = self
+            .fetch(&source.asset_url(version, SUMS_FILE))
+            .await
+            .context("Could not fetch the release checksums")?;
+        if let Some(key) = &source.public_key {
+            let (_, signature) = self
+                .fetch(&format!("{sums_url}.sig"))
+                .await
+                .context("Could not fetch the checksum signature")?;
+            verify_signature(key, &sums, &signature)?;
+        }
+        let expected = parse_sums(&String::from_utf8_lossy(&sums))
+            .remove(&asset)
+            .with_context(|| format!("The release has no {asset} for this platform"))?;
+
+        // Take the binary from the release the checksums came from, even if
+        // a newer one was published in between.
+        let tag = version.map(str::to_string).or_else(|| tag_of(&sums_url));
+        let (_, binary) = self
+            .fetch(&source.asset_url(tag.as_deref(), &asset))
+            .await?;
+        let actual = sha256_hex(&binary);
+        if actual != expected {
+            anyhow::bail!("{asset} failed its checksum (expected {expected}, got {actual})");
+        }
+
+        let path = self.binary_path(tool);
+        let dir = path.parent().expect("binary path has a parent");
+        fs::create_dir_all(dir).await?;
+        let mut staged = path.clone().into_os_string();
+        staged.push(".new");
+        let staged = PathBuf::from(staged);
+        fs::write(&staged, &binary).await?;
+        make_executable(&staged).await?;
+
+        let mut versions = self.versions(tool);
+        if path.is_file() {
+            fs::rename(&path, self.previous_path(tool)).await?;
+            versions.previous = versions.current.take();
+        }
+        fs::rename(&staged, &path).await?;
+        versions.current = Some(tag.unwrap_or_else(|| "latest".to_string()));
+        self.save_versions(tool, &versions).await?;
+
+        let installed = self
+            .installed(tool)
+            .context("Installed binary is missing")?;
+        info!(
+            "Installed {} {} at {}",
+            tool,
+            installed.version,
+            installed.path.display()
+        );
+        Ok(installed)
+    }
+
+    /// Swap the managed `tool` with the version it replaced.
+    pub async fn rollback(&self, tool: Tool) -> Result<InstalledTool> {
+        let path = self.binary_path(tool);
+        let previous = self.previous_path(tool);
+        if !previous.is_file() {
+            anyhow::bail!("There is no previous {tool} to roll back to");
+        }
+        let mut swap = path.clone().into_os_string();
+        swap.push(".swap");
+        let swap = PathBuf::from(swap);
+        if path.is_file() {
+            fs::rename(&path, &swap).await?;
+        }
+        fs::rename(&previous, &path).await?;
+        if swap.is_file() {
+            fs::rename(&swap, &previous).await?;
+        }
+
+        let mut versions = self.versions(tool);
+        std::mem::swap(&mut versions.current, &mut versions.previous);
+        self.save_versions(tool, &versions).await?;
+        let installed = self.installed(tool).context("Restored binary is missing")?;
+        warn!("Rolled {} back to {}", tool, installed.version);
+        Ok(installed)
+    }
+}
+
+#[cfg(unix)]
+async fn make_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).await?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+async fn make_executable(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+/// The managed install of `tool` in the default tools directory, if any.
+/// Binary lookups try it before anything on the system.
+pub fn managed_binary(tool: Tool) -> Option<PathBuf> {
+    let path = binary_path_in(&default_tools_dir(), tool);
+    path.is_file().then_some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::rand::SystemRandom;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// A stand-in release server: serves `files` by path and redirects
+    /// `/latest/download/<asset>` to the `latest` tag, as GitHub does.
+    async fn release_server(files: HashMap<String, Vec<u8>>, latest: &str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let latest = latest.to_string();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = vec![0u8; 4096];
+                let n = socket.read(&mut buf).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&buf[..n]).to_string();
+                let path = request.split_whitespace().nth(1).unwrap_or("/").to_string();
+                let response = if let Some(asset) = path.strip_prefix("/releases/latest/download/")
+                {
+                    format!(
+                        "HTTP/1.1 302 Found\r\nLocation: /releases/download/{latest}/{asset}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                    )
+                    .into_bytes()
+                } else if let Some(body) = files.get(&path) {
+                    let mut response = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                        body.len()
+                    )
+                    .into_bytes();
+                    response.extend_from_slice(body);
+                    response
+                } else {
+                    b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                        .to_vec()
+                };
+                let _ = socket.write_all(&response).await;
+            }
+        });
+        format!("http://{addr}/releases")
+    }
+
+    /// A release `tag` of yt-dlp whose binary is `binary`.
+    fn release(files: &mut HashMap<String, Vec<u8>>, tag: &str, binary: &[u8]) -> Vec<u8> {
+        let asset = Tool::YtDlp.asset_name();
+        let sums = format!(
+            "{}  {asset}\n{}  yt-dlp.tar.gz\n",
+            sha256_hex(binary),
+            sha256_hex(b"source")
+        );
+        files.insert(format!("/releases/download/{tag}/{asset}"), binary.to_vec());
+        files.insert(
+            format!("/releases/download/{tag}/{SUMS_FILE}"),
+            sums.clone().into_bytes(),
+        );
+        sums.into_bytes()
+    }
+
+    #[test]
+    fn parses_sums_and_keys() {
+        let hash = sha256_hex(b"abc");
+        assert_eq!(
+            hash,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        let sums = parse_sums(&format!("{hash}  yt-dlp\n{hash} *yt-dlp.exe\nnot a line\n"));
+        assert_eq!(sums.len(), 2);
+        assert_eq!(sums["yt-dlp.exe"], hash);
+
+        assert_eq!(parse_public_key(&"ab".repeat(32)).unwrap(), vec![0xab; 32]);
+        assert!(parse_public_key("abcd").is_err());
+        assert_eq!("YT-DLP".parse::<Tool>(), Ok(Tool::YtDlp));
+        assert!(Tool::Ffmpeg.asset_name().starts_with("ffmpeg-"));
+        assert_eq!(
+            ToolSource::ytdlp().asset_url(Some("2026.09.01"), SUMS_FILE),
+            "https://github.com/yt-dlp/yt-dlp/releases/download/2026.09.01/SHA2-256SUMS"
+        );
+    }
+
+    #[tokio::test]
+    async fn installs_latest_and_pinned_releases_and_rolls_back() {
+        let mut files = HashMap::new();
+        release(&mut files, "2026.08.01", b"old yt-dlp");
+        release(&mut files, "2026.09.01", b"new yt-dlp");
+        let base = release_server(files, "2026.09.01").await;
+        let dir = tempfile::tempdir().unwrap();
+        let manager = ToolManager::new(dir.path());
+        let source = ToolSource::new(base).insecure();
+        assert!(manager.installed(Tool::YtDlp).is_none());
+
+        let pinned = manager
+            .install(Tool::YtDlp, &source, Some("2026.08.01"))
+            .await
+            .unwrap();
+        assert_eq!(pinned.version, "2026.08.01");
+        assert_eq!(std::fs::read(&pinned.path).unwrap(), b"old yt-dlp");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&pinned.path)
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o111, 0o111);
+        }
+        assert_eq!(manager.previous_version(Tool::YtDlp), None);
+
+        let latest = manager.install(Tool::YtDlp, &source, None).await.unwrap();
+        assert_eq!(latest.version, "2026.09.01");
+        assert_eq!(std::fs::read(&latest.path).unwrap(), b"new yt-dlp");
+        assert_eq!(
+            manager.previous_version(Tool::YtDlp).as_deref(),
+            Some("2026.08.01")
+        );
+
+        let restored = manager.rollback(Tool::YtDlp).await.unwrap();
+        assert_eq!(restored.version, "2026.08.01");
+        assert_eq!(std::fs::read(&restored.path).unwrap(), b"old yt-dlp");
+        assert_eq!(
+            manager.previous_version(Tool::YtDlp).as_deref(),
+            Some("2026.09.01")
+        );
+        assert!(manager.rollback(Tool::Ffmpeg).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn refuses_binaries_that_fail_verification() {
+        let asset = Tool::YtDlp.asset_name();
+        let mut files = HashMap::new();
+        let sums = release(&mut files, "2026.09.01", b"genuine");
+        files.insert(
+            format!("/releases/download/2026.09.01/{asset}"),
+            b"tampered".to_vec(),
+        );
+        let rng = SystemRandom::new();
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+        let key = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let public_key = key.public_key().as_ref().to_vec();
+        release(&mut files, "2026.10.01", b"signed");
+        let signed_sums = files[&format!("/releases/download/2026.10.01/{SUMS_FILE}")].clone();
+        files.insert(
+            format!("/releases/download/2026.10.01/{SUMS_FILE}.sig"),
+            key.sign(&signed_sums).as_ref().to_vec(),
+        );
+        files.insert(
+            format!("/releases/download/2026.09.01/{SUMS_FILE}.sig"),
+            key.sign(&sums).as_ref().to_vec(),
+        );
+        let base = release_server(files, "2026.10.01").await;
+        let dir = tempfile::tempdir().unwrap();
+        let manager = ToolManager::new(dir.path());
+
+        let unsigned = manager
+            .install(Tool::YtDlp, &ToolSource::new(&base), None)
+            .await
+            .unwrap_err();
+        assert!(unsigned.to_string().contains("--insecure"), "{unsigned}");
+        assert!(manager.installed(Tool::YtDlp).is_none());
+
+        let tampered = manager
+            .install(
+                Tool::YtDlp,
+                &ToolSource::new(&base).insecure(),
+                Some("2026.09.01"),
+            )
+            .await
+            .unwrap_err();
+        assert!(tampered.to_string().contains("checksum"), "{tampered}");
+        assert!(manager.installed(Tool::YtDlp).is_none());
+
+        let wrong_key = ToolSource::new(&base).with_public_key(vec![7; 32]);
+        assert!(manager
+            .install(Tool::YtDlp, &wrong_key, None)
+            .await
+            .is_err());
+        assert!(manager.installed(Tool::YtDlp).is_none());
+
+        let pinned = ToolSource::new(&base).with_public_key(public_key);
+        let installed = manager.install(Tool::YtDlp, &pinned, None).await.unwrap();
+        assert_eq!(installed.version, "2026.10.01");
+        assert_eq!(std::fs::read(&installed.path).unwrap(), b"signed");
+    }
+}
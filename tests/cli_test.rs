@@ -61,6 +61,11 @@ fn rejects_unsupported_format() {
     assert!(Cli::try_parse_from(["rustloader", "URL", "-f", "mkv"]).is_err());
 }
 
+#[test]
+fn rejects_malformed_format_selector() {
+    assert!(Cli::try_parse_from(["rustloader", "URL", "-f", "mkv[height>]"]).is_err());
+}
+
 // ---- Binary-level smoke tests (no network) ----
 
 const BIN: &str = env!("CARGO_BIN_EXE_rustloader");
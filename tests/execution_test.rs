@@ -1,6 +1,6 @@
 use chrono::Utc;
 use rustloader::downloader::{DownloadConfig, DownloadEngine};
-use rustloader::extractor::{Format, VideoInfo};
+use rustloader::extractor::{Format, LiveStatus, VideoInfo};
 use rustloader::queue::{EventLog, QueueManager};
 use rustloader::utils::{FileOrganizer, MetadataManager, OrganizationSettings};
 use std::sync::Arc;
@@ -40,6 +40,9 @@ async fn test_execution_concurrency_limit() {
             status: rustloader::queue::TaskStatus::Queued,
             progress: None,
             added_at: Utc::now(),
+            batch: None,
+            target_dir: None,
+            requested_format: None,
         };
         qm.add_task(task).await.unwrap();
     }
@@ -105,6 +108,9 @@ async fn test_fsm_transitions() {
         status: rustloader::queue::TaskStatus::Queued,
         progress: None,
         added_at: Utc::now(),
+        batch: None,
+        target_dir: None,
+        requested_format: None,
     };
     qm.add_task(task).await.unwrap();
 
@@ -141,3 +147,69 @@ async fn test_fsm_transitions() {
     let t = tasks.iter().find(|t| t.id == task_id).unwrap();
     assert!(matches!(t.status, rustloader::queue::TaskStatus::Cancelled));
 }
+
+#[tokio::test]
+async fn test_scheduled_streams_wait_without_a_slot() {
+    let temp_dir = tempdir().expect("Failed to create temp dir");
+    let base_dir = temp_dir.path().to_path_buf();
+    let event_log = Arc::new(EventLog::new(&base_dir).await.unwrap());
+    let engine = DownloadEngine::new(DownloadConfig::default());
+    let org = FileOrganizer::new(OrganizationSettings::default())
+        .await
+        .unwrap();
+    let meta = MetadataManager::new(&base_dir);
+    let qm = Arc::new(QueueManager::new(1, engine, org, meta, event_log));
+
+    // A premiere an hour away, added ahead of a regular download.
+    let premiere = rustloader::queue::DownloadTask {
+        id: "premiere".to_string(),
+        video_info: VideoInfo {
+            title: "Premiere".to_string(),
+            live_status: Some(LiveStatus::IsUpcoming),
+            release_timestamp: Some(Utc::now().timestamp() + 3600),
+            ..Default::default()
+        },
+        format: Format::default(),
+        output_path: base_dir.join("premiere.mp4"),
+        status: rustloader::queue::TaskStatus::Queued,
+        progress: None,
+        added_at: Utc::now(),
+        batch: None,
+        target_dir: None,
+        requested_format: None,
+    };
+    qm.add_task(premiere).await.unwrap();
+    let regular = rustloader::queue::DownloadTask {
+        id: "regular".to_string(),
+        video_info: VideoInfo {
+            title: "Regular".to_string(),
+            ..Default::default()
+        },
+        format: Format::default(),
+        output_path: base_dir.join("regular.mp4"),
+        status: rustloader::queue::TaskStatus::Queued,
+        progress: None,
+        added_at: Utc::now(),
+        batch: None,
+        target_dir: None,
+        requested_format: None,
+    };
+    qm.add_task(regular).await.unwrap();
+
+    let qm_clone = qm.clone();
+    tokio::spawn(async move {
+        qm_clone.start().await;
+    });
+    sleep(Duration::from_millis(700)).await;
+
+    let tasks = qm.get_all_tasks().await;
+    let premiere = tasks.iter().find(|t| t.id == "premiere").unwrap();
+    assert_eq!(premiere.status, rustloader::queue::TaskStatus::Waiting);
+    assert!(premiere
+        .progress
+        .as_ref()
+        .is_some_and(|p| p.starts_at.is_some()));
+    // The only slot went to the regular download, not the premiere.
+    let regular = tasks.iter().find(|t| t.id == "regular").unwrap();
+    assert_ne!(regular.status, rustloader::queue::TaskStatus::Queued);
+}
@@ -24,6 +24,8 @@ fn sample_format() -> Format {
         tbr: None,
         vbr: None,
         abr: None,
+        language: None,
+        dynamic_range: None,
     }
 }
 
@@ -44,6 +46,14 @@ fn sample_video() -> VideoInfo {
         view_count: None,
         like_count: None,
         extractor: Some("test".to_string()),
+        playlist_title: None,
+        subtitles: Default::default(),
+        automatic_captions: Default::default(),
+        chapters: Vec::new(),
+        live_status: None,
+        is_live: None,
+        was_live: None,
+        release_timestamp: None,
     }
 }
 
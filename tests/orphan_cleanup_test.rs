@@ -39,6 +39,9 @@ fn make_task(id: &str, output_path: PathBuf) -> DownloadTask {
         status: TaskStatus::Queued,
         progress: None,
         added_at: Utc::now(),
+        batch: None,
+        target_dir: None,
+        requested_format: None,
     }
 }
 
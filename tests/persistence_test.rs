@@ -1,8 +1,8 @@
 use chrono::Utc;
 use rustloader::downloader::{DownloadConfig, DownloadEngine};
 use rustloader::extractor::{Format, VideoInfo};
-use rustloader::queue::{EventLog, QueueEvent, QueueManager, TaskStatus};
-use rustloader::utils::{FileOrganizer, MetadataManager, OrganizationSettings};
+use rustloader::queue::{DownloadTask, EventLog, QueueEvent, QueueManager, TaskBatch, TaskStatus};
+use rustloader::utils::{ContentType, FileOrganizer, MetadataManager, OrganizationSettings};
 use std::path::PathBuf;
 use std::sync::Arc;
 use tempfile::tempdir;
@@ -38,6 +38,9 @@ async fn test_persistence_rehydration() {
             }),
             output_path: base_dir.join("test.mp4"),
             timestamp: Utc::now(),
+            batch: None,
+            target_dir: None,
+            requested_format: None,
         },
         QueueEvent::TaskStarted {
             task_id: task_id.clone(),
@@ -125,6 +128,9 @@ async fn test_persistence_corruption_resilience() {
         format: Box::new(Format::default()),
         output_path: PathBuf::from("/tmp/video.mp4"),
         timestamp: Utc::now(),
+        batch: None,
+        target_dir: None,
+        requested_format: None,
     };
 
     let valid_json = serde_json::to_string(&valid_event).unwrap();
@@ -165,3 +171,63 @@ async fn test_persistence_corruption_resilience() {
 
     println!("corruption_test PASSED");
 }
+
+#[tokio::test]
+async fn test_playlist_batch_survives_rehydration() {
+    let temp_dir = tempdir().expect("Failed to create temp dir");
+    let base_dir = temp_dir.path().to_path_buf();
+
+    let new_manager = |event_log: EventLog| async {
+        let engine = DownloadEngine::new(DownloadConfig::default());
+        let file_organizer = FileOrganizer::new(OrganizationSettings::default())
+            .await
+            .expect("Failed to create FileOrganizer");
+        QueueManager::new(
+            1,
+            engine,
+            file_organizer,
+            MetadataManager::new(&base_dir),
+            Arc::new(event_log),
+        )
+    };
+
+    // A log line written before tasks carried a batch must still load.
+    let legacy = r#"{"TaskAdded":{"task_id":"legacy","video_info":{"id":"x","title":"Old"},"format":{"format_id":"best","ext":"mp4","url":""},"output_path":"/tmp/old.mp4","timestamp":"2024-01-01T00:00:00Z"}}"#;
+    tokio::fs::write(base_dir.join("events.jsonl"), format!("{legacy}\n"))
+        .await
+        .unwrap();
+
+    let first = new_manager(EventLog::new(&base_dir).await.unwrap()).await;
+    let task = DownloadTask::new(
+        VideoInfo {
+            title: "Part 2".into(),
+            ..Default::default()
+        },
+        Format::default(),
+        base_dir.join("part2.mp4"),
+    )
+    .with_batch(TaskBatch {
+        id: "batch-1".into(),
+        name: "Lofi Mix".into(),
+        index: 2,
+    });
+    first.add_task(task).await.expect("add_task");
+
+    let second = new_manager(EventLog::new(&base_dir).await.unwrap()).await;
+    second.rehydrate().await.expect("Failed to rehydrate");
+    let tasks = second.get_all_tasks().await;
+    assert_eq!(tasks.len(), 2);
+
+    let old = tasks.iter().find(|t| t.id == "legacy").unwrap();
+    assert!(old.batch.is_none());
+    assert_eq!(old.content_type(), ContentType::Video);
+
+    let entry = tasks.iter().find(|t| t.id != "legacy").unwrap();
+    assert_eq!(entry.batch.as_ref().map(|b| b.index), Some(2));
+    assert_eq!(
+        entry.content_type(),
+        ContentType::Playlist {
+            name: "Lofi Mix".into()
+        }
+    );
+}
@@ -66,6 +66,9 @@ fn create_dummy_task(id: &str, base_dir: &std::path::Path) -> DownloadTask {
         status: TaskStatus::Queued,
         progress: None,
         added_at: Utc::now(),
+        batch: None,
+        target_dir: None,
+        requested_format: None,
     }
 }
 